@@ -0,0 +1,109 @@
+//! Baseline of known findings that `check --baseline` does not fail on.
+//!
+//! A baseline lets docref be adopted in a repository that already has stale
+//! or broken references: record today's findings once, and CI only fails on
+//! new ones. An entry stops being suppressed as soon as its finding changes
+//! kind, e.g. a baselined stale reference that later breaks.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Finding;
+use crate::error::Error;
+use crate::lockfile::LockEntry;
+
+/// Default baseline location, relative to the project root.
+pub const DEFAULT_PATH: &str = ".docref-baseline.toml";
+
+/// The baseline file as a whole. Entries are kept sorted so lookups can
+/// binary-search and the file diffs cleanly when rewritten.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Known findings, sorted by (source, target, symbol, finding).
+    pub entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Whether `entry` was already reported as `finding` when the baseline was written.
+    pub fn contains(&self, entry: &LockEntry, finding: Finding) -> bool {
+        let probe = BaselineEntry {
+            finding,
+            source: entry.source.clone(),
+            symbol: entry.symbol.clone(),
+            target: entry.target.clone(),
+        };
+        return self.entries.binary_search(&probe).is_ok();
+    }
+
+    /// Create a baseline from unsorted entries. Sorts and deduplicates.
+    pub fn new(mut entries: Vec<BaselineEntry>) -> Self {
+        entries.sort();
+        entries.dedup();
+        return Self { entries };
+    }
+
+    /// Read and parse a baseline from disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FileNotFound` if the file doesn't exist,
+    /// `Error::Io` for other read failures, or `Error::TomlDe` if the
+    /// content is invalid TOML.
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let content = match std::fs::read_to_string(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(Error::FileNotFound { path: path.to_path_buf() });
+            },
+            Err(e) => return Err(Error::Io(e)),
+            Ok(c) => c,
+        };
+        let raw: Self = toml::from_str(&content)?;
+        // Hand-edited baselines may be unsorted; normalize rather than reject.
+        return Ok(Self::new(raw.entries));
+    }
+
+    /// Write the baseline to disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::TomlSer` if serialization fails,
+    /// or `Error::Io` if the file cannot be written.
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        let content = toml::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        return Ok(());
+    }
+}
+
+/// One known finding, keyed like its lockfile entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BaselineEntry {
+    /// The kind of finding recorded when the baseline was written.
+    pub finding: Finding,
+    /// The markdown file containing the reference.
+    pub source: PathBuf,
+    /// The symbol name within the target file.
+    pub symbol: String,
+    /// The target source file being referenced.
+    pub target: PathBuf,
+}
+
+impl Ord for BaselineEntry {
+    /// Compare entries by (source, target, symbol, finding) for deterministic ordering.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        return (&self.source, &self.target, &self.symbol, self.finding).cmp(&(
+            &other.source,
+            &other.target,
+            &other.symbol,
+            other.finding,
+        ));
+    }
+}
+
+impl PartialOrd for BaselineEntry {
+    /// Delegate to `Ord` implementation.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        return Some(self.cmp(other));
+    }
+}
@@ -0,0 +1,101 @@
+//! Persistent cache of semantic hashes, so repeated checks skip parsing
+//! target files that haven't changed.
+//!
+//! Entries are keyed by a digest of the target's content plus the query,
+//! hash mode, and slug style, so an edited file simply misses. The whole
+//! cache is discarded when docref's version or any grammar changes, and
+//! each save keeps only the entries that run used.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use crate::config::SlugStyle;
+use crate::error::Error;
+use crate::grammar::{self, Grammars};
+use crate::types::{HashMode, SemanticHash, SymbolQuery};
+
+/// Cache location, relative to the project root.
+pub const FILE_NAME: &str = ".docref.cache";
+
+/// Semantic hashes from earlier runs, by target content and query.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct HashCache {
+    /// Hashes by [`HashCache::key`].
+    entries: BTreeMap<String, SemanticHash>,
+    /// Whether a hash was added since loading.
+    #[serde(skip)]
+    modified: bool,
+    /// Keys looked up or added during this run; the rest are dropped on save.
+    #[serde(skip)]
+    used: BTreeSet<String>,
+    /// The docref and grammar versions the hashes were computed with.
+    version: String,
+}
+
+impl HashCache {
+    /// The cached hash for `key`, if an earlier run computed it.
+    pub fn get(&mut self, key: &str) -> Option<SemanticHash> {
+        let hash = self.entries.get(key)?.clone();
+        self.used.insert(key.to_string());
+        return Some(hash);
+    }
+
+    /// Remember the hash computed for `key`.
+    pub fn insert(&mut self, key: String, hash: SemanticHash) {
+        self.used.insert(key.clone());
+        self.entries.insert(key, hash);
+        self.modified = true;
+        return;
+    }
+
+    /// Cache key for hashing `query` in a file whose content has `digest`.
+    pub fn key(digest: &str, query: &SymbolQuery, mode: HashMode, slug_style: SlugStyle) -> String {
+        let absent = if matches!(query, SymbolQuery::Absent(_)) { "!" } else { "" };
+        return format!("{digest} {mode:?} {slug_style:?} {absent}{}", query.display_name());
+    }
+
+    /// Read the cache under `root`. A missing or unreadable cache, or one
+    /// written by another docref version or with other `grammars`, starts
+    /// out empty.
+    pub fn load(root: &Path, grammars: &Grammars) -> Self {
+        let version = current_version(grammars);
+        let cached = std::fs::read(root.join(FILE_NAME))
+            .ok()
+            .and_then(|bytes| return serde_json::from_slice::<Self>(&bytes).ok())
+            .filter(|cache| return cache.version == version);
+        tracing::debug!(hit = cached.is_some(), "loading hash cache");
+        return cached.unwrap_or_else(|| return Self { version, ..Self::default() });
+    }
+
+    /// Write the entries this run used back under `root`, skipping the
+    /// write when nothing changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if the cache file can't be written.
+    pub fn save(mut self, root: &Path) -> Result<(), Error> {
+        let before = self.entries.len();
+        let used = std::mem::take(&mut self.used);
+        self.entries.retain(|key, _| return used.contains(key));
+        if !self.modified && self.entries.len() == before {
+            return Ok(());
+        }
+        let json = serde_json::to_vec(&self).map_err(std::io::Error::from)?;
+        std::fs::write(root.join(FILE_NAME), json)?;
+        return Ok(());
+    }
+}
+
+/// The docref version plus every grammar's shape, which changes whenever
+/// a grammar is upgraded.
+fn current_version(grammars: &Grammars) -> String {
+    return format!("{} {}", env!("CARGO_PKG_VERSION"), grammar::fingerprint(grammars));
+}
+
+/// Digest of a target file's exact content, for [`HashCache::key`].
+pub fn digest(source: &str) -> String {
+    return format!("{:x}", Sha256::digest(source.as_bytes()));
+}
@@ -0,0 +1,117 @@
+//! `CODEOWNERS` parsing, to route drifted references to the teams that own them.
+//!
+//! Patterns follow GitHub's rules: gitignore-style globs, anchored to the
+//! repository root when they contain a slash, and the last matching line
+//! wins. A matching line with no owners leaves the path unowned.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::error::Error;
+
+/// Where GitHub looks for the file, in the order it checks them.
+pub const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Ownership rules from a `CODEOWNERS` file, in file order.
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    /// Parsed lines; later rules take precedence.
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Read the first `CODEOWNERS` file found under `root`, or no rules if
+    /// the project has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if an existing file can't be read.
+    pub fn load(root: &Path) -> Result<Self, Error> {
+        let Some(path) = LOCATIONS.iter().map(|l| return root.join(l)).find(|p| return p.is_file()) else {
+            return Ok(Self::default());
+        };
+        tracing::debug!(path = %path.display(), "loading CODEOWNERS");
+        return Ok(Self::parse(&std::fs::read_to_string(path)?));
+    }
+
+    /// Owners of a root-relative path, by the last rule that matches it.
+    pub fn owners_of(&self, path: &Path) -> &[String] {
+        let path = path.to_string_lossy().replace('\\', "/");
+        return self
+            .rules
+            .iter()
+            .rev()
+            .find(|rule| return rule.pattern.is_match(&path))
+            .map_or(&[], |rule| return &rule.owners);
+    }
+
+    /// Parse `CODEOWNERS` content. Comments, blank lines, and patterns that
+    /// can't be translated are skipped.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace().take_while(|f| return !f.starts_with('#'));
+                let pattern = pattern_regex(fields.next()?)?;
+                return Some(Rule { owners: fields.map(String::from).collect(), pattern });
+            })
+            .collect();
+        return Self { rules };
+    }
+}
+
+/// One `CODEOWNERS` line.
+#[derive(Debug)]
+struct Rule {
+    /// Users or teams, e.g. `@org/docs`; empty to leave matches unowned.
+    owners: Vec<String>,
+    /// The pattern, matching a path or anything under it.
+    pattern: Regex,
+}
+
+/// Translate a `CODEOWNERS` pattern into a regex over slash-separated paths.
+///
+/// A pattern with a slash before its end is anchored to the root, one
+/// without matches at any depth, and any match also covers everything
+/// beneath a matched directory.
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let body = pattern.trim_start_matches('/').trim_end_matches('/');
+    if body.is_empty() {
+        return None;
+    }
+    let anchored = pattern.starts_with('/') || body.contains('/');
+    let glob = regex::escape(body)
+        .replace(r"\*\*/", "(?:.*/)?")
+        .replace(r"\*\*", ".*")
+        .replace(r"\*", "[^/]*")
+        .replace(r"\?", "[^/]");
+    let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+    return Regex::new(&format!("{prefix}{glob}(?:/.*)?$")).ok();
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc, reason = "test code uses unwrap freely")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let owners = CodeOwners::parse(
+            "# comment\n* @org/everyone\n/docs/ @org/docs # inline\nsrc/**/auth.rs @org/identity\n*.md\n",
+        );
+        assert_eq!(owners.owners_of(Path::new("src/lib.rs")), ["@org/everyone"]);
+        assert_eq!(owners.owners_of(Path::new("docs/api/guide.txt")), ["@org/docs"]);
+        assert_eq!(owners.owners_of(Path::new("src/a/b/auth.rs")), ["@org/identity"]);
+        assert_eq!(owners.owners_of(Path::new("src/auth.rs")), ["@org/identity"]);
+        assert!(owners.owners_of(Path::new("docs/guide.md")).is_empty());
+    }
+
+    #[test]
+    fn unanchored_patterns_match_at_any_depth() {
+        let owners = CodeOwners::parse("build/ @org/infra\n/top.rs @org/top\n");
+        assert_eq!(owners.owners_of(Path::new("services/web/build/out.js")), ["@org/infra"]);
+        assert_eq!(owners.owners_of(Path::new("top.rs")), ["@org/top"]);
+        assert!(owners.owners_of(Path::new("nested/top.rs")).is_empty());
+    }
+}
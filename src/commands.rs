@@ -1,34 +1,60 @@
 //! Core CLI commands for docref: init, check, status, resolve, update, fix, refs.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::time::SystemTime;
 
 use serde::Serialize;
 
-use crate::config;
+use crate::baseline::{Baseline, BaselineEntry};
+use crate::codeowners::CodeOwners;
+use crate::config::{self, Finding, Severity};
 use crate::diagnostics;
 use crate::error;
 use crate::freshness::{
-    CheckResult, compare_lockfile_entry_against_source, parse_symbol_query,
-    resolve_and_hash_all_references,
+    CheckResult, MemberChanges, compare_lockfile_entries_against_source, hash_query_in_source, member_changes,
+    parse_symbol_query, relocated_lines, resolve_and_hash_all_references_with_progress,
 };
 use crate::grammar;
-use crate::hasher;
-use crate::lockfile::Lockfile;
+use crate::history;
+use crate::junit;
+use crate::lockfile::{Acceptance, LockEntry, Lockfile};
+use crate::package;
+use crate::progress::Progress;
 use crate::resolver;
+use crate::sarif;
 use crate::scanner;
-use crate::types::Reference;
+use crate::types::{Reference, SymbolQuery};
 
 /// JSON output for a single check entry.
 #[derive(Serialize)]
 struct CheckEntryJson {
+    /// Members a stale wildcard's type gained since it was locked.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    members_added: Vec<String>,
+    /// Members a stale wildcard's type lost since it was locked.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    members_removed: Vec<String>,
+    /// Where a stale line range's unchanged lines are now, e.g. `L42-L77`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    moved_to: Option<String>,
     /// Optional reason for broken status.
     #[serde(skip_serializing_if = "Option::is_none")]
     reason: Option<String>,
+    /// How a non-fresh entry counted: "error", "warning", or "baseline".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<String>,
     /// The markdown file containing the reference.
     source: PathBuf,
-    /// Freshness status: "fresh", "stale", or "broken".
+    /// Whole days a stale entry has been stale, when `stale_grace_days` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_days: Option<u64>,
+    /// Freshness status: "fresh", "stale", "broken", "ambiguous", or "orphaned".
     status: String,
     /// The symbol name (empty for whole-file refs).
     symbol: String,
@@ -41,64 +67,692 @@ struct CheckEntryJson {
 struct CheckJson {
     /// All tracked entries with their statuses.
     entries: Vec<CheckEntryJson>,
+    /// Which docref produced the report, when, and for which project.
+    metadata: ReportMetadataJson,
     /// Summary counts.
     summary: CheckSummaryJson,
+    /// Present when the run stopped early at the failure limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    truncated: Option<CheckTruncatedJson>,
+}
+
+/// Options that adjust how `check` weighs its findings.
+#[derive(Default)]
+pub struct CheckOptions {
+    /// Baseline file whose findings are reported but never fail the check.
+    pub baseline: Option<PathBuf>,
+    /// URL prefix for file links in the markdown report, e.g. a blob URL
+    /// at the PR's commit. Falls back to the GitHub Actions environment.
+    pub link_base: Option<String>,
+    /// Stop after this many error-level findings (`--fail-fast` is 1).
+    pub max_failures: Option<u32>,
+    /// Cap applied to every finding's configured severity.
+    pub max_severity: Option<Severity>,
+    /// File to write the JSON report to, relative to the project root.
+    pub output: Option<PathBuf>,
+}
+
+/// Per-run rules for turning a finding into a [`Verdict`].
+struct CheckPolicy {
+    /// Known findings loaded from `--baseline`, empty if none was given.
+    baseline: Baseline,
+    /// Stop once this many error-level findings have been seen.
+    max_failures: Option<u32>,
+    /// Highest severity any finding may have this run.
+    max_severity: Severity,
+}
+
+impl CheckPolicy {
+    /// Whether `failures` error-level findings reach the `--max-failures` limit.
+    fn limit_reached(&self, failures: u32) -> bool {
+        return self.max_failures.is_some_and(|max| return failures >= max);
+    }
+
+    /// Decide how `finding` on `entry` counts toward the check result. A
+    /// stale finding younger than `stale_grace_days` is at most a warning.
+    fn verdict(&self, config: &config::Config, entry: &LockEntry, finding: Finding, stale_days: Option<u64>) -> Verdict {
+        let in_grace =
+            config.severity.stale_grace_days.zip(stale_days).is_some_and(|(grace, days)| return days <= grace);
+        let cap = if in_grace { self.max_severity.capped_at(Severity::Warning) } else { self.max_severity };
+        let severity = config.severity.for_finding(finding, &entry.source).capped_at(cap);
+        if severity == Severity::Ignore {
+            return Verdict::Ignored;
+        }
+        if self.baseline.contains(entry, finding) {
+            return Verdict::Baselined;
+        }
+        return match severity {
+            Severity::Error => Verdict::Error,
+            Severity::Ignore => Verdict::Ignored,
+            Severity::Warning => Verdict::Warning,
+        };
+    }
+}
+
+/// Entries evaluated by one `check` run, before rendering.
+struct CheckRun<'a> {
+    /// Number of lockfile entries examined; less than the total when stopped early.
+    checked: usize,
+    /// Every reported entry in lockfile order, fresh ones included.
+    entries: Vec<EvaluatedEntry<'a>>,
+}
+
+impl CheckRun<'_> {
+    /// Count error-level findings as (non-stale, stale).
+    fn error_counts(&self) -> (u32, u32) {
+        let (stale, broken): (Vec<_>, Vec<_>) = self
+            .entries
+            .iter()
+            .filter(|e| return e.verdict == Some(Verdict::Error))
+            .partition(|e| return matches!(e.result, CheckResult::Stale));
+        let count = |v: Vec<_>| return u32::try_from(v.len()).unwrap_or(u32::MAX);
+        return (count(broken), count(stale));
+    }
+
+    /// Exit code: 2 if any error-level finding other than staleness,
+    /// 1 if only error-level stale findings, otherwise success.
+    fn exit_code(&self) -> ExitCode {
+        return ExitCode::from(self.exit_status());
+    }
+
+    /// The numeric value of [`Self::exit_code`].
+    fn exit_status(&self) -> u8 {
+        let (broken, stale) = self.error_counts();
+        if broken > 0 {
+            return 2;
+        } else if stale > 0 {
+            return 1;
+        }
+        return 0;
+    }
 }
 
 /// Summary counts for the check command JSON output.
-#[derive(Serialize)]
+#[derive(Default, Serialize)]
 struct CheckSummaryJson {
+    /// Number of ambiguous references.
+    ambiguous: u32,
+    /// Number of findings suppressed by the baseline.
+    baselined: u32,
     /// Number of broken references.
     broken: u32,
     /// Number of fresh references.
     fresh: u32,
+    /// Number of orphaned entries.
+    orphaned: u32,
     /// Number of stale references.
     stale: u32,
+    /// Number of reported findings that were only warnings.
+    warnings: u32,
+}
+
+impl CheckSummaryJson {
+    /// Count one evaluated entry by status and verdict.
+    const fn record(&mut self, evaluated: &EvaluatedEntry<'_>) {
+        let counter = match evaluated.result {
+            CheckResult::Ambiguous => &mut self.ambiguous,
+            CheckResult::Broken(_) => &mut self.broken,
+            CheckResult::Fresh => &mut self.fresh,
+            CheckResult::Orphaned => &mut self.orphaned,
+            CheckResult::Stale => &mut self.stale,
+        };
+        *counter = counter.saturating_add(1);
+        match evaluated.verdict {
+            Some(Verdict::Baselined) => self.baselined = self.baselined.saturating_add(1),
+            Some(Verdict::Warning) => self.warnings = self.warnings.saturating_add(1),
+            Some(Verdict::Error | Verdict::Ignored) | None => {},
+        }
+        return;
+    }
 }
 
-/// A pending rewrite: replace a symbol fragment in a markdown file.
+/// Where a check run stopped after reaching its failure limit.
+#[derive(Serialize)]
+struct CheckTruncatedJson {
+    /// Number of lockfile entries examined before stopping.
+    checked: usize,
+    /// The failure limit that was reached.
+    max_failures: u32,
+    /// Total number of lockfile entries.
+    total: usize,
+}
+
+/// A lockfile entry paired with its check result and verdict.
+struct EvaluatedEntry<'a> {
+    /// The lockfile entry that was checked.
+    entry: &'a LockEntry,
+    /// How a stale wildcard's members changed since it was locked.
+    members: Option<MemberChanges>,
+    /// Where a stale line range's unchanged lines are now, e.g. `L42-L77`.
+    moved_to: Option<String>,
+    /// Freshness of the entry against the current source.
+    result: CheckResult,
+    /// Whole days a stale entry has been stale, when `stale_grace_days` is set.
+    stale_days: Option<u64>,
+    /// How the finding counts, or `None` when fresh.
+    verdict: Option<Verdict>,
+}
+
+impl EvaluatedEntry<'_> {
+    /// Convert to the JSON shape used by `check --format json`.
+    fn to_json(&self) -> CheckEntryJson {
+        let (members_added, members_removed) =
+            self.members.as_ref().map(|m| return (m.added.clone(), m.removed.clone())).unwrap_or_default();
+        return CheckEntryJson {
+            members_added,
+            members_removed,
+            moved_to: self.moved_to.clone(),
+            reason: self.result.reason().map(String::from),
+            severity: self.verdict.map(|v| return v.name().to_string()),
+            source: self.entry.source.clone(),
+            stale_days: self.stale_days,
+            status: self.result.name().to_string(),
+            symbol: self.entry.symbol.clone(),
+            target: self.entry.target.clone(),
+        };
+    }
+}
+
+/// A pending rewrite of part of a link in a markdown file: its `#fragment`,
+/// or its path when the symbol moved to another file.
 struct FixAction {
+    /// Byte range of the rewritten text within the line.
+    columns: Range<usize>,
     /// The markdown file to rewrite.
     file: PathBuf,
-    /// The 1-based line number where the symbol appears.
+    /// The 1-based line number where the link appears.
     line: u32,
-    /// The symbol name to replace in the reference.
-    new_symbol: String,
-    /// The original broken symbol name.
-    old_symbol: String,
+    /// The replacement text, e.g. `#new_name` or `../src/other.rs`.
+    new_text: String,
+    /// The text the span must still hold for the rewrite to apply.
+    old_text: String,
+}
+
+/// Project state `fix` consults beyond the broken reference itself.
+struct FixContext<'a> {
+    /// Loaded configuration, for resolving and re-forming namespaced paths.
+    config: &'a config::Config,
+    /// The lockfile, whose hashes identify a symbol that moved. `None` before `init`.
+    lockfile: Option<Lockfile>,
+    /// Project root.
+    root: &'a Path,
+    /// Source files under the root with a grammar, walked on first use.
+    sources: OnceCell<Vec<PathBuf>>,
+}
+
+impl FixContext<'_> {
+    /// Root-relative paths of every parseable file in the project, skipping
+    /// hidden directories.
+    fn sources(&self) -> &[PathBuf] {
+        return self.sources.get_or_init(|| {
+            return walkdir::WalkDir::new(self.root)
+                .into_iter()
+                .filter_entry(|e| return e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+                .filter_map(Result::ok)
+                .filter(|e| return !e.file_type().is_dir())
+                .filter_map(|e| return e.path().strip_prefix(self.root).ok().map(Path::to_path_buf))
+                .filter(|path| return grammar::language_for_path(path, &self.config.grammars).is_ok())
+                .collect();
+        });
+    }
+}
+
+/// Options for `fix` and targeted fixes.
+#[derive(Default)]
+pub struct FixOptions {
+    /// Print a unified diff of each markdown file's edits to stdout.
+    pub diff: bool,
+    /// Leave markdown untouched and only report what would change.
+    pub dry_run: bool,
 }
 
 /// Output format for commands that support structured output.
-enum OutputFormat {
+pub(crate) enum OutputFormat {
     /// JSON output for machine consumption.
     Json,
+    /// A JUnit XML report for CI test result views (`check` only).
+    Junit,
+    /// A markdown document to post as a pull request comment (`check` only).
+    Markdown,
+    /// A SARIF 2.1.0 log for code scanning tools (`check` only).
+    Sarif,
     /// Human-readable text (default).
     Text,
 }
 
+/// JSON output for a single `refs` match.
+#[derive(Serialize)]
+struct RefEntryJson {
+    /// The markdown file containing the reference.
+    source: PathBuf,
+    /// The symbol name (empty for whole-file refs).
+    symbol: String,
+    /// The target source file.
+    target: PathBuf,
+}
+
+/// JSON output for the refs command.
+#[derive(Serialize)]
+struct RefsJson {
+    /// Lockfile entries matching the query.
+    entries: Vec<RefEntryJson>,
+}
+
+/// Top-level metadata block shared by JSON reports.
+#[derive(Serialize)]
+struct ReportMetadataJson {
+    /// Absolute project root the report was produced for.
+    root: PathBuf,
+    /// When the report was produced, in RFC 3339 UTC.
+    timestamp: String,
+    /// The docref version that produced the report.
+    version: &'static str,
+}
+
+impl ReportMetadataJson {
+    /// Metadata for a report produced now against `root`.
+    fn current(root: &Path) -> Self {
+        return Self {
+            root: std::path::absolute(root).unwrap_or_else(|_| return root.to_path_buf()),
+            timestamp: humantime::format_rfc3339_seconds(std::time::SystemTime::now()).to_string(),
+            version: env!("CARGO_PKG_VERSION"),
+        };
+    }
+}
+
+/// Per-status tallies for a set of lockfile entries.
+#[derive(Default, Serialize)]
+struct StatusCountsJson {
+    /// Number of ambiguous references.
+    ambiguous: u32,
+    /// Number of broken references.
+    broken: u32,
+    /// Number of fresh references.
+    fresh: u32,
+    /// Number of orphaned entries.
+    orphaned: u32,
+    /// Whole-number percentage of entries that are fresh.
+    percent_fresh: u32,
+    /// Number of stale references.
+    stale: u32,
+    /// Number of entries counted.
+    total: u32,
+}
+
+impl StatusCountsJson {
+    /// Add another set of tallies to this one and refresh the derived totals.
+    fn add(&mut self, other: &Self) {
+        self.ambiguous = self.ambiguous.saturating_add(other.ambiguous);
+        self.broken = self.broken.saturating_add(other.broken);
+        self.fresh = self.fresh.saturating_add(other.fresh);
+        self.orphaned = self.orphaned.saturating_add(other.orphaned);
+        self.stale = self.stale.saturating_add(other.stale);
+        self.total = self.total.saturating_add(other.total);
+        self.percent_fresh = percent_of(self.fresh, self.total);
+        return;
+    }
+
+    /// Count one check result and refresh the derived totals.
+    fn record(&mut self, result: &CheckResult) {
+        let counter = match *result {
+            CheckResult::Ambiguous => &mut self.ambiguous,
+            CheckResult::Broken(_) => &mut self.broken,
+            CheckResult::Fresh => &mut self.fresh,
+            CheckResult::Orphaned => &mut self.orphaned,
+            CheckResult::Stale => &mut self.stale,
+        };
+        *counter = counter.saturating_add(1);
+        self.total = self.total.saturating_add(1);
+        self.percent_fresh = percent_of(self.fresh, self.total);
+        return;
+    }
+}
+
 /// JSON output for a single status entry.
 #[derive(Serialize)]
 struct StatusEntryJson {
+    /// Who accepted the stored hash, when, and against which commit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accepted: Option<Acceptance>,
+    /// Owners of the target and markdown file, from `CODEOWNERS`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    code_owners: Vec<String>,
     /// The stored hash.
     hash: String,
+    /// Note recorded on the lockfile entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    /// Owner recorded on the lockfile entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
     /// Optional reason for broken status.
     #[serde(skip_serializing_if = "Option::is_none")]
     reason: Option<String>,
     /// The markdown file containing the reference.
     source: PathBuf,
+    /// Whole days a stale entry has been stale, judged from git history.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_days: Option<u64>,
+    /// When a stale entry went stale, in RFC 3339 UTC.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_since: Option<String>,
     /// Freshness status.
     status: String,
     /// The symbol name.
     symbol: String,
+    /// Tags recorded on the lockfile entry.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
     /// The target source file.
     target: PathBuf,
 }
 
+/// Dimension for the extra status summary breakdown chosen with `--by`.
+#[derive(Debug, Clone, Copy)]
+pub enum StatusGrouping {
+    /// Language of the target file, from grammar detection.
+    Language,
+    /// Namespace prefix of the target, if any.
+    Namespace,
+    /// Owners from `CODEOWNERS` of the target and the markdown file; an
+    /// entry counts toward each of its owners.
+    Owner,
+    /// Team owning the target path, from `[teams]` in the config.
+    Team,
+}
+
+impl StatusGrouping {
+    /// The groups a lockfile entry falls into: always one, except by owner.
+    fn keys(self, config: &config::Config, entry: &LockEntry, code_owners: &[String]) -> Vec<String> {
+        return match self {
+            Self::Language => vec![grammar::language_name(&entry.target).unwrap_or("(plain text)").to_string()],
+            Self::Namespace => {
+                let target = entry.target.to_string_lossy();
+                vec![target.split_once(':').map_or("(none)", |(namespace, _)| return namespace).to_string()]
+            },
+            Self::Owner if code_owners.is_empty() => vec!["(unowned)".to_string()],
+            Self::Owner => code_owners.to_vec(),
+            Self::Team => {
+                let path = config.resolve_target(&entry.target).unwrap_or_else(|_| return entry.target.clone());
+                vec![config.team_for(&path).unwrap_or("(unowned)").to_string()]
+            },
+        };
+    }
+
+    /// Lowercase name used in output headings and JSON.
+    const fn name(self) -> &'static str {
+        return match self {
+            Self::Language => "language",
+            Self::Namespace => "namespace",
+            Self::Owner => "owner",
+            Self::Team => "team",
+        };
+    }
+}
+
+impl std::str::FromStr for StatusGrouping {
+    type Err = String;
+
+    /// Parse `language`, `namespace`, `owner`, or `team`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "language" => Ok(Self::Language),
+            "namespace" => Ok(Self::Namespace),
+            "owner" => Ok(Self::Owner),
+            "team" => Ok(Self::Team),
+            _ => Err(format!("unknown grouping `{s}` (expected language, namespace, owner, or team)")),
+        };
+    }
+}
+
 /// JSON output for the status command.
 #[derive(Serialize)]
-struct StatusJson {
-    /// All tracked entries with their statuses and hashes.
-    entries: Vec<StatusEntryJson>,
+struct StatusJson<'a> {
+    /// All tracked entries with their statuses and hashes, omitted with `--summary-only`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entries: Option<Vec<StatusEntryJson>>,
+    /// Which docref produced the report, when, and for which project.
+    metadata: ReportMetadataJson,
+    /// Totals across the lockfile and per markdown source.
+    summary: &'a StatusSummaryJson,
+}
+
+/// Options for `status`: extra grouping, entry filters, and report output.
+#[derive(Default)]
+pub struct StatusOptions {
+    /// Find each stale entry's age in git history even when
+    /// `stale_grace_days` isn't set.
+    pub ages: bool,
+    /// Dimension for the extra summary breakdown, if any.
+    pub by: Option<StatusGrouping>,
+    /// File to write the JSON report to, relative to the project root.
+    pub output: Option<PathBuf>,
+    /// Only include entries with this owner.
+    pub owner: Option<String>,
+    /// Print only the summary, without per-reference lines.
+    pub summary_only: bool,
+    /// Only include entries carrying this tag.
+    pub tag: Option<String>,
+}
+
+impl StatusOptions {
+    /// Whether a lockfile entry passes the owner and tag filters.
+    fn includes(&self, entry: &LockEntry) -> bool {
+        let owner_matches = self.owner.as_ref().is_none_or(|owner| return entry.owner.as_ref() == Some(owner));
+        let tag_matches = self.tag.as_ref().is_none_or(|tag| return entry.tags.contains(tag));
+        return owner_matches && tag_matches;
+    }
+}
+
+/// One `status` line: a lockfile entry with its result and attribution.
+struct StatusRow<'a> {
+    /// Owners of the target and markdown file, from `CODEOWNERS`.
+    code_owners: Vec<String>,
+    /// The lockfile entry that was checked.
+    entry: &'a LockEntry,
+    /// Freshness of the entry against the current source.
+    result: CheckResult,
+    /// When a stale entry went stale, if git history can tell.
+    stale_since: Option<SystemTime>,
+}
+
+impl StatusRow<'_> {
+    /// Text appended to a non-fresh line: how long it's been stale and who owns it.
+    fn annotations(&self) -> String {
+        let mut notes = String::new();
+        if let Some(since) = self.stale_since {
+            let _ = write!(notes, " (stale {} days)", history::days_since(since));
+        }
+        if !matches!(self.result, CheckResult::Fresh) && !self.code_owners.is_empty() {
+            let _ = write!(notes, "  owners: {}", self.code_owners.join(" "));
+        }
+        return notes;
+    }
+
+    /// Convert to the JSON shape used by `status --format json`.
+    fn to_json(&self) -> StatusEntryJson {
+        let entry = self.entry;
+        return StatusEntryJson {
+            accepted: entry.accepted.clone(),
+            code_owners: self.code_owners.clone(),
+            hash: entry.hash.0.clone(),
+            note: entry.note.clone(),
+            owner: entry.owner.clone(),
+            reason: self.result.reason().map(String::from),
+            source: entry.source.clone(),
+            stale_days: self.stale_since.map(history::days_since),
+            stale_since: self.stale_since.map(|t| return humantime::format_rfc3339_seconds(t).to_string()),
+            status: self.result.name().to_string(),
+            symbol: entry.symbol.clone(),
+            tags: entry.tags.clone(),
+            target: entry.target.clone(),
+        };
+    }
+}
+
+/// Status tallies for one markdown source file.
+#[derive(Serialize)]
+struct StatusSourceJson {
+    /// Counts for references declared in this file.
+    #[serde(flatten)]
+    counts: StatusCountsJson,
+    /// The markdown file containing the references.
+    source: PathBuf,
+}
+
+/// Lockfile-wide status totals with a per-source rollup, plus an optional
+/// breakdown chosen with `--by`.
+#[derive(Default, Serialize)]
+struct StatusSummaryJson {
+    /// Counts across every entry.
+    #[serde(flatten)]
+    counts: StatusCountsJson,
+    /// The `--by` dimension that `groups` breaks down, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grouped_by: Option<&'static str>,
+    /// Per-group counts keyed by language, namespace, owner, or team name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    groups: BTreeMap<String, StatusCountsJson>,
+    /// Per-source counts, sorted by path.
+    sources: Vec<StatusSourceJson>,
+}
+
+impl StatusSummaryJson {
+    /// Count one entry's result overall, under its markdown source, and
+    /// under each of `groups` when a breakdown was requested.
+    fn record(&mut self, entry: &LockEntry, groups: Vec<String>, result: &CheckResult) {
+        self.counts.record(result);
+        for group in groups {
+            self.groups.entry(group).or_default().record(result);
+        }
+        // Lockfile entries are sorted by source, so a new source is always last.
+        let is_new_source = self.sources.last().is_none_or(|s| return s.source != entry.source);
+        if is_new_source {
+            self.sources.push(StatusSourceJson {
+                counts: StatusCountsJson::default(),
+                source: entry.source.clone(),
+            });
+        }
+        if let Some(last) = self.sources.last_mut() {
+            last.counts.record(result);
+        }
+        return;
+    }
+}
+
+/// How one non-fresh entry counts toward the check result.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    /// Recorded in the baseline; reported but never fails the check.
+    Baselined,
+    /// Reported and fails the check.
+    Error,
+    /// Configured as `ignore`; neither reported nor counted.
+    Ignored,
+    /// Reported without failing the check.
+    Warning,
+}
+
+impl Verdict {
+    /// Lowercase name used in JSON output.
+    const fn name(self) -> &'static str {
+        return match self {
+            Self::Baselined => "baseline",
+            Self::Error => "error",
+            Self::Ignored => "ignore",
+            Self::Warning => "warning",
+        };
+    }
+}
+
+/// JSON a `watch --exec` hook receives on stdin after each re-check.
+#[derive(Serialize)]
+struct WatchHookJson {
+    /// The exit code `check` would return.
+    exit_code: u8,
+    /// Every reported finding; fresh entries are left out.
+    findings: Vec<CheckEntryJson>,
+    /// Summary counts.
+    summary: CheckSummaryJson,
+}
+
+/// JSON output for one member project in `workspace status`.
+#[derive(Serialize)]
+struct WorkspaceProjectJson {
+    /// Counts for the project's references; absent if it couldn't be checked.
+    #[serde(flatten)]
+    counts: Option<StatusCountsJson>,
+    /// Why the project couldn't be checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// The exit code `docref check` would give for this project alone.
+    exit_code: u8,
+    /// Project directory, relative to the workspace root.
+    path: PathBuf,
+}
+
+impl WorkspaceProjectJson {
+    /// A project that couldn't be checked, failing the workspace with exit 2.
+    fn failed(member: &Path, error: String) -> Self {
+        return Self { counts: None, error: Some(error), exit_code: 2, path: workspace_member_path(member) };
+    }
+}
+
+/// JSON output for `workspace status`.
+#[derive(Serialize)]
+struct WorkspaceStatusJson {
+    /// Which docref produced the report, when, and for which workspace.
+    metadata: ReportMetadataJson,
+    /// Member projects in discovery order.
+    projects: Vec<WorkspaceProjectJson>,
+    /// Counts across every project that could be checked.
+    summary: StatusCountsJson,
+}
+
+/// Re-hash every lockfile entry for `file#symbol` against the current code,
+/// recording `note` and, if configured, acceptance provenance. Absence
+/// assertions are skipped. The caller writes the lockfile.
+///
+/// # Errors
+///
+/// Returns `Error::SymbolNotFound` if no entry matches, or errors from
+/// resolution and hashing.
+pub fn accept_reference(
+    root: &Path,
+    config: &config::Config,
+    lockfile: &mut Lockfile,
+    file: &Path,
+    symbol: &str,
+    note: Option<&str>,
+) -> Result<(), error::Error> {
+    let disk_path = package::source_path(root, config.resolve_target(file)?);
+    let source = package::read_source(&root.join(&disk_path))
+        .map_err(|_err| return error::Error::FileNotFound { path: disk_path.clone() })?;
+
+    let query = parse_symbol_query(symbol);
+    let mut updated = false;
+    for entry in &mut lockfile.entries {
+        if entry.target == file && entry.symbol == symbol && !entry.absent {
+            entry.hash = hash_query_in_source(root, &disk_path, &source, &query, entry.mode, config)?;
+            entry.note = note.map(String::from).or_else(|| return entry.note.take());
+            updated = true;
+        }
+    }
+
+    if !updated {
+        return Err(error::Error::SymbolNotFound {
+            file: file.to_path_buf(),
+            referenced_from: vec![],
+            suggestions: vec![],
+            symbol: symbol.to_string(),
+        });
+    }
+    let accepted = lockfile
+        .entries
+        .iter_mut()
+        .filter(|e| return e.target == file && e.symbol == symbol && !e.absent);
+    record_acceptance(root, config, accepted);
+    return Ok(());
 }
 
 /// Apply fix actions by rewriting markdown files.
@@ -106,148 +760,266 @@ struct StatusJson {
 /// # Errors
 ///
 /// Returns `Error::Io` if any markdown file cannot be read or written.
-fn apply_fixes(fixes: &[FixAction]) -> Result<(), error::Error> {
-    // Group fixes by file so each file is read/written once.
-    let mut by_file: HashMap<PathBuf, Vec<&FixAction>> = HashMap::new();
+fn apply_fixes(root: &Path, fixes: &[FixAction], options: &FixOptions) -> Result<(), error::Error> {
+    // Group fixes by file so each file is read/written once, in a stable order for diffs.
+    let mut by_file: BTreeMap<PathBuf, Vec<&FixAction>> = BTreeMap::new();
     for fix in fixes {
         by_file.entry(fix.file.clone()).or_default().push(fix);
     }
 
-    for (path, file_fixes) in &by_file {
-        let content = std::fs::read_to_string(path)?;
+    for (file, file_fixes) in &by_file {
+        let path = root.join(file);
+        let content = std::fs::read_to_string(&path)?;
         let mut lines: Vec<String> = content.lines().map(String::from).collect();
 
+        // Rewrite right to left so earlier spans on a line stay valid.
+        let mut file_fixes = file_fixes.clone();
+        file_fixes.sort_by_key(|fix| return std::cmp::Reverse((fix.line, fix.columns.start)));
         for fix in file_fixes {
-            rewrite_symbol_on_line(&mut lines, fix);
+            rewrite_span_on_line(&mut lines, fix);
         }
 
         let mut output = lines.join("\n");
         if content.ends_with('\n') {
             output.push('\n');
         }
-        std::fs::write(path, output)?;
+        if options.diff {
+            print_unified_diff(file, &content, &output);
+        }
+        if !options.dry_run {
+            std::fs::write(&path, output)?;
+        }
     }
 
     return Ok(());
 }
 
+/// Write a baseline of every current finding so `check --baseline` tolerates them.
+///
+/// Findings are recorded regardless of their configured severity, so later
+/// `[severity]` changes don't resurface debt that was already accepted.
+///
+/// # Errors
+///
+/// Returns errors from lockfile reading, hash computation, or writing the baseline.
+pub fn baseline_write(root: &Path, path: &Path) -> Result<(), error::Error> {
+    let config = config::Config::load(root)?;
+    let lockfile = Lockfile::read(&root.join(".docref.lock"))?;
+
+    let tracked: Vec<&LockEntry> = lockfile.entries.iter().collect();
+    let results = compare_lockfile_entries_against_source(root, &config, &tracked)?;
+    let mut entries: Vec<BaselineEntry> = Vec::new();
+    for (entry, result) in tracked.into_iter().zip(results) {
+        let Some(finding) = result.finding() else {
+            continue;
+        };
+        entries.push(BaselineEntry {
+            finding,
+            source: entry.source.clone(),
+            symbol: entry.symbol.clone(),
+            target: entry.target.clone(),
+        });
+    }
+
+    let baseline = Baseline::new(entries);
+    baseline.write(&root.join(path))?;
+    eprintln!("Wrote {} known findings to {}", baseline.entries.len(), path.display());
+    return Ok(());
+}
+
 /// Read lockfile, re-resolve and re-hash each entry, compare.
 ///
+/// Each finding is weighed by the `[severity]` config, then capped at
+/// `--max-severity` if given. Findings recorded in the baseline are still
+/// listed but never fail the check. Only error-level findings affect the
+/// exit code, and `max_failures` stops the run once that many are found.
+///
 /// # Errors
 ///
-/// Returns errors from lockfile reading or hash computation.
-pub fn check(format: &str) -> Result<ExitCode, error::Error> {
-    let output_format = parse_output_format(format)?;
-    let root = PathBuf::from(".");
+/// Returns errors from lockfile or baseline reading, or hash computation.
+pub fn check(root: &Path, format: &str, options: &CheckOptions) -> Result<ExitCode, error::Error> {
+    let output_format = parse_check_format(format)?;
     let lock_path = root.join(".docref.lock");
-    let config = config::Config::load(&root)?;
+    let config = config::Config::load(root)?;
     let lockfile = Lockfile::read(&lock_path)?;
-
-    return match output_format {
-        OutputFormat::Json => check_json(&root, &config, &lockfile),
-        OutputFormat::Text => check_text(&root, &config, &lockfile),
+    let baseline_path = options.baseline.as_ref().map(|p| return root.join(p));
+    let baseline = baseline_path.as_deref().map(Baseline::read).transpose()?.unwrap_or_default();
+    let policy = CheckPolicy {
+        baseline,
+        max_failures: options.max_failures,
+        max_severity: options.max_severity.unwrap_or(Severity::Error),
     };
+    let run = evaluate_check(root, &config, &lockfile, &policy)?;
+    let total = lockfile.entries.len();
+
+    // With `--output`, the JSON report goes to the file and stdout keeps only text output.
+    let report_path = options.output.as_ref().map(|p| return root.join(p));
+    if matches!(output_format, OutputFormat::Text) {
+        check_text(&run, total);
+    }
+    if matches!(output_format, OutputFormat::Markdown) {
+        let link_base = options.link_base.clone().or_else(github_blob_url);
+        print!("{}", check_markdown(&run, total, link_base.as_deref()));
+    }
+    if matches!(output_format, OutputFormat::Junit) {
+        print!("{}", check_junit(root, &config, &run));
+    }
+    if matches!(output_format, OutputFormat::Sarif) {
+        println!("{}", check_sarif(root, &config, &run));
+    }
+    if matches!(output_format, OutputFormat::Json) || report_path.is_some() {
+        check_json(root, &run, total, policy.max_failures, report_path.as_deref())?;
+    }
+    return Ok(run.exit_code());
 }
 
-/// Produce JSON check output and determine exit code.
+/// Report a `watch` re-check of the `affected` entries after files changed.
+///
+/// Text output has a line for each affected entry, including ones that are
+/// fresh again, then the summary across the whole lockfile. JSON output is
+/// the full check report. Other check formats are rejected.
+///
+/// `results` holds the latest result for every lockfile entry, in order.
 ///
 /// # Errors
 ///
-/// Returns errors from hash computation.
-fn check_json(
-    root: &std::path::Path,
+/// Returns errors from an unknown format or reading git history.
+pub fn check_changed(
+    root: &Path,
     config: &config::Config,
     lockfile: &Lockfile,
+    results: &[CheckResult],
+    affected: &[&LockEntry],
+    format: &str,
 ) -> Result<ExitCode, error::Error> {
-    let mut entries: Vec<CheckEntryJson> = Vec::new();
-    let mut summary = CheckSummaryJson { broken: 0, fresh: 0, stale: 0 };
-
-    for entry in &lockfile.entries {
-        let (status, reason) = match compare_lockfile_entry_against_source(root, config, entry)? {
-            CheckResult::Broken(r) => {
-                summary.broken = summary.broken.saturating_add(1);
-                ("broken", Some(r.to_string()))
-            },
-            CheckResult::Fresh => {
-                summary.fresh = summary.fresh.saturating_add(1);
-                ("fresh", None)
-            },
-            CheckResult::Stale => {
-                summary.stale = summary.stale.saturating_add(1);
-                ("stale", None)
-            },
-        };
-        entries.push(CheckEntryJson {
-            reason,
-            source: entry.source.clone(),
-            status: status.to_string(),
-            symbol: entry.symbol.clone(),
-            target: entry.target.clone(),
-        });
+    let output_format = parse_output_format(format)?;
+    let policy = CheckPolicy { baseline: Baseline::default(), max_failures: None, max_severity: Severity::Error };
+    let run = evaluate_results(root, config, lockfile, results, &policy)?;
+    let total = lockfile.entries.len();
+    if matches!(output_format, OutputFormat::Json) {
+        check_json(root, &run, total, None, None)?;
+        return Ok(run.exit_code());
     }
+    let changed = run.entries.iter().filter(|e| return affected.iter().any(|&a| return std::ptr::eq(a, e.entry)));
+    for evaluated in changed {
+        if matches!(evaluated.result, CheckResult::Fresh) {
+            println!("FRESH   {}", format_ref(&evaluated.entry.target, &evaluated.entry.symbol));
+        }
+        print_check_line(evaluated);
+    }
+    print_check_summary(&run, total);
+    return Ok(run.exit_code());
+}
 
-    let broken = summary.broken;
-    let stale = summary.stale;
-    let output = CheckJson { entries, summary };
-    println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+/// Emit the JSON check report, including a truncation note if the run stopped early.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if the report file can't be written.
+fn check_json(
+    root: &Path,
+    run: &CheckRun<'_>,
+    total: usize,
+    max_failures: Option<u32>,
+    path: Option<&Path>,
+) -> Result<(), error::Error> {
+    return write_json(&check_report(root, run, total, max_failures), path);
+}
+
+/// Render every checked entry as a JUnit test case. Error-level findings
+/// fail; warnings and baselined findings pass with a note.
+fn check_junit(root: &Path, config: &config::Config, run: &CheckRun<'_>) -> String {
+    let references = scan_finding_sources(root, config, run);
+    let cases: Vec<junit::Case> = run.entries.iter().map(|e| return junit_case(e, &references)).collect();
+    return junit::render(&cases);
+}
 
-    if broken > 0 {
-        return Ok(ExitCode::from(2));
-    } else if stale > 0 {
-        return Ok(ExitCode::from(1));
+/// Render the check report as markdown to post verbatim as a pull request
+/// comment: a headline, then tables of findings in a collapsed section.
+fn check_markdown(run: &CheckRun<'_>, total: usize, link_base: Option<&str>) -> String {
+    let (stale, other): (Vec<&EvaluatedEntry<'_>>, Vec<&EvaluatedEntry<'_>>) = run
+        .entries
+        .iter()
+        .filter(|e| return e.verdict.is_some_and(|v| return v != Verdict::Ignored))
+        .partition(|e| return matches!(e.result, CheckResult::Stale));
+    let (broken_count, stale_count) = run.error_counts();
+    let found = stale.len().saturating_add(other.len());
+    let mut out = match (broken_count, stale_count, found) {
+        (0, 0, 0) => format!("### docref: all {total} references fresh\n"),
+        (0, 0, _) => format!("### docref: no errors, {found} warnings or baselined findings\n"),
+        _ => format!("### docref: {broken_count} broken, {stale_count} stale\n"),
+    };
+    if found == 0 {
+        return out;
+    }
+    let _ = writeln!(out, "\n<details>\n<summary>{found} of {total} references need attention</summary>");
+    if !stale.is_empty() {
+        markdown_stale_section(&mut out, &stale, link_base);
+    }
+    if !other.is_empty() {
+        markdown_broken_section(&mut out, &other, link_base);
+    }
+    if run.checked < total {
+        let _ = writeln!(out, "\nStopped at the failure limit after checking {} of {total} references.", run.checked);
+    }
+    out.push_str("\n</details>\n");
+    return out;
+}
+
+/// The JSON check report, including a truncation note if the run stopped early.
+fn check_report(root: &Path, run: &CheckRun<'_>, total: usize, max_failures: Option<u32>) -> CheckJson {
+    let mut summary = CheckSummaryJson::default();
+    for evaluated in &run.entries {
+        summary.record(evaluated);
     }
-    return Ok(ExitCode::SUCCESS);
+    let truncated = max_failures.filter(|_| return run.checked < total).map(|max_failures| {
+        return CheckTruncatedJson { checked: run.checked, max_failures, total };
+    });
+    return CheckJson {
+        entries: run.entries.iter().map(EvaluatedEntry::to_json).collect(),
+        metadata: ReportMetadataJson::current(root),
+        summary,
+        truncated,
+    };
 }
 
-/// Produce human-readable text check output and determine exit code.
+/// The report `check --format json` prints for the project at `root`, as a
+/// string.
 ///
 /// # Errors
 ///
-/// Returns errors from hash computation.
-fn check_text(
-    root: &std::path::Path,
-    config: &config::Config,
-    lockfile: &Lockfile,
-) -> Result<ExitCode, error::Error> {
-    let mut stale_refs: Vec<String> = Vec::new();
-    let mut broken_count = 0_u32;
+/// Returns errors from config or lockfile reading, or hash computation.
+pub fn check_report_json(root: &Path) -> Result<String, error::Error> {
+    let config = config::Config::load(root)?;
+    let lockfile = Lockfile::read(&root.join(".docref.lock"))?;
+    let policy = CheckPolicy { baseline: Baseline::default(), max_failures: None, max_severity: Severity::Error };
+    let run = evaluate_check(root, &config, &lockfile, &policy)?;
+    let report = check_report(root, &run, lockfile.entries.len(), None);
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::from)?;
+    return Ok(json);
+}
 
-    for entry in &lockfile.entries {
-        let refstr = format_ref(&entry.target, &entry.symbol);
-        match compare_lockfile_entry_against_source(root, config, entry)? {
-            CheckResult::Broken(reason) => {
-                broken_count = broken_count.saturating_add(1);
-                println!("BROKEN  {refstr} ({reason})");
-            },
-            CheckResult::Fresh => {},
-            CheckResult::Stale => {
-                println!("STALE   {refstr}");
-                stale_refs.push(refstr);
-            },
-        }
-    }
+/// Render the reported findings as a SARIF log, placing each on the markdown
+/// line that holds its link by re-scanning the files with findings.
+fn check_sarif(root: &Path, config: &config::Config, run: &CheckRun<'_>) -> String {
+    let references = scan_finding_sources(root, config, run);
+    let findings: Vec<sarif::Finding<'_>> =
+        run.entries.iter().filter_map(|e| return sarif_finding(e, &references)).collect();
+    return sarif::render(&findings);
+}
 
-    let stale_count: u32 = stale_refs.len().try_into().unwrap_or(u32::MAX);
-    if broken_count > 0 {
-        eprintln!();
-        eprintln!("{broken_count} broken, {stale_count} stale");
-        return Ok(ExitCode::from(2));
-    } else if !stale_refs.is_empty() {
-        eprintln!();
-        eprintln!("# Stale References");
-        eprintln!();
-        eprintln!("{stale_count} references have changed since the docs were written:");
-        eprintln!();
-        for r in &stale_refs {
-            eprintln!("- `{r}`");
-        }
+/// Print human-readable check output: one line per finding, then a summary.
+fn check_text(run: &CheckRun<'_>, total: usize) {
+    for evaluated in &run.entries {
+        print_check_line(evaluated);
+    }
+    let checked = run.checked;
+    if checked < total {
         eprintln!();
-        print_update_hints(&stale_refs);
-        return Ok(ExitCode::from(1));
+        eprintln!("Stopped after reaching the failure limit: checked {checked} of {total} references.");
     }
-    let total = lockfile.entries.len();
-    eprintln!("All {total} references fresh");
-    return Ok(ExitCode::SUCCESS);
+    print_check_summary(run, total);
+    return;
 }
 
 /// Sort a broken reference into fixable (close match found) or unfixable.
@@ -263,12 +1035,7 @@ fn classify_broken_ref(
         None => unfixable.push(format!("{location}  #{symbol}")),
         Some(suggestion) => {
             eprintln!("fix: {location}  #{symbol} -> #{suggestion}");
-            fixes.push(FixAction {
-                file: reference.source.clone(),
-                line: reference.source_line,
-                new_symbol: suggestion,
-                old_symbol: symbol.to_string(),
-            });
+            fixes.push(fragment_fix(reference, symbol, &suggestion));
         },
     }
     return;
@@ -276,36 +1043,57 @@ fn classify_broken_ref(
 
 /// Try resolving each reference in a target group, collecting fixable and unfixable entries.
 ///
+/// A symbol gone from its file is first looked for elsewhere in the project,
+/// so a move rewrites the link's path; otherwise a close name match rewrites
+/// its fragment.
+///
 /// # Errors
 ///
 /// Returns resolution errors other than `SymbolNotFound` (which are classified, not propagated).
 fn collect_fixes_for_target(
-    root: &std::path::Path,
-    config: &config::Config,
-    target: &std::path::Path,
+    ctx: &FixContext<'_>,
+    target: &Path,
     refs: &[Reference],
     fixes: &mut Vec<FixAction>,
     unfixable: &mut Vec<String>,
 ) -> Result<(), error::Error> {
-    let disk_path = config.resolve_target(target)?;
-    let target_path = root.join(&disk_path);
-    let Ok(source) = std::fs::read_to_string(&target_path) else {
-        unfixable.push(format!("{}  (file not found)", target.display()));
+    // Absence assertions are satisfied by a missing file and have no symbol to fix.
+    if refs.iter().all(|r| return matches!(r.symbol, SymbolQuery::Absent(_))) {
+        return Ok(());
+    }
+    let config = ctx.config;
+    let disk_path = fix_path_case(ctx, config.resolve_target(target)?, refs, fixes);
+    let Ok(source) = std::fs::read_to_string(ctx.root.join(&disk_path)) else {
+        let moved: Vec<FixAction> = refs.iter().filter_map(|r| return find_moved_symbol(ctx, r)).collect();
+        if moved.len() < refs.iter().filter(|r| return !matches!(r.symbol, SymbolQuery::Absent(_))).count() {
+            unfixable.push(format!("{}  (file not found)", target.display()));
+        }
+        fixes.extend(moved);
         return Ok(());
     };
 
-    let Ok(language) = grammar::language_for_path(&disk_path) else {
-        unfixable.push(format!("{}  (unsupported language)", target.display()));
+    let Ok(language) = grammar::language_for_path(&disk_path, &config.grammars) else {
+        // Whole-file refs to plain files are hashed raw and have no symbol to fix,
+        // and a file without a grammar can't contain a symbol asserted absent.
+        let symbolless = |r: &Reference| {
+            return matches!(r.symbol, SymbolQuery::Absent(_) | SymbolQuery::Lines { .. } | SymbolQuery::WholeFile);
+        };
+        if !refs.iter().all(symbolless) {
+            unfixable.push(format!("{}  (unsupported language)", target.display()));
+        }
         return Ok(());
     };
 
     for reference in refs {
-        if matches!(reference.symbol, crate::types::SymbolQuery::WholeFile) {
+        if matches!(reference.symbol, SymbolQuery::Absent(_) | SymbolQuery::Lines { .. } | SymbolQuery::WholeFile) {
             continue;
         }
-        match resolver::resolve(&disk_path, &source, &language, &reference.symbol) {
-            Err(error::Error::SymbolNotFound { symbol, suggestions, .. }) => {
-                classify_broken_ref(reference, &symbol, &suggestions, fixes, unfixable);
+        let resolved =
+            resolver::resolve(&disk_path, &source, &language, &reference.symbol, config.slug_style, &config.grammars);
+        match resolved {
+            Err(error::Error::SymbolNotFound { symbol, suggestions, .. }) => match find_moved_symbol(ctx, reference) {
+                Some(moved) => fixes.push(moved),
+                None => classify_broken_ref(reference, &symbol, &suggestions, fixes, unfixable),
             },
             Err(e) => return Err(e),
             Ok(_) => {},
@@ -315,22 +1103,184 @@ fn collect_fixes_for_target(
     return Ok(());
 }
 
-/// Scan markdown, find broken references, auto-fix those with a close match.
+/// Summarize wildcard member changes, e.g. `added: Config.timeout; removed: Config.port`.
+fn describe_members(members: &MemberChanges) -> String {
+    let mut parts = Vec::new();
+    if !members.added.is_empty() {
+        parts.push(format!("added: {}", members.added.join(", ")));
+    }
+    if !members.removed.is_empty() {
+        parts.push(format!("removed: {}", members.removed.join(", ")));
+    }
+    return parts.join("; ");
+}
+
+/// `CODEOWNERS` owners of a reference: those of its target file, then those
+/// of its markdown file, without repeats.
+fn entry_code_owners(config: &config::Config, codeowners: &CodeOwners, entry: &LockEntry) -> Vec<String> {
+    let target = config.resolve_target(&entry.target).unwrap_or_else(|_| return entry.target.clone());
+    let mut owners = codeowners.owners_of(&target).to_vec();
+    for owner in codeowners.owners_of(&entry.source) {
+        if !owners.contains(owner) {
+            owners.push(owner.clone());
+        }
+    }
+    return owners;
+}
+
+/// Compare lockfile entries against source and decide each verdict,
+/// stopping once the policy's failure limit is reached.
+///
+/// Every entry is hashed up front, grouped by target file so each file is
+/// parsed once; the limit only cuts the report short.
+///
+/// # Errors
+///
+/// Returns errors from hash computation.
+fn evaluate_check<'a>(
+    root: &Path,
+    config: &config::Config,
+    lockfile: &'a Lockfile,
+    policy: &CheckPolicy,
+) -> Result<CheckRun<'a>, error::Error> {
+    let tracked: Vec<&LockEntry> = lockfile.entries.iter().collect();
+    let results = compare_lockfile_entries_against_source(root, config, &tracked)?;
+    return evaluate_results(root, config, lockfile, &results, policy);
+}
+
+/// Decide the verdict for each entry's comparison `result`, in lockfile
+/// order, stopping once the policy's failure limit is reached.
+///
+/// # Errors
+///
+/// Returns errors from reading git history for `stale_grace_days`.
+fn evaluate_results<'a>(
+    root: &Path,
+    config: &config::Config,
+    lockfile: &'a Lockfile,
+    results: &[CheckResult],
+    policy: &CheckPolicy,
+) -> Result<CheckRun<'a>, error::Error> {
+    let mut run = CheckRun { checked: 0, entries: Vec::new() };
+    let mut failures = 0_u32;
+    for (entry, &result) in lockfile.entries.iter().zip(results) {
+        run.checked = run.checked.saturating_add(1);
+        let stale_days = match result {
+            CheckResult::Stale if config.severity.stale_grace_days.is_some() => {
+                history::stale_since(root, config, entry)?.map(history::days_since)
+            },
+            _ => None,
+        };
+        let verdict = result.finding().map(|f| return policy.verdict(config, entry, f, stale_days));
+        if verdict == Some(Verdict::Ignored) {
+            continue;
+        }
+        if verdict == Some(Verdict::Error) {
+            failures = failures.saturating_add(1);
+        }
+        let is_stale = matches!(result, CheckResult::Stale);
+        let members = is_stale.then(|| return member_changes(root, config, entry, &lockfile.entries)).flatten();
+        let moved_to = is_stale
+            .then(|| return relocated_lines(root, config, entry))
+            .flatten()
+            .map(|lines| return lines.display_name());
+        run.entries.push(EvaluatedEntry { entry, members, moved_to, result, stale_days, verdict });
+        if policy.limit_reached(failures) {
+            break;
+        }
+    }
+    return Ok(run);
+}
+
+/// Find where a broken reference's symbol moved: the one other file of the
+/// same kind holding a same-named symbol whose hash matches the lockfile.
+///
+/// Returns a rewrite of the link's path, in the form the markdown file used:
+/// namespaced when the new file is still under the namespace, otherwise
+/// relative to the markdown file (or the root, for root-relative links).
+fn find_moved_symbol(ctx: &FixContext<'_>, reference: &Reference) -> Option<FixAction> {
+    if !matches!(reference.symbol, SymbolQuery::Bare(_) | SymbolQuery::Scoped { .. }) {
+        return None;
+    }
+    let symbol = reference.symbol.display_name();
+    let entry = ctx.lockfile.as_ref()?.entries.iter().find(|e| {
+        return e.source == reference.source && e.target == reference.target && e.symbol == symbol;
+    })?;
+    let old_path = ctx.config.resolve_target(&reference.target).ok()?;
+    let candidates: Vec<&PathBuf> = ctx
+        .sources()
+        .iter()
+        .filter(|path| return **path != old_path && path.extension() == old_path.extension())
+        .filter(|path| return has_identical_symbol(ctx, path, &reference.symbol, entry))
+        .collect();
+    let [new_path] = candidates.as_slice() else {
+        return None;
+    };
+
+    let line = markdown_line(ctx.root, reference)?;
+    let old_text = line.get(reference.target_span.clone())?.to_string();
+    let new_text = moved_link_path(ctx.config, reference, &old_text, new_path);
+    let location = format!("{}:{}", reference.source.display(), reference.source_line);
+    eprintln!("fix: {location}  #{symbol} moved: {old_text} -> {new_text}");
+    return Some(FixAction {
+        columns: reference.target_span.clone(),
+        file: reference.source.clone(),
+        line: reference.source_line,
+        new_text,
+        old_text,
+    });
+}
+
+/// Explanation of a finding for report formats that show one per entry,
+/// such as SARIF and JUnit. `None` for fresh entries.
+fn finding_message(evaluated: &EvaluatedEntry<'_>) -> Option<String> {
+    let entry = evaluated.entry;
+    let refstr = format_ref(&entry.target, &entry.symbol);
+    let message = match (evaluated.result, evaluated.stale_days) {
+        (CheckResult::Ambiguous, _) => format!("{refstr} matches more than one declaration"),
+        (CheckResult::Broken(reason), _) => {
+            format!("{refstr} is broken ({reason}); run `docref fix` to repoint renamed or moved symbols")
+        },
+        (CheckResult::Fresh, _) => return None,
+        (CheckResult::Orphaned, _) => {
+            format!("{} was removed but .docref.lock still tracks it", entry.source.display())
+        },
+        (CheckResult::Stale, _) if let Some(members) = &evaluated.members => {
+            format!("{refstr} members changed ({}); re-run `docref init` to track them", describe_members(members))
+        },
+        (CheckResult::Stale, _) if let Some(lines) = &evaluated.moved_to => {
+            format!("{refstr} moved unchanged to #{lines}; point the link there and re-run `docref init`")
+        },
+        (CheckResult::Stale, days) => {
+            let age = days.map(|d| return format!(" {d} days ago")).unwrap_or_default();
+            format!("{refstr} changed{age} since the docs were reviewed; if they still hold, `docref update {refstr}`")
+        },
+    };
+    return Some(message);
+}
+
+/// Scan markdown, find broken references, auto-fix those with a close match
+/// or whose symbol moved unchanged to another file.
 /// Outputs a markdown report of what was fixed and what couldn't be.
 ///
 /// # Errors
 ///
 /// Returns errors from scanning, config loading, or file I/O.
-pub fn fix() -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
-    let config = config::Config::load(&root)?;
-    let grouped = scanner::scan(&root, &config)?;
+pub fn fix(root: &Path, options: &FixOptions) -> Result<(), error::Error> {
+    let config = config::Config::load(root)?;
+    let grouped = scanner::scan(root, &config)?;
+    let ctx = FixContext {
+        config: &config,
+        lockfile: Lockfile::read(&root.join(".docref.lock")).ok(),
+        root,
+        sources: OnceCell::new(),
+    };
 
     let mut fixes: Vec<FixAction> = Vec::new();
     let mut unfixable: Vec<String> = Vec::new();
 
     for (target, refs) in &grouped {
-        collect_fixes_for_target(&root, &config, target, refs, &mut fixes, &mut unfixable)?;
+        collect_fixes_for_target(&ctx, target, refs, &mut fixes, &mut unfixable)?;
     }
 
     if fixes.is_empty() && unfixable.is_empty() {
@@ -339,13 +1289,23 @@ pub fn fix() -> Result<(), error::Error> {
     }
 
     if !fixes.is_empty() {
-        apply_fixes(&fixes)?;
+        apply_fixes(root, &fixes, options)?;
     }
 
-    print_fix_report(&fixes, &unfixable);
+    print_fix_report(&fixes, &unfixable, options.dry_run);
     return Ok(());
 }
 
+/// Queue link-path rewrites for references whose target exists on disk only
+/// with different letter case, returning the path to read the target from.
+fn fix_path_case(ctx: &FixContext<'_>, disk_path: PathBuf, refs: &[Reference], fixes: &mut Vec<FixAction>) -> PathBuf {
+    let Some(on_disk) = scanner::find_case_mismatch(ctx.root, &disk_path) else {
+        return disk_path;
+    };
+    fixes.extend(refs.iter().filter_map(|r| return path_case_fix(ctx, r, &on_disk)));
+    return on_disk;
+}
+
 /// Fix a specific broken reference with a user-chosen symbol.
 ///
 /// Validates that `new_symbol` exists in the target file before rewriting.
@@ -353,8 +1313,7 @@ pub fn fix() -> Result<(), error::Error> {
 /// # Errors
 ///
 /// Returns errors from scanning, resolution, or file I/O.
-pub fn fix_targeted(reference: &str, new_symbol: &str) -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+pub fn fix_targeted(root: &Path, reference: &str, new_symbol: &str, options: &FixOptions) -> Result<(), error::Error> {
     let (target_file, old_symbol) = split_reference(reference);
 
     if old_symbol.is_empty() {
@@ -362,18 +1321,18 @@ pub fn fix_targeted(reference: &str, new_symbol: &str) -> Result<(), error::Erro
         return Ok(());
     }
 
-    let config = config::Config::load(&root)?;
+    let config = config::Config::load(root)?;
 
     // Validate the new symbol exists in the target.
-    let disk_path = config.resolve_target(&target_file)?;
-    let source = std::fs::read_to_string(root.join(&disk_path))
+    let disk_path = package::source_path(root, config.resolve_target(&target_file)?);
+    let source = package::read_source(&root.join(&disk_path))
         .map_err(|_err| return error::Error::FileNotFound { path: disk_path.clone() })?;
-    let language = grammar::language_for_path(&disk_path)?;
+    let language = grammar::language_for_path(&disk_path, &config.grammars)?;
     let query = parse_symbol_query(new_symbol);
-    resolver::resolve(&disk_path, &source, &language, &query)?;
+    resolver::resolve(&disk_path, &source, &language, &query, config.slug_style, &config.grammars)?;
 
     // Scan markdown to find all references using the old symbol.
-    let grouped = scanner::scan(&root, &config)?;
+    let grouped = scanner::scan(root, &config)?;
     let Some(refs) = grouped.get(&target_file) else {
         eprintln!("No references to `{}` found in markdown.", target_file.display());
         return Ok(());
@@ -382,14 +1341,7 @@ pub fn fix_targeted(reference: &str, new_symbol: &str) -> Result<(), error::Erro
     let fixes: Vec<FixAction> = refs
         .iter()
         .filter(|r| return r.symbol.display_name() == old_symbol)
-        .map(|r| {
-            return FixAction {
-                file: r.source.clone(),
-                line: r.source_line,
-                new_symbol: new_symbol.to_string(),
-                old_symbol: old_symbol.clone(),
-            };
-        })
+        .map(|r| return fragment_fix(r, &old_symbol, new_symbol))
         .collect();
 
     if fixes.is_empty() {
@@ -397,19 +1349,39 @@ pub fn fix_targeted(reference: &str, new_symbol: &str) -> Result<(), error::Erro
         return Ok(());
     }
 
-    apply_fixes(&fixes)?;
-    print_fix_report(&fixes, &[]);
+    apply_fixes(root, &fixes, options)?;
+    print_fix_report(&fixes, &[], options.dry_run);
     return Ok(());
 }
 
 /// Format a reference as `file#symbol` or just `file` for whole-file refs.
-fn format_ref(target: &std::path::Path, symbol: &str) -> String {
+pub(crate) fn format_ref(target: &Path, symbol: &str) -> String {
     if symbol.is_empty() {
         return target.display().to_string();
     }
     return format!("{}#{symbol}", target.display());
 }
 
+/// Rewrite of a reference's `#fragment` from one symbol name to another.
+fn fragment_fix(reference: &Reference, old_symbol: &str, new_symbol: &str) -> FixAction {
+    let span = &reference.symbol_span;
+    return FixAction {
+        columns: span.start.saturating_sub(1)..span.end,
+        file: reference.source.clone(),
+        line: reference.source_line,
+        new_text: format!("#{new_symbol}"),
+        old_text: format!("#{old_symbol}"),
+    };
+}
+
+/// Blob URL prefix for the commit under test when running in GitHub
+/// Actions, so markdown report links open the files at that commit.
+fn github_blob_url() -> Option<String> {
+    let var = |name: &str| return std::env::var(name).ok().filter(|value| return !value.is_empty());
+    let (server, repository, sha) = (var("GITHUB_SERVER_URL")?, var("GITHUB_REPOSITORY")?, var("GITHUB_SHA")?);
+    return Some(format!("{server}/{repository}/blob/{sha}"));
+}
+
 /// Group entry indices by their target file path.
 ///
 /// # Errors
@@ -432,55 +1404,331 @@ fn group_indices_by_target(
     return Ok(by_target);
 }
 
-/// Output a comprehensive reference document for docref.
-pub fn info(json: bool) {
-    return crate::info::run(json);
+/// Whether `path` holds the query's symbol with the hash a lockfile entry recorded.
+fn has_identical_symbol(ctx: &FixContext<'_>, path: &Path, query: &SymbolQuery, entry: &LockEntry) -> bool {
+    let Ok(source) = std::fs::read_to_string(ctx.root.join(path)) else {
+        return false;
+    };
+    return hash_query_in_source(ctx.root, path, &source, query, entry.mode, ctx.config)
+        .is_ok_and(|hash| return hash == entry.hash);
+}
+
+/// Output a comprehensive reference document for docref, or one section of it.
+pub fn info(root: &Path, json: bool, topic: Option<crate::info::Topic>) {
+    return crate::info::run(root, json, topic);
 }
 
 /// Scan markdown, resolve all references, hash symbols, write lockfile.
+/// Notes, owners, and tags recorded in an existing lockfile are kept.
+///
+/// Progress goes to stderr through [`Progress`]: a spinner and bar on a
+/// terminal, a periodic log line otherwise.
 ///
 /// # Errors
 ///
 /// Returns errors from scanning, resolution, hashing, or lockfile writing.
-pub fn init() -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+pub fn init(root: &Path) -> Result<(), error::Error> {
     let lock_path = root.join(".docref.lock");
 
-    let config = config::Config::load(&root)?;
-    let grouped = scanner::scan(&root, &config)?;
-    let entries = resolve_and_hash_all_references(&root, &config, &grouped)?;
-    let lockfile = Lockfile::new(entries);
+    let config = config::Config::load(root)?;
+    let mut scanning = Progress::spinner("Scanning markdown");
+    let (mut files, mut found) = (0_u64, 0_usize);
+    let grouped = scanner::scan_with_progress(root, &config, &mut |_, references| {
+        files = files.saturating_add(1);
+        found = found.saturating_add(references);
+        scanning.update(files, &format!("{files} files, {found} references"));
+    })?;
+    drop(scanning);
+    warn_case_mismatches(root, &config, &grouped);
+
+    let total = grouped.values().map(Vec::len).sum::<usize>();
+    let mut hashing = Progress::bar("Resolving and hashing", u64::try_from(total).unwrap_or(u64::MAX));
+    let mut done = 0_u64;
+    let entries = resolve_and_hash_all_references_with_progress(root, &config, &grouped, &mut |_, references| {
+        done = done.saturating_add(u64::try_from(references).unwrap_or(u64::MAX));
+        hashing.update(done, "references");
+    })?;
+    drop(hashing);
+    let mut lockfile = Lockfile::new(entries);
+    if let Ok(previous) = Lockfile::read(&lock_path) {
+        lockfile.carry_metadata_from(&previous);
+    }
 
     lockfile.write(&lock_path)?;
     let count = lockfile.entries.len();
     eprintln!("Wrote {count} references to .docref.lock");
 
-    return Ok(());
+    return Ok(());
+}
+
+/// The JUnit test case for a checked entry, locating any finding on the
+/// markdown line in `references` that holds its link.
+fn junit_case(evaluated: &EvaluatedEntry<'_>, references: &[Reference]) -> junit::Case {
+    let entry = evaluated.entry;
+    let source = entry.source.display().to_string();
+    let location = reference_line(references, entry)
+        .map_or_else(|| return source.clone(), |line| return format!("{source}:{line}"));
+    let finding = finding_message(evaluated).map(|message| return format!("{location}: {message}"));
+    let (failure, note) = match evaluated.verdict {
+        Some(Verdict::Error) => {
+            let kind = evaluated.result.finding().map_or("", Finding::name);
+            (finding.map(|message| return junit::Failure { kind, message }), None)
+        },
+        Some(verdict) => (None, finding.map(|message| return format!("{}: {message}", verdict.name()))),
+        None => (None, None),
+    };
+    return junit::Case { classname: source, failure, name: format_ref(&entry.target, &entry.symbol), note };
+}
+
+/// One-based line number of a byte offset in `source`.
+fn line_at(source: &str, byte: u32) -> usize {
+    let end = usize::try_from(byte).unwrap_or(usize::MAX).min(source.len());
+    let before = source.get(..end).unwrap_or(source);
+    return before.matches('\n').count().saturating_add(1);
+}
+
+/// Append the table of broken, ambiguous, and orphaned references.
+fn markdown_broken_section(out: &mut String, entries: &[&EvaluatedEntry<'_>], link_base: Option<&str>) {
+    out.push_str("\n#### Broken\n\n| Reference | Problem | Documented in |\n| --- | --- | --- |\n");
+    for evaluated in entries {
+        let problem = match evaluated.result {
+            CheckResult::Ambiguous => "matches several declarations",
+            CheckResult::Broken(reason) => reason,
+            CheckResult::Fresh | CheckResult::Stale => "",
+            CheckResult::Orphaned => "markdown file removed",
+        };
+        let reference = markdown_reference_cell(evaluated, link_base);
+        let source = markdown_link(&evaluated.entry.source.display().to_string(), &evaluated.entry.source, link_base);
+        let _ = writeln!(out, "| {reference} | {problem} | {source} |");
+    }
+    out.push_str("\nRun `docref fix` to repoint links whose symbol was renamed or moved.\n");
+    return;
+}
+
+/// The markdown line a reference was scanned from.
+fn markdown_line(root: &Path, reference: &Reference) -> Option<String> {
+    let content = std::fs::read_to_string(root.join(&reference.source)).ok()?;
+    let idx = usize::try_from(reference.source_line).ok()?.saturating_sub(1);
+    return content.lines().nth(idx).map(String::from);
+}
+
+/// `label` as inline code, linked to `path` under `link_base` when one is known.
+fn markdown_link(label: &str, path: &Path, link_base: Option<&str>) -> String {
+    let label = label.replace('|', "\\|");
+    return link_base.map_or_else(
+        || return format!("`{label}`"),
+        |base| return format!("[`{label}`]({}/{})", base.trim_end_matches('/'), path.display()),
+    );
+}
+
+/// The reference column of a markdown findings table, linked to the
+/// target file and marked when the finding doesn't fail the check.
+fn markdown_reference_cell(evaluated: &EvaluatedEntry<'_>, link_base: Option<&str>) -> String {
+    let refstr = format_ref(&evaluated.entry.target, &evaluated.entry.symbol);
+    let cell = markdown_link(&refstr, &evaluated.entry.target, link_base);
+    return match evaluated.verdict {
+        Some(Verdict::Baselined) => format!("{cell} (baseline)"),
+        Some(Verdict::Warning) => format!("{cell} (warning)"),
+        Some(Verdict::Error | Verdict::Ignored) | None => cell,
+    };
+}
+
+/// Append the table of stale references and the commands that accept them.
+fn markdown_stale_section(out: &mut String, entries: &[&EvaluatedEntry<'_>], link_base: Option<&str>) {
+    out.push_str("\n#### Stale\n\n| Reference | Documented in |\n| --- | --- |\n");
+    for evaluated in entries {
+        let reference = markdown_reference_cell(evaluated, link_base);
+        let source = markdown_link(&evaluated.entry.source.display().to_string(), &evaluated.entry.source, link_base);
+        let _ = writeln!(out, "| {reference} | {source} |");
+    }
+    out.push_str("\nIf the docs still describe the code correctly, accept the changes:\n\n```sh\n");
+    let mut refs: Vec<String> = entries.iter().map(|e| return format_ref(&e.entry.target, &e.entry.symbol)).collect();
+    refs.sort();
+    refs.dedup();
+    for refstr in refs {
+        let _ = writeln!(out, "docref update {refstr}");
+    }
+    out.push_str("```\n");
+    return;
+}
+
+/// The link path for a symbol's new file, written the way `old_text` was:
+/// namespaced, root-relative, or relative to the markdown file.
+fn moved_link_path(config: &config::Config, reference: &Reference, old_text: &str, new_path: &Path) -> String {
+    if let Some((namespace, _)) = old_text.split_once(':') {
+        let rest = config
+            .resolve_namespace(namespace)
+            .ok()
+            .and_then(|dir| return new_path.strip_prefix(dir).ok().map(Path::to_path_buf));
+        if let Some(rest) = rest {
+            return format!("{namespace}:{}", rest.display());
+        }
+    }
+    if Path::new(old_text) == reference.target {
+        return new_path.display().to_string();
+    }
+    return relative_link_path(reference.source.parent().unwrap_or(Path::new("")), new_path);
+}
+
+/// Parse `check --format`, which also accepts `markdown`.
+///
+/// # Errors
+///
+/// Returns `Error::LockfileCorrupt` (reused as generic user error) for unknown formats.
+fn parse_check_format(s: &str) -> Result<OutputFormat, error::Error> {
+    match s {
+        "junit" => return Ok(OutputFormat::Junit),
+        "markdown" => return Ok(OutputFormat::Markdown),
+        "sarif" => return Ok(OutputFormat::Sarif),
+        _ => {},
+    }
+    return parse_output_format(s).map_err(|_err| {
+        return error::Error::LockfileCorrupt {
+            reason: format!("unknown format: {s} (expected 'text', 'json', 'junit', 'markdown', or 'sarif')"),
+        };
+    });
+}
+
+/// Parse a format string into an `OutputFormat`.
+///
+/// # Errors
+///
+/// Returns `Error::LockfileCorrupt` (reused as generic user error) for unknown formats.
+pub(crate) fn parse_output_format(s: &str) -> Result<OutputFormat, error::Error> {
+    return match s {
+        "json" => Ok(OutputFormat::Json),
+        "text" => Ok(OutputFormat::Text),
+        _ => Err(error::Error::LockfileCorrupt {
+            reason: format!("unknown format: {s} (expected 'text' or 'json')"),
+        }),
+    };
+}
+
+/// Rewrite one reference's link path to the on-disk letter case, written the
+/// way the link was (namespaced, root-relative, or relative).
+fn path_case_fix(ctx: &FixContext<'_>, reference: &Reference, on_disk: &Path) -> Option<FixAction> {
+    let line = markdown_line(ctx.root, reference)?;
+    let old_text = line.get(reference.target_span.clone())?.to_string();
+    let new_text = moved_link_path(ctx.config, reference, &old_text, on_disk);
+    let location = format!("{}:{}", reference.source.display(), reference.source_line);
+    eprintln!("fix: {location}  path case: {old_text} -> {new_text}");
+    return Some(FixAction {
+        columns: reference.target_span.clone(),
+        file: reference.source.clone(),
+        line: reference.source_line,
+        new_text,
+        old_text,
+    });
+}
+
+/// Whole-number percentage of `part` in `total`, 100 when `total` is zero.
+fn percent_of(part: u32, total: u32) -> u32 {
+    return part.saturating_mul(100).checked_div(total).unwrap_or(100);
+}
+
+/// Print one non-fresh check result with its verdict marker.
+fn print_check_line(evaluated: &EvaluatedEntry<'_>) {
+    let refstr = format_ref(&evaluated.entry.target, &evaluated.entry.symbol);
+    let source = evaluated.entry.source.display();
+    let suffix = match evaluated.verdict {
+        Some(Verdict::Baselined) => " [baseline]",
+        Some(Verdict::Error | Verdict::Ignored) | None => "",
+        Some(Verdict::Warning) => " [warning]",
+    };
+    match evaluated.result {
+        CheckResult::Ambiguous => println!("AMBIG   {refstr}{suffix}"),
+        CheckResult::Broken(reason) => println!("BROKEN  {refstr} ({reason}){suffix}"),
+        CheckResult::Fresh => {},
+        CheckResult::Orphaned => println!("ORPHAN  {refstr} ({source} not found){suffix}"),
+        CheckResult::Stale => match (&evaluated.members, &evaluated.moved_to, evaluated.stale_days) {
+            (Some(members), ..) => println!("STALE   {refstr} (members {}){suffix}", describe_members(members)),
+            (None, Some(lines), _) => println!("STALE   {refstr} (lines moved to #{lines}){suffix}"),
+            (None, None, Some(days)) => println!("STALE   {refstr} (stale {days} days){suffix}"),
+            (None, None, None) => println!("STALE   {refstr}{suffix}"),
+        },
+    }
+    return;
+}
+
+/// Print the closing summary for text `check` output.
+fn print_check_summary(run: &CheckRun<'_>, total: usize) {
+    let (broken_count, stale_count) = run.error_counts();
+    let tolerated = run
+        .entries
+        .iter()
+        .filter(|e| return matches!(e.verdict, Some(Verdict::Baselined | Verdict::Warning)))
+        .count();
+    if broken_count > 0 {
+        eprintln!();
+        eprintln!("{broken_count} broken, {stale_count} stale");
+    } else if stale_count > 0 {
+        let stale_refs: Vec<String> = run
+            .entries
+            .iter()
+            .filter(|e| return e.verdict == Some(Verdict::Error))
+            .map(|e| return format_ref(&e.entry.target, &e.entry.symbol))
+            .collect();
+        print_stale_summary(&stale_refs);
+    } else if tolerated > 0 {
+        eprintln!();
+        eprintln!("{tolerated} warnings or baselined findings, no errors across {total} references");
+    } else {
+        eprintln!("All {total} references fresh");
+    }
+    return;
 }
 
-/// Parse a format string into an `OutputFormat`.
+/// Print the trace from `resolve --explain`, then surface the resolution error if any.
 ///
 /// # Errors
 ///
-/// Returns `Error::LockfileCorrupt` (reused as generic user error) for unknown formats.
-fn parse_output_format(s: &str) -> Result<OutputFormat, error::Error> {
-    return match s {
-        "json" => Ok(OutputFormat::Json),
-        "text" => Ok(OutputFormat::Text),
-        _ => Err(error::Error::LockfileCorrupt {
-            reason: format!("unknown format: {s} (expected 'text' or 'json')"),
-        }),
+/// Returns the resolution error recorded in the explanation.
+fn print_explanation(
+    file: &str,
+    name: &str,
+    source: &str,
+    explanation: resolver::Explanation,
+) -> Result<(), error::Error> {
+    println!("grammar: {}", explanation.grammar);
+    println!("query: {name}");
+    println!("candidates ({}):", explanation.candidates.len());
+    for candidate in &explanation.candidates {
+        let verdict = if candidate.accepted { "accepted" } else { "rejected" };
+        println!(
+            "  {verdict}  {}  [{}]  bytes {}..{}: {}",
+            candidate.qualified_name,
+            candidate.node_path.join(" > "),
+            candidate.byte_range.start,
+            candidate.byte_range.end,
+            candidate.reason,
+        );
+    }
+
+    let range = match explanation.outcome {
+        Ok(resolved) => resolved.byte_range,
+        Err(e) => {
+            println!("result: unresolved");
+            return Err(e);
+        },
     };
+    println!(
+        "result: {file}#{name} at bytes {}..{} (lines {}-{})",
+        range.start,
+        range.end,
+        line_at(source, range.start),
+        line_at(source, range.end.saturating_sub(1)),
+    );
+    return Ok(());
 }
 
 /// Print a markdown summary of fix results.
-fn print_fix_report(fixes: &[FixAction], unfixable: &[String]) {
+fn print_fix_report(fixes: &[FixAction], unfixable: &[String], dry_run: bool) {
     if !fixes.is_empty() {
-        eprintln!("## Fixed\n");
+        eprintln!("{}\n", if dry_run { "## Would fix" } else { "## Fixed" });
         for fix in fixes {
             eprintln!(
-                "- {}:{}  `#{}` -> `#{}`",
-                fix.file.display(), fix.line, fix.old_symbol, fix.new_symbol,
+                "- {}:{}  `{}` -> `{}`",
+                fix.file.display(), fix.line, fix.old_text, fix.new_text,
             );
         }
         eprintln!();
@@ -494,12 +1742,72 @@ fn print_fix_report(fixes: &[FixAction], unfixable: &[String]) {
         eprintln!();
     }
 
-    if !fixes.is_empty() {
+    if dry_run {
+        eprintln!("Dry run: no markdown files were changed.");
+    } else if !fixes.is_empty() {
         eprintln!("Run `docref init` to regenerate the lockfile.");
     }
     return;
 }
 
+/// Print the stale-reference report with update hints.
+fn print_stale_summary(stale_refs: &[String]) {
+    eprintln!();
+    eprintln!("# Stale References");
+    eprintln!();
+    eprintln!("{} references have changed since the docs were written:", stale_refs.len());
+    eprintln!();
+    for r in stale_refs {
+        eprintln!("- `{r}`");
+    }
+    eprintln!();
+    print_update_hints(stale_refs);
+    return;
+}
+
+/// Print the `--by` breakdown, worst-drifting groups first.
+fn print_status_groups(grouped_by: &str, groups: &BTreeMap<String, StatusCountsJson>) {
+    let mut sorted: Vec<(&String, &StatusCountsJson)> = groups.iter().collect();
+    sorted.sort_by_key(|(_, c)| return c.percent_fresh);
+    println!();
+    println!("By {grouped_by}:");
+    for (name, c) in sorted {
+        println!("  {name}  {}/{} fresh ({}%)", c.fresh, c.total, c.percent_fresh);
+    }
+    return;
+}
+
+/// Print status totals and the per-source rollup.
+fn print_status_summary(summary: &StatusSummaryJson) {
+    let counts = &summary.counts;
+    println!(
+        "{} references: {} fresh, {} stale, {} broken, {} ambiguous, {} orphaned ({}% fresh)",
+        counts.total, counts.fresh, counts.stale, counts.broken, counts.ambiguous, counts.orphaned, counts.percent_fresh,
+    );
+    if summary.sources.is_empty() {
+        return;
+    }
+    println!();
+    println!("By source:");
+    for source in &summary.sources {
+        let c = &source.counts;
+        println!("  {}  {}/{} fresh ({}%)", source.source.display(), c.fresh, c.total, c.percent_fresh);
+    }
+    if let Some(grouped_by) = summary.grouped_by {
+        print_status_groups(grouped_by, &summary.groups);
+    }
+    return;
+}
+
+/// Print a git-style unified diff of one markdown file's edits to stdout.
+fn print_unified_diff(file: &Path, before: &str, after: &str) {
+    let diff = similar::TextDiff::from_lines(before, after);
+    let old_header = format!("a/{}", file.display());
+    let new_header = format!("b/{}", file.display());
+    print!("{}", diff.unified_diff().context_radius(3).header(&old_header, &new_header));
+    return;
+}
+
 /// Print recovery hints to stderr showing exact update commands.
 fn print_update_hints(stale_refs: &[String]) {
     eprintln!();
@@ -521,50 +1829,126 @@ fn print_update_hints(stale_refs: &[String]) {
     return;
 }
 
+/// Stamp `entries` with who accepted their new hashes, when, and against which
+/// commit, if `record_acceptance` is enabled in the config.
+fn record_acceptance<'a>(root: &Path, config: &config::Config, entries: impl Iterator<Item = &'a mut LockEntry>) {
+    if !config.record_acceptance {
+        return;
+    }
+    let acceptance = Acceptance::current(root);
+    for entry in entries {
+        entry.accepted = Some(acceptance.clone());
+    }
+    return;
+}
+
+/// The markdown line of the first link in `references` that matches `entry`.
+fn reference_line(references: &[Reference], entry: &LockEntry) -> Option<u32> {
+    return references
+        .iter()
+        .find(|r| {
+            return r.source == entry.source && r.target == entry.target && r.symbol.display_name() == entry.symbol;
+        })
+        .map(|r| return r.source_line);
+}
+
+/// Re-hash one entry against already-read source if its hash changed.
+///
+/// Returns whether the entry was stale and updated, or `None` if it can't be
+/// hashed (broken or ambiguous). Absence assertions are never stale.
+fn refresh_if_stale(
+    root: &Path,
+    entry: &mut LockEntry,
+    disk_path: &Path,
+    source: &str,
+    config: &config::Config,
+) -> Option<bool> {
+    if entry.absent {
+        return Some(false);
+    }
+    let query = parse_symbol_query(&entry.symbol);
+    let new_hash = hash_query_in_source(root, disk_path, source, &query, entry.mode, config).ok()?;
+    if new_hash == entry.hash {
+        return Some(false);
+    }
+    entry.hash = new_hash;
+    return Some(true);
+}
+
 /// Show which markdown files reference a given target file or symbol.
 ///
+/// `reference` narrows by target (`file` or `file#symbol`); `symbol` matches
+/// entries across every target whose symbol is `symbol` or ends in
+/// `.symbol`, so `validate` also finds `Config.validate`.
+///
 /// # Errors
 ///
-/// Returns errors from lockfile reading.
-pub fn refs(reference: &str) -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+/// Returns errors from lockfile reading or an unknown output format.
+pub fn refs(
+    root: &Path,
+    reference: Option<&str>,
+    symbol: Option<&str>,
+    format: &str,
+) -> Result<(), error::Error> {
+    let output_format = parse_output_format(format)?;
     let lock_path = root.join(".docref.lock");
 
     let lockfile = Lockfile::read(&lock_path)?;
-    let (file, symbol) = split_reference(reference);
+    let target = reference.map(split_reference);
+    let matches: Vec<&LockEntry> = lockfile
+        .entries
+        .iter()
+        .filter(|e| return refs_entry_matches(e, target.as_ref(), symbol))
+        .collect();
 
-    let mut found = false;
-    for entry in &lockfile.entries {
-        if entry.target != file {
-            continue;
-        }
-        if !symbol.is_empty() && entry.symbol != symbol {
-            continue;
-        }
+    if matches!(output_format, OutputFormat::Json) {
+        let entries = matches
+            .iter()
+            .map(|e| return RefEntryJson { source: e.source.clone(), symbol: e.symbol.clone(), target: e.target.clone() })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&RefsJson { entries }).unwrap_or_default());
+        return Ok(());
+    }
+    for entry in &matches {
         let refstr = format_ref(&entry.target, &entry.symbol);
         println!("{} -> {refstr}", entry.source.display());
-        found = true;
     }
-
-    if !found {
-        let refstr = format_ref(&file, &symbol);
-        eprintln!("No references to `{refstr}` found in lockfile.");
+    if matches.is_empty() {
+        let query = match (&target, symbol) {
+            (Some((file, _)), Some(name)) => format!("{}#{name}", file.display()),
+            (Some((file, sym)), None) => format_ref(file, sym),
+            (None, Some(name)) => format!("#{name}"),
+            (None, None) => String::new(),
+        };
+        eprintln!("No references to `{query}` found in lockfile.");
     }
-
     return Ok(());
 }
 
-/// Re-hash entries at given indices against a single parsed target file.
+/// Whether a lockfile entry satisfies the `refs` target and symbol filters.
+fn refs_entry_matches(entry: &LockEntry, target: Option<&(PathBuf, String)>, symbol: Option<&str>) -> bool {
+    if let Some((file, sym)) = target
+        && (entry.target != *file || (!sym.is_empty() && entry.symbol != *sym))
+    {
+        return false;
+    }
+    return symbol.is_none_or(|name| {
+        return entry.symbol == name || entry.symbol.strip_suffix(name).is_some_and(|rest| return rest.ends_with('.'));
+    });
+}
+
+/// Re-hash entries at given indices against a single read target file.
 ///
 /// # Errors
 ///
 /// Returns errors from resolution or hashing.
 fn rehash_entries_for_target(
+    root: &Path,
     lockfile: &mut Lockfile,
     indices: &[usize],
-    disk_path: &std::path::Path,
+    disk_path: &Path,
     source: &str,
-    language: &tree_sitter::Language,
+    config: &config::Config,
 ) -> Result<(), error::Error> {
     for &idx in indices {
         let Some(entry) = lockfile.entries.get(idx) else {
@@ -572,14 +1956,12 @@ fn rehash_entries_for_target(
                 reason: format!("index {idx} out of bounds"),
             });
         };
-        let symbol = entry.symbol.clone();
-        let new_hash = if symbol.is_empty() {
-            hasher::hash_file(source, language)?
-        } else {
-            let query = parse_symbol_query(&symbol);
-            let resolved = resolver::resolve(disk_path, source, language, &query)?;
-            hasher::hash_symbol(source, language, &resolved)?
-        };
+        // Absence assertions have a fixed hash; there is nothing to accept.
+        if entry.absent {
+            continue;
+        }
+        let query = parse_symbol_query(&entry.symbol);
+        let new_hash = hash_query_in_source(root, disk_path, source, &query, entry.mode, config)?;
         let Some(entry_mut) = lockfile.entries.get_mut(idx) else {
             return Err(error::Error::LockfileCorrupt {
                 reason: format!("index {idx} out of bounds"),
@@ -590,27 +1972,58 @@ fn rehash_entries_for_target(
     return Ok(());
 }
 
+/// A `/`-separated path from one root-relative directory to a root-relative file.
+fn relative_link_path(from_dir: &Path, to: &Path) -> String {
+    let from: Vec<_> = from_dir.components().collect();
+    let target: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&target).take_while(|(a, b)| return a == b).count();
+    let parts: Vec<String> = from
+        .iter()
+        .skip(common)
+        .map(|_| return "..".to_string())
+        .chain(target.iter().skip(common).map(|c| return c.as_os_str().to_string_lossy().to_string()))
+        .collect();
+    return parts.join("/");
+}
+
 /// List all symbols in a file, or resolve a specific symbol to its reference path.
 ///
+/// With `explain`, prints how the symbol was resolved: the grammar, every
+/// candidate declaration with its CST node path and verdict, and the final
+/// byte range.
+///
 /// # Errors
 ///
 /// Returns errors from file reading, language detection, or resolution.
-pub fn resolve(file: &str, symbol: Option<&str>) -> Result<(), error::Error> {
+pub fn resolve(root: &Path, file: &str, symbol: Option<&str>, explain: bool) -> Result<(), error::Error> {
+    let config = config::Config::load(root)?;
     let file_path = PathBuf::from(file);
-    let source = std::fs::read_to_string(&file_path)
+    let source = std::fs::read_to_string(root.join(&file_path))
         .map_err(|_err| return error::Error::FileNotFound { path: file_path.clone() })?;
-    let language = grammar::language_for_path(&file_path)?;
+    let language = match grammar::language_for_path(&file_path, &config.grammars) {
+        Err(error::Error::UnsupportedLanguage { .. }) if resolver::has_unparsed_symbols(&file_path) => {
+            return resolve_unparsed(file, &source, symbol, explain);
+        },
+        other => other?,
+    };
 
     match symbol {
         None => {
-            let symbols = resolver::list_symbols(&file_path, &source, &language)?;
+            let symbols = resolver::list_symbols(&file_path, &source, &language, config.slug_style, &config.grammars)?;
             for sym in &symbols {
-                println!("{file}#{}", sym.name);
+                let kind = sym.kind.map(|kind| return format!("  ({})", kind.name())).unwrap_or_default();
+                println!("{file}#{}{kind}", sym.name);
             }
         },
+        Some(name) if explain => {
+            let query = parse_symbol_query(name);
+            let explanation =
+                resolver::explain(&file_path, &source, &language, &query, config.slug_style, &config.grammars)?;
+            print_explanation(file, name, &source, explanation)?;
+        },
         Some(name) => {
             let query = parse_symbol_query(name);
-            resolver::resolve(&file_path, &source, &language, &query)?;
+            resolver::resolve(&file_path, &source, &language, &query, config.slug_style, &config.grammars)?;
             println!("{file}#{name}");
         },
     }
@@ -618,146 +2031,222 @@ pub fn resolve(file: &str, symbol: Option<&str>) -> Result<(), error::Error> {
     return Ok(());
 }
 
-/// Replace a symbol fragment on a specific line.
-fn rewrite_symbol_on_line(lines: &mut [String], fix: &FixAction) {
+/// `docref resolve` on a file without a grammar whose symbols come from a
+/// line scan, such as a SQL schema.
+///
+/// # Errors
+///
+/// Returns `Error::SymbolNotFound` or `Error::AmbiguousSymbol` if the symbol
+/// doesn't resolve.
+fn resolve_unparsed(file: &str, source: &str, symbol: Option<&str>, explain: bool) -> Result<(), error::Error> {
+    let file_path = Path::new(file);
+    match symbol {
+        None => {
+            for sym in resolver::list_unparsed_symbols(file_path, source) {
+                println!("{file}#{}", sym.name);
+            }
+        },
+        Some(name) if explain => {
+            let explanation = resolver::explain_unparsed(file_path, source, &parse_symbol_query(name));
+            print_explanation(file, name, source, explanation)?;
+        },
+        Some(name) => {
+            resolver::resolve_unparsed(file_path, source, &parse_symbol_query(name))?;
+            println!("{file}#{name}");
+        },
+    }
+    return Ok(());
+}
+
+/// Replace a link's fragment or path at its recorded span on a specific line.
+///
+/// Titles, prose, and other links on the line are untouched. The line is left
+/// as-is if the span no longer holds the old text.
+fn rewrite_span_on_line(lines: &mut [String], fix: &FixAction) {
     let idx = usize::try_from(fix.line).unwrap_or(0).saturating_sub(1);
     let Some(line) = lines.get_mut(idx) else { return };
-    let old_fragment = format!("#{}", fix.old_symbol);
-    let new_fragment = format!("#{}", fix.new_symbol);
-    *line = line.replace(&old_fragment, &new_fragment);
+    if line.get(fix.columns.clone()) != Some(fix.old_text.as_str()) {
+        return;
+    }
+    line.replace_range(fix.columns.clone(), &fix.new_text);
     return;
 }
 
+/// The SARIF finding for a reported entry, on the line of the first link in
+/// `references` that matches it. `None` for fresh or ignored entries.
+fn sarif_finding<'a>(evaluated: &EvaluatedEntry<'a>, references: &[Reference]) -> Option<sarif::Finding<'a>> {
+    let entry = evaluated.entry;
+    let level = match evaluated.verdict? {
+        Verdict::Baselined => sarif::Level::Note,
+        Verdict::Error => sarif::Level::Error,
+        Verdict::Ignored => return None,
+        Verdict::Warning => sarif::Level::Warning,
+    };
+    let message = finding_message(evaluated)?;
+    let line = reference_line(references, entry);
+    return Some(sarif::Finding { kind: evaluated.result.finding()?, level, line, message, source: &entry.source });
+}
+
+/// Re-scan the markdown files holding the run's findings, so reports can
+/// point at the line of each link. Files that can't be read are skipped.
+fn scan_finding_sources(root: &Path, config: &config::Config, run: &CheckRun<'_>) -> Vec<Reference> {
+    let sources: BTreeSet<&Path> = run
+        .entries
+        .iter()
+        .filter(|e| return e.verdict.is_some())
+        .map(|e| return e.entry.source.as_path())
+        .collect();
+    return sources
+        .into_iter()
+        .filter_map(|source| {
+            let content = std::fs::read_to_string(root.join(source)).ok()?;
+            return scanner::scan_content(root, config, source, &content).ok();
+        })
+        .flatten()
+        .collect();
+}
+
 /// Parse a `file#symbol` or bare `file` string into its components.
 ///
-/// Returns an empty symbol string for bare file references.
+/// Returns an empty symbol string for bare file references. A JSON pointer
+/// symbol becomes its dotted path, as in links.
 fn split_reference(input: &str) -> (PathBuf, String) {
     return match input.split_once('#') {
-        Some((file, symbol)) => (PathBuf::from(file), symbol.to_string()),
+        Some((file, symbol)) => (PathBuf::from(file), scanner::symbol_path(symbol)),
         None => (PathBuf::from(input), String::new()),
     };
 }
 
 /// Show all tracked references and their current freshness. Always exits 0.
 ///
+/// A trailing summary gives per-status counts, the percentage fresh, and a
+/// rollup per markdown source. `options` select an extra breakdown, filter
+/// entries by owner or tag, omit the per-entry lines, and name a file for the
+/// JSON report.
+///
 /// # Errors
 ///
-/// Returns errors from lockfile reading or hash computation.
-pub fn status(format: &str) -> Result<(), error::Error> {
+/// Returns errors from lockfile reading, hash computation, or writing the report.
+pub fn status(root: &Path, format: &str, options: &StatusOptions) -> Result<(), error::Error> {
     let output_format = parse_output_format(format)?;
-    let root = PathBuf::from(".");
     let lock_path = root.join(".docref.lock");
-    let config = config::Config::load(&root)?;
+    let config = config::Config::load(root)?;
     let lockfile = Lockfile::read(&lock_path)?;
+    let (rows, summary) = status_rows(root, &config, &lockfile, options)?;
+
+    let report_path = options.output.as_ref().map(|p| return root.join(p));
+    if matches!(output_format, OutputFormat::Text) {
+        status_text(&rows, &summary, options.summary_only);
+    }
+    if matches!(output_format, OutputFormat::Json) || report_path.is_some() {
+        write_json(&status_report(root, &rows, &summary, options.summary_only), report_path.as_deref())?;
+    }
+    return Ok(());
+}
 
-    return match output_format {
-        OutputFormat::Json => status_json(&root, &config, &lockfile),
-        OutputFormat::Text => status_text(&root, &config, &lockfile),
+/// The JSON status report, as `status --format json` prints it.
+fn status_report<'a>(
+    root: &Path,
+    rows: &[StatusRow<'_>],
+    summary: &'a StatusSummaryJson,
+    summary_only: bool,
+) -> StatusJson<'a> {
+    let entries = (!summary_only).then(|| return rows.iter().map(StatusRow::to_json).collect());
+    return StatusJson {
+        entries,
+        metadata: ReportMetadataJson::current(root),
+        summary,
     };
 }
 
-/// Produce JSON status output.
+/// The report `status --format json` prints for the project at `root`, as
+/// a string.
 ///
 /// # Errors
 ///
-/// Returns errors from hash computation.
-fn status_json(
-    root: &std::path::Path,
-    config: &config::Config,
-    lockfile: &Lockfile,
-) -> Result<(), error::Error> {
-    let mut entries: Vec<StatusEntryJson> = Vec::new();
-
-    for entry in &lockfile.entries {
-        let result = compare_lockfile_entry_against_source(root, config, entry)?;
-        let (status_str, reason) = match result {
-            CheckResult::Broken(r) => ("broken", Some(r.to_string())),
-            CheckResult::Fresh => ("fresh", None),
-            CheckResult::Stale => ("stale", None),
-        };
-        entries.push(StatusEntryJson {
-            hash: entry.hash.0.clone(),
-            reason,
-            source: entry.source.clone(),
-            status: status_str.to_string(),
-            symbol: entry.symbol.clone(),
-            target: entry.target.clone(),
-        });
-    }
-
-    let output = StatusJson { entries };
-    println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
-    return Ok(());
+/// Returns errors from config or lockfile reading, or hash computation.
+pub fn status_report_json(root: &Path) -> Result<String, error::Error> {
+    let config = config::Config::load(root)?;
+    let lockfile = Lockfile::read(&root.join(".docref.lock"))?;
+    let (rows, summary) = status_rows(root, &config, &lockfile, &StatusOptions::default())?;
+    let report = status_report(root, &rows, &summary, false);
+    let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::from)?;
+    return Ok(json);
 }
 
-/// Produce human-readable text status output.
+/// Check the entries `options` select and tally them into a summary,
+/// returning one row per entry alongside it.
 ///
 /// # Errors
 ///
-/// Returns errors from hash computation.
-fn status_text(
-    root: &std::path::Path,
+/// Returns errors from reading `CODEOWNERS` or git history, or hash computation.
+fn status_rows<'a>(
+    root: &Path,
     config: &config::Config,
-    lockfile: &Lockfile,
-) -> Result<(), error::Error> {
-    for entry in &lockfile.entries {
-        let refstr = format_ref(&entry.target, &entry.symbol);
-        let result = compare_lockfile_entry_against_source(root, config, entry)?;
-        let label = match result {
+    lockfile: &'a Lockfile,
+    options: &StatusOptions,
+) -> Result<(Vec<StatusRow<'a>>, StatusSummaryJson), error::Error> {
+    let codeowners = CodeOwners::load(root)?;
+    let by = options.by;
+    let mut rows = Vec::with_capacity(lockfile.entries.len());
+    let mut summary = StatusSummaryJson {
+        grouped_by: by.map(StatusGrouping::name),
+        ..StatusSummaryJson::default()
+    };
+    let tracked: Vec<&LockEntry> = lockfile.entries.iter().filter(|e| return options.includes(e)).collect();
+    let results = compare_lockfile_entries_against_source(root, config, &tracked)?;
+    // Ages walk git history per entry, so they're only found when asked for.
+    let wants_ages = !options.summary_only && (options.ages || config.severity.stale_grace_days.is_some());
+    for (entry, result) in tracked.into_iter().zip(results) {
+        let code_owners = entry_code_owners(config, &codeowners, entry);
+        summary.record(entry, by.map(|b| return b.keys(config, entry, &code_owners)).unwrap_or_default(), &result);
+        let stale_since = match result {
+            CheckResult::Stale if wants_ages => history::stale_since(root, config, entry)?,
+            _ => None,
+        };
+        rows.push(StatusRow { code_owners, entry, result, stale_since });
+    }
+    return Ok((rows, summary));
+}
+
+/// Print human-readable status lines followed by the summary.
+fn status_text(rows: &[StatusRow<'_>], summary: &StatusSummaryJson, summary_only: bool) {
+    let lines = if summary_only { &[][..] } else { rows };
+    for row in lines {
+        let refstr = format_ref(&row.entry.target, &row.entry.symbol);
+        let notes = row.annotations();
+        let label = match row.result {
+            CheckResult::Ambiguous => "AMBIG ",
             CheckResult::Broken(reason) => {
-                println!("BROKEN  {refstr} ({reason})");
+                println!("BROKEN  {refstr} ({reason}){notes}");
                 continue;
             },
             CheckResult::Fresh => "FRESH ",
+            CheckResult::Orphaned => "ORPHAN",
             CheckResult::Stale => "STALE ",
         };
-        println!("{label}  {refstr}");
+        println!("{label}  {refstr}{notes}");
     }
-    return Ok(());
+    if !lines.is_empty() {
+        println!();
+    }
+    print_status_summary(summary);
+    return;
 }
 
-/// Re-hash a specific reference and update the lockfile.
+/// Re-hash a specific reference and update the lockfile. `note`, when given,
+/// is recorded on the entry, e.g. to explain why a stale reference was accepted.
 ///
 /// # Errors
 ///
 /// Returns errors from lockfile I/O, resolution, or hashing.
-pub fn update(reference: &str) -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+pub fn update(root: &Path, reference: &str, note: Option<&str>) -> Result<(), error::Error> {
     let lock_path = root.join(".docref.lock");
 
-    let config = config::Config::load(&root)?;
+    let config = config::Config::load(root)?;
     let (file, symbol) = split_reference(reference);
     let mut lockfile = Lockfile::read(&lock_path)?;
-
-    let disk_path = config.resolve_target(&file)?;
-    let source = std::fs::read_to_string(root.join(&disk_path))
-        .map_err(|_err| return error::Error::FileNotFound { path: disk_path.clone() })?;
-    let language = grammar::language_for_path(&disk_path)?;
-
-    let new_hash = if symbol.is_empty() {
-        hasher::hash_file(&source, &language)?
-    } else {
-        let query = parse_symbol_query(&symbol);
-        let resolved = resolver::resolve(&disk_path, &source, &language, &query)?;
-        hasher::hash_symbol(&source, &language, &resolved)?
-    };
-
-    let mut updated = false;
-    for entry in &mut lockfile.entries {
-        if entry.target == file && entry.symbol == symbol {
-            entry.hash = new_hash.clone();
-            updated = true;
-        }
-    }
-
-    if !updated {
-        return Err(error::Error::SymbolNotFound {
-            file,
-            referenced_from: vec![],
-            suggestions: vec![],
-            symbol,
-        });
-    }
+    accept_reference(root, &config, &mut lockfile, &file, &symbol, note)?;
 
     lockfile.write(&lock_path)?;
     eprintln!("Updated {}", format_ref(&file, &symbol));
@@ -771,26 +2260,20 @@ pub fn update(reference: &str) -> Result<(), error::Error> {
 /// # Errors
 ///
 /// Returns errors from lockfile I/O, resolution, or hashing.
-pub fn update_all() -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+pub fn update_all(root: &Path) -> Result<(), error::Error> {
     let lock_path = root.join(".docref.lock");
 
-    let config = config::Config::load(&root)?;
+    let config = config::Config::load(root)?;
     let mut lockfile = Lockfile::read(&lock_path)?;
 
-    for entry in &mut lockfile.entries {
-        let disk_path = config.resolve_target(&entry.target)?;
-        let source = std::fs::read_to_string(root.join(&disk_path))
+    for entry in lockfile.entries.iter_mut().filter(|e| return !e.absent) {
+        let disk_path = package::source_path(root, config.resolve_target(&entry.target)?);
+        let source = package::read_source(&root.join(&disk_path))
             .map_err(|_err| return error::Error::FileNotFound { path: disk_path.clone() })?;
-        let language = grammar::language_for_path(&disk_path)?;
-        entry.hash = if entry.symbol.is_empty() {
-            hasher::hash_file(&source, &language)?
-        } else {
-            let query = parse_symbol_query(&entry.symbol);
-            let resolved = resolver::resolve(&disk_path, &source, &language, &query)?;
-            hasher::hash_symbol(&source, &language, &resolved)?
-        };
+        let query = parse_symbol_query(&entry.symbol);
+        entry.hash = hash_query_in_source(root, &disk_path, &source, &query, entry.mode, &config)?;
     }
+    record_acceptance(root, &config, lockfile.entries.iter_mut().filter(|e| return !e.absent));
 
     lockfile.write(&lock_path)?;
     let count = lockfile.entries.len();
@@ -805,12 +2288,11 @@ pub fn update_all() -> Result<(), error::Error> {
 /// # Errors
 ///
 /// Returns errors from lockfile I/O, resolution, or hashing.
-pub fn update_file(source_file: &str) -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+pub fn update_file(root: &Path, source_file: &str) -> Result<(), error::Error> {
     let lock_path = root.join(".docref.lock");
     let source_path = PathBuf::from(source_file);
 
-    let config = config::Config::load(&root)?;
+    let config = config::Config::load(root)?;
     let mut lockfile = Lockfile::read(&lock_path)?;
 
     let matching_indices: Vec<usize> = lockfile
@@ -830,13 +2312,17 @@ pub fn update_file(source_file: &str) -> Result<(), error::Error> {
     let by_target = group_indices_by_target(&lockfile, &matching_indices)?;
 
     for (target, indices) in &by_target {
-        let disk_path = config.resolve_target(target)?;
+        let disk_path = package::source_path(root, config.resolve_target(target)?);
         let target_path = root.join(&disk_path);
-        let source = std::fs::read_to_string(&target_path)
+        let source = package::read_source(&target_path)
             .map_err(|_err| return error::Error::FileNotFound { path: target_path })?;
-        let language = grammar::language_for_path(&disk_path)?;
-        rehash_entries_for_target(&mut lockfile, indices, &disk_path, &source, &language)?;
+        rehash_entries_for_target(root, &mut lockfile, indices, &disk_path, &source, &config)?;
     }
+    let accepted = lockfile
+        .entries
+        .iter_mut()
+        .filter(|e| return e.source == source_path && !e.absent);
+    record_acceptance(root, &config, accepted);
 
     lockfile.write(&lock_path)?;
     let count = matching_indices.len();
@@ -844,3 +2330,239 @@ pub fn update_file(source_file: &str) -> Result<(), error::Error> {
 
     return Ok(());
 }
+
+/// Re-hash only the lockfile entries whose code changed, leaving fresh ones
+/// untouched. Each target file is read once for all of its entries.
+///
+/// Entries that can't be hashed (missing files or symbols, ambiguous names)
+/// are skipped and counted rather than failing the whole update.
+///
+/// # Errors
+///
+/// Returns errors from config loading or lockfile I/O.
+pub fn update_stale(root: &Path) -> Result<(), error::Error> {
+    let lock_path = root.join(".docref.lock");
+
+    let config = config::Config::load(root)?;
+    let mut lockfile = Lockfile::read(&lock_path)?;
+    let all_indices: Vec<usize> = (0..lockfile.entries.len()).collect();
+    let by_target = group_indices_by_target(&lockfile, &all_indices)?;
+
+    let mut outcomes: Vec<(usize, Option<bool>)> = Vec::new();
+    for (target, indices) in &by_target {
+        let disk_path = config.resolve_target(target).ok().map(|p| return package::source_path(root, p));
+        let source = disk_path.as_ref().and_then(|p| return package::read_source(&root.join(p)).ok());
+        for &idx in indices {
+            let outcome = match (lockfile.entries.get_mut(idx), &disk_path, &source) {
+                (Some(entry), Some(path), Some(text)) => refresh_if_stale(root, entry, path, text, &config),
+                _ => None,
+            };
+            outcomes.push((idx, outcome));
+        }
+    }
+    let refreshed: Vec<usize> =
+        outcomes.iter().filter(|(_, o)| return *o == Some(true)).map(|(idx, _)| return *idx).collect();
+    let updated = refreshed.len();
+    let skipped = outcomes.iter().filter(|(_, o)| return o.is_none()).count();
+    let accepted = lockfile
+        .entries
+        .iter_mut()
+        .enumerate()
+        .filter(|(idx, _)| return refreshed.contains(idx))
+        .map(|(_, e)| return e);
+    record_acceptance(root, &config, accepted);
+
+    if updated > 0 {
+        lockfile.write(&lock_path)?;
+    }
+    let fresh = lockfile.entries.len().saturating_sub(updated).saturating_sub(skipped);
+    eprintln!("Updated {updated} stale references ({fresh} already fresh)");
+    if skipped > 0 {
+        eprintln!("Skipped {skipped} broken or ambiguous references; run `docref check` for details");
+    }
+
+    return Ok(());
+}
+
+/// Warn about links whose path matches a file on disk only with different
+/// letter case: they resolve on macOS and Windows but break on Linux CI.
+fn warn_case_mismatches(root: &Path, config: &config::Config, grouped: &HashMap<PathBuf, Vec<Reference>>) {
+    for (target, refs) in grouped {
+        let on_disk = config.resolve_target(target).ok().and_then(|p| return scanner::find_case_mismatch(root, &p));
+        let Some(on_disk) = on_disk else {
+            continue;
+        };
+        for reference in refs {
+            eprintln!(
+                "warning: {}:{}  {} differs in case from {} on disk; run `docref fix` to correct it",
+                reference.source.display(),
+                reference.source_line,
+                target.display(),
+                on_disk.display(),
+            );
+        }
+    }
+    return;
+}
+
+/// What a `watch --exec` hook receives: the exit code `check` would return
+/// for `results`, and the JSON summary of findings for its stdin.
+///
+/// # Errors
+///
+/// Returns errors from reading git history for `stale_grace_days`.
+pub fn watch_hook_payload(
+    root: &Path,
+    config: &config::Config,
+    lockfile: &Lockfile,
+    results: &[CheckResult],
+) -> Result<(u8, String), error::Error> {
+    let policy = CheckPolicy { baseline: Baseline::default(), max_failures: None, max_severity: Severity::Error };
+    let run = evaluate_results(root, config, lockfile, results, &policy)?;
+    let mut summary = CheckSummaryJson::default();
+    for evaluated in &run.entries {
+        summary.record(evaluated);
+    }
+    let payload = WatchHookJson {
+        exit_code: run.exit_status(),
+        findings: run.entries.iter().filter(|e| return e.verdict.is_some()).map(EvaluatedEntry::to_json).collect(),
+        summary,
+    };
+    let json = serde_json::to_string(&payload).map_err(std::io::Error::from)?;
+    return Ok((payload.exit_code, json));
+}
+
+/// Check one batch of member projects, a thread each. A project whose
+/// check panics is reported as failed rather than dropped.
+fn workspace_batch(root: &Path, batch: &[PathBuf]) -> Vec<WorkspaceProjectJson> {
+    return std::thread::scope(|scope| {
+        let handles: Vec<_> = batch
+            .iter()
+            .map(|member| return (member, scope.spawn(|| return workspace_project(root, member))))
+            .collect();
+        return handles
+            .into_iter()
+            .map(|(member, handle)| {
+                let panicked = || return WorkspaceProjectJson::failed(member, "check panicked".to_string());
+                return handle.join().unwrap_or_else(|_| return panicked());
+            })
+            .collect();
+    });
+}
+
+/// How a member is shown in reports: its path, or `.` for the root.
+fn workspace_member_path(member: &Path) -> PathBuf {
+    return if member.as_os_str().is_empty() { PathBuf::from(".") } else { member.to_path_buf() };
+}
+
+/// Member projects of the workspace at `root`, relative to it: the
+/// `[workspace] members` list, or else every directory holding a
+/// `.docref.lock`, skipping hidden directories.
+fn workspace_members(root: &Path, config: &config::Config) -> Vec<PathBuf> {
+    if !config.workspace.members.is_empty() {
+        return config.workspace.members.clone();
+    }
+    let mut members: Vec<PathBuf> = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            return e.depth() == 0 || !e.file_type().is_dir() || !e.file_name().to_string_lossy().starts_with('.');
+        })
+        .filter_map(Result::ok)
+        .filter(|e| return e.file_name() == ".docref.lock")
+        .filter_map(|e| return e.path().parent()?.strip_prefix(root).ok().map(Path::to_path_buf))
+        .collect();
+    members.sort();
+    return members;
+}
+
+/// Check one member project in its own directory, with its own config.
+fn workspace_project(root: &Path, member: &Path) -> WorkspaceProjectJson {
+    let project_root = root.join(member);
+    let policy = CheckPolicy { baseline: Baseline::default(), max_failures: None, max_severity: Severity::Error };
+    let checked = config::Config::load(&project_root).and_then(|config| {
+        let lockfile = Lockfile::read(&project_root.join(".docref.lock"))?;
+        let run = evaluate_check(&project_root, &config, &lockfile, &policy)?;
+        let mut counts = StatusCountsJson::default();
+        for evaluated in &run.entries {
+            counts.record(&evaluated.result);
+        }
+        return Ok((counts, run.exit_status()));
+    });
+    return match checked {
+        Ok((counts, exit_code)) => {
+            WorkspaceProjectJson { counts: Some(counts), error: None, exit_code, path: workspace_member_path(member) }
+        },
+        Err(e) => WorkspaceProjectJson::failed(member, e.to_string()),
+    };
+}
+
+/// Check every member project of the workspace at `root`, up to one per CPU
+/// at a time, and print a per-project and aggregate freshness rollup.
+///
+/// The exit code is the worst any project would give on its own: 2 for
+/// broken references or a project that can't be checked, 1 for stale ones.
+///
+/// # Errors
+///
+/// Returns errors from loading the workspace config or an unknown output format.
+pub fn workspace_status(root: &Path, format: &str) -> Result<ExitCode, error::Error> {
+    let output_format = parse_output_format(format)?;
+    let config = config::Config::load(root)?;
+    let members = workspace_members(root, &config);
+    let workers = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let projects: Vec<WorkspaceProjectJson> =
+        members.chunks(workers).flat_map(|batch| return workspace_batch(root, batch)).collect();
+    let mut summary = StatusCountsJson::default();
+    for counts in projects.iter().filter_map(|p| return p.counts.as_ref()) {
+        summary.add(counts);
+    }
+    let exit_code = projects.iter().map(|p| return p.exit_code).max().unwrap_or(0);
+    if matches!(output_format, OutputFormat::Json) {
+        write_json(&WorkspaceStatusJson { metadata: ReportMetadataJson::current(root), projects, summary }, None)?;
+    } else {
+        workspace_text(&projects, &summary);
+    }
+    return Ok(ExitCode::from(exit_code));
+}
+
+/// Print one line per member project, then the workspace totals.
+fn workspace_text(projects: &[WorkspaceProjectJson], summary: &StatusCountsJson) {
+    let width = projects.iter().map(|p| return p.path.display().to_string().len()).max().unwrap_or(0);
+    for project in projects {
+        let path = project.path.display().to_string();
+        match (&project.counts, &project.error) {
+            (Some(c), _) => println!(
+                "{path:<width$}  {}/{} fresh ({}%), {} stale, {} broken, {} ambiguous, {} orphaned",
+                c.fresh, c.total, c.percent_fresh, c.stale, c.broken, c.ambiguous, c.orphaned,
+            ),
+            (None, error) => println!("{path:<width$}  error: {}", error.as_deref().unwrap_or("unknown")),
+        }
+    }
+    println!();
+    println!(
+        "{} projects, {} references: {} fresh, {} stale, {} broken, {} ambiguous, {} orphaned ({}% fresh)",
+        projects.len(),
+        summary.total,
+        summary.fresh,
+        summary.stale,
+        summary.broken,
+        summary.ambiguous,
+        summary.orphaned,
+        summary.percent_fresh,
+    );
+    return;
+}
+
+/// Pretty-print a JSON report to `path`, or to stdout when there is none.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if the file can't be written.
+fn write_json<T: Serialize>(value: &T, path: Option<&Path>) -> Result<(), error::Error> {
+    let json = serde_json::to_string_pretty(value).unwrap_or_default();
+    match path {
+        Some(path) => std::fs::write(path, format!("{json}\n"))?,
+        None => println!("{json}"),
+    }
+    return Ok(());
+}
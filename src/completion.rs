@@ -0,0 +1,90 @@
+//! Dynamic shell completion for `file#symbol` arguments.
+//!
+//! Enabled by sourcing `COMPLETE=<shell> docref`. Symbols for a file come from
+//! the lockfile when it's tracked, otherwise from resolving the file directly.
+
+use std::ffi::OsStr;
+use std::path::Path;
+
+use clap_complete::engine::{CompletionCandidate, PathCompleter, ValueCompleter as _};
+
+use crate::config;
+use crate::grammar;
+use crate::lockfile::Lockfile;
+use crate::resolver;
+
+/// Complete a file path, preferring targets already tracked in the lockfile.
+fn complete_file(root: &Path, current: &str) -> Vec<CompletionCandidate> {
+    let mut targets: Vec<String> = read_lockfile(root)
+        .entries
+        .iter()
+        .map(|e| return e.target.to_string_lossy().into_owned())
+        .filter(|target| return target.starts_with(current))
+        .collect();
+    targets.sort();
+    targets.dedup();
+
+    if targets.is_empty() {
+        return PathCompleter::file().complete(OsStr::new(current));
+    }
+    return targets.into_iter().map(CompletionCandidate::new).collect();
+}
+
+/// Complete a reference argument: file paths before `#`, symbols after it.
+pub fn complete_reference(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    let root = Path::new(".");
+    let Some((file, prefix)) = current.split_once('#') else {
+        return complete_file(root, current);
+    };
+
+    return symbols_for(root, file)
+        .into_iter()
+        .filter(|symbol| return symbol.starts_with(prefix))
+        .map(|symbol| return CompletionCandidate::new(format!("{file}#{symbol}")))
+        .collect();
+}
+
+/// Read the lockfile, treating a missing or corrupt one as empty.
+fn read_lockfile(root: &Path) -> Lockfile {
+    return Lockfile::read(&root.join(".docref.lock")).unwrap_or_else(|_| return Lockfile::new(Vec::new()));
+}
+
+/// List every addressable symbol in an untracked file via the resolver.
+fn resolved_symbols(root: &Path, file: &str) -> Vec<String> {
+    let Ok(config) = config::Config::load(root) else {
+        return Vec::new();
+    };
+    let Ok(disk_path) = config.resolve_target(Path::new(file)) else {
+        return Vec::new();
+    };
+    let Ok(source) = std::fs::read_to_string(root.join(&disk_path)) else {
+        return Vec::new();
+    };
+    let Ok(language) = grammar::language_for_path(&disk_path, &config.grammars) else {
+        return Vec::new();
+    };
+    return resolver::list_symbols(&disk_path, &source, &language, config.slug_style, &config.grammars)
+        .map(|symbols| return symbols.into_iter().map(|s| return s.name).collect())
+        .unwrap_or_default();
+}
+
+/// Candidate symbols for a file: tracked ones from the lockfile, falling back
+/// to the resolver when the file has no lockfile entries yet.
+fn symbols_for(root: &Path, file: &str) -> Vec<String> {
+    let mut tracked: Vec<String> = read_lockfile(root)
+        .entries
+        .into_iter()
+        .filter(|e| return e.target == Path::new(file) && !e.symbol.is_empty())
+        .map(|e| return e.symbol)
+        .collect();
+    tracked.sort();
+    tracked.dedup();
+
+    if tracked.is_empty() {
+        return resolved_symbols(root, file);
+    }
+    return tracked;
+}
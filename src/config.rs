@@ -1,7 +1,17 @@
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use crate::error::Error;
+use crate::grammar;
+use crate::scanner::normalize_path;
+use crate::types::HashMode;
+
+/// Markdown file extensions scanned when `.docref.toml` doesn't set
+/// `markdown_extensions`.
+const DEFAULT_MARKDOWN_EXTENSIONS: &[&str] = &["md", "mdx", "org"];
+
+/// Shortcode name scanned when `.docref.toml` doesn't set `shortcode`.
+const DEFAULT_SHORTCODE: &str = "docref";
 
 /// Project configuration loaded from `.docref.toml`.
 ///
@@ -9,15 +19,52 @@ use crate::error::Error;
 /// Namespaces map short prefixes to directory paths for cross-project references.
 #[derive(Debug)]
 pub struct Config {
+    /// Let targets resolve outside the project root or their namespace directory.
+    pub allow_outside_root: bool,
     /// Path prefixes to exclude from scanning.
     exclude: Vec<String>,
+    /// Absolute or out-of-root path prefixes that targets may resolve into.
+    external_paths: Vec<PathBuf>,
+    /// Grammars loaded at runtime from `[grammars]`, including inherited ones.
+    pub grammars: grammar::Grammars,
+    /// Hash mode for links that don't choose one with a title or `@doc`.
+    pub hash_mode: HashMode,
     /// Path prefixes to include when scanning.
     include: Vec<String>,
+    /// Extensions, without the dot, of the markdown files to scan.
+    markdown_extensions: Vec<String>,
     /// Namespace prefix-to-directory mappings.
     pub namespaces: HashMap<String, NamespaceEntry>,
+    /// Record timestamp, git commit, and user on entries re-hashed by `update`.
+    pub record_acceptance: bool,
+    /// Severity assigned to each kind of check finding.
+    pub severity: SeverityConfig,
+    /// Name of the Hugo/Jekyll shortcode that wraps references; empty disables it.
+    pub shortcode: String,
+    /// Algorithm used to turn markdown headings into symbol slugs.
+    pub slug_style: SlugStyle,
+    /// Team name to the code path prefixes it owns, from `[teams]`.
+    teams: HashMap<String, Vec<String>>,
+    /// Also scan Obsidian-style `[[path#symbol|text]]` wiki links.
+    pub wiki_links: bool,
+    /// Member projects for `workspace status`, from `[workspace]`.
+    pub workspace: WorkspaceConfig,
 }
 
 impl Config {
+    /// Whether a resolved target lies under one of the `external_paths`
+    /// prefixes. Both sides are compared after collapsing `.` and `..`.
+    fn is_allowed_external(&self, resolved: &Path) -> bool {
+        let resolved = normalize_path(resolved);
+        return self.external_paths.iter().any(|prefix| return resolved.starts_with(normalize_path(prefix)));
+    }
+
+    /// Whether `path` is a markdown file by the `markdown_extensions` setting.
+    pub fn is_markdown(&self, path: &Path) -> bool {
+        let ext = path.extension().and_then(|ext| return ext.to_str()).unwrap_or("");
+        return self.markdown_extensions.iter().any(|markdown| return markdown == ext);
+    }
+
     /// Load config from `.docref.toml` in the given root directory.
     /// Follows `extends` chains to inherit parent namespaces, detecting cycles.
     ///
@@ -34,7 +81,7 @@ impl Config {
     }
 
     /// If `extends` is set, validate the path, detect cycles, and recursively
-    /// load the parent config, returning its namespaces.
+    /// load the parent config, returning its namespaces and grammars.
     ///
     /// # Errors
     ///
@@ -46,12 +93,13 @@ impl Config {
         root: &Path,
         namespace_base: &Path,
         chain: &mut Vec<PathBuf>,
-    ) -> Result<HashMap<String, NamespaceEntry>, Error> {
+    ) -> Result<(HashMap<String, NamespaceEntry>, grammar::Grammars), Error> {
         let Some(extends_rel) = extends else {
-            return Ok(HashMap::new());
+            return Ok((HashMap::new(), grammar::Grammars::default()));
         };
 
         let parent_config = root.join(extends_rel);
+        tracing::debug!(extends = %parent_config.display(), "following extends");
         if !parent_config.exists() {
             return Err(Error::ConfigNotFound {
                 path: parent_config,
@@ -86,7 +134,7 @@ impl Config {
         };
 
         let parent = Self::load_recursive(parent_dir, &parent_namespace_base, chain)?;
-        return Ok(parent.namespaces);
+        return Ok((parent.namespaces, parent.grammars));
     }
 
     /// Load config recursively. `namespace_base` is the relative path from the
@@ -103,17 +151,41 @@ impl Config {
     ) -> Result<Self, Error> {
         let raw = Self::read_toml(root)?;
         let Some(raw) = raw else {
+            tracing::debug!(root = %root.display(), "no .docref.toml, scanning everything");
             return Ok(Self::scan_everything_by_default());
         };
+        tracing::debug!(
+            root = %root.display(),
+            include = ?raw.include,
+            exclude = ?raw.exclude,
+            "loaded .docref.toml"
+        );
 
-        let parent_namespaces =
+        let (parent_namespaces, mut grammars) =
             Self::load_parent(raw.extends.as_ref(), root, namespace_base, chain)?;
+        for (ext, entry) in &raw.grammars {
+            grammars.configure(ext, root.join(&entry.library), entry.name.as_deref().unwrap_or(ext), &entry.query);
+        }
         let namespaces = Self::merge_namespaces(parent_namespaces, raw.namespaces, namespace_base);
 
         return Ok(Self {
+            allow_outside_root: raw.allow_outside_root,
             exclude: raw.exclude,
+            external_paths: raw.external_paths,
+            grammars,
+            hash_mode: raw.hash_mode,
             include: raw.include,
+            markdown_extensions: raw.markdown_extensions.map_or_else(default_markdown_extensions, |extensions| {
+                return extensions.iter().map(|ext| return ext.trim_start_matches('.').to_string()).collect();
+            }),
             namespaces,
+            record_acceptance: raw.record_acceptance,
+            severity: raw.severity,
+            shortcode: raw.shortcode.unwrap_or_else(|| return DEFAULT_SHORTCODE.to_string()),
+            slug_style: raw.slug_style,
+            teams: raw.teams,
+            wiki_links: raw.wiki_links,
+            workspace: raw.workspace,
         });
     }
 
@@ -137,6 +209,13 @@ impl Config {
         return base;
     }
 
+    /// Configured namespace names in sorted order.
+    pub fn namespace_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.namespaces.keys().cloned().collect();
+        names.sort();
+        return names;
+    }
+
     /// Read and parse `.docref.toml`, returning `None` if the file doesn't exist.
     ///
     /// # Errors
@@ -153,37 +232,114 @@ impl Config {
         return Ok(Some(raw));
     }
 
+    /// Fail if `relative` leaves its base directory, unless `allow_outside_root`
+    /// is set or the `resolved` path falls under an `external_paths` prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PathOutsideRoot` naming the original `target`.
+    fn reject_escaping_path(&self, target: &Path, relative: &Path, resolved: &Path) -> Result<(), Error> {
+        if self.allow_outside_root || !escapes_base(relative) || self.is_allowed_external(resolved) {
+            return Ok(());
+        }
+        tracing::debug!(target = %target.display(), "rejected target outside the project root");
+        return Err(Error::PathOutsideRoot {
+            path: target.to_path_buf(),
+        });
+    }
+
+    /// Resolve a namespace name to its directory, relative to the load root.
+    ///
+    /// A namespace whose value is itself namespaced (`authv2 = "auth:v2"`)
+    /// is an alias and is followed transitively until a plain directory
+    /// is reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownNamespace` if any name in the chain isn't
+    /// configured, or `Error::NamespaceCycle` if an alias refers back to
+    /// itself.
+    pub fn resolve_namespace(&self, name: &str) -> Result<PathBuf, Error> {
+        return self.resolve_namespace_chain(name, Vec::new());
+    }
+
+    /// Follow one link of a namespace alias chain. `chain` holds the names
+    /// already visited, so a repeat means the aliases loop.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownNamespace` or `Error::NamespaceCycle`.
+    fn resolve_namespace_chain(&self, name: &str, mut chain: Vec<String>) -> Result<PathBuf, Error> {
+        let is_cycle = chain.iter().any(|visited| return visited == name);
+        chain.push(name.to_string());
+        if is_cycle {
+            return Err(Error::NamespaceCycle { chain });
+        }
+
+        let entry = self.namespaces.get(name).ok_or_else(|| {
+            return Error::UnknownNamespace {
+                available: self.namespace_names(),
+                name: name.to_string(),
+            };
+        })?;
+
+        let Some((next, rest)) = entry.path.split_once(':') else {
+            let directory = entry.directory();
+            tracing::trace!(?chain, directory = %directory.display(), "resolved namespace");
+            return Ok(directory);
+        };
+        tracing::trace!(namespace = name, alias_of = next, "following namespace alias");
+        return Ok(self.resolve_namespace_chain(next, chain)?.join(rest));
+    }
+
     /// Resolve a potentially namespace-prefixed target to a relative path.
     ///
     /// Targets like `auth:src/lib.rs` are split on the first `:` and the
-    /// namespace prefix is replaced with the mapped directory. Plain paths
-    /// pass through unchanged.
+    /// namespace prefix is replaced with the mapped directory, following
+    /// aliases via [`Config::resolve_namespace`]. Plain paths pass through
+    /// unchanged.
+    ///
+    /// Paths that climb out of the project root, or out of their namespace
+    /// directory, are rejected unless `allow_outside_root` is set or they fall
+    /// under an `external_paths` prefix, so a link like `../../../etc/passwd#root`
+    /// can't make docref read arbitrary files. Relative prefixes are resolved
+    /// against the project root, never the working directory.
     ///
     /// # Errors
     ///
     /// Returns `Error::UnknownNamespace` if the prefix doesn't match any
-    /// configured namespace.
+    /// configured namespace, `Error::NamespaceCycle` if its aliases loop, or
+    /// `Error::PathOutsideRoot` if the path escapes its base directory.
     pub fn resolve_target(&self, target: &Path) -> Result<PathBuf, Error> {
         let target_str = target.to_string_lossy();
         let Some((namespace, path)) = target_str.split_once(':') else {
+            self.reject_escaping_path(target, target, target)?;
             return Ok(target.to_path_buf());
         };
 
-        let entry = self.namespaces.get(namespace).ok_or_else(|| {
-            return Error::UnknownNamespace {
-                name: namespace.to_string(),
-            };
-        })?;
-
-        return Ok(entry.config_root.join(&entry.path).join(path));
+        let resolved = self.resolve_namespace(namespace)?.join(path);
+        self.reject_escaping_path(target, Path::new(path), &resolved)?;
+        return Ok(resolved);
     }
 
     /// Default config that includes everything and excludes nothing.
     fn scan_everything_by_default() -> Self {
         return Self {
+            allow_outside_root: false,
             exclude: Vec::new(),
+            external_paths: Vec::new(),
+            grammars: grammar::Grammars::default(),
+            hash_mode: HashMode::Body,
             include: Vec::new(),
+            markdown_extensions: default_markdown_extensions(),
             namespaces: HashMap::new(),
+            record_acceptance: false,
+            severity: SeverityConfig::default(),
+            shortcode: DEFAULT_SHORTCODE.to_string(),
+            slug_style: SlugStyle::default(),
+            teams: HashMap::new(),
+            wiki_links: false,
+            workspace: WorkspaceConfig::default(),
         };
     }
 
@@ -208,23 +364,111 @@ impl Config {
             .iter()
             .any(|p| return relative_path.starts_with(p.as_str()));
     }
+
+    /// The team that owns a code path, by the longest matching `[teams]` prefix.
+    pub fn team_for(&self, path: &Path) -> Option<&str> {
+        let path = path.to_string_lossy();
+        return self
+            .teams
+            .iter()
+            .flat_map(|(team, prefixes)| return prefixes.iter().map(move |p| return (team, p)))
+            .filter(|(_, prefix)| return path.starts_with(prefix.as_str()))
+            .max_by(|a, b| return a.1.len().cmp(&b.1.len()).then_with(|| return b.0.cmp(a.0)))
+            .map(|(team, _)| return team.as_str());
+    }
 }
 
 /// Raw TOML structure for `.docref.toml`.
 #[derive(serde::Deserialize)]
 struct DocrefTomlConfig {
+    /// Whether targets may resolve outside the project root.
+    #[serde(default)]
+    allow_outside_root: bool,
     /// Glob patterns for paths to exclude.
     #[serde(default)]
     exclude: Vec<String>,
     /// Path to a parent config file to inherit from.
     #[serde(default)]
     extends: Option<String>,
+    /// Absolute or out-of-root path prefixes targets may resolve into.
+    #[serde(default)]
+    external_paths: Vec<PathBuf>,
+    /// Grammars to load at runtime, by file extension.
+    #[serde(default)]
+    grammars: HashMap<String, GrammarConfig>,
+    /// Default hash mode for unannotated links.
+    #[serde(default)]
+    hash_mode: HashMode,
     /// Glob patterns for paths to include.
     #[serde(default)]
     include: Vec<String>,
+    /// Markdown file extensions to scan, overriding the default.
+    #[serde(default)]
+    markdown_extensions: Option<Vec<String>>,
     /// Namespace prefix-to-path mappings.
     #[serde(default)]
     namespaces: HashMap<String, String>,
+    /// Whether `update` records acceptance provenance on entries.
+    #[serde(default)]
+    record_acceptance: bool,
+    /// Per-finding severity overrides from `[severity]`.
+    #[serde(default)]
+    severity: SeverityConfig,
+    /// Shortcode name to scan, overriding the default.
+    #[serde(default)]
+    shortcode: Option<String>,
+    /// Heading slug algorithm for markdown targets.
+    #[serde(default)]
+    slug_style: SlugStyle,
+    /// Team name to owned code path prefixes.
+    #[serde(default)]
+    teams: HashMap<String, Vec<String>>,
+    /// Whether to scan wiki links.
+    #[serde(default)]
+    wiki_links: bool,
+    /// Workspace member projects.
+    #[serde(default)]
+    workspace: WorkspaceConfig,
+}
+
+/// Kind of non-fresh result `check` can report for a lockfile entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Finding {
+    /// A bare symbol now matches more than one declaration.
+    Ambiguous,
+    /// The target file, namespace, or symbol is gone.
+    Broken,
+    /// The markdown file that declared the reference no longer exists.
+    Orphaned,
+    /// The referenced code changed since it was locked.
+    Stale,
+}
+
+impl Finding {
+    /// Lowercase name, as written in `[severity]` and reports.
+    pub const fn name(self) -> &'static str {
+        return match self {
+            Self::Ambiguous => "ambiguous",
+            Self::Broken => "broken",
+            Self::Orphaned => "orphaned",
+            Self::Stale => "stale",
+        };
+    }
+}
+
+/// A `[grammars.<ext>]` table: a compiled tree-sitter grammar loaded at
+/// runtime for files with that extension.
+#[derive(Debug, serde::Deserialize)]
+struct GrammarConfig {
+    /// Shared library holding the grammar, relative to the config's directory.
+    library: PathBuf,
+    /// Grammar name, whose `tree_sitter_<name>` function the library
+    /// exports. Defaults to the extension.
+    #[serde(default)]
+    name: Option<String>,
+    /// Query whose `@declaration` captures are symbols named by `@name`.
+    query: String,
 }
 
 /// A namespace mapping from a config file.
@@ -241,6 +485,211 @@ pub struct NamespaceEntry {
     pub path: String,
 }
 
+impl NamespaceEntry {
+    /// The `.docref.toml` that defined this entry, relative to the load root.
+    pub fn config_file(&self) -> PathBuf {
+        return self.config_root.join(".docref.toml");
+    }
+
+    /// The mapped directory, relative to the load root.
+    pub fn directory(&self) -> PathBuf {
+        return self.config_root.join(&self.path);
+    }
+
+    /// Whether this entry was inherited from a parent config via `extends`.
+    pub fn is_inherited(&self) -> bool {
+        return !self.config_root.as_os_str().is_empty();
+    }
+}
+
+/// How seriously `check` treats a finding.
+///
+/// Only `Error` findings affect the exit code. `Warning` findings are
+/// printed but leave the exit code at 0, and `Ignore` findings are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Reported and fails the check.
+    Error,
+    /// Neither reported nor counted.
+    Ignore,
+    /// Reported without failing the check.
+    Warning,
+}
+
+impl Severity {
+    /// Lower this severity to at most `max`, e.g. for `--max-severity warning`.
+    pub const fn capped_at(self, max: Self) -> Self {
+        if self.rank() > max.rank() {
+            return max;
+        }
+        return self;
+    }
+
+    /// Ordering from least to most severe.
+    const fn rank(self) -> u8 {
+        return match self {
+            Self::Error => 2,
+            Self::Ignore => 0,
+            Self::Warning => 1,
+        };
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    /// Parse `ignore`, `warning`, or `error`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "error" => Ok(Self::Error),
+            "ignore" => Ok(Self::Ignore),
+            "warning" => Ok(Self::Warning),
+            _ => Err(format!("unknown severity `{s}` (expected ignore, warning, or error)")),
+        };
+    }
+}
+
+/// Severity for each kind of check finding, from the `[severity]` table.
+///
+/// Defaults keep the historical behavior: stale, broken, and ambiguous
+/// references fail the check; orphaned entries (whose markdown source no
+/// longer exists) are only warned about. `[[severity.override]]` entries
+/// adjust individual kinds for markdown sources under a path prefix.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct SeverityConfig {
+    /// Severity for [`Finding::Ambiguous`].
+    pub ambiguous: Severity,
+    /// Severity for [`Finding::Broken`].
+    pub broken: Severity,
+    /// Severity for [`Finding::Orphaned`].
+    pub orphaned: Severity,
+    /// Path-scoped adjustments, applied in order so later entries win.
+    #[serde(rename = "override")]
+    pub overrides: Vec<SeverityOverride>,
+    /// Severity for [`Finding::Stale`].
+    pub stale: Severity,
+    /// Days a reference may stay stale as a warning before `stale` applies,
+    /// judged from git history. Unset applies `stale` right away.
+    pub stale_grace_days: Option<u64>,
+}
+
+impl SeverityConfig {
+    /// Severity of `finding` for a reference declared in the markdown file `source`.
+    pub fn for_finding(&self, finding: Finding, source: &Path) -> Severity {
+        let source = source.to_string_lossy();
+        return self
+            .overrides
+            .iter()
+            .rev()
+            .filter(|o| return source.starts_with(o.path.as_str()))
+            .find_map(|o| return o.get(finding))
+            .unwrap_or_else(|| {
+                return match finding {
+                    Finding::Ambiguous => self.ambiguous,
+                    Finding::Broken => self.broken,
+                    Finding::Orphaned => self.orphaned,
+                    Finding::Stale => self.stale,
+                };
+            });
+    }
+}
+
+impl Default for SeverityConfig {
+    /// Fail on stale, broken, and ambiguous; warn on orphaned.
+    fn default() -> Self {
+        return Self {
+            ambiguous: Severity::Error,
+            broken: Severity::Error,
+            orphaned: Severity::Warning,
+            overrides: Vec::new(),
+            stale: Severity::Error,
+            stale_grace_days: None,
+        };
+    }
+}
+
+/// Severity adjustments for markdown sources under a path prefix.
+///
+/// Kinds left unset fall through to earlier overrides and then to the
+/// top-level `[severity]` values.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct SeverityOverride {
+    /// Severity for [`Finding::Ambiguous`] under this prefix.
+    #[serde(default)]
+    pub ambiguous: Option<Severity>,
+    /// Severity for [`Finding::Broken`] under this prefix.
+    #[serde(default)]
+    pub broken: Option<Severity>,
+    /// Severity for [`Finding::Orphaned`] under this prefix.
+    #[serde(default)]
+    pub orphaned: Option<Severity>,
+    /// Markdown path prefix this override applies to, e.g. `docs/drafts/`.
+    pub path: String,
+    /// Severity for [`Finding::Stale`] under this prefix.
+    #[serde(default)]
+    pub stale: Option<Severity>,
+}
+
+impl SeverityOverride {
+    /// The severity this override sets for `finding`, if any.
+    const fn get(&self, finding: Finding) -> Option<Severity> {
+        return match finding {
+            Finding::Ambiguous => self.ambiguous,
+            Finding::Broken => self.broken,
+            Finding::Orphaned => self.orphaned,
+            Finding::Stale => self.stale,
+        };
+    }
+}
+
+/// How markdown heading text is converted into addressable slugs.
+///
+/// `Default` collapses any run of non-alphanumeric characters into a single
+/// hyphen. `Github` follows GitHub's anchor rules: punctuation is dropped,
+/// each space becomes a hyphen, and repeated slugs in a document get `-1`,
+/// `-2` suffixes, so anchors copied from rendered GitHub pages resolve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugStyle {
+    /// docref's original slugger.
+    #[default]
+    Default,
+    /// GitHub-flavored markdown anchor slugs.
+    Github,
+}
+
+/// The `[workspace]` table: member projects checked by `workspace status`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    /// Project directories relative to the workspace root, each with its own
+    /// `.docref.toml` and `.docref.lock`. Empty means discover them.
+    pub members: Vec<PathBuf>,
+}
+
+/// The markdown extensions scanned by default, as owned strings.
+fn default_markdown_extensions() -> Vec<String> {
+    return DEFAULT_MARKDOWN_EXTENSIONS.iter().map(ToString::to_string).collect();
+}
+
+/// Whether a relative path is absolute or climbs above its starting directory
+/// through `..` components. Decided lexically, without touching the filesystem.
+fn escapes_base(path: &Path) -> bool {
+    let mut depth = 0_usize;
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::Normal(_) => depth = depth.saturating_add(1),
+            Component::ParentDir if depth == 0 => return true,
+            Component::ParentDir => depth = depth.saturating_sub(1),
+            Component::Prefix(_) | Component::RootDir => return true,
+        }
+    }
+    return false;
+}
+
 #[cfg(test)]
 #[allow(clippy::missing_panics_doc, reason = "test code uses unwrap freely")]
 mod tests {
@@ -344,6 +793,35 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn grammars_belong_to_the_config_that_declares_them() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let grammar = |library: &str| {
+            return format!("[grammars.hcl]\nlibrary = \"{library}\"\nquery = \"(block) @x\"\n");
+        };
+        let root = tmp.path().join("root");
+        let child = root.join("child");
+        let sibling = tmp.path().join("sibling");
+        for dir in [&child, &sibling] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(root.join(".docref.toml"), grammar("root.so")).unwrap();
+        std::fs::write(child.join(".docref.toml"), "extends = \"../.docref.toml\"\n").unwrap();
+        std::fs::write(sibling.join(".docref.toml"), grammar("sibling.so")).unwrap();
+
+        let configs = [&root, &child, &sibling].map(|dir| return Config::load(dir).unwrap());
+        let libraries: Vec<String> = configs
+            .iter()
+            .map(|config| {
+                return grammar::language_for_path(Path::new("main.hcl"), &config.grammars).unwrap_err().to_string();
+            })
+            .collect();
+        assert!(libraries[0].contains("root.so"), "{}", libraries[0]);
+        assert!(libraries[1].contains("root.so"), "inherited from the parent: {}", libraries[1]);
+        assert!(libraries[2].contains("sibling.so"), "{}", libraries[2]);
+        assert_eq!(grammar::fingerprint(&configs[0].grammars), grammar::fingerprint(&configs[1].grammars));
+    }
+
     #[test]
     fn loads_namespaces_from_config() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -361,6 +839,115 @@ shared = "packages/shared"
         assert_eq!(config.namespaces.len(), 2);
     }
 
+    #[test]
+    fn loads_severity_overrides_with_defaults() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "[severity]\nstale = \"warning\"\n").unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        assert_eq!(config.severity.stale, Severity::Warning);
+        assert_eq!(config.severity.broken, Severity::Error);
+        assert_eq!(config.severity.orphaned, Severity::Warning);
+    }
+
+    #[test]
+    fn loads_hash_mode_from_config() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "hash_mode = \"docs\"\n").unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        assert_eq!(config.hash_mode, HashMode::Docs);
+    }
+
+    #[test]
+    fn loads_slug_style_from_config() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "slug_style = \"github\"\n").unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        assert_eq!(config.slug_style, SlugStyle::Github);
+    }
+
+    #[test]
+    fn resolve_target_follows_namespace_aliases() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join(".docref.toml"),
+            r#"
+[namespaces]
+auth = "services/auth"
+authv2 = "auth:v2"
+current = "authv2:src"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        let resolved = config
+            .resolve_target(&PathBuf::from("current:lib.rs"))
+            .unwrap();
+        assert_eq!(resolved, PathBuf::from("services/auth/v2/src/lib.rs"));
+    }
+
+    #[test]
+    fn resolve_target_namespace_alias_cycle_errors() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join(".docref.toml"),
+            r#"
+[namespaces]
+a = "b:x"
+b = "a:y"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        let err = config
+            .resolve_target(&PathBuf::from("a:lib.rs"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("a -> b -> a"), "expected cycle chain: {err}");
+    }
+
+    #[test]
+    fn resolve_target_rejects_paths_outside_root() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "[namespaces]\nauth = \"services/auth\"\n").unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        for target in ["../../etc/passwd", "/etc/passwd", "src/../../lib.rs", "auth:../billing/lib.rs"] {
+            let result = config.resolve_target(&PathBuf::from(target));
+            assert!(matches!(result, Err(Error::PathOutsideRoot { .. })), "{target} should be rejected");
+        }
+        assert!(config.resolve_target(&PathBuf::from("src/../lib.rs")).is_ok());
+    }
+
+    #[test]
+    fn resolve_target_allows_outside_root_when_configured() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "allow_outside_root = true\n").unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        let resolved = config.resolve_target(&PathBuf::from("../sdk/lib.rs")).unwrap();
+        assert_eq!(resolved, PathBuf::from("../sdk/lib.rs"));
+    }
+
+    #[test]
+    fn resolve_target_allows_listed_external_paths() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "external_paths = [\"../sdk/\", \"/opt/sdk\"]\n").unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        for allowed in ["../sdk/src/lib.rs", "docs/../../sdk/lib.rs", "/opt/sdk/lib.rs"] {
+            assert!(config.resolve_target(&PathBuf::from(allowed)).is_ok(), "{allowed} is listed");
+        }
+        for rejected in ["../sdk-old/lib.rs", "../other/lib.rs", "/opt/lib.rs"] {
+            let result = config.resolve_target(&PathBuf::from(rejected));
+            assert!(matches!(result, Err(Error::PathOutsideRoot { .. })), "{rejected} is not listed");
+        }
+    }
+
     #[test]
     fn resolve_target_unknown_namespace_errors() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -397,4 +984,41 @@ auth = "services/auth"
             .unwrap();
         assert_eq!(resolved, PathBuf::from("src/lib.rs"));
     }
+
+    #[test]
+    fn team_for_picks_longest_matching_prefix() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join(".docref.toml"),
+            "[teams]\nplatform = [\"services/\"]\nidentity = [\"services/auth/\", \"docs/auth/\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        assert_eq!(config.team_for(Path::new("services/auth/src/lib.rs")), Some("identity"));
+        assert_eq!(config.team_for(Path::new("services/billing/main.go")), Some("platform"));
+        assert_eq!(config.team_for(Path::new("src/lib.rs")), None);
+    }
+
+    #[test]
+    fn severity_capped_at_lowers_but_never_raises() {
+        assert_eq!(Severity::Error.capped_at(Severity::Warning), Severity::Warning);
+        assert_eq!(Severity::Ignore.capped_at(Severity::Error), Severity::Ignore);
+    }
+
+    #[test]
+    fn severity_override_applies_by_source_prefix() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join(".docref.toml"),
+            "[severity]\norphaned = \"error\"\n\n[[severity.override]]\npath = \"docs/drafts/\"\nstale = \"warning\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(tmp.path()).unwrap();
+        let severity = &config.severity;
+        assert_eq!(severity.for_finding(Finding::Stale, Path::new("docs/drafts/a.md")), Severity::Warning);
+        assert_eq!(severity.for_finding(Finding::Stale, Path::new("docs/guide.md")), Severity::Error);
+        assert_eq!(severity.for_finding(Finding::Orphaned, Path::new("docs/drafts/a.md")), Severity::Error);
+    }
 }
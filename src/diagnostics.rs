@@ -6,13 +6,32 @@
 use std::fmt::Write as _;
 
 use crate::error::Error;
-use crate::types::SourceRef;
+use crate::types::{ReferenceFailure, SourceRef};
 
 /// ANSI escape code for bold text.
 const BOLD: &str = "\x1b[1m";
 /// ANSI escape code to reset text formatting.
 const RESET: &str = "\x1b[0m";
 
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    for ca in a.chars() {
+        let mut current: Vec<usize> = Vec::with_capacity(previous.len());
+        current.push(previous.first().map_or(0, |d| return d.saturating_add(1)));
+        for (window, cb) in previous.windows(2).zip(&b_chars) {
+            let left = current.last().copied().unwrap_or_default();
+            let diagonal = window.first().copied().unwrap_or_default();
+            let up = window.last().copied().unwrap_or_default();
+            let substitution = diagonal.saturating_add(usize::from(ca != *cb));
+            current.push(substitution.min(up.saturating_add(1)).min(left.saturating_add(1)));
+        }
+        previous = current;
+    }
+    return previous.last().copied().unwrap_or_default();
+}
+
 /// Find the closest matching suggestion by stripping generics and comparing.
 pub(crate) fn find_closest_suggestion(symbol: &str, suggestions: &[String]) -> Option<String> {
     let normalized = strip_generics(symbol);
@@ -21,6 +40,18 @@ pub(crate) fn find_closest_suggestion(symbol: &str, suggestions: &[String]) -> O
         .cloned();
 }
 
+/// Find the candidate nearest to `name` by edit distance, if any is close
+/// enough to be a plausible typo (at most half the name's length).
+pub(crate) fn find_nearest_name(name: &str, candidates: &[String]) -> Option<String> {
+    let max_distance = (name.chars().count() / 2).max(1);
+    return candidates
+        .iter()
+        .map(|c| return (edit_distance(name, c), c))
+        .filter(|(distance, _)| return *distance <= max_distance)
+        .min_by_key(|(distance, _)| return *distance)
+        .map(|(_, c)| return c.clone());
+}
+
 /// Render an error as valid markdown with bold headings and print to stderr.
 pub fn print_error(e: &Error) {
     let md = render_error(e);
@@ -104,12 +135,16 @@ pub fn render_error(e: &Error) -> String {
         Error::SymbolNotFound { file, symbol, suggestions, referenced_from } => {
             render_symbol_not_found(&file.display().to_string(), symbol, suggestions, referenced_from)
         },
+        Error::SymbolPresent { file, symbol } => render_symbol_present(&file.display().to_string(), symbol),
         Error::AmbiguousSymbol { file, symbol, candidates } => {
             render_ambiguous_symbol(&file.display().to_string(), symbol, candidates)
         },
         Error::UnsupportedLanguage { ext } => render_unsupported_language(ext),
-        Error::UnknownNamespace { name } => render_unknown_namespace(name),
+        Error::UnknownNamespace { name, available } => render_unknown_namespace(name, available),
+        Error::PathOutsideRoot { path } => render_path_outside_root(path),
+        Error::UnresolvedReferences { failures } => render_unresolved_references(failures),
         Error::ConfigCycle { chain } => render_config_cycle(chain),
+        Error::NamespaceCycle { chain } => render_namespace_cycle(chain),
         Error::NamespaceInUse { name, count } => render_namespace_in_use(name, *count),
         Error::FileTooLarge { file, size_bytes, max_bytes } => render_file_too_large(file, *size_bytes, *max_bytes),
         _ => render_generic(e),
@@ -139,6 +174,7 @@ fn render_generic(e: &Error) -> String {
     return match e {
         Error::FileNotFound { path } => render_file_not_found(path),
         Error::ConfigNotFound { path } => render_config_not_found(path),
+        Error::GrammarLoad { ext, reason } => render_grammar_load(ext, reason),
         Error::LockfileCorrupt { reason } => render_lockfile_corrupt(reason),
         Error::ParseFailed { file, reason } => render_parse_failed(file, reason),
         Error::Io(e) => format!("# Error: I/O\n\n{e}\n"),
@@ -149,6 +185,20 @@ fn render_generic(e: &Error) -> String {
     };
 }
 
+/// Render a grammar-load diagnostic pointing at the `[grammars]` entry.
+fn render_grammar_load(ext: &str, reason: &str) -> String {
+    return format!("\
+# Error: Grammar Not Loaded
+
+The grammar for `.{ext}` could not be loaded: {reason}
+
+## Fix
+
+Check `[grammars.{ext}]` in `.docref.toml`: `library` must be a tree-sitter grammar compiled as a shared library
+for this platform, exporting `tree_sitter_<name>`, and `query` must be valid for it.
+");
+}
+
 /// Render a lockfile-corrupt diagnostic with regeneration instructions.
 fn render_lockfile_corrupt(reason: &str) -> String {
     return format!("\
@@ -180,6 +230,24 @@ Run `docref init` to scan markdown and generate the lockfile:
     .to_string();
 }
 
+/// Render a namespace cycle diagnostic showing the alias chain.
+fn render_namespace_cycle(chain: &[String]) -> String {
+    let chain_str = chain.join(" -> ");
+
+    return format!(
+        "\
+# Error: Namespace Cycle Detected
+
+Circular namespace aliases: {chain_str}
+
+## Fix
+
+Point one of these namespaces at a directory instead of another namespace
+in `.docref.toml`.
+"
+    );
+}
+
 /// Render a namespace-in-use diagnostic showing reference count and force option.
 fn render_namespace_in_use(name: &str, count: usize) -> String {
     return format!(
@@ -206,6 +274,23 @@ Could not parse `{}`: {reason}
 ", file.display());
 }
 
+/// Render a path-outside-root diagnostic with namespace and opt-out hints.
+fn render_path_outside_root(path: &std::path::Path) -> String {
+    return format!("\
+# Error: Target Outside Project Root
+
+`{}` resolves outside the project root (or its namespace directory).
+docref refuses to read files there by default.
+
+## Fix
+
+Point the link at a file inside the project, map the directory with
+`docref namespace add <name> <path>`, or allow its prefix in `.docref.toml`:
+
+    external_paths = [\"../sdk/\"]
+", path.display());
+}
+
 /// Render a symbol-not-found diagnostic with suggestions and fix hints.
 fn render_symbol_not_found(
     file: &str,
@@ -250,15 +335,43 @@ Symbol `{symbol}` does not exist in `{file}`.
     return out;
 }
 
-/// Render an unknown-namespace diagnostic with configuration instructions.
-fn render_unknown_namespace(name: &str) -> String {
+/// Render a diagnostic for a symbol that was documented as removed but exists.
+fn render_symbol_present(file: &str, symbol: &str) -> String {
     return format!(
         "\
+# Error: Symbol Still Present
+
+Symbol `{symbol}` exists in `{file}`, but the docs assert it was removed.
+
+## Fix
+
+Remove the symbol, or drop the `docref: absent` annotation from the link
+if the symbol is back on purpose.
+"
+    );
+}
+
+/// Render an unknown-namespace diagnostic with a did-you-mean suggestion,
+/// the configured namespaces, and configuration instructions.
+fn render_unknown_namespace(name: &str, available: &[String]) -> String {
+    let mut out = format!("\
 # Error: Unknown Namespace
 
 Namespace `{name}` is not configured.
+");
 
-## Fix
+    if let Some(suggestion) = find_nearest_name(name, available) {
+        let _ = write!(out, "\n## Did you mean `{suggestion}:`?\n");
+    }
+    if !available.is_empty() {
+        out.push_str("\n## Configured namespaces\n\n");
+        for ns in available {
+            let _ = writeln!(out, "- `{ns}`");
+        }
+    }
+
+    let _ = write!(out, "\
+\n## Fix
 
 Add it to `.docref.toml`:
 
@@ -268,8 +381,39 @@ Add it to `.docref.toml`:
 Or run:
 
     docref namespace add {name} path/to/{name}
-"
-    );
+");
+    return out;
+}
+
+/// Render every failed reference from a batch run, grouped by markdown file.
+fn render_unresolved_references(failures: &[ReferenceFailure]) -> String {
+    let mut out = format!("\
+# Error: Unresolved References
+
+{} references could not be resolved.
+", failures.len());
+
+    let mut current_file = None;
+    for failure in failures {
+        if current_file != Some(&failure.source) {
+            let _ = write!(out, "\n## {}\n\n", failure.source.display());
+            current_file = Some(&failure.source);
+        }
+        let _ = writeln!(out, "- line {}: `{}`: {}", failure.source_line, failure.target, failure.reason);
+    }
+
+    out.push_str("\
+\n## Fix
+
+Repair renamed symbols automatically where a close match exists:
+
+    docref fix
+
+List the symbols a file provides:
+
+    docref resolve <file>
+");
+    return out;
 }
 
 /// Render an unsupported-language diagnostic listing supported extensions.
@@ -290,7 +434,7 @@ No tree-sitter grammar for `.{ext}` files.
 }
 
 /// Remove generic parameters (`<...>`) from a symbol name for fuzzy comparison.
-fn strip_generics(s: &str) -> String {
+pub(crate) fn strip_generics(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
     let mut depth = 0_u32;
     for ch in s.chars() {
@@ -0,0 +1,194 @@
+//! Embeddable entry point for driving docref from other tools.
+//!
+//! An [`Engine`] wraps a project root and its configuration. Build one with
+//! [`Engine::builder`], optionally registering an event callback to render
+//! progress as markdown files are scanned and lockfile entries are checked.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::freshness::{
+    CheckResult, compare_lockfile_entries_against_source, resolve_and_hash_all_references_with_progress,
+};
+use crate::lockfile::{LockEntry, Lockfile};
+use crate::scanner;
+use crate::types::Reference;
+
+/// A configured docref project that can scan, lock, and check references.
+pub struct Engine {
+    /// Loaded or overridden project configuration.
+    config: Config,
+    /// Progress callback, if one was registered.
+    on_event: Option<EventCallback>,
+    /// Project root that all paths are relative to.
+    root: PathBuf,
+}
+
+impl Engine {
+    /// Start configuring an engine rooted at the current directory.
+    pub fn builder() -> EngineBuilder {
+        return EngineBuilder {
+            config: None,
+            on_event: None,
+            root: PathBuf::from("."),
+        };
+    }
+
+    /// Compare every lockfile entry against the current source.
+    ///
+    /// Emits `EntryChecked` per entry and `Finished` at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from lockfile reading or hash computation.
+    pub fn check(&self) -> Result<Vec<(LockEntry, CheckResult)>, Error> {
+        let lockfile = Lockfile::read(&self.lock_path())?;
+        let tracked: Vec<&LockEntry> = lockfile.entries.iter().collect();
+        let checked = compare_lockfile_entries_against_source(&self.root, &self.config, &tracked)?;
+        let mut results = Vec::with_capacity(lockfile.entries.len());
+        for (entry, result) in lockfile.entries.into_iter().zip(checked) {
+            self.emit(&Event::EntryChecked { entry: &entry, result: &result });
+            results.push((entry, result));
+        }
+        self.emit(&Event::Finished { references: results.len() });
+        return Ok(results);
+    }
+
+    /// The configuration this engine runs with.
+    pub const fn config(&self) -> &Config {
+        return &self.config;
+    }
+
+    /// Forward an event to the registered callback, if any.
+    fn emit(&self, event: &Event<'_>) {
+        if let Some(callback) = &self.on_event {
+            callback(event);
+        }
+        return;
+    }
+
+    /// Scan markdown, resolve and hash every reference, and write the lockfile.
+    ///
+    /// Emits `FileScanned` per markdown file, `TargetHashed` per target file,
+    /// and `Finished` at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from scanning, resolution, hashing, or lockfile writing.
+    pub fn init(&self) -> Result<Lockfile, Error> {
+        let grouped = self.scan()?;
+        let entries =
+            resolve_and_hash_all_references_with_progress(&self.root, &self.config, &grouped, &mut |path, references| {
+                self.emit(&Event::TargetHashed { path, references });
+            })?;
+        let mut lockfile = Lockfile::new(entries);
+        if let Ok(previous) = Lockfile::read(&self.lock_path()) {
+            lockfile.carry_metadata_from(&previous);
+        }
+        lockfile.write(&self.lock_path())?;
+        self.emit(&Event::Finished { references: lockfile.entries.len() });
+        return Ok(lockfile);
+    }
+
+    /// Path of the project lockfile.
+    fn lock_path(&self) -> PathBuf {
+        return self.root.join(".docref.lock");
+    }
+
+    /// The project root this engine runs against.
+    pub fn root(&self) -> &Path {
+        return &self.root;
+    }
+
+    /// Scan markdown for references, grouped by target file.
+    ///
+    /// Emits `FileScanned` per markdown file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if any markdown file cannot be read.
+    pub fn scan(&self) -> Result<HashMap<PathBuf, Vec<Reference>>, Error> {
+        return scanner::scan_with_progress(&self.root, &self.config, &mut |path, references| {
+            self.emit(&Event::FileScanned { path, references });
+        });
+    }
+}
+
+/// Builder for [`Engine`]. Obtain one from [`Engine::builder`].
+pub struct EngineBuilder {
+    /// Config to use instead of loading `.docref.toml` from the root.
+    config: Option<Config>,
+    /// Progress callback to install on the engine.
+    on_event: Option<EventCallback>,
+    /// Project root; defaults to the current directory.
+    root: PathBuf,
+}
+
+impl EngineBuilder {
+    /// Finish building, loading `.docref.toml` from the root unless a config
+    /// override was given.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from config loading.
+    pub fn build(self) -> Result<Engine, Error> {
+        let config = match self.config {
+            Some(config) => config,
+            None => Config::load(&self.root)?,
+        };
+        return Ok(Engine { config, on_event: self.on_event, root: self.root });
+    }
+
+    /// Use this config instead of loading `.docref.toml` from the root.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        return self;
+    }
+
+    /// Call `callback` with progress events as the engine runs.
+    pub fn on_event(mut self, callback: impl Fn(&Event<'_>) + Send + Sync + 'static) -> Self {
+        self.on_event = Some(Box::new(callback));
+        return self;
+    }
+
+    /// Run against this project root instead of the current directory.
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        return self;
+    }
+}
+
+/// Progress reported to an [`Engine`] event callback.
+pub enum Event<'a> {
+    /// A lockfile entry was compared against its current source.
+    EntryChecked {
+        /// The entry that was checked.
+        entry: &'a LockEntry,
+        /// How the entry compared.
+        result: &'a CheckResult,
+    },
+    /// A markdown file was scanned for references.
+    FileScanned {
+        /// Markdown file, relative to the project root.
+        path: &'a Path,
+        /// References found in the file.
+        references: usize,
+    },
+    /// A run finished after processing this many references.
+    Finished {
+        /// References locked or checked by the run.
+        references: usize,
+    },
+    /// Every reference to one target file was resolved and hashed.
+    TargetHashed {
+        /// Target file, as written in the references (possibly namespaced).
+        path: &'a Path,
+        /// References to the file that were processed.
+        references: usize,
+    },
+}
+
+/// Boxed progress callback shared by [`Engine`] and [`EngineBuilder`].
+type EventCallback = Box<dyn Fn(&Event<'_>) + Send + Sync>;
@@ -1,7 +1,7 @@
 /// Crate-level error types for docref diagnostics.
 use std::path::PathBuf;
 
-use crate::types::SourceRef;
+use crate::types::{ReferenceFailure, SourceRef};
 
 /// All errors in docref carry enough context to produce a useful diagnostic
 /// without a debugger. Each variant names the file, symbol, or reason for failure.
@@ -55,6 +55,15 @@ pub enum Error {
         size_bytes: u64,
     },
 
+    /// A `[grammars]` entry couldn't be loaded.
+    #[error("could not load grammar for .{ext}: {reason}")]
+    GrammarLoad {
+        /// File extension the grammar was configured for.
+        ext: String,
+        /// What went wrong: the library, its language function, or the query.
+        reason: String,
+    },
+
     /// Underlying I/O error from the filesystem.
     #[error("io: {0}")]
     Io(
@@ -77,6 +86,13 @@ pub enum Error {
         path: PathBuf,
     },
 
+    /// Namespace aliases refer back to themselves.
+    #[error("namespace cycle detected: {}", chain.join(" -> "))]
+    NamespaceCycle {
+        /// Ordered chain of namespace names forming the cycle.
+        chain: Vec<String>,
+    },
+
     /// A namespace still has active references and cannot be removed without `--force`.
     #[error("namespace `{name}` is in use by {count} references (use --force to remove)")]
     NamespaceInUse {
@@ -86,6 +102,15 @@ pub enum Error {
         name: String,
     },
 
+    /// A line range or whole-file query was given where a symbol is needed.
+    #[error("not a symbol: `{query}` in {} names lines, not a declaration", file.display())]
+    NotASymbol {
+        /// File the query was made against.
+        file: PathBuf,
+        /// The query as written.
+        query: String,
+    },
+
     /// Tree-sitter failed to parse a source file.
     #[error("parse failed: {}: {reason}", file.display())]
     ParseFailed {
@@ -95,6 +120,13 @@ pub enum Error {
         reason: String,
     },
 
+    /// A reference target resolves outside the project root or its namespace directory.
+    #[error("target outside project root: {}", path.display())]
+    PathOutsideRoot {
+        /// The target as written in the reference.
+        path: PathBuf,
+    },
+
     /// A referenced symbol does not exist in the target file.
     #[error("symbol not found: `{symbol}` in {}", file.display())]
     SymbolNotFound {
@@ -108,6 +140,15 @@ pub enum Error {
         symbol: String,
     },
 
+    /// A symbol documented as removed exists in the target file.
+    #[error("symbol still present: `{symbol}` in {}", file.display())]
+    SymbolPresent {
+        /// File that contains the symbol.
+        file: PathBuf,
+        /// Symbol name asserted to be absent.
+        symbol: String,
+    },
+
     /// TOML deserialization failed.
     #[error("toml deserialize: {0}")]
     TomlDe(
@@ -127,10 +168,19 @@ pub enum Error {
     /// No configured namespace matches the given name.
     #[error("unknown namespace: `{name}`")]
     UnknownNamespace {
+        /// Configured namespace names, sorted, for did-you-mean suggestions.
+        available: Vec<String>,
         /// Namespace identifier that was not found.
         name: String,
     },
 
+    /// Several references failed to resolve in one batch run.
+    #[error("{} references could not be resolved", failures.len())]
+    UnresolvedReferences {
+        /// Every failed reference, sorted by markdown file and line.
+        failures: Vec<ReferenceFailure>,
+    },
+
     /// No tree-sitter grammar registered for this file extension.
     #[error("no grammar for extension: .{ext}")]
     UnsupportedLanguage {
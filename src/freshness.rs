@@ -1,71 +1,430 @@
 //! Freshness checking and batch resolution for lockfile entries.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 
+use tree_sitter::Language;
+
+use crate::cache::{self, HashCache};
 use crate::config;
 use crate::error;
 use crate::grammar;
 use crate::hasher;
 use crate::lockfile::LockEntry;
+use crate::package;
 use crate::resolver;
-use crate::types::{Reference, SourceRef, SymbolQuery};
+use crate::scanner;
+use crate::types::{HashMode, Reference, ReferenceFailure, ResolvedSymbol, SemanticHash, SourceRef, SymbolQuery};
+
+/// How many Python re-export hops a reference may follow, e.g.
+/// `pkg/__init__.py` to `pkg/api/__init__.py` to `pkg/api/errors.py`.
+const MAX_REEXPORT_DEPTH: usize = 4;
 
 /// Result of checking a single lockfile entry.
+#[derive(Clone, Copy)]
 pub enum CheckResult {
+    /// The bare symbol now matches more than one declaration.
+    Ambiguous,
     /// The target file, language, or symbol could not be resolved.
     Broken(&'static str),
     /// The entry hash matches the current source — no changes.
     Fresh,
+    /// The markdown file that declared the reference no longer exists.
+    Orphaned,
     /// The entry hash differs from the current source — symbol body changed.
     Stale,
 }
 
-/// Check one lockfile entry against the current source.
+impl CheckResult {
+    /// The kind of finding this result represents, or `None` when fresh.
+    pub const fn finding(&self) -> Option<config::Finding> {
+        return match *self {
+            Self::Ambiguous => Some(config::Finding::Ambiguous),
+            Self::Broken(_) => Some(config::Finding::Broken),
+            Self::Fresh => None,
+            Self::Orphaned => Some(config::Finding::Orphaned),
+            Self::Stale => Some(config::Finding::Stale),
+        };
+    }
+
+    /// Lowercase status name used in JSON output.
+    pub const fn name(&self) -> &'static str {
+        return match *self {
+            Self::Ambiguous => "ambiguous",
+            Self::Broken(_) => "broken",
+            Self::Fresh => "fresh",
+            Self::Orphaned => "orphaned",
+            Self::Stale => "stale",
+        };
+    }
+
+    /// Why the entry can't be checked, for broken and orphaned results.
+    pub const fn reason(&self) -> Option<&'static str> {
+        return match *self {
+            Self::Ambiguous | Self::Fresh | Self::Stale => None,
+            Self::Broken(reason) => Some(reason),
+            Self::Orphaned => Some("source file not found"),
+        };
+    }
+}
+
+/// How a type's members differ from those a wildcard entry was locked with.
+pub struct MemberChanges {
+    /// Members declared now that have no lockfile entry.
+    pub added: Vec<String>,
+    /// Members with a lockfile entry that are no longer declared.
+    pub removed: Vec<String>,
+}
+
+/// A target file's content, parsed at most once however many queries are
+/// hashed against it.
+struct ParsedSource<'a> {
+    /// Hashes from earlier runs, with the digest of this file's content.
+    cache: Option<(&'a mut HashCache, String)>,
+    /// The project's config, for its slug style and `[grammars]`.
+    config: &'a config::Config,
+    /// Declarations, parsed by the first query that needs them.
+    declarations: Option<resolver::Declarations>,
+    /// The file's path relative to the project root.
+    disk_path: &'a Path,
+    /// The file's content.
+    source: &'a str,
+}
+
+impl<'a> ParsedSource<'a> {
+    /// Confirm that a symbol asserted to be absent really is.
+    ///
+    /// Files without a grammar have no symbols, so they always pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SymbolPresent` if the symbol resolves, even ambiguously,
+    /// or parse errors.
+    fn confirm_absent(&mut self, query: &SymbolQuery) -> Result<SemanticHash, error::Error> {
+        let Ok(language) = grammar::language_for_path(self.disk_path, &self.config.grammars) else {
+            return Ok(absent_hash());
+        };
+        return match self.declarations(&language)?.resolve(query) {
+            Err(error::Error::SymbolNotFound { .. }) => Ok(absent_hash()),
+            Err(error::Error::AmbiguousSymbol { .. }) | Ok(_) => Err(error::Error::SymbolPresent {
+                file: self.disk_path.to_path_buf(),
+                symbol: query.display_name(),
+            }),
+            Err(e) => Err(e),
+        };
+    }
+
+    /// The source's declarations, parsing it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FileTooLarge` or `Error::ParseFailed` from parsing.
+    fn declarations(&mut self, language: &Language) -> Result<&resolver::Declarations, error::Error> {
+        let declarations = match self.declarations.take() {
+            Some(declarations) => declarations,
+            None => {
+                let (slug_style, grammars) = (self.config.slug_style, &self.config.grammars);
+                resolver::Declarations::parse(self.disk_path, self.source, language, slug_style, grammars)?
+            },
+        };
+        return Ok(self.declarations.insert(declarations));
+    }
+
+    /// A loaded lockfile target, or `None` if its path didn't resolve or
+    /// the file couldn't be read.
+    fn for_target(
+        disk_path: &'a Result<PathBuf, error::Error>,
+        source: Option<&'a str>,
+        config: &'a config::Config,
+    ) -> Option<Self> {
+        let disk_path = disk_path.as_ref().ok()?;
+        return source.map(|text| return Self::new(disk_path, text, config));
+    }
+
+    /// Hash a symbol query against the source; see [`hash_query_in_source`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedLanguage` for symbol queries on files without
+    /// a grammar, `Error::SymbolPresent` when an absent symbol exists, or
+    /// resolution and hashing errors.
+    fn hash(&mut self, root: &Path, query: &SymbolQuery, mode: HashMode) -> Result<SemanticHash, error::Error> {
+        let slug_style = self.config.slug_style;
+        let key = self.cache.as_ref().map(|(_, digest)| return HashCache::key(digest, query, mode, slug_style));
+        let cached = self.cache.as_mut().zip(key.as_deref()).and_then(|((cache, _), key)| return cache.get(key));
+        if let Some(hash) = cached {
+            return Ok(hash);
+        }
+        let (mut hash, reusable) = self.hash_uncached(root, query, mode)?;
+        if mode == HashMode::Exists {
+            hash = existence_hash();
+        }
+        if let (Some((cache, _)), Some(key), true) = (self.cache.as_mut(), key, reusable) {
+            cache.insert(key, hash.clone());
+        }
+        return Ok(hash);
+    }
+
+    /// Hash a reference: one hash for most queries, and for a wildcard the
+    /// member list followed by each member, keyed by lockfile symbol.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::hash`].
+    fn hash_reference(
+        &mut self,
+        root: &Path,
+        query: &SymbolQuery,
+        mode: HashMode,
+    ) -> Result<Vec<(String, SemanticHash)>, error::Error> {
+        let mut hashes = vec![(query.display_name(), self.hash(root, query, mode)?)];
+        let SymbolQuery::Members(parent) = query else {
+            return Ok(hashes);
+        };
+        let language = grammar::language_for_path(self.disk_path, &self.config.grammars)?;
+        for member in self.declarations(&language)?.members(parent)? {
+            let hash = self.hash(root, &parse_symbol_query(&member), mode)?;
+            hashes.push((member, hash));
+        }
+        return Ok(hashes);
+    }
+
+    /// Hash a symbol query by parsing the source, and say whether the hash
+    /// depends on this file alone. One found through a Python re-export
+    /// also depends on the defining module, so it can't be cached by this
+    /// file's content.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::hash`].
+    fn hash_uncached(
+        &mut self,
+        root: &Path,
+        query: &SymbolQuery,
+        mode: HashMode,
+    ) -> Result<(SemanticHash, bool), error::Error> {
+        if let SymbolQuery::Absent(inner) = query {
+            return Ok((self.confirm_absent(inner)?, true));
+        }
+        if let SymbolQuery::Lines { end, start } = *query {
+            return Ok((hash_line_range(self.disk_path, self.source, start, end)?, true));
+        }
+        let language = match grammar::language_for_path(self.disk_path, &self.config.grammars) {
+            Err(error::Error::UnsupportedLanguage { .. }) if matches!(query, SymbolQuery::WholeFile) => {
+                return Ok((hasher::hash_raw(self.source), true));
+            },
+            Err(error::Error::UnsupportedLanguage { .. })
+                if is_region(query) || resolver::has_unparsed_symbols(self.disk_path) =>
+            {
+                return Ok((hash_raw_symbol(self.disk_path, self.source, query)?, true));
+            },
+            other => other?,
+        };
+        if matches!(query, SymbolQuery::WholeFile) {
+            return Ok((hasher::hash_file(self.source, &language, mode)?, true));
+        }
+        if let SymbolQuery::Members(parent) = query {
+            let members = self.declarations(&language)?.members(parent)?;
+            return Ok((hasher::hash_raw(&members.join("\n")), true));
+        }
+        let (disk_path, source, config) = (self.disk_path, self.source, self.config);
+        let is_python = disk_path.extension().is_some_and(|ext| return ext == "py");
+        let resolved = match self.declarations(&language)?.resolve(query) {
+            Err(e @ error::Error::SymbolNotFound { .. }) if is_python => {
+                let (module_source, resolved) =
+                    follow_python_reexport(root, disk_path, source, query, config, MAX_REEXPORT_DEPTH).ok_or(e)?;
+                return Ok((hasher::hash_symbol(&module_source, &language, &resolved, mode)?, false));
+            },
+            other => other?,
+        };
+        let parsed = grammar::parsed_source(disk_path, source);
+        return Ok((hasher::hash_symbol(&parsed, &language, &resolved, mode)?, true));
+    }
+
+    /// Wrap already-read source; nothing is parsed until a query needs it.
+    const fn new(disk_path: &'a Path, source: &'a str, config: &'a config::Config) -> Self {
+        return Self { cache: None, config, declarations: None, disk_path, source };
+    }
+
+    /// Look hashes up in `cache` before parsing, and record new ones there.
+    fn with_cache(mut self, cache: &'a mut HashCache) -> Self {
+        self.cache = Some((cache, cache::digest(self.source)));
+        return self;
+    }
+}
+
+/// Hash recorded for absence assertions, which have no symbol body to hash.
+fn absent_hash() -> SemanticHash {
+    return hasher::hash_raw("");
+}
+
+/// Decide how one lockfile entry compares, for [`compare_entry`].
 ///
 /// # Errors
 ///
 /// Returns errors from resolution or hashing that aren't recoverable as broken/stale.
-pub fn compare_lockfile_entry_against_source(
+fn classify_entry(
     root: &Path,
-    config: &config::Config,
     entry: &LockEntry,
+    disk_path: &Result<PathBuf, error::Error>,
+    target: Option<&mut ParsedSource<'_>>,
 ) -> Result<CheckResult, error::Error> {
-    let Ok(disk_path) = config.resolve_target(&entry.target) else {
-        return Ok(CheckResult::Broken("unknown namespace"));
+    if !root.join(&entry.source).exists() {
+        return Ok(CheckResult::Orphaned);
+    }
+    let disk_path = match *disk_path {
+        Err(error::Error::PathOutsideRoot { .. }) => return Ok(CheckResult::Broken("outside project root")),
+        Err(_) => return Ok(CheckResult::Broken("unknown namespace")),
+        Ok(ref p) => p,
     };
-    let target_path = root.join(&disk_path);
-    let Ok(source) = std::fs::read_to_string(&target_path) else {
+    let Some(target) = target else {
+        // A deleted file can't contain a symbol asserted to be gone.
+        if entry.absent {
+            return Ok(CheckResult::Fresh);
+        }
+        if scanner::find_case_mismatch(root, disk_path).is_some() {
+            return Ok(CheckResult::Broken("path case differs on disk"));
+        }
         return Ok(CheckResult::Broken("file not found"));
     };
 
-    let Ok(language) = grammar::language_for_path(&disk_path) else {
-        return Ok(CheckResult::Broken("unsupported language"));
-    };
-
-    let new_hash = if entry.symbol.is_empty() {
-        hasher::hash_file(&source, &language)?
-    } else {
-        let query = parse_symbol_query(&entry.symbol);
-        let resolved = match resolver::resolve(&disk_path, &source, &language, &query) {
-            Err(error::Error::SymbolNotFound { .. }) => {
-                return Ok(CheckResult::Broken("symbol removed"));
-            },
-            Err(e) => return Err(e),
-            Ok(r) => r,
-        };
-        hasher::hash_symbol(&source, &language, &resolved)?
+    let query = entry_query(entry);
+    let new_hash = match target.hash(root, &query, entry.mode) {
+        Err(error::Error::AmbiguousSymbol { .. }) => return Ok(CheckResult::Ambiguous),
+        Err(error::Error::SymbolPresent { .. }) => return Ok(CheckResult::Broken("symbol reappeared")),
+        // The file shrank below the range; the lines may have moved up.
+        Err(error::Error::SymbolNotFound { .. }) if matches!(query, SymbolQuery::Lines { .. }) => {
+            return Ok(CheckResult::Stale);
+        },
+        Err(error::Error::SymbolNotFound { .. }) => return Ok(CheckResult::Broken("symbol removed")),
+        Err(error::Error::UnsupportedLanguage { .. }) => {
+            return Ok(CheckResult::Broken("unsupported language"));
+        },
+        Err(e) => return Err(e),
+        Ok(h) => h,
     };
 
-    if new_hash == entry.hash {
+    // Whole-file entries locked before their language had a grammar hold a
+    // raw hash; they stay fresh until the content changes.
+    let is_legacy_raw = matches!(query, SymbolQuery::WholeFile) && hasher::hash_raw(target.source) == entry.hash;
+    if new_hash == entry.hash || is_legacy_raw {
         return Ok(CheckResult::Fresh);
     } else {
         return Ok(CheckResult::Stale);
     }
 }
 
+/// Fold batch failures into one error. A lone failure keeps its detailed
+/// diagnostic; several become a list grouped by markdown file.
+fn collect_failures(mut failures: Vec<(Vec<&Reference>, error::Error)>) -> error::Error {
+    let is_single = matches!(failures.as_slice(), [(refs, _)] if refs.len() == 1);
+    if is_single && let Some((_, e)) = failures.pop() {
+        return e;
+    }
+
+    let mut flattened: Vec<ReferenceFailure> = failures
+        .iter()
+        .flat_map(|(refs, e)| {
+            return refs.iter().map(move |r| return ReferenceFailure {
+                reason: e.to_string(),
+                source: r.source.clone(),
+                source_line: r.source_line,
+                target: link_target(r),
+            });
+        })
+        .collect();
+    flattened.sort_by(|a, b| return (&a.source, a.source_line).cmp(&(&b.source, b.source_line)));
+    return error::Error::UnresolvedReferences { failures: flattened };
+}
+
+/// Check one lockfile entry against its already-loaded target, for
+/// [`compare_lockfile_entry_against_source`] and
+/// [`compare_lockfile_entries_against_source`].
+///
+/// `disk_path` is where the entry's target resolved to, and `target` the
+/// file's content, or `None` when it couldn't be read.
+///
+/// # Errors
+///
+/// Returns errors from resolution or hashing that aren't recoverable as broken/stale.
+fn compare_entry(
+    root: &Path,
+    entry: &LockEntry,
+    disk_path: &Result<PathBuf, error::Error>,
+    target: Option<&mut ParsedSource<'_>>,
+) -> Result<CheckResult, error::Error> {
+    let result = classify_entry(root, entry, disk_path, target)?;
+    tracing::debug!(
+        source = %entry.source.display(),
+        target = %entry.target.display(),
+        symbol = %entry.symbol,
+        result = result.name(),
+        reason = result.reason(),
+        "checked entry"
+    );
+    return Ok(result);
+}
+
+/// Check lockfile entries against the current source, reading and parsing
+/// each target file once for all of the entries that point into it.
+///
+/// Hashes are looked up in the project's `.docref.cache` first, so a target
+/// whose content hasn't changed since an earlier run isn't parsed at all.
+/// Failing to write the cache back only loses that speed-up.
+///
+/// Results are in the order of `entries`.
+///
+/// # Errors
+///
+/// Returns errors from resolution or hashing that aren't recoverable as broken/stale.
+pub fn compare_lockfile_entries_against_source(
+    root: &Path,
+    config: &config::Config,
+    entries: &[&LockEntry],
+) -> Result<Vec<CheckResult>, error::Error> {
+    let mut by_target: BTreeMap<&Path, Vec<(usize, &LockEntry)>> = BTreeMap::new();
+    for (idx, &entry) in entries.iter().enumerate() {
+        by_target.entry(&entry.target).or_default().push((idx, entry));
+    }
+    let mut cache = HashCache::load(root, &config.grammars);
+    let mut results = Vec::with_capacity(entries.len());
+    for (target, group) in by_target {
+        let (disk_path, source) = load_target(root, config, target);
+        let mut parsed = ParsedSource::for_target(&disk_path, source.as_deref(), config)
+            .map(|parsed| return parsed.with_cache(&mut cache));
+        for (idx, entry) in group {
+            results.push((idx, compare_entry(root, entry, &disk_path, parsed.as_mut())?));
+        }
+    }
+    if let Err(e) = cache.save(root) {
+        tracing::debug!(error = %e, "could not write hash cache");
+    }
+    results.sort_by_key(|&(idx, _)| return idx);
+    return Ok(results.into_iter().map(|(_, result)| return result).collect());
+}
+
+/// Check one lockfile entry against the current source.
+///
+/// An entry whose markdown source is gone is reported as orphaned without
+/// looking at the target, since nothing documents it anymore. To check
+/// many entries, [`compare_lockfile_entries_against_source`] parses each
+/// target only once.
+///
+/// # Errors
+///
+/// Returns errors from resolution or hashing that aren't recoverable as broken/stale.
+pub fn compare_lockfile_entry_against_source(
+    root: &Path,
+    config: &config::Config,
+    entry: &LockEntry,
+) -> Result<CheckResult, error::Error> {
+    let (disk_path, source) = load_target(root, config, &entry.target);
+    let mut parsed = ParsedSource::for_target(&disk_path, source.as_deref(), config);
+    return compare_entry(root, entry, &disk_path, parsed.as_mut());
+}
+
 /// Enrich a `SymbolNotFound` error with the markdown locations that reference the broken symbol.
-fn enrich_with_source_locations(e: error::Error, refs: &[Reference]) -> error::Error {
+fn enrich_with_source_locations(root: &Path, e: error::Error, refs: &[Reference]) -> error::Error {
     let error::Error::SymbolNotFound { file, symbol, suggestions, .. } = e else {
         return e;
     };
@@ -73,7 +432,7 @@ fn enrich_with_source_locations(e: error::Error, refs: &[Reference]) -> error::E
         .filter(|r| return r.symbol.display_name() == symbol)
         .map(|r| {
             return SourceRef {
-                content: read_line_from_file(&r.source, r.source_line),
+                content: read_line_from_file(&root.join(&r.source), r.source_line),
                 file: r.source.clone(),
                 line: r.source_line,
             };
@@ -82,29 +441,185 @@ fn enrich_with_source_locations(e: error::Error, refs: &[Reference]) -> error::E
     return error::Error::SymbolNotFound { file, referenced_from: sources, suggestions, symbol };
 }
 
-/// Hash a single reference — whole-file or symbol-scoped.
+/// The symbol query a lockfile entry was recorded from.
+fn entry_query(entry: &LockEntry) -> SymbolQuery {
+    let query = parse_symbol_query(&entry.symbol);
+    if entry.absent {
+        return SymbolQuery::Absent(Box::new(query));
+    }
+    return query;
+}
+
+/// Hash recorded for existence-only references, the same whatever the
+/// symbol's content.
+fn existence_hash() -> SemanticHash {
+    return hasher::hash_raw("exists");
+}
+
+/// Resolve `query` in the module a Python `from <module> import` names,
+/// following further re-exports while `depth` allows.
+fn follow_python_import(
+    root: &Path,
+    importer: &Path,
+    import: &resolver::PythonImport,
+    query: &SymbolQuery,
+    config: &config::Config,
+    depth: usize,
+) -> Option<(String, ResolvedSymbol)> {
+    let module_path = python_module_path(root, importer, &import.module)?;
+    let module_source = std::fs::read_to_string(root.join(&module_path)).ok()?;
+    let module_query = if import.name == "*" { query.clone() } else { rename_query_head(query, &import.name)? };
+    let language = grammar::language_for_path(&module_path, &config.grammars).ok()?;
+    let resolved =
+        resolver::resolve(&module_path, &module_source, &language, &module_query, config.slug_style, &config.grammars);
+    if let Ok(resolved) = resolved {
+        return Some((module_source, resolved));
+    }
+    let remaining = depth.saturating_sub(1);
+    if remaining == 0 {
+        return None;
+    }
+    return follow_python_reexport(root, &module_path, &module_source, &module_query, config, remaining);
+}
+
+/// Find a symbol a Python module doesn't define but re-exports, as
+/// packages do with `from .errors import ClientError` in `__init__.py`.
+///
+/// Returns the defining module's source and the symbol's location in it,
+/// or `None` if no import within `depth` hops leads to a definition.
+fn follow_python_reexport(
+    root: &Path,
+    disk_path: &Path,
+    source: &str,
+    query: &SymbolQuery,
+    config: &config::Config,
+    depth: usize,
+) -> Option<(String, ResolvedSymbol)> {
+    let head = query_head(query)?;
+    let language = grammar::language_for_path(disk_path, &config.grammars).ok()?;
+    let imports = resolver::python_imports(disk_path, source, &language).ok()?;
+    return imports
+        .iter()
+        .filter(|import| return import.alias == head || import.alias == "*")
+        .find_map(|import| return follow_python_import(root, disk_path, import, query, config, depth));
+}
+
+/// Hash lines `start` through `end` of a target file.
 ///
 /// # Errors
 ///
-/// Returns resolution or hashing errors.
-fn hash_reference(
-    disk_path: &std::path::Path,
+/// Returns `Error::SymbolNotFound` when the file has fewer lines.
+fn hash_line_range(disk_path: &Path, source: &str, start: u32, end: u32) -> Result<SemanticHash, error::Error> {
+    return hasher::hash_lines(source, start, end).ok_or_else(|| {
+        return error::Error::SymbolNotFound {
+            file: disk_path.to_path_buf(),
+            referenced_from: Vec::new(),
+            suggestions: Vec::new(),
+            symbol: SymbolQuery::Lines { end, start }.display_name(),
+        };
+    });
+}
+
+/// Hash a symbol query — whole-file or symbol-scoped — against already-read source.
+///
+/// Whole-file queries on files without a tree-sitter grammar fall back to
+/// a raw content hash, so any text artifact can be tracked; `mode` has no
+/// effect on those. Absence assertions hash to a fixed value while the
+/// symbol stays gone. A Python symbol that `disk_path` only re-exports is
+/// hashed where it's defined, up to a few imports away.
+///
+/// # Errors
+///
+/// Returns `Error::UnsupportedLanguage` for symbol queries on files without
+/// a grammar, `Error::SymbolPresent` when an absent symbol exists, or
+/// resolution and hashing errors.
+pub fn hash_query_in_source(
+    root: &Path,
+    disk_path: &Path,
     source: &str,
-    language: &tree_sitter::Language,
-    reference: &Reference,
-) -> Result<crate::types::SemanticHash, error::Error> {
-    if matches!(reference.symbol, SymbolQuery::WholeFile) {
-        return hasher::hash_file(source, language);
-    }
-    let resolved = resolver::resolve(disk_path, source, language, &reference.symbol)?;
-    return hasher::hash_symbol(source, language, &resolved);
+    query: &SymbolQuery,
+    mode: HashMode,
+    config: &config::Config,
+) -> Result<SemanticHash, error::Error> {
+    return ParsedSource::new(disk_path, source, config).hash(root, query, mode);
+}
+
+/// Hash a symbol of a file without a grammar, such as an `@region` or a
+/// SQL table, as raw text.
+///
+/// # Errors
+///
+/// Returns `Error::SymbolNotFound` or `Error::AmbiguousSymbol` if the file
+/// doesn't declare exactly one symbol by that name.
+fn hash_raw_symbol(disk_path: &Path, source: &str, query: &SymbolQuery) -> Result<SemanticHash, error::Error> {
+    let resolved = resolver::resolve_unparsed(disk_path, source, query)?;
+    let start = usize::try_from(resolved.byte_range.start).unwrap_or(0);
+    let end = usize::try_from(resolved.byte_range.end).unwrap_or(0);
+    return Ok(hasher::hash_raw(source.get(start..end).unwrap_or("")));
+}
+
+/// Whether `query` names a region fenced by `docref:begin` marker comments.
+fn is_region(query: &SymbolQuery) -> bool {
+    return matches!(query, SymbolQuery::Bare(name) if name.starts_with('@'));
+}
+
+/// Format a reference as its link target, e.g. `src/lib.rs#add`.
+fn link_target(reference: &Reference) -> String {
+    let target = reference.target.display();
+    return match reference.symbol.display_name().as_str() {
+        "" => target.to_string(),
+        symbol => format!("{target}#{symbol}"),
+    };
+}
+
+/// Read a lockfile target: where its path resolves to, and the file's
+/// content if it resolved and could be read.
+fn load_target(root: &Path, config: &config::Config, target: &Path) -> (Result<PathBuf, error::Error>, Option<String>) {
+    let disk_path = config.resolve_target(target).map(|p| return package::source_path(root, p));
+    let source = disk_path.as_ref().ok().and_then(|p| return package::read_source(&root.join(p)).ok());
+    return (disk_path, source);
 }
 
-/// Parse a symbol string into bare, dot-scoped, or whole-file form.
+/// How a stale wildcard entry's members changed, if they did, comparing
+/// the type's current members with the member entries beside it in `entries`.
+pub fn member_changes(
+    root: &Path,
+    config: &config::Config,
+    entry: &LockEntry,
+    entries: &[LockEntry],
+) -> Option<MemberChanges> {
+    let SymbolQuery::Members(parent) = parse_symbol_query(&entry.symbol) else {
+        return None;
+    };
+    let (disk_path, source) = load_target(root, config, &entry.target);
+    let disk_path = disk_path.ok()?;
+    let language = grammar::language_for_path(&disk_path, &config.grammars).ok()?;
+    let declarations =
+        resolver::Declarations::parse(&disk_path, &source?, &language, config.slug_style, &config.grammars).ok()?;
+    let current: BTreeSet<String> = declarations.members(&parent).ok()?.into_iter().collect();
+    let prefix = format!("{parent}.");
+    let recorded: BTreeSet<String> = entries
+        .iter()
+        .filter(|e| return e.source == entry.source && e.target == entry.target && !e.absent)
+        .filter(|e| return e.symbol.strip_prefix(&prefix).is_some_and(|rest| return !rest.contains('.') && rest != "*"))
+        .map(|e| return e.symbol.clone())
+        .collect();
+    let added: Vec<String> = current.difference(&recorded).cloned().collect();
+    let removed: Vec<String> = recorded.difference(&current).cloned().collect();
+    return (!added.is_empty() || !removed.is_empty()).then_some(MemberChanges { added, removed });
+}
+
+/// Parse a symbol string into bare, dot-scoped, wildcard, line-range, or whole-file form.
 pub fn parse_symbol_query(symbol: &str) -> SymbolQuery {
     if symbol.is_empty() {
         return SymbolQuery::WholeFile;
     }
+    if let Some(lines) = scanner::parse_line_range(symbol) {
+        return lines;
+    }
+    if let Some(parent) = symbol.strip_suffix(".*").filter(|parent| return !parent.is_empty()) {
+        return SymbolQuery::Members(parent.to_string());
+    }
     return match symbol.split_once('.') {
         None => SymbolQuery::Bare(symbol.to_string()),
         Some((parent, child)) => SymbolQuery::Scoped {
@@ -114,6 +629,37 @@ pub fn parse_symbol_query(symbol: &str) -> SymbolQuery {
     };
 }
 
+/// The file a Python `from <module> import` names, relative to the root.
+///
+/// A relative module (`.errors`, `..util`) is looked up from the importing
+/// package; an absolute one (`pkg.errors`) from each enclosing directory.
+fn python_module_path(root: &Path, importer: &Path, module: &str) -> Option<PathBuf> {
+    let dotted = module.trim_start_matches('.');
+    if dotted.is_empty() {
+        return None;
+    }
+    let relative: PathBuf = dotted.split('.').collect();
+    let package = importer.parent()?;
+    let bases: Vec<&Path> = match module.len().saturating_sub(dotted.len()) {
+        0 => package.ancestors().collect(),
+        dots => package.ancestors().nth(dots.saturating_sub(1)).into_iter().collect(),
+    };
+    return bases
+        .into_iter()
+        .flat_map(|base| return [base.join(&relative).with_extension("py"), base.join(&relative).join("__init__.py")])
+        .find(|candidate| return root.join(candidate).is_file());
+}
+
+/// The name a query looks up at module level: the symbol itself, or the
+/// class of a `Class.member` query.
+fn query_head(query: &SymbolQuery) -> Option<&str> {
+    return match query {
+        SymbolQuery::Bare(name) => Some(name),
+        SymbolQuery::Scoped { parent, .. } => Some(parent),
+        SymbolQuery::Absent(_) | SymbolQuery::Lines { .. } | SymbolQuery::Members(_) | SymbolQuery::WholeFile => None,
+    };
+}
+
 /// Read a single line from a file. Returns empty string on any failure.
 fn read_line_from_file(path: &Path, line: u32) -> String {
     let Ok(content) = std::fs::read_to_string(path) else {
@@ -123,41 +669,132 @@ fn read_line_from_file(path: &Path, line: u32) -> String {
     return content.lines().nth(idx).unwrap_or("").trim().to_string();
 }
 
+/// Where a stale line-range entry's lines sit now, when the same block of
+/// lines is still in the target: the nearest one if it appears twice.
+pub fn relocated_lines(root: &Path, config: &config::Config, entry: &LockEntry) -> Option<SymbolQuery> {
+    let SymbolQuery::Lines { end, start } = parse_symbol_query(&entry.symbol) else {
+        return None;
+    };
+    let span = end.saturating_sub(start);
+    let (_, source) = load_target(root, config, &entry.target);
+    let count = usize::try_from(span).ok()?.saturating_add(1);
+    let new_start = hasher::find_line_range(&source?, &entry.hash, count, start)?;
+    return Some(SymbolQuery::Lines { end: new_start.saturating_add(span), start: new_start });
+}
+
+/// `query` with its module-level name replaced, for an imported alias.
+fn rename_query_head(query: &SymbolQuery, name: &str) -> Option<SymbolQuery> {
+    return match query {
+        SymbolQuery::Bare(_) => Some(SymbolQuery::Bare(name.to_string())),
+        SymbolQuery::Scoped { child, .. } => {
+            Some(SymbolQuery::Scoped { child: child.clone(), parent: name.to_string() })
+        },
+        SymbolQuery::Absent(_) | SymbolQuery::Lines { .. } | SymbolQuery::Members(_) | SymbolQuery::WholeFile => None,
+    };
+}
+
 /// Resolve all references and produce lockfile entries.
 /// Groups are already keyed by target file, so each file is parsed once.
 ///
+/// Every reference is attempted even after a failure, so a run reports all
+/// broken links at once rather than one per invocation.
+///
 /// # Errors
 ///
-/// Returns errors from file reading, language detection, resolution, or hashing.
+/// Returns the original error when exactly one reference fails, or
+/// `Error::UnresolvedReferences` listing every failure otherwise.
 pub fn resolve_and_hash_all_references(
     root: &Path,
     config: &config::Config,
     grouped: &HashMap<PathBuf, Vec<Reference>>,
+) -> Result<Vec<LockEntry>, error::Error> {
+    return resolve_and_hash_all_references_with_progress(root, config, grouped, &mut |_, _| {});
+}
+
+/// Resolve all references like [`resolve_and_hash_all_references`], calling
+/// `on_target` with each target file and its reference count once the
+/// group has been processed.
+///
+/// # Errors
+///
+/// Returns the original error when exactly one reference fails, or
+/// `Error::UnresolvedReferences` listing every failure otherwise.
+pub fn resolve_and_hash_all_references_with_progress(
+    root: &Path,
+    config: &config::Config,
+    grouped: &HashMap<PathBuf, Vec<Reference>>,
+    on_target: &mut dyn FnMut(&Path, usize),
 ) -> Result<Vec<LockEntry>, error::Error> {
     let mut entries = Vec::new();
+    let mut failures: Vec<(Vec<&Reference>, error::Error)> = Vec::new();
 
     for (target, refs) in grouped {
-        let disk_path = config.resolve_target(target)?;
-        let target_path = root.join(&disk_path);
-        let source =
-            std::fs::read_to_string(&target_path).map_err(|_err| return error::Error::FileNotFound {
-                path: target_path.clone(),
-            })?;
-
-        let language = grammar::language_for_path(&disk_path)?;
-
-        for reference in refs {
-            let hash = hash_reference(&disk_path, &source, &language, reference)
-                .map_err(|e| return enrich_with_source_locations(e, refs))?;
-
-            entries.push(LockEntry {
-                hash,
-                source: reference.source.clone(),
-                symbol: reference.symbol.display_name(),
-                target: reference.target.clone(),
-            });
+        if let Err(e) = resolve_and_hash_target(root, config, target, refs, &mut entries, &mut failures) {
+            failures.push((refs.iter().collect(), e));
+        }
+        on_target(target, refs.len());
+    }
+
+    if failures.is_empty() {
+        return Ok(entries);
+    }
+    return Err(collect_failures(failures));
+}
+
+/// Resolve and hash every reference to one target file, appending entries
+/// and per-reference failures.
+///
+/// A missing target file fails every reference in the group except absence
+/// assertions, which it satisfies.
+///
+/// # Errors
+///
+/// Returns an error when the target's namespace can't be resolved, which
+/// fails every reference in the group.
+fn resolve_and_hash_target<'r>(
+    root: &Path,
+    config: &config::Config,
+    target: &Path,
+    refs: &'r [Reference],
+    entries: &mut Vec<LockEntry>,
+    failures: &mut Vec<(Vec<&'r Reference>, error::Error)>,
+) -> Result<(), error::Error> {
+    let disk_path = package::source_path(root, config.resolve_target(target)?);
+    let target_path = root.join(&disk_path);
+    let source = package::read_source(&target_path).ok();
+    tracing::debug!(
+        target = %target.display(),
+        disk_path = %disk_path.display(),
+        found = source.is_some(),
+        references = refs.len(),
+        "hashing target"
+    );
+
+    let mut parsed = source.as_deref().map(|text| return ParsedSource::new(&disk_path, text, config));
+    for reference in refs {
+        let hashed = match (parsed.as_mut(), &reference.symbol) {
+            (Some(target), query) => target.hash_reference(root, query, reference.mode),
+            (None, query @ SymbolQuery::Absent(_)) => Ok(vec![(query.display_name(), absent_hash())]),
+            (None, _) => Err(error::Error::FileNotFound { path: target_path.clone() }),
+        };
+        match hashed {
+            Ok(hashes) => entries.extend(hashes.into_iter().map(|(symbol, hash)| {
+                return LockEntry {
+                    absent: matches!(reference.symbol, SymbolQuery::Absent(_)),
+                    accepted: None,
+                    hash,
+                    mode: reference.mode,
+                    note: None,
+                    owner: None,
+                    source: reference.source.clone(),
+                    symbol,
+                    tags: Vec::new(),
+                    target: reference.target.clone(),
+                };
+            })),
+            Err(e) => failures.push((vec![reference], enrich_with_source_locations(root, e, refs))),
         }
     }
 
-    return Ok(entries);
+    return Ok(());
 }
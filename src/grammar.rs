@@ -1,30 +1,255 @@
 /// Tree-sitter grammar resolution by file extension.
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-use tree_sitter::Language;
+use tree_sitter::{Language, Query};
+use tree_sitter_language::LanguageFn;
 
 use crate::error::Error;
 
+/// Every grammar docref is built with, sorted by display name.
+pub const GRAMMARS: &[Grammar] = &[
+    Grammar { extensions: &["bash", "sh"], name: "bash", title: "Bash" },
+    Grammar { extensions: &["cs"], name: "csharp", title: "C#" },
+    Grammar { extensions: &["cc", "cpp", "h", "hpp"], name: "cpp", title: "C++" },
+    Grammar { extensions: &["css"], name: "css", title: "CSS" },
+    Grammar { extensions: &["go"], name: "go", title: "Go" },
+    Grammar { extensions: &["json"], name: "json", title: "JSON" },
+    Grammar { extensions: &["java"], name: "java", title: "Java" },
+    Grammar { extensions: &["js", "jsx"], name: "javascript", title: "JavaScript" },
+    Grammar { extensions: &["md", "markdown"], name: "markdown", title: "Markdown" },
+    Grammar { extensions: &["php"], name: "php", title: "PHP" },
+    Grammar { extensions: &["py"], name: "python", title: "Python" },
+    Grammar { extensions: &["rs"], name: "rust", title: "Rust" },
+    Grammar { extensions: &["svelte"], name: "svelte", title: "Svelte" },
+    Grammar { extensions: &["swift"], name: "swift", title: "Swift" },
+    Grammar { extensions: &["toml"], name: "toml", title: "TOML" },
+    Grammar { extensions: &["ts", "tsx"], name: "typescript", title: "TypeScript" },
+    Grammar { extensions: &["yaml", "yml"], name: "yaml", title: "YAML" },
+];
+
+/// A grammar configured under `[grammars]`, loaded from its shared library
+/// the first time a file needs it.
+#[derive(Debug)]
+struct DynamicGrammar {
+    /// Where the grammar is loaded from.
+    library: PathBuf,
+    /// The loaded grammar, or why it couldn't be loaded, once first needed.
+    loaded: OnceLock<Result<LoadedGrammar, String>>,
+    /// Grammar name, whose `tree_sitter_<name>` function the library exports.
+    name: String,
+    /// Query whose `@declaration` captures are symbols named by `@name`.
+    query: String,
+}
+
+/// A tree-sitter grammar compiled into docref.
+pub struct Grammar {
+    /// File extensions, without the leading dot, that select the grammar.
+    pub extensions: &'static [&'static str],
+    /// Lowercase language name, as used by `status --by language`.
+    pub name: &'static str,
+    /// Display name for tables.
+    pub title: &'static str,
+}
+
+/// Grammars configured under `[grammars]`, by extension.
+#[derive(Debug, Default)]
+pub struct Grammars {
+    /// Each configured grammar, by the extension it handles.
+    by_ext: BTreeMap<String, DynamicGrammar>,
+}
+
+impl Grammars {
+    /// Use the grammar `name` from `library`, with the declarations `query`,
+    /// for files ending in `.ext`. Nothing is loaded until such a file is.
+    pub fn configure(&mut self, ext: &str, library: PathBuf, name: &str, query: &str) {
+        let grammar =
+            DynamicGrammar { library, loaded: OnceLock::new(), name: name.to_string(), query: query.to_string() };
+        self.by_ext.insert(ext.to_string(), grammar);
+    }
+
+    /// The grammar configured for `ext`, loading its library and compiling
+    /// its query on first use. `None` if `[grammars]` doesn't configure one.
+    ///
+    /// # Errors
+    ///
+    /// The inner result is `Error::GrammarLoad` if the grammar can't be
+    /// loaded or the query doesn't compile against it.
+    pub fn get(&self, ext: &str) -> Option<Result<&LoadedGrammar, Error>> {
+        let grammar = self.by_ext.get(ext)?;
+        let loaded = grammar.loaded.get_or_init(|| return load_grammar(ext, grammar));
+        return Some(loaded.as_ref().map_err(|reason| {
+            return Error::GrammarLoad { ext: ext.to_string(), reason: reason.clone() };
+        }));
+    }
+}
+
+/// A `[grammars]` grammar, loaded, with its declarations query compiled.
+#[derive(Debug)]
+pub struct LoadedGrammar {
+    /// The grammar's language.
+    pub language: Language,
+    /// Query whose `@declaration` captures are symbols named by `@name`.
+    pub query: Query,
+}
+
+/// A summary of every grammar's ABI version and table sizes, which changes
+/// whenever a grammar is upgraded.
+///
+/// `[grammars]` libraries aren't loaded for this; each contributes its
+/// size and modification time instead, in extension order.
+pub fn fingerprint(grammars: &Grammars) -> String {
+    let builtin = GRAMMARS
+        .iter()
+        .flat_map(|grammar| return grammar.extensions)
+        .filter_map(|ext| return language_for_path(&Path::new("file").with_extension(ext), &Grammars::default()).ok())
+        .map(|language| {
+            return format!(
+                "{}.{}.{}.{}",
+                language.abi_version(),
+                language.node_kind_count(),
+                language.parse_state_count(),
+                language.field_count(),
+            );
+        });
+    let dynamic = grammars.by_ext.iter().map(|(ext, grammar)| {
+        let metadata = std::fs::metadata(&grammar.library).ok();
+        let modified = metadata.as_ref().and_then(|m| return m.modified().ok()).and_then(|time| {
+            return time.duration_since(std::time::UNIX_EPOCH).ok();
+        });
+        let len = metadata.map(|m| return m.len()).unwrap_or_default();
+        return format!("{ext}:{len}.{}", modified.map(|d| return d.as_nanos()).unwrap_or_default());
+    });
+    return builtin.chain(dynamic).collect::<Vec<_>>().join(",");
+}
+
 /// Map a file extension to its tree-sitter language.
 ///
+/// Grammars compiled into docref take precedence over the ones `grammars`
+/// configures, which are loaded the first time they're needed.
+///
 /// # Errors
 ///
-/// Returns `Error::UnsupportedLanguage` for unknown extensions.
-pub fn language_for_path(path: &Path) -> Result<Language, Error> {
+/// Returns `Error::UnsupportedLanguage` for unknown extensions, or
+/// `Error::GrammarLoad` if the configured grammar can't be loaded.
+pub fn language_for_path(path: &Path, grammars: &Grammars) -> Result<Language, Error> {
     let ext = path.extension().and_then(|e| return e.to_str()).unwrap_or("");
 
     return match ext {
         "bash" | "sh" => Ok(tree_sitter_bash::LANGUAGE.into()),
+        "cc" | "cpp" | "h" | "hpp" => Ok(tree_sitter_cpp::LANGUAGE.into()),
+        "cs" => Ok(tree_sitter_c_sharp::LANGUAGE.into()),
+        "css" => Ok(tree_sitter_css::LANGUAGE.into()),
         "go" => Ok(tree_sitter_go::LANGUAGE.into()),
+        "java" => Ok(tree_sitter_java::LANGUAGE.into()),
         "js" => Ok(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+        "json" => Ok(tree_sitter_json::LANGUAGE.into()),
         "jsx" => Ok(tree_sitter_typescript::LANGUAGE_TSX.into()),
         "md" | "markdown" => Ok(tree_sitter_md::LANGUAGE.into()),
+        "php" => Ok(tree_sitter_php::LANGUAGE_PHP.into()),
         "py" => Ok(tree_sitter_python::LANGUAGE.into()),
         "rs" => Ok(tree_sitter_rust::LANGUAGE.into()),
+        "svelte" => Ok(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+        "swift" => Ok(tree_sitter_swift::LANGUAGE.into()),
+        "toml" => Ok(tree_sitter_toml_ng::LANGUAGE.into()),
         "ts" => Ok(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
         "tsx" => Ok(tree_sitter_typescript::LANGUAGE_TSX.into()),
-        _ => Err(Error::UnsupportedLanguage {
-            ext: ext.to_string(),
-        }),
+        "yaml" | "yml" => Ok(tree_sitter_yaml::LANGUAGE.into()),
+        _ => grammars.get(ext).map_or_else(
+            || return Err(Error::UnsupportedLanguage { ext: ext.to_string() }),
+            |loaded| return loaded.map(|grammar| return grammar.language.clone()),
+        ),
     };
 }
+
+/// Human-readable language name for a path, or `None` without a grammar.
+pub fn language_name(path: &Path) -> Option<&'static str> {
+    let ext = path.extension().and_then(|e| return e.to_str()).unwrap_or("");
+
+    return GRAMMARS
+        .iter()
+        .find(|grammar| return grammar.extensions.contains(&ext))
+        .map(|grammar| return grammar.name);
+}
+
+/// Load a `[grammars]` grammar and compile its declarations query.
+///
+/// # Errors
+///
+/// Returns why the library couldn't be loaded or the query didn't compile.
+fn load_grammar(ext: &str, grammar: &DynamicGrammar) -> Result<LoadedGrammar, String> {
+    let language = load_language(&grammar.library, &grammar.name)?;
+    let query = Query::new(&language, &grammar.query).map_err(|e| return format!("invalid query: {e}"))?;
+    tracing::debug!(ext, library = %grammar.library.display(), "loaded grammar");
+    return Ok(LoadedGrammar { language, query });
+}
+
+/// Load the language function `tree_sitter_<name>` from the shared library
+/// at `library`, which then stays loaded for the life of the process.
+///
+/// # Errors
+///
+/// Returns why, if the library can't be opened, doesn't export the
+/// function, or holds a grammar of an incompatible ABI version.
+fn load_language(library: &Path, name: &str) -> Result<Language, String> {
+    // SAFETY: opening the library runs its initializers. `[grammars]` names
+    // a tree-sitter grammar that the project's config chose to trust.
+    let loaded = unsafe { libloading::Library::new(library) }
+        .map_err(|e| return format!("{}: {e}", library.display()))?;
+    let symbol = format!("tree_sitter_{name}");
+    // SAFETY: tree-sitter grammars export `const TSLanguage *tree_sitter_<name>(void)`,
+    // which is the signature requested here.
+    let function = unsafe { loaded.get::<unsafe extern "C" fn() -> *const ()>(symbol.as_bytes()) }
+        .map_err(|e| return format!("{symbol}: {e}"))?;
+    // SAFETY: `function` is a tree-sitter language function, and the library
+    // is never unloaded, so the pointer stays valid.
+    let language = Language::new(unsafe { LanguageFn::from_raw(*function) });
+    std::mem::forget(loaded);
+    let abi = language.abi_version();
+    if !(tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION..=tree_sitter::LANGUAGE_VERSION).contains(&abi) {
+        return Err(format!("{symbol} has ABI version {abi}, which this docref can't parse"));
+    }
+    return Ok(language);
+}
+
+/// The text a file's grammar parses.
+///
+/// That's `source` itself, except in a Svelte component, where everything
+/// outside the `<script>` blocks is blanked to spaces so the TypeScript
+/// grammar sees only code, at unchanged offsets.
+pub fn parsed_source<'a>(path: &Path, source: &'a str) -> Cow<'a, str> {
+    if path.extension().is_none_or(|ext| return ext != "svelte") {
+        return Cow::Borrowed(source);
+    }
+    let scripts = svelte_scripts(source);
+    let blanked = source
+        .char_indices()
+        .map(|(i, c)| {
+            let kept = c == '\n' || scripts.iter().any(|script| return script.contains(&i));
+            return if kept { c.to_string() } else { " ".repeat(c.len_utf8()) };
+        })
+        .collect();
+    return Cow::Owned(blanked);
+}
+
+/// Byte ranges of the contents of each `<script>` element in `source`.
+fn svelte_scripts(source: &str) -> Vec<Range<usize>> {
+    let mut scripts = Vec::new();
+    let mut from = 0;
+    while let Some(open) = source.get(from..).and_then(|rest| return rest.find("<script")) {
+        let tag = from.saturating_add(open);
+        let Some(end) = source.get(tag..).and_then(|rest| return rest.find('>')) else {
+            break;
+        };
+        let start = tag.saturating_add(end).saturating_add(1);
+        let Some(len) = source.get(start..).and_then(|rest| return rest.find("</script>")) else {
+            break;
+        };
+        from = start.saturating_add(len);
+        scripts.push(start..from);
+    }
+    return scripts;
+}
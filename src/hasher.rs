@@ -1,34 +1,87 @@
 /// Semantic hashing of resolved symbols via tree-sitter normalization.
+use std::ops::Range;
 use std::path::PathBuf;
 
 use sha2::{Digest as _, Sha256};
 use tree_sitter::{Language, Node, Parser};
 
 use crate::error::Error;
-use crate::types::{ResolvedSymbol, SemanticHash};
+use crate::types::{HashMode, ResolvedSymbol, SemanticHash};
 
-/// Recursively collect non-comment, non-whitespace leaf token text.
-fn collect_semantic_leaf_tokens<'a>(node: Node<'a>, source: &'a str, tokens: &mut Vec<&'a str>) {
-    if node.child_count() == 0 {
-        let kind = node.kind();
-
-        // Skip comments.
-        if kind.contains("comment") {
-            return;
-        }
+/// Node kinds hashed as a single token, because part of their text isn't
+/// covered by any child: the digits of CSS `4px` sit beside its `unit` node.
+const ATOMIC_KINDS: &[&str] = &["float_value", "integer_value"];
 
+/// Recursively collect non-whitespace leaf token text.
+///
+/// Comments are skipped unless `mode` is `Docs`, where they contribute one
+/// token per word so reflowing a comment doesn't change the hash. In
+/// `Signature` mode, `body` fields are skipped entirely.
+fn collect_semantic_leaf_tokens<'a>(node: Node<'a>, source: &'a str, mode: HashMode, tokens: &mut Vec<&'a str>) {
+    let is_comment = node.kind().contains("comment");
+    if is_comment && mode == HashMode::Docs {
+        tokens.extend(source[node.start_byte()..node.end_byte()].split_whitespace());
+        return;
+    }
+    if node.child_count() == 0 || ATOMIC_KINDS.contains(&node.kind()) {
         let text = &source[node.start_byte()..node.end_byte()];
         let trimmed = text.trim();
-        if !trimmed.is_empty() {
+        if !is_comment && !trimmed.is_empty() {
             tokens.push(trimmed);
         }
         return;
     }
 
     let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        collect_semantic_leaf_tokens(child, source, tokens);
+    let mut has_child = cursor.goto_first_child();
+    while has_child {
+        let is_body = cursor.field_name() == Some("body");
+        if !(is_body && mode == HashMode::Signature) {
+            collect_semantic_leaf_tokens(cursor.node(), source, mode, tokens);
+        }
+        has_child = cursor.goto_next_sibling();
+    }
+}
+
+/// Widen a symbol's byte range back over the doc comments directly above it.
+///
+/// Attributes between the comments and the declaration (`#[derive]` in
+/// Rust) are stepped over. A blank line ends the comment block.
+fn extend_over_leading_comments(source: &str, language: &Language, range: &Range<u32>) -> Range<u32> {
+    let mut parser = Parser::new();
+    let tree = parser.set_language(language).ok().and_then(|()| return parser.parse(source, None));
+    let start = usize::try_from(range.start).unwrap_or(0);
+    let end = usize::try_from(range.end).unwrap_or(0);
+    let Some(mut node) = tree.as_ref().and_then(|t| return t.root_node().descendant_for_byte_range(start, end)) else {
+        return range.clone();
+    };
+
+    let mut new_start = range.start;
+    while let Some(previous) = node.prev_sibling()
+        && previous.end_position().row.saturating_add(1) >= node.start_position().row
+        && (previous.kind().contains("comment") || previous.kind() == "attribute_item")
+    {
+        if previous.kind().contains("comment") {
+            new_start = u32::try_from(previous.start_byte()).unwrap_or(new_start);
+        }
+        node = previous;
     }
+    return new_start..range.end;
+}
+
+/// Find where a block of `count` lines hashing to `hash` sits now, for a
+/// line-range reference whose lines drifted.
+///
+/// Returns the 1-based first line of the matching block closest to `near`,
+/// or `None` if no block of that length matches.
+pub fn find_line_range(source: &str, hash: &SemanticHash, count: usize, near: u32) -> Option<u32> {
+    let lines: Vec<&str> = source.lines().collect();
+    return lines
+        .windows(count.max(1))
+        .enumerate()
+        .filter(|(_, window)| return hash_raw(&window.join("\n")) == *hash)
+        .filter_map(|(idx, _)| return u32::try_from(idx).ok().map(|idx| return idx.saturating_add(1)))
+        .min_by_key(|&start| return start.abs_diff(near));
 }
 
 /// Compute a semantic hash for an entire file's content.
@@ -39,20 +92,38 @@ fn collect_semantic_leaf_tokens<'a>(node: Node<'a>, source: &'a str, tokens: &mu
 /// # Errors
 ///
 /// Returns `Error::ParseFailed` if tree-sitter cannot parse the file.
-pub fn hash_file(source: &str, language: &Language) -> Result<SemanticHash, Error> {
+pub fn hash_file(source: &str, language: &Language, mode: HashMode) -> Result<SemanticHash, Error> {
     let len = u32::try_from(source.len()).map_err(|_err| return Error::ParseFailed {
         file: PathBuf::from("<whole-file>"),
         reason: "file length exceeds u32 range".to_string(),
     })?;
     let whole = ResolvedSymbol { byte_range: 0..len };
-    return hash_symbol(source, language, &whole);
+    return hash_symbol(source, language, &whole, mode);
+}
+
+/// Compute a hash of lines `start` through `end` of a file, or `None` when
+/// the file has fewer lines. Normalized like [`hash_raw`].
+pub fn hash_lines(source: &str, start: u32, end: u32) -> Option<SemanticHash> {
+    return line_range(source, start, end).map(|text| return hash_raw(&text));
+}
+
+/// Compute a hash of raw file content for files without a grammar.
+///
+/// No token normalization is possible without a parser, so the only
+/// normalization is line endings: `\r\n` and lone `\r` become `\n`, keeping
+/// hashes stable across checkouts with different `core.autocrlf` settings.
+pub fn hash_raw(source: &str) -> SemanticHash {
+    let normalized = source.replace("\r\n", "\n").replace('\r', "\n");
+    let hash = Sha256::digest(normalized.as_bytes());
+    return SemanticHash(format!("{hash:x}"));
 }
 
 /// Compute a semantic hash for a resolved symbol.
 ///
 /// Normalization: extract the symbol's subtree, walk leaf nodes,
 /// strip comment and whitespace nodes, join remaining text with
-/// single spaces, then SHA-256 hash the result.
+/// single spaces, then SHA-256 hash the result. `mode` narrows the
+/// hash to the signature or widens it to include doc comments.
 ///
 /// # Errors
 ///
@@ -61,13 +132,19 @@ pub fn hash_symbol(
     source: &str,
     language: &Language,
     symbol: &ResolvedSymbol,
+    mode: HashMode,
 ) -> Result<SemanticHash, Error> {
-    let start = usize::try_from(symbol.byte_range.start)
+    let byte_range = if mode == HashMode::Docs {
+        extend_over_leading_comments(source, language, &symbol.byte_range)
+    } else {
+        symbol.byte_range.clone()
+    };
+    let start = usize::try_from(byte_range.start)
         .map_err(|_err| return Error::ParseFailed {
             file: PathBuf::from("symbol"),
             reason: "byte range start exceeds platform usize".to_string(),
         })?;
-    let end = usize::try_from(symbol.byte_range.end)
+    let end = usize::try_from(byte_range.end)
         .map_err(|_err| return Error::ParseFailed {
             file: PathBuf::from("symbol"),
             reason: "byte range end exceeds platform usize".to_string(),
@@ -87,15 +164,24 @@ pub fn hash_symbol(
         reason: "hash re-parse failed".to_string(),
     })?;
 
-    let normalized = normalize_symbol_to_semantic_tokens(tree.root_node(), snippet);
+    let normalized = normalize_symbol_to_semantic_tokens(tree.root_node(), snippet, mode);
     let hash = Sha256::digest(normalized.as_bytes());
 
     return Ok(SemanticHash(format!("{hash:x}")));
 }
 
+/// The text of lines `start` through `end` (1-based, inclusive) joined
+/// with `\n`, or `None` when the file has fewer lines.
+pub fn line_range(source: &str, start: u32, end: u32) -> Option<String> {
+    let first = usize::try_from(start).ok()?.checked_sub(1)?;
+    let last = usize::try_from(end).ok()?;
+    let lines: Vec<&str> = source.lines().collect();
+    return lines.get(first..last).map(|range| return range.join("\n"));
+}
+
 /// Walk leaf nodes, skip comments and whitespace, join with single space.
-fn normalize_symbol_to_semantic_tokens(node: Node<'_>, source: &str) -> String {
+fn normalize_symbol_to_semantic_tokens(node: Node<'_>, source: &str, mode: HashMode) -> String {
     let mut tokens = Vec::new();
-    collect_semantic_leaf_tokens(node, source, &mut tokens);
+    collect_semantic_leaf_tokens(node, source, mode, &mut tokens);
     return tokens.join(" ");
 }
@@ -0,0 +1,133 @@
+//! Git history lookups: how long a stale reference has been stale.
+//!
+//! A reference goes stale with the first commit after the last one whose
+//! version of the target still hashes to the lockfile entry. Outside a git
+//! repository, or when no recent enough committed version matched, the age
+//! is unknown.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::freshness::{hash_query_in_source, parse_symbol_query};
+use crate::lockfile::LockEntry;
+
+/// Most commits of a target compared against its lockfile entry.
+const MAX_COMMITS: usize = 50;
+
+/// Seconds in a day, for reporting ages in whole days.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Whole days since `since`, zero if it lies in the future.
+pub fn days_since(since: SystemTime) -> u64 {
+    let elapsed = SystemTime::now().duration_since(since).unwrap_or_default();
+    return elapsed.as_secs().checked_div(SECONDS_PER_DAY).unwrap_or(0);
+}
+
+/// Content of `path` as committed in `commit`, untrimmed.
+fn file_at_commit(root: &Path, commit: &str, path: &Path) -> Option<String> {
+    // `./` makes the path relative to `root` rather than the repository top.
+    let spec = format!("{commit}:./{}", path.to_string_lossy().replace('\\', "/"));
+    let output = Command::new("git").arg("-C").arg(root).args(["show", &spec]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    return String::from_utf8(output.stdout).ok();
+}
+
+/// Trimmed stdout of a successful `git` command run in `root`, if non-empty.
+pub(crate) fn git_output(root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").arg("-C").arg(root).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?;
+    let trimmed = text.trim();
+    return (!trimmed.is_empty()).then(|| return trimmed.to_string());
+}
+
+/// Commit time of the newest commit that added or removed `entry`'s hash in
+/// the lockfile, if the lockfile has been committed with it.
+fn hash_recorded_at(root: &Path, entry: &LockEntry) -> Option<u64> {
+    let pickaxe = format!("-S{}", entry.hash.0);
+    return git_output(root, &["log", "-1", "--format=%ct", &pickaxe, "--", ".docref.lock"])?.parse().ok();
+}
+
+/// When a stale entry went stale, or `None` when the history can't tell.
+///
+/// That is the commit time of the first change after the last version that
+/// matched, or now if the latest commit still matches and the drift is
+/// uncommitted.
+///
+/// Commits touching the target are walked newest first. Older versions can
+/// differ from the stored hash too (before it was accepted), so the sequence
+/// isn't sorted and can't be bisected. See [`target_commits`] for how far
+/// back the walk goes.
+///
+/// # Errors
+///
+/// Returns errors from resolving the target's namespace.
+pub fn stale_since(root: &Path, config: &Config, entry: &LockEntry) -> Result<Option<SystemTime>, Error> {
+    let disk_path = config.resolve_target(&entry.target)?;
+    let Some(log) = git_output(root, &["log", "--format=%H %ct", "--", &disk_path.to_string_lossy()]) else {
+        return Ok(None);
+    };
+    let commits = target_commits(&log, hash_recorded_at(root, entry));
+    let query = parse_symbol_query(&entry.symbol);
+    let matches = |commit: &str| {
+        let Some(source) = file_at_commit(root, commit, &disk_path) else {
+            return false;
+        };
+        return hash_query_in_source(root, &disk_path, &source, &query, entry.mode, config)
+            .is_ok_and(|hash| return hash == entry.hash);
+    };
+    let Some(last_match) = commits.iter().position(|&(commit, _)| return matches(commit)) else {
+        return Ok(None);
+    };
+    let since = match last_match.checked_sub(1).and_then(|idx| return commits.get(idx)) {
+        Some(&(_, time)) => SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(time)),
+        None => Some(SystemTime::now()),
+    };
+    return Ok(since);
+}
+
+/// The `%H %ct` lines of a target's log, newest first, that could still hold
+/// the locked version.
+///
+/// The walk stops at the newest commit made by the time the lockfile
+/// recorded the hash, since that was the version being locked, and after
+/// [`MAX_COMMITS`] commits in any case.
+fn target_commits(log: &str, recorded_at: Option<u64>) -> Vec<(&str, u64)> {
+    let mut commits: Vec<(&str, u64)> = log
+        .lines()
+        .filter_map(|line| {
+            let (commit, time) = line.split_once(' ')?;
+            return Some((commit, time.parse().ok()?));
+        })
+        .take(MAX_COMMITS)
+        .collect();
+    let locked = recorded_at.and_then(|at| return commits.iter().position(|&(_, time)| return time <= at));
+    if let Some(locked) = locked {
+        commits.truncate(locked.saturating_add(1));
+    }
+    return commits;
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc, reason = "test code uses unwrap freely")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_commits_stop_at_the_locked_version() {
+        let log = "c3 300\nc2 200\nc1 100";
+        assert_eq!(target_commits(log, Some(250)), [("c3", 300), ("c2", 200)]);
+        assert_eq!(target_commits(log, Some(200)), [("c3", 300), ("c2", 200)]);
+        assert_eq!(target_commits(log, None).len(), 3);
+
+        let long: String = (0..80_u64).map(|n| format!("c{n} {}\n", 1000 - n)).collect();
+        assert_eq!(target_commits(&long, None).len(), MAX_COMMITS);
+    }
+}
@@ -1,13 +1,28 @@
 //! The `info` subcommand — outputs a comprehensive docref reference document
 //! as either markdown (for humans) or JSON (for tooling).
 
-use std::path::PathBuf;
+use std::path::Path;
 
 use serde::Serialize;
 
 use crate::config;
+use crate::grammar;
 use crate::lockfile::Lockfile;
 
+/// Sections in the order the full document prints them.
+const TOPIC_ORDER: [Topic; 10] = [
+    Topic::HowItWorks,
+    Topic::Syntax,
+    Topic::Paths,
+    Topic::Setup,
+    Topic::Commands,
+    Topic::Examples,
+    Topic::Config,
+    Topic::Languages,
+    Topic::State,
+    Topic::ExitCodes,
+];
+
 /// Snapshot of the current project state for rendering.
 struct CurrentState {
     /// Whether `.docref.toml` was found.
@@ -43,6 +58,8 @@ struct InfoJson {
 /// JSON representation of a supported language grammar.
 #[derive(Serialize)]
 struct LanguageInfo {
+    /// Tree-sitter ABI version the grammar was generated with.
+    abi_version: usize,
     /// File extensions triggering this grammar.
     extensions: Vec<String>,
     /// Human-readable language name.
@@ -69,8 +86,80 @@ struct StateJson {
     namespaces: Vec<NamespaceJson>,
 }
 
+/// One section of the reference document, printed alone with `--topic`.
+#[derive(Debug, Clone, Copy)]
+pub enum Topic {
+    /// Every command with a one-line description.
+    Commands,
+    /// The `.docref.toml` format.
+    Config,
+    /// Worked examples for each command.
+    Examples,
+    /// Exit codes and their meaning.
+    ExitCodes,
+    /// The mental model: links become tracked references.
+    HowItWorks,
+    /// Grammars compiled into this build.
+    Languages,
+    /// How link paths are resolved.
+    Paths,
+    /// The recommended setup flow.
+    Setup,
+    /// Config, lockfile, and namespaces of the current project.
+    State,
+    /// Every supported reference form.
+    Syntax,
+}
+
+impl Topic {
+    /// Name accepted by `--topic`.
+    const fn name(self) -> &'static str {
+        return match self {
+            Self::Commands => "commands",
+            Self::Config => "config",
+            Self::Examples => "examples",
+            Self::ExitCodes => "exit-codes",
+            Self::HowItWorks => "how-it-works",
+            Self::Languages => "languages",
+            Self::Paths => "paths",
+            Self::Setup => "setup",
+            Self::State => "state",
+            Self::Syntax => "syntax",
+        };
+    }
+
+    /// Print this section.
+    fn print(self, state: &CurrentState) {
+        match self {
+            Self::Commands => print_section_commands(),
+            Self::Config => print_section_configuration(),
+            Self::Examples => print_section_examples(),
+            Self::ExitCodes => print_section_exit_codes(),
+            Self::HowItWorks => print_section_how_it_works(),
+            Self::Languages => print_section_languages(),
+            Self::Paths => print_section_path_resolution(),
+            Self::Setup => print_section_setup(),
+            Self::State => print_section_state(state),
+            Self::Syntax => print_section_reference_syntax(),
+        }
+        return;
+    }
+}
+
+impl std::str::FromStr for Topic {
+    type Err = String;
+
+    /// Parse a section name such as `commands` or `exit-codes`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return TOPIC_ORDER.into_iter().find(|topic| return topic.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = TOPIC_ORDER.iter().map(|topic| return topic.name()).collect();
+            return format!("unknown topic `{s}` (expected one of: {})", names.join(", "));
+        });
+    }
+}
+
 /// Collect project state from the given root directory.
-fn gather_state(root: &std::path::Path) -> CurrentState {
+fn gather_state(root: &Path) -> CurrentState {
     let config_path = root.join(".docref.toml");
     let lock_path = root.join(".docref.lock");
 
@@ -111,7 +200,7 @@ fn print_json(state: &CurrentState) {
         exit_codes: vec![
             ExitCodeInfo { code: 0, meaning: "Success / all references fresh".to_string() },
             ExitCodeInfo { code: 1, meaning: "Stale references found".to_string() },
-            ExitCodeInfo { code: 2, meaning: "Broken references found".to_string() },
+            ExitCodeInfo { code: 2, meaning: "Broken, ambiguous, or orphaned references found".to_string() },
             ExitCodeInfo { code: 3, meaning: "Runtime error".to_string() },
         ],
         supported_languages: supported_languages(),
@@ -128,16 +217,9 @@ fn print_json(state: &CurrentState) {
 fn print_markdown(state: &CurrentState) {
     let version = env!("CARGO_PKG_VERSION");
     print_section_title(version);
-    print_section_how_it_works();
-    print_section_reference_syntax();
-    print_section_path_resolution();
-    print_section_setup();
-    print_section_commands();
-    print_section_configuration();
-    print_section_languages();
-    print_section_state(state);
-    println!();
-    print_section_exit_codes();
+    for topic in TOPIC_ORDER {
+        topic.print(state);
+    }
     return;
 }
 
@@ -149,21 +231,38 @@ fn print_section_commands() {
 
     docref init                          Scan markdown, hash symbols, write .docref.lock
     docref check                         Verify all references (exit 0/1/2)
+    docref check --baseline              Fail only on findings not in the baseline
+    docref check --fail-fast             Stop at the first failure (or --max-failures N)
+    docref check --exit-zero             Report findings but always exit 0
+    docref check --output <file>         Write the JSON report to a file (also status)
+    docref baseline write                Record current findings in .docref-baseline.toml
     docref status                        Show freshness of all tracked references
+    docref status --summary-only         Counts, % fresh, and per-source rollup only
+    docref status --by <grouping>        Breakdown by language, namespace, owner, or team
+    docref status --owner <o> --tag <t>  Only entries with this owner or tag
     docref update <file#symbol>          Re-hash after intentional code changes
+    docref update <ref> --note <text>    Re-hash and record why the change was accepted
     docref update --from <file.md>       Re-hash all refs from a markdown file
     docref update --all                  Re-hash everything
-    docref fix                           Auto-fix all broken refs (closest match)
+    docref update --all --stale-only     Re-hash only entries whose code changed
+    docref fix                           Auto-fix broken refs (close match or moved symbol)
     docref fix <file#sym> <newsym>       Fix a specific broken reference
+    docref fix --dry-run --diff          Print fixes as a unified diff, write nothing
     docref resolve <file>                List addressable symbols in a source file
+    docref resolve <file> <s> --explain  Trace candidates, CST path, and byte range
     docref refs <file#symbol>            Show which markdown files reference a target
+    docref refs --symbol <name>          Find references to a symbol in any file
     docref namespace add <name> <path>   Map a short name to a directory
-    docref namespace list                Show all namespace mappings
+    docref namespace list [--json]       Show namespace mappings; JSON adds paths and counts
     docref namespace remove <name>       Remove a namespace mapping
     docref namespace rename <old> <new>  Rename (rewrites config + markdown)
-    docref info                          Show this reference document
-    docref info --json                   Machine-readable output
-    docref watch                         Watch source files and re-check on changes
+    docref info [--json] [--topic <s>]   This document, one section of it, or machine-readable
+    docref lsp                           Language server: inline diagnostics and quick fixes
+    docref serve [--addr <host:port>]    HTTP dashboard plus /api/status and /api/check
+    docref watch [--tui | --exec <cmd>]  Re-check on changes; --tui dashboard, --exec hook
+    docref workspace status              Check every member project, one combined exit code
+    docref -C <dir> <command>            Run as if started in <dir> (also --cwd)
+    docref --log-level debug <command>   Trace config, namespaces, and entries (or DOCREF_LOG)
 
 "
     );
@@ -179,14 +278,95 @@ fn print_section_configuration() {
     include = [\"docs/\", \"src/\"]         # only scan these paths for markdown
     exclude = [\"docs/archive/\"]          # skip these within included paths
     extends = \"../.docref.toml\"          # inherit parent config
+    slug_style = \"github\"                # heading anchors follow GitHub's rules
+    wiki_links = true                    # also scan [[path#symbol|text]] wiki links
+    markdown_extensions = [\"md\", \"mdx\", \"org\"]  # doc files to scan; this is the default
+    shortcode = \"docref\"                 # shortcode name to scan; \"\" disables
+    record_acceptance = true             # update stamps entries with time, commit, user
+    allow_outside_root = false           # true lets links read files outside the project
+    external_paths = [\"../sdk/\"]         # out-of-tree or absolute prefixes links may use
 
     [namespaces]
     auth = \"services/auth\"               # auth:src/lib.rs -> services/auth/src/lib.rs
+    authv2 = \"auth:v2\"                   # alias, resolves to services/auth/v2
+
+    [teams]
+    identity = [\"services/auth/\"]        # code path prefixes, for status --by team
+
+    [severity]
+    orphaned = \"error\"                   # ignore, warning, or error per finding kind
+    stale_grace_days = 30                # stale is only a warning for its first 30 days
+
+    [[severity.override]]
+    path = \"docs/drafts/\"                # markdown sources under this prefix
+    stale = \"warning\"                    # report, but don't fail the check
+
+    [[severity.override]]
+    path = \"docs/rfc/\"                   # archived docs: tracked, never fail CI
+    stale = \"ignore\"
 
 Include/exclude patterns are path prefixes, not globs. Without .docref.toml,
 ALL markdown under the project root is scanned. Create a config to avoid
 errors from third-party markdown in node_modules, vendor, etc.
 
+A namespace value with a `name:` prefix is an alias for another namespace.
+Aliases resolve transitively; cycles are reported as errors.
+
+Severity kinds are stale, broken, ambiguous, and orphaned. All default to
+error except orphaned (warning). Warnings are printed but exit 0.
+`docref check --max-severity warning` caps every finding for one run.
+With stale_grace_days, a stale reference warns until git history shows it
+has been stale longer than that; `status` prints every stale entry's age.
+
+"
+    );
+    return;
+}
+
+/// Print worked examples for each command.
+fn print_section_examples() {
+    print!(
+        "\
+## Examples
+
+    # init: start tracking, then gate CI on check
+    docref init && docref check            exit 0 while every reference is fresh
+
+    # check: a referenced function changed
+    docref check                           STALE src/lib.rs#add, exit 1
+    docref check --format json --output docref-report.json
+    docref check --format markdown > comment.md   post as a PR comment
+    docref check --format sarif > docref.sarif    upload to code scanning
+    docref check --format junit > docref.xml      CI test results view
+    docref baseline write && docref check --baseline   adopt with existing drift
+
+    # update: fix the prose first, then accept the new code
+    docref update src/lib.rs#add --note \"signature reviewed\"
+    docref update --from docs/guide.md     everything one page references
+    docref update --all --stale-only       only entries whose code changed
+
+    # status: where is drift concentrated?
+    docref status --summary-only --by team
+    docref status --tag public --format json
+
+    # fix: a symbol was renamed, moved, or linked with the wrong case
+    docref fix --dry-run --diff            preview the markdown edits
+    docref fix src/lib.rs#old_name new_name
+
+    # resolve and refs: what can I link to, and who links to it?
+    docref resolve src/lib.rs              addressable symbols in a file
+    docref resolve src/lib.rs add --explain
+    docref refs src/lib.rs#add             markdown files referencing add
+
+    # namespace: short, move-proof links across a monorepo
+    docref namespace add auth services/auth
+    [load](auth:src/config.rs#load)        resolves to services/auth/src/config.rs
+    docref namespace rename auth identity  rewrites config, lockfile, markdown
+
+    # watch: re-check while editing
+    docref watch
+    docref watch --tui                     a accepts, i ignores, f filters
+
 "
     );
     return;
@@ -202,8 +382,11 @@ fn print_section_exit_codes() {
 |------|---------|
 | 0    | Success / all references fresh |
 | 1    | Stale references found |
-| 2    | Broken references found |
+| 2    | Broken, ambiguous, or orphaned references found |
 | 3    | Runtime error |
+
+Pass --exit-zero to any command to report findings but exit 0.
+Runtime errors still exit 3.
 "
     );
     return;
@@ -231,24 +414,19 @@ applies. Both forms are tracked; both detect when the referenced code changes.
     return;
 }
 
-/// Print supported language table.
+/// Print the table of grammars compiled into this build.
 fn print_section_languages() {
-    print!(
-        "\
-## Supported Languages
-
-| Extension       | Language   |
-|-----------------|------------|
-| .bash .sh       | Bash       |
-| .go             | Go         |
-| .js .jsx        | JavaScript |
-| .md             | Markdown   |
-| .py             | Python     |
-| .rs             | Rust       |
-| .ts .tsx        | TypeScript |
-
-"
-    );
+    println!("## Supported Languages");
+    println!();
+    println!("| Extension       | Language   | ABI |");
+    println!("|-----------------|------------|-----|");
+    for language in supported_languages() {
+        let extensions = language.extensions.join(" ");
+        println!("| {extensions:<15} | {:<10} | {:<3} |", language.language, language.abi_version);
+    }
+    println!();
+    println!("Files with other extensions can still be tracked by whole-file references.");
+    println!();
     return;
 }
 
@@ -289,13 +467,24 @@ fn print_section_reference_syntax() {
     [text](path/to/file.rs#Type.method)       dot-scoped sub-symbol
     [text](ns:path/to/file.rs#symbol)         namespaced reference
     [text](path/to/file.rs)                   whole-file reference (no #symbol)
+    [text](path/to/file.rs#symbol \"docref: sig\")  per-reference hash mode
+    <a href=\"path/to/file.rs#symbol\">       HTML anchor inside markdown
+    [[path/to/file.rs#symbol|text]]           wiki link, root-relative (wiki_links = true)
+    [[path/to/file.rs#symbol][text]]          Org link, in .org documents
+    {{{{< docref \"path/to/file.rs#symbol\" >}}}}   Hugo/Jekyll shortcode (name set by `shortcode`)
 
 Choose the form that matches what your docs describe:
 
   - **#symbol** — when documenting a specific function, type, constant, or method.
     Use `docref resolve <file>` to list addressable symbols.
   - **bare path** — when no specific symbol applies: config files, scripts,
-    templates, manifests, or when the entire file is the subject.
+    templates, manifests, or when the entire file is the subject. Files
+    without a grammar (.csv, .txt, ...) are hashed as raw text.
+  - **\"docref: sig\"** — a link title picks what the hash covers: `sig` tracks
+    only the signature, `docs` also tracks comments and doc comments above
+    the symbol. Unannotated links hash the body without comments.
+  - **\"docref: absent\"** — asserts the symbol was removed; `check` reports
+    it broken if the symbol reappears.
 
 "
     );
@@ -349,6 +538,7 @@ fn print_section_state(state: &CurrentState) {
             .join(", ");
         println!("Namespaces: {ns_list}");
     }
+    println!();
     return;
 }
 
@@ -366,37 +556,32 @@ and detect when code changes make them stale.
     return;
 }
 
-/// Output the comprehensive docref reference document.
-pub fn run(json: bool) {
-    let root = PathBuf::from(".");
-    let state = gather_state(&root);
+/// Output the comprehensive docref reference document, or only the section
+/// named by `topic`.
+pub fn run(root: &Path, json: bool, topic: Option<Topic>) {
+    let state = gather_state(root);
 
-    if json {
-        print_json(&state);
-    } else {
-        print_markdown(&state);
+    match (json, topic) {
+        (true, _) => print_json(&state),
+        (false, Some(topic)) => topic.print(&state),
+        (false, None) => print_markdown(&state),
     }
     return;
 }
 
-/// Build the list of supported languages for JSON output.
+/// List the grammars compiled into this build, loading each one so the
+/// table reflects what the binary actually supports.
 fn supported_languages() -> Vec<LanguageInfo> {
-    return vec![
-        LanguageInfo {
-            extensions: vec![".bash".to_string(), ".sh".to_string()],
-            language: "Bash".to_string(),
-        },
-        LanguageInfo { extensions: vec![".go".to_string()], language: "Go".to_string() },
-        LanguageInfo {
-            extensions: vec![".js".to_string(), ".jsx".to_string()],
-            language: "JavaScript".to_string(),
-        },
-        LanguageInfo { extensions: vec![".md".to_string()], language: "Markdown".to_string() },
-        LanguageInfo { extensions: vec![".py".to_string()], language: "Python".to_string() },
-        LanguageInfo { extensions: vec![".rs".to_string()], language: "Rust".to_string() },
-        LanguageInfo {
-            extensions: vec![".ts".to_string(), ".tsx".to_string()],
-            language: "TypeScript".to_string(),
-        },
-    ];
+    return grammar::GRAMMARS
+        .iter()
+        .filter_map(|entry| {
+            let sample = Path::new("sample").with_extension(entry.extensions.first()?);
+            let language = grammar::language_for_path(&sample, &grammar::Grammars::default()).ok()?;
+            return Some(LanguageInfo {
+                abi_version: language.abi_version(),
+                extensions: entry.extensions.iter().map(|ext| return format!(".{ext}")).collect(),
+                language: entry.title.to_string(),
+            });
+        })
+        .collect();
 }
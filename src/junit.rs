@@ -0,0 +1,74 @@
+//! JUnit XML reports for `check --format junit`, which CI systems such as
+//! Jenkins and GitLab render as test results: one test case per reference.
+
+use std::fmt::Write as _;
+
+/// One checked reference, reported as a test case.
+pub struct Case {
+    /// The markdown file holding the link, used as the case's class.
+    pub classname: String,
+    /// Why the reference fails the check, if it does.
+    pub failure: Option<Failure>,
+    /// The reference, as `file#symbol`.
+    pub name: String,
+    /// A finding that doesn't fail the check, such as a warning.
+    pub note: Option<String>,
+}
+
+/// A reference that fails the check.
+pub struct Failure {
+    /// Kind of finding, such as `stale`.
+    pub kind: &'static str,
+    /// The markdown location and the reason, e.g. `docs/guide.md:3: ...`.
+    pub message: String,
+}
+
+/// Escape `text` for use in XML content or a double-quoted attribute.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("&quot;"),
+            '&' => escaped.push_str("&amp;"),
+            '\'' => escaped.push_str("&apos;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            other => escaped.push(other),
+        }
+    }
+    return escaped;
+}
+
+/// Render `cases` as a JUnit XML document with a single `docref` suite.
+pub fn render(cases: &[Case]) -> String {
+    let failures = cases.iter().filter(|case| return case.failure.is_some()).count();
+    let tests = cases.len();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(out, "<testsuites name=\"docref\" tests=\"{tests}\" failures=\"{failures}\">");
+    let _ = writeln!(out, "  <testsuite name=\"docref\" tests=\"{tests}\" failures=\"{failures}\">");
+    for case in cases {
+        render_case(&mut out, case);
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    return out;
+}
+
+/// Append one `<testcase>` element to `out`.
+fn render_case(out: &mut String, case: &Case) {
+    let (classname, name) = (escape(&case.classname), escape(&case.name));
+    let _ = write!(out, "    <testcase classname=\"{classname}\" name=\"{name}\" file=\"{classname}\"");
+    if case.failure.is_none() && case.note.is_none() {
+        out.push_str("/>\n");
+        return;
+    }
+    out.push_str(">\n");
+    if let Some(failure) = &case.failure {
+        let message = escape(&failure.message);
+        let _ = writeln!(out, "      <failure type=\"{}\" message=\"{message}\">{message}</failure>", failure.kind);
+    }
+    if let Some(note) = &case.note {
+        let _ = writeln!(out, "      <system-out>{}</system-out>", escape(note));
+    }
+    out.push_str("    </testcase>\n");
+    return;
+}
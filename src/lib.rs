@@ -0,0 +1,64 @@
+//! Semantic code references for markdown.
+//!
+//! The `docref` binary is a thin CLI over this library. Tools that want to
+//! drive docref directly, such as build scripts or editors, should start
+//! from [`engine::Engine`].
+
+/// Known-findings baseline for adopting docref incrementally.
+pub mod baseline;
+/// Persistent cache of semantic hashes keyed by file content.
+pub mod cache;
+/// `CODEOWNERS` parsing for attributing references to owning teams.
+pub mod codeowners;
+/// Command implementations for each CLI subcommand.
+pub mod commands;
+/// Dynamic shell completion for reference arguments.
+pub mod completion;
+/// Configuration loading and namespace resolution.
+pub mod config;
+/// User-facing diagnostic formatting.
+pub mod diagnostics;
+/// Embeddable engine with progress callbacks for driving docref from other tools.
+pub mod engine;
+/// Unified error type for the crate.
+pub mod error;
+/// Freshness checking logic for locked references.
+pub mod freshness;
+/// Tree-sitter grammar loading and symbol extraction.
+pub mod grammar;
+/// Content hashing for reference targets.
+pub mod hasher;
+/// Git history lookups for how long references have been stale.
+pub mod history;
+/// Info command output generation.
+pub mod info;
+/// JUnit XML reports of check results for CI test views.
+pub mod junit;
+/// Lockfile serialization and deserialization.
+pub mod lockfile;
+/// Language server publishing reference diagnostics to editors.
+pub mod lsp;
+/// Man page and markdown CLI reference generation.
+pub mod manpage;
+/// Namespace mapping management.
+pub mod namespace;
+/// Go package directories read as one source for cross-file symbols.
+pub mod package;
+/// Progress bars and periodic log lines for long-running commands.
+pub mod progress;
+/// Symbol resolution from source files.
+pub mod resolver;
+/// SARIF logs of check findings for code scanning tools.
+pub mod sarif;
+/// Markdown scanning and reference extraction.
+pub mod scanner;
+/// HTTP dashboard and JSON API for a long-running freshness monitor.
+pub mod serve;
+/// Interactive terminal dashboard for watch mode.
+pub mod tui;
+/// Core domain types for references and symbols.
+pub mod types;
+/// File watching and live re-check.
+pub mod watch;
+
+pub use engine::{Engine, EngineBuilder, Event};
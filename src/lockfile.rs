@@ -5,28 +5,75 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 
 use crate::error::Error;
-use crate::types::SemanticHash;
+use crate::history::git_output;
+use crate::types::{HashMode, SemanticHash};
+
+/// Who accepted an entry's current hash, when, and against which code version.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Acceptance {
+    /// When the entry was re-hashed, as an RFC 3339 timestamp.
+    pub at: String,
+    /// Git `user.name`, or `$USER`, of whoever ran the update. Omitted when unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+    /// The project's git `HEAD` commit at the time. Omitted outside a repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+impl Acceptance {
+    /// Provenance for an acceptance happening now in the project at `root`.
+    pub fn current(root: &Path) -> Self {
+        let by = git_output(root, &["config", "user.name"])
+            .or_else(|| return std::env::var("USER").ok().filter(|user| return !user.is_empty()));
+        return Self {
+            at: humantime::format_rfc3339_seconds(std::time::SystemTime::now()).to_string(),
+            by,
+            commit: git_output(root, &["rev-parse", "HEAD"]),
+        };
+    }
+}
 
 /// A single tracked reference in the lockfile.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct LockEntry {
+    /// The entry asserts that the symbol does not exist. Omitted when false.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub absent: bool,
+    /// Provenance of the last `update` that accepted the current hash, when
+    /// `record_acceptance` is enabled. Omitted when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted: Option<Acceptance>,
     /// The semantic hash of the resolved symbol body.
     pub hash: SemanticHash,
+    /// Which part of the symbol the hash covers. Omitted for the default.
+    #[serde(default, skip_serializing_if = "HashMode::is_body")]
+    pub mode: HashMode,
+    /// Free-form note, e.g. why a stale reference was accepted. Omitted when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Person or team responsible for the reference. Omitted when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
     /// The markdown file containing the reference.
     pub source: PathBuf,
     /// The symbol name within the target file.
     pub symbol: String,
+    /// Arbitrary labels, such as the audience of the docs. Omitted when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// The target source file being referenced.
     pub target: PathBuf,
 }
 
 impl Ord for LockEntry {
-    /// Compare entries by (source, target, symbol) for deterministic ordering.
+    /// Compare entries by (source, target, symbol, mode) for deterministic ordering.
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        return (&self.source, &self.target, &self.symbol).cmp(&(
+        return (&self.source, &self.target, &self.symbol, self.mode).cmp(&(
             &other.source,
             &other.target,
             &other.symbol,
+            other.mode,
         ));
     }
 }
@@ -38,7 +85,7 @@ impl PartialOrd for LockEntry {
     }
 }
 
-/// The lockfile as a whole. Entries are sorted by (source, target, symbol).
+/// The lockfile as a whole. Entries are sorted by (source, target, symbol, mode).
 /// Constructed only via `Lockfile::new()` or `Lockfile::parse()`, both of
 /// which enforce sorting and uniqueness.
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,6 +95,23 @@ pub struct Lockfile {
 }
 
 impl Lockfile {
+    /// Copy `note`, `owner`, and `tags` from matching entries of a previous
+    /// lockfile, so regenerating it keeps hand-recorded metadata. Acceptance
+    /// provenance is kept only while the hash it accepted is unchanged.
+    pub fn carry_metadata_from(&mut self, previous: &Self) {
+        let matched = self.entries.iter_mut().filter_map(|entry| {
+            let idx = previous.entries.binary_search(entry).ok()?;
+            return Some((entry, previous.entries.get(idx)?));
+        });
+        for (entry, old) in matched {
+            entry.accepted = (entry.hash == old.hash).then(|| return old.accepted.clone()).flatten();
+            entry.note.clone_from(&old.note);
+            entry.owner.clone_from(&old.owner);
+            entry.tags.clone_from(&old.tags);
+        }
+        return;
+    }
+
     /// Create a new lockfile from unsorted entries. Sorts and deduplicates.
     pub fn new(mut entries: Vec<LockEntry>) -> Self {
         entries.sort();
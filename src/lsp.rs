@@ -0,0 +1,512 @@
+//! Minimal language server that shows reference health inline in editors
+//! such as VS Code and Neovim.
+//!
+//! Diagnostics are published for open markdown documents: broken and
+//! ambiguous references as errors, stale ones as warnings, and links not yet
+//! in the lockfile as information. They are recomputed when a document is
+//! opened or edited, and for every open document whenever any file is
+//! saved, since a code change can make references stale. Stale references
+//! offer a code action that accepts the new code like `docref update`;
+//! broken ones with a close symbol match offer to rewrite the fragment.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, ExecuteCommand, Request as _};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability, Command,
+    Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, ExecuteCommandOptions, ExecuteCommandParams, NumberOrString, Position,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TextDocumentSyncSaveOptions, TextEdit, Url, WorkspaceEdit,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::commands;
+use crate::config::Config;
+use crate::diagnostics::{find_closest_suggestion, find_nearest_name};
+use crate::error::Error;
+use crate::freshness::{CheckResult, compare_lockfile_entries_against_source};
+use crate::grammar;
+use crate::lockfile::{LockEntry, Lockfile};
+use crate::package;
+use crate::resolver;
+use crate::scanner;
+use crate::types::{Reference, SymbolQuery};
+
+/// Command run by the "accept" code action, with the reference as its argument.
+const ACCEPT_COMMAND: &str = "docref.accept";
+
+/// JSON-RPC error code for a handler that failed.
+const INTERNAL_ERROR: i32 = -32603;
+
+/// JSON-RPC error code for parameters that don't match the method.
+const INVALID_PARAMS: i32 = -32602;
+
+/// JSON-RPC error code for a method the server doesn't implement.
+const METHOD_NOT_FOUND: i32 = -32601;
+
+/// The code action a diagnostic offers, carried in its `data` field.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", tag = "action")]
+enum QuickFix {
+    /// Re-hash a stale reference, like `docref update`.
+    Accept {
+        /// The reference as the lockfile records it, e.g. `src/lib.rs#add`.
+        reference: String,
+    },
+    /// Point a broken link's `#fragment` at the closest existing symbol.
+    Rename {
+        /// Where the fragment, including its `#`, sits in the document.
+        fragment: lsp_types::Range,
+        /// The symbol to link to instead.
+        symbol: String,
+    },
+}
+
+/// Open markdown documents and the connection diagnostics are sent on.
+struct Server<'a> {
+    /// The client connection.
+    connection: &'a Connection,
+    /// Text of each open markdown document, as last sent by the client.
+    documents: HashMap<Url, String>,
+    /// Canonical project root.
+    root: PathBuf,
+}
+
+impl Server<'_> {
+    /// Quick fixes for the docref diagnostics a code action request covers.
+    fn code_actions(params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+        return params
+            .context
+            .diagnostics
+            .iter()
+            .filter_map(|diagnostic| {
+                let fix = serde_json::from_value(diagnostic.data.clone()?).ok()?;
+                let action = code_action(&params.text_document.uri, diagnostic, fix);
+                return Some(CodeActionOrCommand::CodeAction(action));
+            })
+            .collect();
+    }
+
+    /// Diagnostics for one open document, or a single error diagnostic if
+    /// the project can't be checked.
+    fn diagnose(&self, uri: &Url, text: &str) -> Vec<Diagnostic> {
+        let Some(source) = self.project_path(uri) else {
+            return Vec::new();
+        };
+        return document_diagnostics(&self.root, &source, text).unwrap_or_else(|e| {
+            return vec![Diagnostic {
+                message: format!("docref: {e}"),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("docref".to_string()),
+                ..Diagnostic::default()
+            }];
+        });
+    }
+
+    /// Take a client's edit; with full sync, the last change is the whole text.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if diagnostics can't be sent.
+    fn did_change(&mut self, mut params: DidChangeTextDocumentParams) -> Result<(), Error> {
+        let text = params.content_changes.pop().map(|change| return change.text).unwrap_or_default();
+        return self.open(params.text_document.uri, text);
+    }
+
+    /// Forget a closed document and clear its diagnostics.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if diagnostics can't be sent.
+    fn did_close(&mut self, params: DidCloseTextDocumentParams) -> Result<(), Error> {
+        self.documents.remove(&params.text_document.uri);
+        return self.publish(params.text_document.uri, Vec::new());
+    }
+
+    /// Run a command from a code action.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from re-hashing the reference or publishing diagnostics.
+    fn execute(&self, params: &ExecuteCommandParams) -> Result<serde_json::Value, Error> {
+        let reference = params.arguments.first().and_then(serde_json::Value::as_str);
+        if let (ACCEPT_COMMAND, Some(reference)) = (params.command.as_str(), reference) {
+            commands::update(&self.root, reference, None)?;
+            self.publish_all()?;
+        }
+        return Ok(serde_json::Value::Null);
+    }
+
+    /// Handle one message from the client. Returns `true` once the client
+    /// asks to shut down.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if a reply can't be sent.
+    fn handle_message(&mut self, message: Message) -> Result<bool, Error> {
+        return match message {
+            Message::Notification(notification) => self.handle_notification(notification).map(|()| return false),
+            Message::Request(request) => self.handle_request(request),
+            Message::Response(_) => Ok(false),
+        };
+    }
+
+    /// Track document lifecycle notifications and republish diagnostics.
+    /// Notifications whose parameters don't parse are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if diagnostics can't be sent.
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), Error> {
+        let params = notification.params;
+        return match notification.method.as_str() {
+            DidChangeTextDocument::METHOD => parse_params(params).map_or(Ok(()), |p| return self.did_change(p)),
+            DidCloseTextDocument::METHOD => parse_params(params).map_or(Ok(()), |p| return self.did_close(p)),
+            DidOpenTextDocument::METHOD => parse_params(params).map_or(Ok(()), |p: DidOpenTextDocumentParams| {
+                return self.open(p.text_document.uri, p.text_document.text);
+            }),
+            DidSaveTextDocument::METHOD => self.publish_all(),
+            _ => Ok(()),
+        };
+    }
+
+    /// Answer a request. Returns `true` once the client asks to shut down.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if the response can't be sent.
+    fn handle_request(&self, request: Request) -> Result<bool, Error> {
+        if self.connection.handle_shutdown(&request).map_err(io_error)? {
+            return Ok(true);
+        }
+        let method = request.method.clone();
+        let response = match method.as_str() {
+            CodeActionRequest::METHOD => {
+                respond(request, |params: CodeActionParams| return Ok(Self::code_actions(&params)))
+            },
+            ExecuteCommand::METHOD => respond(request, |params: ExecuteCommandParams| return self.execute(&params)),
+            _ => Response::new_err(request.id, METHOD_NOT_FOUND, format!("unhandled method: {method}")),
+        };
+        self.connection.sender.send(Message::Response(response)).map_err(io_error)?;
+        return Ok(false);
+    }
+
+    /// Remember an opened or edited markdown document and publish its diagnostics.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if diagnostics can't be sent.
+    fn open(&mut self, uri: Url, text: String) -> Result<(), Error> {
+        if self.project_path(&uri).is_none() {
+            return Ok(());
+        }
+        let diagnostics = self.diagnose(&uri, &text);
+        self.documents.insert(uri.clone(), text);
+        return self.publish(uri, diagnostics);
+    }
+
+    /// A document's path relative to the project root, if it's a markdown
+    /// file inside the project.
+    fn project_path(&self, uri: &Url) -> Option<PathBuf> {
+        let path = uri.to_file_path().ok()?;
+        let is_markdown = Config::load(&self.root).map_or_else(
+            |_| return path.extension().is_some_and(|ext| return ext == "md"),
+            |config| return config.is_markdown(&path),
+        );
+        if !is_markdown {
+            return None;
+        }
+        let path = path.canonicalize().unwrap_or(path);
+        return path.strip_prefix(&self.root).ok().map(Path::to_path_buf);
+    }
+
+    /// Send the diagnostics for one document, replacing any sent before.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if the client connection is closed.
+    fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> Result<(), Error> {
+        let params = PublishDiagnosticsParams { diagnostics, uri, version: None };
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.connection.sender.send(Message::Notification(notification)).map_err(io_error)?;
+        return Ok(());
+    }
+
+    /// Recompute and send diagnostics for every open document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if the client connection is closed.
+    fn publish_all(&self) -> Result<(), Error> {
+        for (uri, text) in &self.documents {
+            self.publish(uri.clone(), self.diagnose(uri, text))?;
+        }
+        return Ok(());
+    }
+
+    /// Handle messages until the client shuts the server down.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if the connection fails.
+    fn serve(&mut self) -> Result<(), Error> {
+        let stop = self
+            .connection
+            .receiver
+            .iter()
+            .map(|message| return self.handle_message(message))
+            .find(|handled| return !matches!(handled, Ok(false)));
+        return stop.map_or(Ok(()), |handled| return handled.map(drop));
+    }
+}
+
+/// What the server offers the client during the handshake.
+fn capabilities() -> ServerCapabilities {
+    let sync = TextDocumentSyncOptions {
+        change: Some(TextDocumentSyncKind::FULL),
+        open_close: Some(true),
+        save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+        ..TextDocumentSyncOptions::default()
+    };
+    return ServerCapabilities {
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![ACCEPT_COMMAND.to_string()],
+            ..ExecuteCommandOptions::default()
+        }),
+        text_document_sync: Some(TextDocumentSyncCapability::Options(sync)),
+        ..ServerCapabilities::default()
+    };
+}
+
+/// The closest existing symbol to a broken reference's missing one: the
+/// same name without generics, or else a likely typo.
+fn closest_symbol(root: &Path, config: &Config, reference: &Reference) -> Option<String> {
+    if !matches!(reference.symbol, SymbolQuery::Bare(_) | SymbolQuery::Scoped { .. }) {
+        return None;
+    }
+    let disk_path = package::source_path(root, config.resolve_target(&reference.target).ok()?);
+    let source = package::read_source(&root.join(&disk_path)).ok()?;
+    let language = grammar::language_for_path(&disk_path, &config.grammars).ok()?;
+    let resolved =
+        resolver::resolve(&disk_path, &source, &language, &reference.symbol, config.slug_style, &config.grammars);
+    let Err(Error::SymbolNotFound { symbol, suggestions, .. }) = resolved else {
+        return None;
+    };
+    return find_closest_suggestion(&symbol, &suggestions).or_else(|| return find_nearest_name(&symbol, &suggestions));
+}
+
+/// The code action for one diagnostic's quick fix.
+fn code_action(uri: &Url, diagnostic: &Diagnostic, fix: QuickFix) -> CodeAction {
+    let (title, command, edit) = match fix {
+        QuickFix::Accept { reference } => {
+            let title = format!("Accept current code for {reference}");
+            let command = Command {
+                arguments: Some(vec![serde_json::Value::from(reference)]),
+                command: ACCEPT_COMMAND.to_string(),
+                title: title.clone(),
+            };
+            (title, Some(command), None)
+        },
+        QuickFix::Rename { fragment, symbol } => {
+            let edit = TextEdit { new_text: format!("#{symbol}"), range: fragment };
+            let changes = HashMap::from([(uri.clone(), vec![edit])]);
+            let edit = WorkspaceEdit { changes: Some(changes), ..WorkspaceEdit::default() };
+            (format!("Link to closest symbol #{symbol}"), None, Some(edit))
+        },
+    };
+    return CodeAction {
+        command,
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit,
+        is_preferred: Some(true),
+        kind: Some(CodeActionKind::QUICKFIX),
+        title,
+        ..CodeAction::default()
+    };
+}
+
+/// Diagnostics for the references in one markdown document.
+///
+/// Documents the config doesn't scan, and projects without a lockfile yet,
+/// have none.
+///
+/// # Errors
+///
+/// Returns errors from loading the config, scanning, or hashing.
+fn document_diagnostics(root: &Path, source: &Path, text: &str) -> Result<Vec<Diagnostic>, Error> {
+    let config = Config::load(root)?;
+    let Ok(lockfile) = Lockfile::read(&root.join(".docref.lock")) else {
+        return Ok(Vec::new());
+    };
+    if !config.should_scan(&source.to_string_lossy()) {
+        return Ok(Vec::new());
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let references = scanner::scan_content(root, &config, source, text)?;
+    let (tracked, untracked): (Vec<_>, Vec<_>) = references
+        .iter()
+        .map(|reference| return (reference, tracked_entry(&lockfile, source, reference)))
+        .partition(|(_, entry)| return entry.is_some());
+    let tracked: Vec<(&Reference, &LockEntry)> =
+        tracked.into_iter().filter_map(|(reference, entry)| return Some((reference, entry?))).collect();
+    let entries: Vec<&LockEntry> = tracked.iter().map(|&(_, entry)| return entry).collect();
+    let results = compare_lockfile_entries_against_source(root, &config, &entries)?;
+
+    let findings = tracked.iter().zip(&results).filter_map(|(&(reference, entry), result)| {
+        let line = line_text(&lines, reference);
+        return finding_diagnostic(root, &config, line, reference, entry, result);
+    });
+    let untracked = untracked.into_iter().map(|(reference, _)| {
+        return untracked_diagnostic(line_text(&lines, reference), reference);
+    });
+    return Ok(findings.chain(untracked).collect());
+}
+
+/// The diagnostic for a tracked reference, or `None` when it's fresh.
+fn finding_diagnostic(
+    root: &Path,
+    config: &Config,
+    line: &str,
+    reference: &Reference,
+    entry: &LockEntry,
+    result: &CheckResult,
+) -> Option<Diagnostic> {
+    let name = reference_name(entry);
+    let (severity, message, fix) = match *result {
+        CheckResult::Ambiguous => {
+            (DiagnosticSeverity::ERROR, format!("{name} is ambiguous: it matches more than one declaration"), None)
+        },
+        CheckResult::Broken(reason) => {
+            let fix = closest_symbol(root, config, reference).map(|symbol| {
+                let fragment = reference.symbol_span.start.saturating_sub(1)..reference.symbol_span.end;
+                return QuickFix::Rename { fragment: line_range(line, reference.source_line, fragment), symbol };
+            });
+            (DiagnosticSeverity::ERROR, format!("{name} is broken: {reason}"), fix)
+        },
+        CheckResult::Fresh | CheckResult::Orphaned => return None,
+        CheckResult::Stale => {
+            let message = format!("{name} is stale: the code changed since it was locked");
+            (DiagnosticSeverity::WARNING, message, Some(QuickFix::Accept { reference: name }))
+        },
+    };
+    return Some(Diagnostic {
+        code: Some(NumberOrString::String(result.name().to_string())),
+        data: fix.and_then(|fix| return serde_json::to_value(fix).ok()),
+        message,
+        range: link_range(line, reference),
+        severity: Some(severity),
+        source: Some("docref".to_string()),
+        ..Diagnostic::default()
+    });
+}
+
+/// Wrap a protocol or channel failure as an I/O error.
+fn io_error(e: impl std::fmt::Display) -> Error {
+    return Error::Io(std::io::Error::other(e.to_string()));
+}
+
+/// An LSP range for a byte range within a one-based markdown line.
+/// Columns count UTF-16 code units, the protocol's default encoding.
+fn line_range(line: &str, line_number: u32, bytes: Range<usize>) -> lsp_types::Range {
+    let column = |byte: usize| {
+        let units = line.get(..byte).map_or(0, |prefix| return prefix.encode_utf16().count());
+        return u32::try_from(units).unwrap_or(u32::MAX);
+    };
+    let line_idx = line_number.saturating_sub(1);
+    return lsp_types::Range {
+        end: Position { character: column(bytes.end), line: line_idx },
+        start: Position { character: column(bytes.start), line: line_idx },
+    };
+}
+
+/// The markdown line a reference was found on.
+fn line_text<'a>(lines: &[&'a str], reference: &Reference) -> &'a str {
+    let idx = usize::try_from(reference.source_line.saturating_sub(1)).unwrap_or(usize::MAX);
+    return lines.get(idx).copied().unwrap_or_default();
+}
+
+/// The range of a reference's link path and fragment.
+fn link_range(line: &str, reference: &Reference) -> lsp_types::Range {
+    let end = reference.target_span.end.max(reference.symbol_span.end);
+    return line_range(line, reference.source_line, reference.target_span.start..end);
+}
+
+/// Parse notification parameters, ignoring notifications that don't match.
+fn parse_params<P: DeserializeOwned>(params: serde_json::Value) -> Option<P> {
+    return serde_json::from_value(params).ok();
+}
+
+/// A lockfile entry's reference, as `docref update` accepts it.
+fn reference_name(entry: &LockEntry) -> String {
+    let target = entry.target.display();
+    return match entry.symbol.as_str() {
+        "" => target.to_string(),
+        symbol => format!("{target}#{symbol}"),
+    };
+}
+
+/// Answer a request with `handler`'s result, or with an error if its
+/// parameters don't parse or the handler fails.
+fn respond<P: DeserializeOwned, R: Serialize>(request: Request, handler: impl FnOnce(P) -> Result<R, Error>) -> Response {
+    return match serde_json::from_value::<P>(request.params) {
+        Err(e) => Response::new_err(request.id, INVALID_PARAMS, e.to_string()),
+        Ok(params) => match handler(params) {
+            Err(e) => Response::new_err(request.id, INTERNAL_ERROR, e.to_string()),
+            Ok(result) => Response::new_ok(request.id, result),
+        },
+    };
+}
+
+/// Serve the language server protocol over stdin and stdout until the
+/// client shuts the server down.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if the root can't be resolved, the handshake fails,
+/// or the connection breaks.
+pub fn run(root: &Path) -> Result<(), Error> {
+    let root = root.canonicalize()?;
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(capabilities()).map_err(io_error)?;
+    connection.initialize(capabilities).map_err(io_error)?;
+    Server { connection: &connection, documents: HashMap::new(), root }.serve()?;
+    // The writer thread exits once the last sender is gone.
+    drop(connection);
+    io_threads.join()?;
+    return Ok(());
+}
+
+/// The lockfile entry recorded for a reference in `source`, if any.
+fn tracked_entry<'a>(lockfile: &'a Lockfile, source: &Path, reference: &Reference) -> Option<&'a LockEntry> {
+    let symbol = reference.symbol.display_name();
+    return lockfile
+        .entries
+        .iter()
+        .find(|e| return e.source == source && e.target == reference.target && e.symbol == symbol);
+}
+
+/// The diagnostic for a link that isn't in the lockfile yet.
+fn untracked_diagnostic(line: &str, reference: &Reference) -> Diagnostic {
+    let name = match reference.symbol.display_name().as_str() {
+        "" => reference.target.display().to_string(),
+        symbol => format!("{}#{symbol}", reference.target.display()),
+    };
+    return Diagnostic {
+        code: Some(NumberOrString::String("untracked".to_string())),
+        message: format!("{name} isn't in .docref.lock yet; run `docref init` to track it"),
+        range: link_range(line, reference),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        source: Some("docref".to_string()),
+        ..Diagnostic::default()
+    };
+}
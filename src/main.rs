@@ -2,38 +2,13 @@
 //!
 //! Parses command-line arguments and dispatches to the appropriate command handler.
 
-/// Command implementations for each CLI subcommand.
-mod commands;
-/// Configuration loading and namespace resolution.
-mod config;
-/// User-facing diagnostic formatting.
-mod diagnostics;
-/// Unified error type for the crate.
-mod error;
-/// Freshness checking logic for locked references.
-mod freshness;
-/// Tree-sitter grammar loading and symbol extraction.
-mod grammar;
-/// Content hashing for reference targets.
-mod hasher;
-/// Info command output generation.
-mod info;
-/// Lockfile serialization and deserialization.
-mod lockfile;
-/// Namespace mapping management.
-mod namespace;
-/// Symbol resolution from source files.
-mod resolver;
-/// Markdown scanning and reference extraction.
-mod scanner;
-/// Core domain types for references and symbols.
-mod types;
-/// File watching and live re-check.
-mod watch;
-
+use std::io::IsTerminal as _;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory as _, Parser, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+use docref::{baseline, commands, completion, config, diagnostics, error, lsp, manpage, namespace, serve, tui, watch};
 
 // ── Help text constants ───────────────────────────────────────────────
 
@@ -47,40 +22,69 @@ Workflow:
   5. docref update <file>              # Accept whole-file changes
 
 Exit codes (check):  0=fresh  1=stale  2=broken  3=error
+Pass --exit-zero to report findings without failing; errors still exit 3.
+Set DOCREF_LOG=debug (or --log-level debug) for traces when filing a bug.
 
 Run `docref info` for the full reference.";
 
+/// After-help text for the `baseline` subcommand.
+const BASELINE_HELP: &str = "\
+Records every current stale, broken, ambiguous, or orphaned reference so
+`docref check --baseline` reports them without failing. New findings, or a
+baselined reference whose finding changes kind, still fail the check.
+
+Examples:
+  docref baseline write                       # Write .docref-baseline.toml
+  docref check --baseline                     # Fail only on new findings
+  docref baseline write --output ci/known.toml";
+
 /// After-help text for the `check` subcommand.
 const CHECK_HELP: &str = "\
 Exit codes:
-  0  All references fresh
+  0  All references fresh (or only warnings)
   1  Stale references (code changed)
-  2  Broken references (symbol/file missing)
+  2  Broken, ambiguous, or orphaned references
+
+Severity of each finding comes from [severity] in .docref.toml.
 
 Examples:
   docref check                      # Verify all references
   docref check && echo 'Fresh'      # CI gate pattern
+  docref check --max-severity warning   # Report without failing
+  docref check --baseline           # Tolerate findings in .docref-baseline.toml
+  docref check --max-failures 20    # Stop after 20 failing references
+  docref check --output report.json # Save the JSON report for a CI artifact
+  docref check --format markdown    # Report to post as a pull request comment
 
 Supports both [text](file#symbol) and [text](file) whole-file references.";
 
 /// After-help text for the `fix` subcommand.
 const FIX_HELP: &str = "\
 Auto-corrects references where the symbol name is a close match
-(e.g., missing generic parameters). Rewrites markdown in-place.
+(e.g., missing generic parameters). A symbol that moved unchanged to
+another file has the link's path rewritten instead, keeping its relative
+or namespaced form, as does a path whose letter case differs from the
+file on disk. Rewrites markdown in-place.
 
 Modes:
-  docref fix                                    # Auto-fix all (closest match)
+  docref fix                                    # Auto-fix all (closest match or moved)
   docref fix src/lib.rs#old_symbol new_symbol   # Replace with a specific symbol
+  docref fix --dry-run --diff                   # Print the edits as a unified diff
 
 Examples:
   docref fix                                         # Fix all broken references
   docref fix src/lib.rs#RingBuffer.new 'RingBuffer<T>.new'
-  docref init || docref fix                          # Init, fix if broken";
+  docref init || docref fix                          # Init, fix if broken
+  docref fix --dry-run --diff > docref-fix.patch     # Suggest fixes in CI";
 
 /// After-help text for the `info` subcommand.
 const INFO_HELP: &str = "\
+Topics: how-it-works, syntax, paths, setup, commands, examples, config,
+languages, state, exit-codes.
+
 Examples:
   docref info                       # Full markdown reference
+  docref info --topic examples      # Worked examples for each command
   docref info --json                # Structured JSON output";
 
 /// After-help text for the `init` subcommand.
@@ -92,18 +96,31 @@ Examples:
   docref init                       # Scan and generate lockfile
   docref init && docref check       # Init then verify";
 
+/// After-help text for the `refs` subcommand.
+const REFS_HELP: &str = "\
+Examples:
+  docref refs src/lib.rs                 # Every doc referencing the file
+  docref refs src/lib.rs#add             # Docs referencing one symbol
+  docref refs --symbol validate          # Any target's `validate`, incl. Type.validate
+  docref refs --symbol add --format json";
+
 /// After-help text for the `resolve` subcommand.
 const RESOLVE_HELP: &str = "\
 Examples:
   docref resolve src/lib.rs              # List all symbols
   docref resolve src/lib.rs add          # Check if 'add' exists
-  docref resolve src/lib.rs Config.validate  # Dot-scoped lookup";
+  docref resolve src/lib.rs Config.validate  # Dot-scoped lookup
+  docref resolve src/lib.rs add --explain    # Show how 'add' was resolved";
 
 /// After-help text for the `status` subcommand.
 const STATUS_HELP: &str = "\
 Examples:
   docref status                     # Show all tracked references
-  docref status | grep STALE        # Find stale references";
+  docref status | grep STALE        # Find stale references
+  docref status --summary-only      # Counts and % fresh, per source file
+  docref status --by team           # Which teams' docs drift most
+  docref status --by owner          # Route drift to CODEOWNERS owners
+  docref status --tag public        # Only entries tagged in .docref.lock";
 
 /// After-help text for the `update` subcommand.
 const UPDATE_HELP: &str = "\
@@ -112,15 +129,29 @@ Modes:
   docref update <file>              # Re-hash a whole-file reference
   docref update --from <file.md>    # Re-hash all refs from a markdown file
   docref update --all               # Re-hash every lockfile entry
+  docref update --all --stale-only  # Re-hash only entries whose code changed
+  docref update <ref> --note <text> # Re-hash and record why the change was accepted
 
 Examples:
   docref update src/lib.rs#add
   docref update src/lib.rs
   docref update --from docs/guide.md
-  docref update --all";
+  docref update --all
+  docref update --all --stale-only";
 
 // ── CLI definition ────────────────────────────────────────────────────
 
+/// Actions available under the `baseline` subcommand.
+#[derive(Subcommand)]
+enum BaselineAction {
+    /// Record all current findings as known
+    Write {
+        /// Baseline file to write
+        #[arg(long, default_value = baseline::DEFAULT_PATH)]
+        output: PathBuf,
+    },
+}
+
 /// Top-level CLI structure parsed by clap.
 #[derive(Parser)]
 #[command(name = "docref", version, about = "Semantic code references for markdown")]
@@ -129,22 +160,63 @@ struct Cli {
     /// The subcommand to execute.
     #[command(subcommand)]
     command: Commands,
+    /// Run as if docref was started in this directory
+    #[arg(short = 'C', long = "cwd", global = true, value_name = "DIR", default_value = ".")]
+    cwd: PathBuf,
+    /// Exit 0 even when findings fail the check (runtime errors still exit 3)
+    #[arg(long, global = true)]
+    exit_zero: bool,
+    /// Log filter for debug traces on stderr, e.g. `debug` (overrides DOCREF_LOG)
+    #[arg(long, global = true, value_name = "FILTER")]
+    log_level: Option<String>,
 }
 
 /// Available CLI subcommands.
 #[derive(Subcommand)]
 enum Commands {
+    /// Record or manage known findings that check should tolerate
+    #[command(after_help = BASELINE_HELP)]
+    Baseline {
+        /// The baseline action to perform.
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
     /// Verify all references are still fresh
     #[command(after_help = CHECK_HELP)]
     Check {
-        /// Output format: text or json
+        /// Report findings listed in this baseline without failing on them
+        #[arg(long, num_args = 0..=1, default_missing_value = baseline::DEFAULT_PATH)]
+        baseline: Option<PathBuf>,
+        /// Stop at the first error-level finding (same as --max-failures 1)
+        #[arg(long, conflicts_with = "max_failures")]
+        fail_fast: bool,
+        /// Output format: text, json, junit, markdown (a PR comment), or sarif (code scanning)
         #[arg(long, default_value = "text")]
         format: String,
+        /// URL prefix for file links in markdown output (default: the GitHub Actions commit)
+        #[arg(long, value_name = "URL")]
+        link_base: Option<String>,
+        /// Stop after this many error-level findings
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        max_failures: Option<u32>,
+        /// Cap every finding at this severity: ignore, warning, or error
+        #[arg(long)]
+        max_severity: Option<config::Severity>,
+        /// Write the JSON report to this file; stdout keeps only text output
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
     },
     /// Auto-fix broken references when a close match exists
     #[command(after_help = FIX_HELP)]
     Fix {
+        /// Print a unified diff of the markdown edits (requires --dry-run)
+        #[arg(long, requires = "dry_run")]
+        diff: bool,
+        /// Report the fixes without rewriting any markdown
+        #[arg(long)]
+        dry_run: bool,
         /// Broken reference in `file#symbol` format (e.g., `src/lib.rs#old_name`)
+        #[arg(add = ArgValueCompleter::new(completion::complete_reference))]
         reference: Option<String>,
         /// Replacement symbol name (required when reference is specified)
         symbol: Option<String>,
@@ -155,10 +227,22 @@ enum Commands {
         /// Output as JSON instead of markdown
         #[arg(long)]
         json: bool,
+        /// Print only this section, e.g. commands, examples, or languages
+        #[arg(long, conflicts_with = "json", value_name = "SECTION")]
+        topic: Option<docref::info::Topic>,
     },
     /// Scan markdown files and generate .docref.lock
     #[command(after_help = INIT_HELP)]
     Init,
+    /// Serve editor diagnostics over the language server protocol on stdio
+    Lsp,
+    /// Generate man pages and a markdown CLI reference
+    #[command(hide = true)]
+    Man {
+        /// Directory to write the generated files into
+        #[arg(long, value_name = "DIR", default_value = "man")]
+        out_dir: PathBuf,
+    },
     /// Manage namespace mappings
     Namespace {
         /// The namespace action to perform.
@@ -166,24 +250,59 @@ enum Commands {
         action: NamespaceAction,
     },
     /// Show which markdown files reference a target file or symbol
+    #[command(after_help = REFS_HELP)]
     Refs {
+        /// Output format: text or json
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// Match this symbol in any target (also matches `Type.symbol`)
+        #[arg(long)]
+        symbol: Option<String>,
         /// Target in file or file#symbol format
-        target: String,
+        #[arg(required_unless_present = "symbol", add = ArgValueCompleter::new(completion::complete_reference))]
+        target: Option<String>,
     },
     /// List addressable symbols in a file, or resolve a specific symbol
     #[command(after_help = RESOLVE_HELP)]
     Resolve {
+        /// Print the grammar, candidate declarations, and final byte range
+        #[arg(long, requires = "symbol")]
+        explain: bool,
         /// Path to the source file
         file: String,
         /// Optional symbol name to resolve
         symbol: Option<String>,
     },
+    /// Serve a freshness dashboard and the status and check reports over HTTP
+    Serve {
+        /// Address to listen on; port 0 picks a free one
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        addr: String,
+    },
     /// Show all tracked references and their current freshness
     #[command(after_help = STATUS_HELP)]
     Status {
+        /// Report how long stale references have been stale, from git history
+        #[arg(long)]
+        ages: bool,
+        /// Break the summary down by language, namespace, owner (CODEOWNERS), or team
+        #[arg(long, value_name = "GROUPING")]
+        by: Option<commands::StatusGrouping>,
         /// Output format: text or json
         #[arg(long, default_value = "text")]
         format: String,
+        /// Write the JSON report to this file; stdout keeps only text output
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+        /// Only show entries with this owner in the lockfile
+        #[arg(long)]
+        owner: Option<String>,
+        /// Print only the summary, without per-reference lines
+        #[arg(long)]
+        summary_only: bool,
+        /// Only show entries carrying this tag in the lockfile
+        #[arg(long)]
+        tag: Option<String>,
     },
     /// Re-hash a stale reference so check passes again
     #[command(after_help = UPDATE_HELP)]
@@ -194,15 +313,34 @@ enum Commands {
         /// Update all references originating from this markdown file
         #[arg(long, conflicts_with = "all")]
         from: Option<String>,
+        /// Record a note on the entry, e.g. why the change was accepted
+        #[arg(long, requires = "reference")]
+        note: Option<String>,
         /// Reference in file#symbol format (e.g., src/lib.rs#add)
-        #[arg(conflicts_with_all = ["from", "all"])]
+        #[arg(conflicts_with_all = ["from", "all"], add = ArgValueCompleter::new(completion::complete_reference))]
         reference: Option<String>,
+        /// With --all, re-hash only entries whose code changed
+        #[arg(long, requires = "all")]
+        stale_only: bool,
     },
     /// Watch source files and re-check on changes
     Watch {
+        /// Shell command to run after each re-check, with the exit code in
+        /// DOCREF_EXIT_CODE and a JSON summary of findings on stdin
+        #[arg(long, conflicts_with = "tui")]
+        exec: Option<String>,
         /// Output format: text or json
         #[arg(long, default_value = "text")]
         format: String,
+        /// Interactive dashboard: filter, inspect, accept or ignore findings
+        #[arg(long, conflicts_with = "format")]
+        tui: bool,
+    },
+    /// Commands spanning every project in a monorepo
+    Workspace {
+        /// The workspace action to run.
+        #[command(subcommand)]
+        action: WorkspaceAction,
     },
 }
 
@@ -216,8 +354,12 @@ enum NamespaceAction {
         /// Directory path (relative to config root)
         path: String,
     },
-    /// List all configured namespaces
-    List,
+    /// List all configured namespaces with paths, origin, and reference counts
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Remove a namespace mapping
     Remove {
         /// Force removal even if references exist
@@ -237,21 +379,94 @@ enum NamespaceAction {
     },
 }
 
+/// Actions available under the `workspace` subcommand.
+#[derive(Subcommand)]
+enum WorkspaceAction {
+    /// Check every member project and print a combined freshness rollup
+    Status {
+        /// Output format: text or json
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+}
+
+/// Route a parsed subcommand to its handler.
+///
+/// # Errors
+///
+/// Returns errors from the underlying command.
+fn dispatch(root: &Path, command: Commands) -> Result<ExitCode, error::Error> {
+    return match command {
+        Commands::Baseline { action: BaselineAction::Write { output } } => {
+            commands::baseline_write(root, &output).map(|()| return ExitCode::SUCCESS)
+        },
+        Commands::Check { baseline, fail_fast, format, link_base, max_failures, max_severity, output } => {
+            let options = commands::CheckOptions { baseline, link_base, max_failures, max_severity, output };
+            dispatch_check(root, &format, fail_fast, options)
+        },
+        Commands::Fix { diff, dry_run, reference, symbol } => {
+            dispatch_fix(root, reference, symbol, &commands::FixOptions { diff, dry_run })
+        },
+        Commands::Info { json, topic } => {
+            commands::info(root, json, topic);
+            Ok(ExitCode::SUCCESS)
+        },
+        Commands::Init => commands::init(root).map(|()| return ExitCode::SUCCESS),
+        Commands::Lsp => lsp::run(root).map(|()| return ExitCode::SUCCESS),
+        Commands::Man { out_dir } => {
+            manpage::generate(&Cli::command(), &root.join(out_dir)).map(|()| return ExitCode::SUCCESS)
+        },
+        Commands::Namespace { action } => dispatch_namespace(root, action),
+        Commands::Refs { format, symbol, target } => {
+            commands::refs(root, target.as_deref(), symbol.as_deref(), &format).map(|()| return ExitCode::SUCCESS)
+        },
+        Commands::Resolve { explain, file, symbol } => {
+            commands::resolve(root, &file, symbol.as_deref(), explain).map(|()| return ExitCode::SUCCESS)
+        },
+        Commands::Serve { addr } => serve::run(root, &addr),
+        Commands::Status { ages, by, format, output, owner, summary_only, tag } => {
+            dispatch_status(root, &format, &commands::StatusOptions { ages, by, output, owner, summary_only, tag })
+        },
+        Commands::Update { all, from, note, reference, stale_only } => {
+            dispatch_update(root, reference, from, note.as_deref(), all, stale_only)
+        },
+        Commands::Watch { exec, format, tui } => dispatch_watch(root, &format, exec.as_deref(), tui),
+        Commands::Workspace { action: WorkspaceAction::Status { format } } => commands::workspace_status(root, &format),
+    };
+}
+
+/// Run `check`, where `--fail-fast` stands for `--max-failures 1`.
+///
+/// # Errors
+///
+/// Returns errors from the underlying check.
+fn dispatch_check(
+    root: &Path,
+    format: &str,
+    fail_fast: bool,
+    options: commands::CheckOptions,
+) -> Result<ExitCode, error::Error> {
+    let max_failures = if fail_fast { Some(1) } else { options.max_failures };
+    return commands::check(root, format, &commands::CheckOptions { max_failures, ..options });
+}
+
 /// Route the `fix` subcommand to the right handler.
 ///
 /// # Errors
 ///
 /// Returns errors from the underlying fix operation.
 fn dispatch_fix(
+    root: &Path,
     reference: Option<String>,
     symbol: Option<String>,
+    options: &commands::FixOptions,
 ) -> Result<ExitCode, error::Error> {
     return match (reference, symbol) {
-        (None, None) => commands::fix().map(|()| return ExitCode::SUCCESS),
-        (Some(r), Some(s)) => commands::fix_targeted(&r, &s).map(|()| return ExitCode::SUCCESS),
+        (None, None) => commands::fix(root, options).map(|()| return ExitCode::SUCCESS),
+        (Some(r), Some(s)) => commands::fix_targeted(root, &r, &s, options).map(|()| return ExitCode::SUCCESS),
         _ => {
             eprintln!("error: provide both a file#symbol reference and a replacement symbol, or neither");
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(3_u8))
         },
     };
 }
@@ -261,71 +476,119 @@ fn dispatch_fix(
 /// # Errors
 ///
 /// Returns errors from the underlying namespace operation.
-fn dispatch_namespace(action: NamespaceAction) -> Result<ExitCode, error::Error> {
+fn dispatch_namespace(root: &Path, action: NamespaceAction) -> Result<ExitCode, error::Error> {
     return match action {
         NamespaceAction::Add { name, path } => {
-            namespace::cmd_add(&name, &path).map(|()| return ExitCode::SUCCESS)
+            namespace::cmd_add(root, &name, &path).map(|()| return ExitCode::SUCCESS)
         },
-        NamespaceAction::List => namespace::cmd_list().map(|()| return ExitCode::SUCCESS),
+        NamespaceAction::List { json } => namespace::cmd_list(root, json).map(|()| return ExitCode::SUCCESS),
         NamespaceAction::Remove { name, force } => {
-            namespace::cmd_remove(&name, force).map(|()| return ExitCode::SUCCESS)
+            namespace::cmd_remove(root, &name, force).map(|()| return ExitCode::SUCCESS)
         },
         NamespaceAction::Rename { old, new } => {
-            namespace::cmd_rename(&old, &new).map(|()| return ExitCode::SUCCESS)
+            namespace::cmd_rename(root, &old, &new).map(|()| return ExitCode::SUCCESS)
         },
     };
 }
 
+/// Run `status`, which always exits 0 once the report is printed.
+///
+/// # Errors
+///
+/// Returns errors from the underlying status report.
+fn dispatch_status(root: &Path, format: &str, options: &commands::StatusOptions) -> Result<ExitCode, error::Error> {
+    return commands::status(root, format, options).map(|()| return ExitCode::SUCCESS);
+}
+
 /// Route the `update` subcommand to the right handler.
 ///
 /// # Errors
 ///
 /// Returns errors from the underlying update operation.
 fn dispatch_update(
+    root: &Path,
     reference: Option<String>,
     from: Option<String>,
+    note: Option<&str>,
     all: bool,
+    stale_only: bool,
 ) -> Result<ExitCode, error::Error> {
+    if all && stale_only {
+        return commands::update_stale(root).map(|()| return ExitCode::SUCCESS);
+    }
     if all {
-        return commands::update_all().map(|()| return ExitCode::SUCCESS);
+        return commands::update_all(root).map(|()| return ExitCode::SUCCESS);
     }
     return match (reference, from) {
-        (Some(r), None) => commands::update(&r).map(|()| return ExitCode::SUCCESS),
-        (None, Some(f)) => commands::update_file(&f).map(|()| return ExitCode::SUCCESS),
+        (Some(r), None) => commands::update(root, &r, note).map(|()| return ExitCode::SUCCESS),
+        (None, Some(f)) => commands::update_file(root, &f).map(|()| return ExitCode::SUCCESS),
         _ => {
             eprintln!("error: provide a file#symbol reference, --from, or --all");
-            Ok(ExitCode::FAILURE)
+            Ok(ExitCode::from(3_u8))
         },
     };
 }
 
+/// Route the `watch` subcommand to the dashboard or the plain watcher.
+///
+/// # Errors
+///
+/// Returns errors from the underlying watcher.
+fn dispatch_watch(root: &Path, format: &str, exec: Option<&str>, tui: bool) -> Result<ExitCode, error::Error> {
+    if tui {
+        return tui::run(root);
+    }
+    return watch::run(root, format, exec);
+}
+
+/// Install the stderr trace subscriber. `--log-level` wins over `DOCREF_LOG`;
+/// with neither, only warnings and errors are logged.
+///
+/// A bare level such as `debug` applies to docref only, so dependency traces
+/// stay quiet; full directives like `docref::config=trace` pass through.
+fn init_logging(level: Option<&str>) {
+    let spec = level.map(String::from).or_else(|| return std::env::var("DOCREF_LOG").ok());
+    let directives = match spec.as_deref() {
+        None | Some("") => "warn".to_string(),
+        Some(filter) if filter.contains(['=', ',']) => filter.to_string(),
+        Some(level) => format!("warn,docref={level}"),
+    };
+    let filter = tracing_subscriber::EnvFilter::try_new(&directives).unwrap_or_else(|e| {
+        eprintln!("warning: ignoring invalid log filter `{directives}`: {e}");
+        return tracing_subscriber::EnvFilter::new("warn");
+    });
+    tracing_subscriber::fmt()
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+    return;
+}
+
 /// Entry point that parses CLI arguments and dispatches to command handlers.
+///
+/// Runtime errors, including invalid arguments, exit 3 so they never collide
+/// with the stale (1) and broken (2) finding codes.
 fn main() -> ExitCode {
-    let cli = Cli::parse();
+    clap_complete::CompleteEnv::with_factory(Cli::command).complete();
 
-    let result = match cli.command {
-        Commands::Check { format } => commands::check(&format),
-        Commands::Fix { reference, symbol } => dispatch_fix(reference, symbol),
-        Commands::Info { json } => {
-            commands::info(json);
-            Ok(ExitCode::SUCCESS)
-        },
-        Commands::Init => commands::init().map(|()| return ExitCode::SUCCESS),
-        Commands::Namespace { action } => dispatch_namespace(action),
-        Commands::Refs { target } => commands::refs(&target).map(|()| return ExitCode::SUCCESS),
-        Commands::Resolve { file, symbol } => {
-            commands::resolve(&file, symbol.as_deref()).map(|()| return ExitCode::SUCCESS)
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() { ExitCode::from(3_u8) } else { ExitCode::SUCCESS };
         },
-        Commands::Status { format } => commands::status(&format).map(|()| return ExitCode::SUCCESS),
-        Commands::Update { reference, from, all } => dispatch_update(reference, from, all),
-        Commands::Watch { format } => watch::run(&format),
     };
 
-    return match result {
+    init_logging(cli.log_level.as_deref());
+
+    let runtime_error = ExitCode::from(3_u8);
+    return match dispatch(&cli.cwd, cli.command) {
+        Ok(code) if cli.exit_zero && code != runtime_error => ExitCode::SUCCESS,
         Ok(code) => code,
         Err(e) => {
             diagnostics::print_error(&e);
-            ExitCode::from(3_u8)
+            runtime_error
         },
     };
 }
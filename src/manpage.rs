@@ -0,0 +1,25 @@
+//! The hidden `man` subcommand — renders man pages and a markdown CLI
+//! reference from the clap definitions, for packagers and docs sites.
+
+use std::path::Path;
+
+use crate::error;
+
+/// File name of the generated markdown CLI reference.
+const MARKDOWN_FILE: &str = "docref.md";
+
+/// Write one man page per visible command plus a markdown CLI reference.
+///
+/// Pages are named after the command path (`docref.1`, `docref-check.1`, ...)
+/// and hidden commands are skipped.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if the output directory or any file can't be written.
+pub fn generate(command: &clap::Command, out_dir: &Path) -> Result<(), error::Error> {
+    std::fs::create_dir_all(out_dir)?;
+    clap_mangen::generate_to(command.clone(), out_dir)?;
+    std::fs::write(out_dir.join(MARKDOWN_FILE), clap_markdown::help_markdown_command(command))?;
+    eprintln!("Wrote man pages and {MARKDOWN_FILE} to {}", out_dir.display());
+    return Ok(());
+}
@@ -1,9 +1,35 @@
 use std::path::{Path, PathBuf};
 
+use serde::Serialize;
+
 use crate::config;
 use crate::error;
 use crate::lockfile::{LockEntry, Lockfile};
 
+/// JSON output for a single namespace in `namespace list --json`.
+#[derive(Serialize)]
+struct NamespaceJson {
+    /// The mapped directory, resolved to an absolute path.
+    absolute_path: PathBuf,
+    /// The `.docref.toml` that defined the namespace, relative to the project root.
+    config_file: PathBuf,
+    /// Whether the namespace came from a parent config via `extends`.
+    inherited: bool,
+    /// Namespace short name.
+    name: String,
+    /// Directory path as written in the defining config.
+    path: String,
+    /// Number of lockfile entries whose target uses this namespace.
+    references: usize,
+}
+
+/// JSON output for `namespace list --json`.
+#[derive(Serialize)]
+struct NamespaceListJson {
+    /// Configured namespaces, sorted by name.
+    namespaces: Vec<NamespaceJson>,
+}
+
 /// Add a namespace mapping to `.docref.toml`.
 /// Creates the `[namespaces]` table if it doesn't exist.
 ///
@@ -41,31 +67,55 @@ fn add_to_config(root: &Path, name: &str, namespace_path: &str) -> Result<(), er
 /// # Errors
 ///
 /// Returns errors from config writing.
-pub fn cmd_add(name: &str, path: &str) -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
-    add_to_config(&root, name, path)?;
+pub fn cmd_add(root: &Path, name: &str, path: &str) -> Result<(), error::Error> {
+    add_to_config(root, name, path)?;
     eprintln!("Added namespace: {name} -> {path}");
     return Ok(());
 }
 
 /// List all configured namespaces, sorted alphabetically.
 ///
+/// Each namespace is shown with its resolved absolute directory, the config
+/// file that defined it (own or inherited via `extends`), and how many
+/// lockfile entries target it. `json` switches to structured output.
+/// Without a lockfile, every namespace has no refs.
+///
 /// # Errors
 ///
-/// Returns errors from config loading.
-pub fn cmd_list() -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
-    let config = config::Config::load(&root)?;
+/// Returns errors from config loading, or reading a lockfile that exists.
+pub fn cmd_list(root: &Path, json: bool) -> Result<(), error::Error> {
+    let config = config::Config::load(root)?;
+    let lockfile = match Lockfile::read(&root.join(".docref.lock")) {
+        Err(error::Error::LockfileNotFound { .. }) => None,
+        read => Some(read?),
+    };
+
+    let mut namespaces: Vec<NamespaceJson> = config
+        .namespaces
+        .iter()
+        .map(|(name, entry)| return describe_namespace(root, &config, name, entry, lockfile.as_ref()))
+        .collect();
+    namespaces.sort_by(|a, b| return a.name.cmp(&b.name));
 
-    if config.namespaces.is_empty() {
+    if json {
+        let output = NamespaceListJson { namespaces };
+        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        return Ok(());
+    }
+    if namespaces.is_empty() {
         println!("No namespaces configured.");
         return Ok(());
     }
-
-    let mut sorted: Vec<_> = config.namespaces.iter().collect();
-    sorted.sort_by_key(|(name, _)| return name.as_str());
-    for (name, entry) in sorted {
-        println!("{name} -> {}", entry.path);
+    for ns in namespaces {
+        let origin = if ns.inherited { "inherited from" } else { "defined in" };
+        println!(
+            "{} -> {}  [{}; {} refs; {origin} {}]",
+            ns.name,
+            ns.path,
+            ns.absolute_path.display(),
+            ns.references,
+            ns.config_file.display(),
+        );
     }
 
     return Ok(());
@@ -78,8 +128,7 @@ pub fn cmd_list() -> Result<(), error::Error> {
 ///
 /// Returns `Error::NamespaceInUse` if references exist (without `--force`),
 /// or errors from config/lockfile operations.
-pub fn cmd_remove(name: &str, force: bool) -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+pub fn cmd_remove(root: &Path, name: &str, force: bool) -> Result<(), error::Error> {
     let lock_path = root.join(".docref.lock");
 
     let prefix = format!("{name}:");
@@ -99,7 +148,7 @@ pub fn cmd_remove(name: &str, force: bool) -> Result<(), error::Error> {
         }
     }
 
-    remove_from_config(&root, name)?;
+    remove_from_config(root, name)?;
 
     if lock_path.exists() {
         let lockfile = Lockfile::read(&lock_path)?;
@@ -121,11 +170,10 @@ pub fn cmd_remove(name: &str, force: bool) -> Result<(), error::Error> {
 /// # Errors
 ///
 /// Returns errors from config or lockfile operations, or markdown rewriting.
-pub fn cmd_rename(old: &str, new: &str) -> Result<(), error::Error> {
-    let root = PathBuf::from(".");
+pub fn cmd_rename(root: &Path, old: &str, new: &str) -> Result<(), error::Error> {
     let lock_path = root.join(".docref.lock");
 
-    rename_in_config(&root, old, new)?;
+    rename_in_config(root, old, new)?;
 
     if lock_path.exists() {
         let lockfile = Lockfile::read(&lock_path)?;
@@ -134,13 +182,42 @@ pub fn cmd_rename(old: &str, new: &str) -> Result<(), error::Error> {
         lockfile.write(&lock_path)?;
     }
 
-    let config = config::Config::load(&root)?;
-    rewrite_in_markdown_files(&root, &config, old, new)?;
+    let config = config::Config::load(root)?;
+    rewrite_in_markdown_files(root, &config, old, new)?;
 
     eprintln!("Renamed namespace: {old} -> {new}");
     return Ok(());
 }
 
+/// Gather list output for one namespace, counting lockfile entries that target it.
+///
+/// Aliases are followed to their final directory; a broken alias chain falls
+/// back to the value as written.
+fn describe_namespace(
+    root: &Path,
+    config: &config::Config,
+    name: &str,
+    entry: &config::NamespaceEntry,
+    lockfile: Option<&Lockfile>,
+) -> NamespaceJson {
+    let directory = root.join(config.resolve_namespace(name).unwrap_or_else(|_| return entry.directory()));
+    let absolute_path = std::fs::canonicalize(&directory)
+        .or_else(|_| return std::path::absolute(&directory))
+        .unwrap_or(directory);
+    let prefix = format!("{name}:");
+    let references = lockfile.map_or(0, |l| {
+        return l.entries.iter().filter(|e| return e.target.to_string_lossy().starts_with(&prefix)).count();
+    });
+    return NamespaceJson {
+        absolute_path,
+        config_file: entry.config_file(),
+        inherited: entry.is_inherited(),
+        name: name.to_string(),
+        path: entry.path.clone(),
+        references,
+    };
+}
+
 /// Parse a `.docref.toml` into a format-preserving document.
 /// Returns an empty document if the file doesn't exist.
 ///
@@ -178,12 +255,14 @@ fn remove_from_config(root: &Path, name: &str) -> Result<(), error::Error> {
         .and_then(toml_edit::Item::as_table_mut)
         .ok_or_else(|| {
             return error::Error::UnknownNamespace {
+                available: Vec::new(),
                 name: name.to_string(),
             };
         })?;
 
     if namespaces.remove(name).is_none() {
         return Err(error::Error::UnknownNamespace {
+            available: table_keys(namespaces),
             name: name.to_string(),
         });
     }
@@ -205,12 +284,15 @@ fn rename_in_config(root: &Path, old: &str, new: &str) -> Result<(), error::Erro
         .and_then(toml_edit::Item::as_table_mut)
         .ok_or_else(|| {
             return error::Error::UnknownNamespace {
+                available: Vec::new(),
                 name: old.to_string(),
             };
         })?;
 
+    let available = table_keys(namespaces);
     let value = namespaces.remove(old).ok_or_else(|| {
         return error::Error::UnknownNamespace {
+            available,
             name: old.to_string(),
         };
     })?;
@@ -237,7 +319,8 @@ fn rename_in_lock_entries(entries: Vec<LockEntry>, old: &str, new: &str) -> Vec<
         .collect();
 }
 
-/// Rewrite namespace prefixes in markdown link targets across all scanned files.
+/// Rewrite namespace prefixes in markdown link, wiki link, HTML anchor,
+/// and shortcode targets across all scanned files.
 ///
 /// # Errors
 ///
@@ -248,13 +331,22 @@ fn rewrite_in_markdown_files(
     old: &str,
     new: &str,
 ) -> Result<(), error::Error> {
-    let old_prefix = format!("]({old}:");
-    let new_prefix = format!("]({new}:");
+    let mut prefixes = vec![
+        (format!("]({old}:"), format!("]({new}:")),
+        (format!("[[{old}:"), format!("[[{new}:")),
+        (format!("href=\"{old}:"), format!("href=\"{new}:")),
+    ];
+    if !config.shortcode.is_empty() {
+        let shortcodes = ["{{<", "{{%", "{%"].into_iter().flat_map(|open| {
+            return ['"', '\''].map(|quote| return format!("{open} {} {quote}", config.shortcode));
+        });
+        prefixes.extend(shortcodes.map(|code| return (format!("{code}{old}:"), format!("{code}{new}:"))));
+    }
 
     for entry in walkdir::WalkDir::new(root)
         .into_iter()
         .filter_map(Result::ok)
-        .filter(|e| return e.path().extension().is_some_and(|ext| return ext == "md"))
+        .filter(|e| return config.is_markdown(e.path()))
     {
         let md_path = entry.path();
         let relative = md_path.strip_prefix(root).unwrap_or(md_path);
@@ -263,11 +355,20 @@ fn rewrite_in_markdown_files(
         }
 
         let content = std::fs::read_to_string(md_path)?;
-        if content.contains(&old_prefix) {
-            let updated = content.replace(&old_prefix, &new_prefix);
+        let updated = prefixes
+            .iter()
+            .fold(content.clone(), |text, (old_prefix, new_prefix)| return text.replace(old_prefix, new_prefix));
+        if updated != content {
             std::fs::write(md_path, updated)?;
         }
     }
 
     return Ok(());
 }
+
+/// Sorted key names of a TOML table, for did-you-mean suggestions.
+fn table_keys(table: &toml_edit::Table) -> Vec<String> {
+    let mut keys: Vec<String> = table.iter().map(|(key, _)| return key.to_string()).collect();
+    keys.sort();
+    return keys;
+}
@@ -0,0 +1,61 @@
+//! Go package directories as reference targets.
+//!
+//! A Go package spans every `.go` file in its directory, so a link such as
+//! `pkg/auth#Validate` names the directory rather than one file. The files
+//! are read as one source, in name order, and resolved together; tree-sitter
+//! accepts the repeated `package` clauses and imports.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name standing in for every Go file of a package directory, so the
+/// path still picks the Go grammar and reads well in messages.
+const GO_FILES: &str = "*.go";
+
+/// The package's Go files in name order, leaving out `_test.go` files.
+///
+/// # Errors
+///
+/// Returns the I/O error from listing `dir`.
+fn go_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| return entry.ok().map(|e| return e.path()))
+        .filter(|path| return path.is_file() && is_package_file(path))
+        .collect();
+    files.sort();
+    return Ok(files);
+}
+
+/// Whether `path` is a non-test Go source file.
+fn is_package_file(path: &Path) -> bool {
+    let name = path.file_name().map(|n| return n.to_string_lossy()).unwrap_or_default();
+    return name.ends_with(".go") && !name.ends_with("_test.go");
+}
+
+/// Read the source at `path`, a file or a package path from [`source_path`].
+///
+/// # Errors
+///
+/// Returns the I/O error from reading the file or listing the directory.
+pub fn read_source(path: &Path) -> io::Result<String> {
+    let dir = path.parent().filter(|_| return path.file_name().is_some_and(|n| return n == GO_FILES));
+    let Some(dir) = dir else {
+        return std::fs::read_to_string(path);
+    };
+    let mut source = String::new();
+    for file in go_files(dir)? {
+        source.push_str(&std::fs::read_to_string(file)?);
+        source.push('\n');
+    }
+    return Ok(source);
+}
+
+/// Where to read a resolved target from: `disk_path` itself, or `dir/*.go`
+/// when it names a directory holding a Go package.
+pub fn source_path(root: &Path, disk_path: PathBuf) -> PathBuf {
+    let dir = root.join(&disk_path);
+    if dir.is_dir() && go_files(&dir).is_ok_and(|files| return !files.is_empty()) {
+        return disk_path.join(GO_FILES);
+    }
+    return disk_path;
+}
@@ -0,0 +1,85 @@
+//! Progress reporting for long-running commands such as `init`.
+//!
+//! On a terminal, progress is drawn as an indicatif spinner or bar on stderr.
+//! Otherwise (CI logs, pipes) a plain line is printed every few seconds, so
+//! short runs stay quiet and long ones still show signs of life.
+
+use std::io::IsTerminal as _;
+use std::time::{Duration, Instant};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Minimum time between plain log lines when stderr is not a terminal.
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Progress of one phase of work, cleared from the terminal when dropped.
+pub struct Progress {
+    /// The spinner or bar, when stderr is a terminal.
+    bar: Option<ProgressBar>,
+    /// When the last plain log line was printed.
+    last_logged: Instant,
+    /// Total units of work, or `None` for a phase of unknown length.
+    length: Option<u64>,
+    /// Phase name shown before the counts.
+    phase: &'static str,
+}
+
+impl Progress {
+    /// A phase with a known amount of work, drawn as a bar.
+    pub fn bar(phase: &'static str, length: u64) -> Self {
+        let bar = std::io::stderr().is_terminal().then(|| {
+            let style = ProgressStyle::with_template("{prefix} [{bar:30}] {pos}/{len} {msg}")
+                .unwrap_or_else(|_| return ProgressStyle::default_bar())
+                .progress_chars("=> ");
+            return ProgressBar::new(length).with_style(style).with_prefix(phase);
+        });
+        return Self { bar, last_logged: Instant::now(), length: Some(length), phase };
+    }
+
+    /// Print a plain progress line if the log interval has passed.
+    fn log(&mut self, position: u64, message: &str) {
+        if self.last_logged.elapsed() < LOG_INTERVAL {
+            return;
+        }
+        self.last_logged = Instant::now();
+        match self.length {
+            Some(length) => eprintln!("{}: {position}/{length} {message}", self.phase),
+            None => eprintln!("{}: {message}", self.phase),
+        }
+        return;
+    }
+
+    /// A phase of unknown length, drawn as a spinner.
+    pub fn spinner(phase: &'static str) -> Self {
+        let bar = std::io::stderr().is_terminal().then(|| {
+            let style = ProgressStyle::with_template("{spinner} {prefix}: {msg}")
+                .unwrap_or_else(|_| return ProgressStyle::default_spinner());
+            let spinner = ProgressBar::new_spinner().with_style(style).with_prefix(phase);
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            return spinner;
+        });
+        return Self { bar, last_logged: Instant::now(), length: None, phase };
+    }
+
+    /// Record that `position` units are done, with a short status message.
+    pub fn update(&mut self, position: u64, message: &str) {
+        match &self.bar {
+            Some(bar) => {
+                bar.set_position(position);
+                bar.set_message(message.to_string());
+            },
+            None => self.log(position, message),
+        }
+        return;
+    }
+}
+
+impl Drop for Progress {
+    /// Clear the spinner or bar so later output starts on a clean line.
+    fn drop(&mut self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+        return;
+    }
+}
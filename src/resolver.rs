@@ -1,31 +1,380 @@
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
 
-use tree_sitter::{Language, Node, Parser, Tree};
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor, StreamingIterator as _, Tree};
 
+use crate::config::SlugStyle;
+use crate::diagnostics::strip_generics;
 use crate::error::Error;
+use crate::grammar::{self, Grammars};
 use crate::types::{ResolvedSymbol, SymbolQuery};
 
+/// Reserved words that can start a line of a Haskell module without
+/// binding a name.
+const HASKELL_KEYWORDS: &[&str] = &[
+    "case", "default", "deriving", "do", "else", "foreign", "if", "import", "in", "infix", "infixl", "infixr",
+    "instance", "let", "module", "of", "then", "where",
+];
+
+/// Modifiers that can precede the keyword of a Kotlin declaration.
+const KOTLIN_MODIFIERS: &[&str] = &[
+    "abstract", "actual", "annotation", "const", "data", "enum", "expect", "external", "final", "infix", "inline",
+    "inner", "internal", "lateinit", "open", "operator", "override", "private", "protected", "public", "sealed",
+    "suspend", "tailrec", "value",
+];
+
 /// Maximum source file size (16 MiB).
 const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
 
+/// Modifiers that can precede the keyword of a Scala declaration.
+const SCALA_MODIFIERS: &[&str] = &[
+    "abstract", "case", "final", "implicit", "inline", "lazy", "opaque", "open", "override", "package", "private",
+    "protected", "sealed", "transparent",
+];
+
+/// How one declaration fared against a query, for `resolve --explain`.
+pub struct Candidate {
+    /// Whether the resolver selected this declaration.
+    pub accepted: bool,
+    /// Byte range of the declaration in the source.
+    pub byte_range: Range<u32>,
+    /// CST node kinds from the tree root down to the declaration.
+    pub node_path: Vec<&'static str>,
+    /// Fully qualified name (e.g., "Type.method").
+    pub qualified_name: String,
+    /// Why the declaration was accepted or rejected.
+    pub reason: String,
+}
+
 /// A raw declaration found while walking the CST.
 struct Declaration {
     /// Byte range of the declaration in the source.
     byte_range: Range<u32>,
+    /// What sort of member this is, where the language tells them apart.
+    kind: Option<SymbolKind>,
     /// Short name of the declaration.
     name: String,
     /// Fully qualified name (e.g., "Type.method").
     qualified_name: String,
 }
 
+/// Every declaration in one parsed source file, so many queries against
+/// the same file share a single parse.
+pub struct Declarations {
+    /// The file the declarations came from, for error messages.
+    file_path: PathBuf,
+    /// Declarations in CST order.
+    items: Vec<Declaration>,
+}
+
+impl Declarations {
+    /// Qualified names of the direct members of `parent`, such as
+    /// `Config.port` for `Config`, sorted and without repeats.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SymbolNotFound` or `Error::AmbiguousSymbol` if `parent`
+    /// doesn't name exactly one declaration.
+    pub fn members(&self, parent: &str) -> Result<Vec<String>, Error> {
+        self.resolve(&SymbolQuery::Members(parent.to_string()))?;
+        let prefix = format!("{}.", strip_generics(parent));
+        let mut members: Vec<String> = self
+            .items
+            .iter()
+            .filter(|d| {
+                let name = strip_generics(&without_trait(&d.qualified_name));
+                return name.strip_prefix(&prefix).is_some_and(|rest| return !rest.contains('.'));
+            })
+            .map(|d| return d.qualified_name.clone())
+            .collect();
+        members.sort();
+        members.dedup();
+        return Ok(members);
+    }
+
+    /// Size-check and parse `source`, collecting its declarations.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::FileTooLarge` if the source exceeds the size limit,
+    /// or `Error::ParseFailed` if tree-sitter cannot parse the source.
+    pub fn parse(
+        file_path: &Path,
+        source: &str,
+        language: &Language,
+        slug_style: SlugStyle,
+        grammars: &Grammars,
+    ) -> Result<Self, Error> {
+        let (_, items) = parse_declarations(file_path, source, language, slug_style, grammars)?;
+        return Ok(Self { file_path: file_path.to_path_buf(), items });
+    }
+
+    /// Resolve one symbol query against the parsed declarations.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::SymbolNotFound` if no declaration matches the query,
+    /// or `Error::AmbiguousSymbol` if multiple declarations match a bare query.
+    pub fn resolve(&self, query: &SymbolQuery) -> Result<ResolvedSymbol, Error> {
+        return find_declaration(&self.items, query, &self.file_path);
+    }
+}
+
+/// Step-by-step account of a single resolution, for `resolve --explain`.
+pub struct Explanation {
+    /// Every declaration considered, in source order.
+    pub candidates: Vec<Candidate>,
+    /// Name of the tree-sitter grammar used to parse the file.
+    pub grammar: String,
+    /// The final resolution, or the error `resolve` would have returned.
+    pub outcome: Result<ResolvedSymbol, Error>,
+}
+
+/// An item found by [`collect_outline_declarations`]: a line indented like
+/// its siblings, with the deeper lines after it.
+struct OutlineItem<'a> {
+    /// The item's first line, without its indentation.
+    line: &'a str,
+    /// Qualified name of the enclosing item, if any.
+    parent: Option<&'a str>,
+    /// Where the item lies in the source.
+    range: Range<usize>,
+    /// The item's text.
+    text: &'a str,
+}
+
+impl OutlineItem<'_> {
+    /// `name`, qualified by the enclosing item.
+    fn qualify(&self, name: &str) -> String {
+        return self.parent.map_or_else(|| return name.to_string(), |parent| return format!("{parent}.{name}"));
+    }
+}
+
+/// A name bound by a top-level Python `from <module> import <name>`, which
+/// is how packages re-export symbols from their `__init__.py`.
+pub struct PythonImport {
+    /// The name bound in the importing module (the `as` alias, if any),
+    /// or `*` for a wildcard import.
+    pub alias: String,
+    /// The module as written, e.g. `.errors` or `pkg.errors`.
+    pub module: String,
+    /// The name in the imported module, or `*` for a wildcard import.
+    pub name: String,
+}
+
+/// An editor folding directive that opens or closes a named region.
+enum RegionDirective {
+    /// `#region Name`, with whitespace in the name replaced by `-`.
+    Begin(String),
+    /// `#endregion`, whatever follows it.
+    End,
+}
+
+/// Issues heading slugs for one markdown document.
+///
+/// In GitHub mode, repeated slugs get `-1`, `-2`, ... suffixes in document
+/// order, matching the anchors GitHub renders. In either mode, a heading
+/// repeated under the same parent is suffixed the same way, so each one has
+/// its own qualified name.
+struct Slugger {
+    /// Number of times each base slug has been issued so far.
+    occurrences: HashMap<String, u32>,
+    /// Qualified names issued so far.
+    qualified: HashSet<String>,
+    /// Which slug algorithm to apply.
+    style: SlugStyle,
+}
+
+impl Slugger {
+    /// Issue a heading's name and qualified name under `parent`, which is
+    /// empty at the top level, suffixing `slug` if `parent` already has it.
+    fn issue_under(&mut self, parent: &str, slug: &str) -> (String, String) {
+        let prefix = if parent.is_empty() { String::new() } else { format!("{parent}.") };
+        let mut name = slug.to_string();
+        let mut count: u32 = 0;
+        while self.qualified.contains(&format!("{prefix}{name}")) {
+            count = count.saturating_add(1);
+            name = format!("{slug}-{count}");
+        }
+        let qualified = format!("{prefix}{name}");
+        self.qualified.insert(qualified.clone());
+        return (name, qualified);
+    }
+
+    /// Create a slugger for a fresh document.
+    fn new(style: SlugStyle) -> Self {
+        return Self {
+            occurrences: HashMap::new(),
+            qualified: HashSet::new(),
+            style,
+        };
+    }
+
+    /// Produce the slug for a heading, suffixing duplicates in GitHub mode.
+    fn slug(&mut self, text: &str) -> String {
+        if self.style == SlugStyle::Default {
+            return slugify(text);
+        }
+
+        let base = slugify_github(text);
+        let mut result = base.clone();
+        while self.occurrences.contains_key(&result) {
+            let count = self.occurrences.entry(base.clone()).or_insert(0);
+            *count = count.saturating_add(1);
+            result = format!("{base}-{count}");
+        }
+        self.occurrences.insert(result.clone(), 0);
+        return result;
+    }
+}
+
 /// A symbol found during file listing (for the resolve command).
 pub struct SymbolInfo {
+    /// What sort of member the symbol is, when known.
+    pub kind: Option<SymbolKind>,
     /// The qualified name (e.g., "add" or "Config.validate").
     pub name: String,
 }
 
+/// What sort of symbol a name is, where the name alone doesn't say: the
+/// flavor of a Python class member, told apart by its decorators, or a
+/// markdown heading whose slug was suffixed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// A `@classmethod`.
+    ClassMethod,
+    /// A plain instance method.
+    Method,
+    /// A `@property` or `@cached_property`, with any setter and deleter.
+    Property,
+    /// A heading repeated under the same parent, named with a `-1`, `-2`, ... suffix.
+    RepeatedHeading,
+    /// A `@staticmethod`.
+    StaticMethod,
+}
+
+impl SymbolKind {
+    /// Name shown in `resolve` listings.
+    pub const fn name(self) -> &'static str {
+        return match self {
+            Self::ClassMethod => "classmethod",
+            Self::Method => "method",
+            Self::Property => "property",
+            Self::RepeatedHeading => "repeated heading",
+            Self::StaticMethod => "staticmethod",
+        };
+    }
+}
+
+/// Also make a module's `default` export answer to the file's basename,
+/// so `Button.tsx#Button` finds `export default function () {}`. Skipped
+/// when a declaration already has that name.
+fn alias_default_export(file_path: &Path, declarations: &mut Vec<Declaration>) {
+    let Some(stem) = file_path.file_stem().and_then(|stem| return stem.to_str()) else {
+        return;
+    };
+    if declarations.iter().any(|d| return d.name == stem) {
+        return;
+    }
+    let Some(default) = declarations.iter().find(|d| return d.qualified_name == "default") else {
+        return;
+    };
+    let byte_range = default.byte_range.clone();
+    declarations.push(Declaration { byte_range, kind: None, name: stem.to_string(), qualified_name: stem.to_string() });
+}
+
+/// End of the statement or block starting at `start`.
+///
+/// That's just past the first `;` outside braces, or past the `}` closing
+/// the first `{`, or the end of `source` if neither comes. `token_len`
+/// skips the language's comments and strings.
+fn braced_item_end(source: &str, start: usize, token_len: fn(&str) -> usize) -> usize {
+    let mut depth = 0_usize;
+    let mut pos = start;
+    while let Some(rest) = source.get(pos..) {
+        let Some(c) = rest.chars().next() else { break };
+        depth = match c {
+            '{' => depth.saturating_add(1),
+            '}' => depth.saturating_sub(1),
+            _ => depth,
+        };
+        pos = pos.saturating_add(token_len(rest));
+        if depth == 0 && matches!(c, ';' | '}') {
+            return pos;
+        }
+    }
+    return source.len();
+}
+
+/// Length of the comment or string opening `rest` in a brace-delimited
+/// language such as Terraform or Protocol Buffers, so braces inside it aren't counted;
+/// otherwise the length of its first character.
+fn braced_token_len(rest: &str) -> usize {
+    if rest.starts_with('#') || rest.starts_with("//") {
+        return rest.find('\n').unwrap_or(rest.len());
+    }
+    if let Some(comment) = rest.strip_prefix("/*") {
+        return comment.find("*/").map_or(rest.len(), |end| return end.saturating_add(4));
+    }
+    let Some(body) = rest.strip_prefix('"') else {
+        return rest.chars().next().map_or(1, char::len_utf8);
+    };
+    let mut escaped = false;
+    let close = body.char_indices().find(|&(_, c)| {
+        let closes = c == '"' && !escaped;
+        escaped = c == '\\' && !escaped;
+        return closes;
+    });
+    return close.map_or(rest.len(), |(end, _)| return end.saturating_add(2));
+}
+
+/// Explain why a declaration was or wasn't selected for `query`.
+///
+/// `matching` is how many declarations share the queried bare name, so an
+/// ambiguous match can say how many rivals it has.
+fn candidate_reason(decl: &Declaration, query: &SymbolQuery, accepted: bool, matching: usize) -> String {
+    return match query {
+        SymbolQuery::Bare(_) if accepted => "name matches".to_string(),
+        SymbolQuery::Bare(name) if decl.name == *name => {
+            format!("name matches, but so do {} other declarations", matching.saturating_sub(1))
+        },
+        SymbolQuery::Bare(name) => format!("name `{}` is not `{name}`", decl.name),
+        SymbolQuery::Scoped { .. } if accepted => "qualified name matches".to_string(),
+        SymbolQuery::Scoped { parent, child } if decl.qualified_name == format!("{parent}.{child}") => {
+            "qualified name matches, but an earlier declaration was chosen".to_string()
+        },
+        SymbolQuery::Scoped { parent, child } => {
+            format!("qualified name `{}` is not `{parent}.{child}`", decl.qualified_name)
+        },
+        SymbolQuery::Absent(inner) => candidate_reason(decl, inner, accepted, matching),
+        SymbolQuery::Lines { .. } => "line-range references do not select a declaration".to_string(),
+        SymbolQuery::Members(parent) if decl.qualified_name == *parent => "type whose members are tracked".to_string(),
+        SymbolQuery::Members(parent) => format!("qualified name `{}` is not `{parent}`", decl.qualified_name),
+        SymbolQuery::WholeFile => "whole-file references do not select a declaration".to_string(),
+    };
+}
+
+/// Whether `line` closes a block, with a bracket or with the `end` of
+/// Scala 3 and Lua.
+fn closes_block(line: &str) -> bool {
+    let is_end = line.strip_prefix("end").is_some_and(|rest| return leading_identifier(rest).is_none());
+    return is_end || closes_bracket(line);
+}
+
+/// Whether `line` closes a bracket the lines before it opened, as the `}`
+/// ending a Kotlin class or the `)` after a parameter list does.
+fn closes_bracket(line: &str) -> bool {
+    return line.starts_with(['}', ')', ']']);
+}
+
 /// Collect members from a TypeScript class, qualified as "Class.member".
+///
+/// Members sharing a name, such as a `get`/`set` accessor pair or a
+/// method's overload signatures, are one declaration spanning them all;
+/// static initialization blocks are likewise one `Class.static`.
 fn collect_class_members(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
     let Some(name_node) = node.child_by_field_name("name") else {
         return;
@@ -39,44 +388,366 @@ fn collect_class_members(node: Node<'_>, source: &str, declarations: &mut Vec<De
         return;
     };
 
+    let first = declarations.len();
     let mut cursor = body.walk();
     for child in body.children(&mut cursor) {
-        if child.kind() != "method_definition" && child.kind() != "public_field_definition" {
+        let Some(decl) = ts_class_member_name(child, source)
+            .and_then(|member_name| return scoped_declaration(child, Some(&class_name), member_name))
+        else {
             continue;
+        };
+        let earlier = declarations.get_mut(first..).and_then(|members| {
+            return members.iter_mut().find(|d| return d.qualified_name == decl.qualified_name);
+        });
+        match earlier {
+            Some(earlier) => earlier.byte_range.end = decl.byte_range.end,
+            None => declarations.push(decl),
         }
-        let Some(name_child) = first_child_of_kind(child, "property_identifier") else {
+    }
+}
+
+/// Walk the tree and collect all named C++ declarations.
+///
+/// Namespaces and enclosing types prefix the dot-path, so `geo::Point::x`
+/// is `geo.Point.x`. A prototype is dropped when the file also defines it.
+fn collect_cpp_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    let mut prototypes = Vec::new();
+    collect_cpp_items(root, None, None, source, &mut declarations, &mut prototypes);
+
+    let defined: HashSet<String> = declarations.iter().map(|d| return d.qualified_name.clone()).collect();
+    declarations.extend(prototypes.into_iter().filter(|p| return !defined.contains(&p.qualified_name)));
+    declarations.sort_by_key(|d| return d.byte_range.start);
+    return declarations;
+}
+
+/// Collect the names a C++ declaration, field, or typedef declares, plus
+/// any class, struct, or enum defined inline as its type. Function
+/// declarators without a body go to `prototypes`.
+fn collect_cpp_declarators(
+    node: Node<'_>,
+    outer: Node<'_>,
+    scope: Option<&str>,
+    source: &str,
+    declarations: &mut Vec<Declaration>,
+    prototypes: &mut Vec<Declaration>,
+) {
+    if let Some(type_node) = node.child_by_field_name("type") {
+        collect_cpp_item(type_node, outer, scope, source, declarations, prototypes);
+    }
+    let mut cursor = node.walk();
+    for declarator in node.children_by_field_name("declarator", &mut cursor) {
+        let Some((path, is_function)) = cpp_declarator_name(declarator, source) else {
             continue;
         };
-        let Ok(member_name) = name_child.utf8_text(source.as_bytes()) else {
+        let Some(declaration) = cpp_declaration(outer, scope, &path) else {
             continue;
         };
-        let Some(start) = u32::try_from(child.start_byte()).ok() else {
+        if is_function && node.kind() != "type_definition" {
+            prototypes.push(declaration);
+        } else {
+            declarations.push(declaration);
+        }
+    }
+}
+
+/// Collect the declarations in one C++ top-level or member item. `outer` is
+/// the node whose range a declaration covers, such as its `template<...>`.
+fn collect_cpp_item(
+    node: Node<'_>,
+    outer: Node<'_>,
+    scope: Option<&str>,
+    source: &str,
+    declarations: &mut Vec<Declaration>,
+    prototypes: &mut Vec<Declaration>,
+) {
+    match node.kind() {
+        "alias_declaration" | "enumerator" | "preproc_def" | "preproc_function_def" => {
+            let name = node.child_by_field_name("name").and_then(|n| return n.utf8_text(source.as_bytes()).ok());
+            declarations.extend(name.and_then(|name| return cpp_declaration(outer, scope, name)));
+        },
+        "class_specifier" | "enum_specifier" | "struct_specifier" | "union_specifier" => {
+            collect_cpp_type(node, outer, scope, source, declarations, prototypes);
+        },
+        "declaration" | "field_declaration" | "type_definition" => {
+            collect_cpp_declarators(node, outer, scope, source, declarations, prototypes);
+        },
+        "function_definition" => {
+            let path = node.child_by_field_name("declarator").and_then(|d| return cpp_declarator_name(d, source));
+            declarations.extend(path.and_then(|(path, _)| return cpp_declaration(outer, scope, &path)));
+        },
+        "linkage_specification" | "namespace_definition" => {
+            collect_cpp_namespace(node, scope, source, declarations, prototypes);
+        },
+        "preproc_elif" | "preproc_else" | "preproc_if" | "preproc_ifdef" => {
+            collect_cpp_items(node, None, scope, source, declarations, prototypes);
+        },
+        "template_declaration" => collect_cpp_items(node, Some(node), scope, source, declarations, prototypes),
+        _ => {},
+    }
+}
+
+/// Collect every item among `node`'s children. With `outer` set (for a
+/// template), declarations cover it rather than the item alone.
+fn collect_cpp_items(
+    node: Node<'_>,
+    outer: Option<Node<'_>>,
+    scope: Option<&str>,
+    source: &str,
+    declarations: &mut Vec<Declaration>,
+    prototypes: &mut Vec<Declaration>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_cpp_item(child, outer.unwrap_or(child), scope, source, declarations, prototypes);
+    }
+}
+
+/// Collect the body of a namespace or `extern "C"` block. Named namespaces
+/// extend the scope; anonymous ones and linkage blocks keep it.
+fn collect_cpp_namespace(
+    node: Node<'_>,
+    scope: Option<&str>,
+    source: &str,
+    declarations: &mut Vec<Declaration>,
+    prototypes: &mut Vec<Declaration>,
+) {
+    let Some(body) = node.child_by_field_name("body") else {
+        return;
+    };
+    let name = node
+        .child_by_field_name("name")
+        .and_then(|n| return n.utf8_text(source.as_bytes()).ok())
+        .map(|name| return name.replace("::", "."));
+    let scope = match (scope, name) {
+        (Some(scope), Some(name)) => Some(format!("{scope}.{name}")),
+        (None, Some(name)) => Some(name),
+        (scope, None) => scope.map(str::to_string),
+    };
+    if body.kind() == "declaration_list" {
+        collect_cpp_items(body, None, scope.as_deref(), source, declarations, prototypes);
+    } else {
+        collect_cpp_item(body, body, scope.as_deref(), source, declarations, prototypes);
+    }
+}
+
+/// Collect a C++ class, struct, union, or enum definition and its members,
+/// qualified as "Class.member". Forward declarations and anonymous types
+/// are skipped.
+fn collect_cpp_type(
+    node: Node<'_>,
+    outer: Node<'_>,
+    scope: Option<&str>,
+    source: &str,
+    declarations: &mut Vec<Declaration>,
+    prototypes: &mut Vec<Declaration>,
+) {
+    let Some(body) = node.child_by_field_name("body") else {
+        return;
+    };
+    let Some((path, _)) = node.child_by_field_name("name").and_then(|n| return cpp_declarator_name(n, source)) else {
+        return;
+    };
+    let Some(declaration) = cpp_declaration(outer, scope, &path) else {
+        return;
+    };
+    let qualified_name = declaration.qualified_name.clone();
+    declarations.push(declaration);
+    collect_cpp_items(body, None, Some(&qualified_name), source, declarations, prototypes);
+}
+
+/// Walk the tree and collect all named C# declarations.
+///
+/// Namespaces don't qualify names, so members are `Type.Member` and nested
+/// types `Outer.Inner`, whether the namespace is file-scoped or a block.
+fn collect_cs_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    collect_cs_items(root, None, source, &mut declarations);
+    return declarations;
+}
+
+/// Collect the variables of a C# field or event field, qualified as "Type.field".
+fn collect_cs_fields(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let Some(variables) = first_child_of_kind(node, "variable_declaration") else {
+        return;
+    };
+    let mut cursor = variables.walk();
+    for declarator in variables.named_children(&mut cursor) {
+        let Some(name_node) = declarator.child_by_field_name("name") else {
             continue;
         };
-        let Some(end) = u32::try_from(child.end_byte()).ok() else {
+        let Ok(name) = name_node.utf8_text(source.as_bytes()) else {
             continue;
         };
-        declarations.push(Declaration {
-            byte_range: start..end,
-            name: member_name.to_string(),
-            qualified_name: format!("{class_name}.{member_name}"),
-        });
+        declarations.extend(cs_declaration(node, outer, name.to_string()));
+    }
+}
+
+/// Collect the declarations in one C# item: a namespace, type, or member
+/// of the type `outer`.
+fn collect_cs_item(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    match node.kind() {
+        "class_declaration" | "enum_declaration" | "interface_declaration" | "record_declaration"
+        | "struct_declaration" => collect_cs_type(node, outer, source, declarations),
+        "constructor_declaration" | "delegate_declaration" | "destructor_declaration" | "enum_member_declaration"
+        | "event_declaration" | "method_declaration" | "parameter" | "property_declaration" => {
+            let name = node.child_by_field_name("name").and_then(|n| return n.utf8_text(source.as_bytes()).ok());
+            let Some(name) = name else {
+                return;
+            };
+            let name = if node.kind() == "destructor_declaration" { format!("~{name}") } else { name.to_string() };
+            declarations.extend(cs_declaration(node, outer, name));
+        },
+        "event_field_declaration" | "field_declaration" => collect_cs_fields(node, outer, source, declarations),
+        "namespace_declaration" => {
+            if let Some(body) = node.child_by_field_name("body") {
+                collect_cs_items(body, outer, source, declarations);
+            }
+        },
+        "preproc_elif" | "preproc_else" | "preproc_if" => collect_cs_items(node, outer, source, declarations),
+        _ => {},
+    }
+}
+
+/// Collect every item among `node`'s children.
+fn collect_cs_items(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_cs_item(child, outer, source, declarations);
+    }
+}
+
+/// Collect a C# class, struct, interface, record, or enum and its members.
+/// A record's positional parameters are its properties.
+fn collect_cs_type(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let Some(name) = node.child_by_field_name("name").and_then(|n| return n.utf8_text(source.as_bytes()).ok()) else {
+        return;
+    };
+    let Some(declaration) = cs_declaration(node, outer, name.to_string()) else {
+        return;
+    };
+    let qualified_name = declaration.qualified_name.clone();
+    declarations.push(declaration);
+
+    if node.kind() == "record_declaration"
+        && let Some(parameters) = first_child_of_kind(node, "parameter_list")
+    {
+        collect_cs_items(parameters, Some(&qualified_name), source, declarations);
+    }
+    if let Some(body) = node.child_by_field_name("body") {
+        collect_cs_items(body, Some(&qualified_name), source, declarations);
+    }
+}
+
+/// Walk the stylesheet and collect every rule under each of its selectors,
+/// with the custom properties it sets as `selector.--name`.
+///
+/// Rules inside `@media`, `@supports`, and other block at-rules are included,
+/// so a selector restyled by a media query has a declaration for each rule.
+fn collect_css_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    collect_css_items(root, source, &mut declarations);
+    return declarations;
+}
+
+/// Collect the rules directly within `node`, descending into at-rule blocks.
+fn collect_css_items(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "rule_set" {
+            collect_css_rule(child, source, declarations);
+        } else if matches!(child.kind(), "at_rule" | "media_statement" | "supports_statement")
+            && let Some(block) = first_child_of_kind(child, "block")
+        {
+            collect_css_items(block, source, declarations);
+        }
+    }
+}
+
+/// Collect a rule under each of its selectors, then the custom properties
+/// it declares and any rules nested in its block.
+fn collect_css_rule(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
+    let selectors =
+        first_child_of_kind(node, "selectors").map(|list| return css_selectors(list, source)).unwrap_or_default();
+    declarations.extend(selectors.iter().filter_map(|selector| return scoped_declaration(node, None, selector)));
+    let Some(block) = first_child_of_kind(node, "block") else {
+        return;
+    };
+    let mut cursor = block.walk();
+    for property in block.named_children(&mut cursor).filter(|c| return c.kind() == "declaration") {
+        let Some(name) = first_child_of_kind(property, "property_name")
+            .and_then(|n| return n.utf8_text(source.as_bytes()).ok())
+            .filter(|name| return name.starts_with("--"))
+        else {
+            continue;
+        };
+        let properties = selectors.iter().filter_map(|outer| return scoped_declaration(property, Some(outer), name));
+        declarations.extend(properties);
     }
+    collect_css_items(block, source, declarations);
 }
 
 /// Dispatch to the correct collector based on file extension.
-fn collect_declarations(root: Node<'_>, source: &str, ext: &str) -> Vec<Declaration> {
+fn collect_declarations(
+    root: Node<'_>,
+    source: &str,
+    ext: &str,
+    slug_style: SlugStyle,
+    grammars: &Grammars,
+) -> Vec<Declaration> {
     return match ext {
         "bash" | "sh" => collect_bash_declarations(root, source),
+        "cc" | "cpp" | "h" | "hpp" => collect_cpp_declarations(root, source),
+        "cs" => collect_cs_declarations(root, source),
+        "css" => collect_css_declarations(root, source),
         "go" => collect_go_declarations(root, source),
+        "java" => collect_java_declarations(root, source),
+        "json" => collect_json_declarations(root, source),
         "js" | "jsx" | "ts" | "tsx" => collect_ts_declarations(root, source),
-        "md" | "markdown" => collect_md_declarations(root, source),
+        "md" | "markdown" => collect_md_declarations(root, source, slug_style),
+        "php" => collect_php_declarations(root, source),
         "py" => collect_py_declarations(root, source),
         "rs" => collect_rust_declarations(root, source),
-        _ => Vec::new(),
+        "svelte" => collect_svelte_declarations(root, source),
+        "swift" => collect_swift_declarations(root, source),
+        "toml" => collect_toml_declarations(root, source),
+        "yaml" | "yml" => collect_yaml_declarations(root, source),
+        _ => grammars
+            .get(ext)
+            .and_then(Result::ok)
+            .map(|grammar| return collect_query_declarations(root, source, &grammar.query))
+            .unwrap_or_default(),
     };
 }
 
+/// Collect the named build stages of a Dockerfile and its `ARG` and `ENV`
+/// variables.
+///
+/// A stage (`FROM golang AS build`) runs to its last instruction, and the
+/// variables in it are qualified by its name (`build.GOFLAGS`).
+fn collect_dockerfile_declarations(source: &str) -> Vec<Declaration> {
+    let mut declarations: Vec<Declaration> = Vec::new();
+    let mut stage: Option<(usize, String)> = None;
+    for (range, instruction) in dockerfile_instructions(source) {
+        let (keyword, args) = instruction.split_once(char::is_whitespace).unwrap_or((&instruction, ""));
+        if keyword.eq_ignore_ascii_case("FROM") {
+            stage = dockerfile_stage(args).map(|name| return (declarations.len(), name.to_string()));
+            let named = stage.as_ref().map(|(_, name)| return name.clone());
+            declarations.extend(named.and_then(|name| return line_declaration(&name, name.clone(), range.clone())));
+        }
+        for name in dockerfile_variables(keyword, args) {
+            let qualified =
+                stage.as_ref().map_or_else(|| return name.to_string(), |(_, stage)| return format!("{stage}.{name}"));
+            declarations.extend(line_declaration(name, qualified, range.clone()));
+        }
+        let current = stage.as_ref().and_then(|(index, _)| return declarations.get_mut(*index));
+        if let Some(decl) = current {
+            decl.byte_range.end = u32::try_from(range.end).unwrap_or(decl.byte_range.end);
+        }
+    }
+    return declarations;
+}
+
 /// Collect members from a TypeScript enum, qualified as "Enum.Member".
 fn collect_enum_members(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
     let Some(name_node) = node.child_by_field_name("name") else {
@@ -132,6 +803,7 @@ fn collect_enum_variants(node: Node<'_>, source: &str, declarations: &mut Vec<De
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: variant_name.to_string(),
             qualified_name: format!("{enum_name}.{variant_name}"),
         });
@@ -160,6 +832,7 @@ fn collect_bash_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
                 };
                 declarations.push(Declaration {
                     byte_range: start..end,
+                    kind: None,
                     name: name.to_string(),
                     qualified_name: name.to_string(),
                 });
@@ -179,6 +852,7 @@ fn collect_bash_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
                 };
                 declarations.push(Declaration {
                     byte_range: start..end,
+                    kind: None,
                     name: name.to_string(),
                     qualified_name: name.to_string(),
                 });
@@ -211,6 +885,7 @@ fn collect_go_const_specs(node: Node<'_>, source: &str, declarations: &mut Vec<D
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: name.to_string(),
             qualified_name: name.to_string(),
         });
@@ -262,6 +937,7 @@ fn collect_go_interface_methods(
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: method_name.to_string(),
             qualified_name: format!("{type_name}.{method_name}"),
         });
@@ -269,6 +945,8 @@ fn collect_go_interface_methods(
 }
 
 /// Collect fields from a Go struct type, qualified as "Struct.Field".
+/// An embedded field is named by its type, so `*pkg.Logger` is
+/// "Struct.Logger".
 fn collect_go_struct_fields(
     type_name: &str,
     type_node: Node<'_>,
@@ -283,10 +961,11 @@ fn collect_go_struct_fields(
         if child.kind() != "field_declaration" {
             continue;
         }
-        let Some(name_node) = child.child_by_field_name("name") else {
-            continue;
-        };
-        let Ok(field_name) = name_node.utf8_text(source.as_bytes()) else {
+        let field_name = child.child_by_field_name("name").map_or_else(
+            || return child.child_by_field_name("type").and_then(|t| return go_type_base_name(t, source)),
+            |name_node| return name_node.utf8_text(source.as_bytes()).ok(),
+        );
+        let Some(field_name) = field_name else {
             continue;
         };
         let Some(start) = u32::try_from(child.start_byte()).ok() else {
@@ -297,6 +976,7 @@ fn collect_go_struct_fields(
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: field_name.to_string(),
             qualified_name: format!("{type_name}.{field_name}"),
         });
@@ -325,6 +1005,7 @@ fn collect_go_type_specs(node: Node<'_>, source: &str, declarations: &mut Vec<De
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: type_name.to_string(),
             qualified_name: type_name.to_string(),
         });
@@ -358,15 +1039,28 @@ fn collect_go_var_specs(node: Node<'_>, source: &str, declarations: &mut Vec<Dec
     }
 }
 
-/// Collect methods from a Rust impl block, qualified as "Type.method".
-fn collect_impl_methods(impl_node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
+/// Collect the top-level functions, types, and type classes of a Haskell
+/// module.
+///
+/// Constructors are
+/// qualified by their type (`Shape.Circle`) and class methods by their
+/// class (`Container.empty`); a signature and its equations are one symbol.
+fn collect_haskell_declarations(source: &str) -> Vec<Declaration> {
+    return collect_outline_declarations(source, closes_bracket, haskell_outline_item);
+}
+
+/// Collect methods, associated consts, and associated types from a Rust
+/// impl block, qualified as "Type.method" ("Type@Trait.method" in a trait impl).
+fn collect_impl_members(impl_node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
     let Some(type_node) = impl_node.child_by_field_name("type") else {
         return;
     };
     let Ok(type_name) = type_node.utf8_text(source.as_bytes()) else {
         return;
     };
-    let type_name = type_name.to_string();
+    let trait_name = impl_node.child_by_field_name("trait").and_then(|t| return rust_trait_name(t, source));
+    let type_name =
+        trait_name.map_or_else(|| return type_name.to_string(), |name| return format!("{type_name}@{name}"));
 
     let Some(body) = impl_node.child_by_field_name("body") else {
         return;
@@ -374,7 +1068,7 @@ fn collect_impl_methods(impl_node: Node<'_>, source: &str, declarations: &mut Ve
 
     let mut cursor = body.walk();
     for child in body.children(&mut cursor) {
-        if let Some(decl) = impl_method_declaration(child, source, &type_name) {
+        if let Some(decl) = impl_member_declaration(child, source, &type_name) {
             declarations.push(decl);
         }
     }
@@ -417,59 +1111,393 @@ fn collect_interface_properties(
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: prop_name.to_string(),
             qualified_name: format!("{iface_name}.{prop_name}"),
         });
     }
 }
 
-/// Walk the tree and collect all headings as declarations.
-///
-/// Nested headings get qualified names: a `### Example` under `## Foo`
-/// becomes `foo.example`. The document title (h1) doesn't participate
-/// in scoping — the file path provides that context.
-fn collect_md_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+/// Walk the tree and collect all named Java declarations, including
+/// nested types, which are qualified by their enclosing types.
+fn collect_java_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
     let mut declarations = Vec::new();
-    walk_markdown_sections_with_scope(root, source, "", &mut declarations);
-    return declarations;
-}
+    let mut cursor = root.walk();
 
-/// Collect methods from a Python class body, qualified as "Class.method".
-fn collect_py_class_members(
-    node: Node<'_>,
-    source: &str,
-    class_name: &str,
-    declarations: &mut Vec<Declaration>,
-) {
-    let Some(body) = node.child_by_field_name("body") else {
-        return;
-    };
+    for node in root.children(&mut cursor) {
+        collect_java_type(node, None, source, &mut declarations);
+    }
 
-    let mut cursor = body.walk();
-    for child in body.children(&mut cursor) {
-        let inner = py_unwrap_decorated(child);
+    return declarations;
+}
 
-        if inner.kind() != "function_definition" {
+/// Collect the variables of a Java field or interface constant, qualified as
+/// "Class.field". Each covers the whole declaration, so a changed type or
+/// modifier makes it stale.
+fn collect_java_fields(node: Node<'_>, type_name: &str, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for child in node.children_by_field_name("declarator", &mut cursor) {
+        let Some(name_node) = child.child_by_field_name("name") else {
             continue;
-        }
-        let Some(name_node) = inner.child_by_field_name("name") else {
+        };
+        let Ok(field_name) = name_node.utf8_text(source.as_bytes()) else {
             continue;
         };
-        let Ok(method_name) = name_node.utf8_text(source.as_bytes()) else {
+        let Some(start) = u32::try_from(node.start_byte()).ok() else {
             continue;
         };
-        if method_name == "__init__" {
-            collect_py_init_attributes(inner, source, class_name, declarations);
+        let Some(end) = u32::try_from(node.end_byte()).ok() else {
             continue;
-        }
-        declarations.extend(py_method_declaration(inner, source, class_name, child));
+        };
+        declarations.push(Declaration {
+            byte_range: start..end,
+            kind: None,
+            name: field_name.to_string(),
+            qualified_name: format!("{type_name}.{field_name}"),
+        });
     }
 }
 
-/// Walk the tree and collect all named Python declarations.
-fn collect_py_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
-    let mut declarations = Vec::new();
-    let mut cursor = root.walk();
+/// Collect the members of a Java class, interface, enum, or record body.
+fn collect_java_members(body: Node<'_>, type_name: &str, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = body.walk();
+    for child in body.children(&mut cursor) {
+        match child.kind() {
+            "annotation_type_element_declaration"
+            | "constructor_declaration"
+            | "enum_constant"
+            | "method_declaration" => {
+                declarations.extend(java_member_declaration(child, source, type_name));
+            },
+            "constant_declaration" | "field_declaration" => {
+                collect_java_fields(child, type_name, source, declarations);
+            },
+            "enum_body_declarations" => collect_java_members(child, type_name, source, declarations),
+            _ => collect_java_type(child, Some(type_name), source, declarations),
+        }
+    }
+}
+
+/// Collect a Java type declaration and its members. Types nested in `outer`
+/// are qualified as "Outer.Inner", and their members as "Outer.Inner.member".
+fn collect_java_type(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    if !matches!(
+        node.kind(),
+        "annotation_type_declaration"
+            | "class_declaration"
+            | "enum_declaration"
+            | "interface_declaration"
+            | "record_declaration"
+    ) {
+        return;
+    }
+    let Some(name_node) = node.child_by_field_name("name") else {
+        return;
+    };
+    let Ok(name) = name_node.utf8_text(source.as_bytes()) else {
+        return;
+    };
+    let (Ok(start), Ok(end)) = (u32::try_from(node.start_byte()), u32::try_from(node.end_byte())) else {
+        return;
+    };
+    let qualified_name = outer.map_or_else(|| return name.to_string(), |outer| return format!("{outer}.{name}"));
+    declarations.push(Declaration {
+        byte_range: start..end,
+        kind: None,
+        name: name.to_string(),
+        qualified_name: qualified_name.clone(),
+    });
+
+    if let Some(body) = node.child_by_field_name("body") {
+        collect_java_members(body, &qualified_name, source, declarations);
+    }
+}
+
+/// Walk the tree and collect every object key as a declaration covering its
+/// value, qualified by the keys above it: `scripts.build`.
+///
+/// Keys inside arrays aren't addressable, since a path can't index a list.
+fn collect_json_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    collect_json_objects(root, None, source, &mut declarations);
+    return declarations;
+}
+
+/// Collect the pairs of every object directly within `node`.
+fn collect_json_objects(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "object" => collect_json_objects(child, outer, source, declarations),
+            "pair" => collect_json_pair(child, outer, source, declarations),
+            _ => {},
+        }
+    }
+}
+
+/// Collect one key-value pair and, when the value is an object, the keys
+/// nested under it.
+fn collect_json_pair(pair: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let Some(Ok(key)) = pair.child_by_field_name("key").map(|k| return k.utf8_text(source.as_bytes())) else {
+        return;
+    };
+    let Some(declaration) = scoped_declaration(pair, outer, key.trim_matches('"')) else {
+        return;
+    };
+    let qualified_name = declaration.qualified_name.clone();
+    declarations.push(declaration);
+    if let Some(value) = pair.child_by_field_name("value").filter(|v| return v.kind() == "object") {
+        collect_json_objects(value, Some(&qualified_name), source, declarations);
+    }
+}
+
+/// Collect the classes, interfaces, and objects of a Kotlin file with their
+/// members, and its top-level functions and properties. Members of a
+/// companion object are qualified by its class: `User.create`.
+fn collect_kotlin_declarations(source: &str) -> Vec<Declaration> {
+    return collect_outline_declarations(source, closes_bracket, kotlin_outline_item);
+}
+
+/// Collect the functions and module tables of a Lua chunk.
+///
+/// A function assigned into a table is qualified by it (`M.greet`), whether
+/// it's declared as `function M.greet()`, `function M:greet()`, or
+/// `M.greet = function()`, or as a field inside a `local M = { ... }`.
+fn collect_lua_declarations(source: &str) -> Vec<Declaration> {
+    return collect_outline_declarations(source, closes_block, lua_outline_item);
+}
+
+/// Collect the headings of `node`'s sections in document order, with their
+/// levels. Headings inside block quotes or lists don't start sections.
+fn collect_markdown_headings<'tree>(node: Node<'tree>, headings: &mut Vec<(usize, Node<'tree>)>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "atx_heading" | "setext_heading" => {
+                headings.extend(heading_level(child).map(|level| return (level, child)));
+            },
+            "section" => collect_markdown_headings(child, headings),
+            _ => {},
+        }
+    }
+}
+
+/// Walk the tree and collect all headings as declarations.
+///
+/// Nested headings get qualified names: a `### Example` under `## Foo`
+/// becomes `foo.example`. The document title (h1) doesn't participate
+/// in scoping — the file path provides that context. A heading's section
+/// runs until the next heading of the same or a higher level; setext
+/// (underlined) headings scope the same way as `#` headings of level 1 and 2.
+fn collect_md_declarations(root: Node<'_>, source: &str, slug_style: SlugStyle) -> Vec<Declaration> {
+    let mut headings = Vec::new();
+    collect_markdown_headings(root, &mut headings);
+    let mut declarations = Vec::new();
+    let mut slugger = Slugger::new(slug_style);
+    let mut scopes: Vec<(usize, String)> = Vec::new();
+    for (index, &(level, heading)) in headings.iter().enumerate() {
+        let slug = extract_heading_inline_text(heading, source).map(|text| return slugger.slug(&text));
+        let Some(slug) = slug.filter(|slug| return !slug.is_empty()) else {
+            continue;
+        };
+        scopes.retain(|(outer, _)| return *outer < level);
+        let parent = scopes.last().filter(|_| return level > 1).map_or("", |(_, parent)| return parent.as_str());
+        let (name, qualified) = slugger.issue_under(parent, &slug);
+        let following = headings.get(index.saturating_add(1)..).unwrap_or_default();
+        let end = following.iter().find(|(next, _)| return *next <= level);
+        let end = end.map_or_else(|| return root.end_byte(), |(_, next)| return next.start_byte());
+        if let (Ok(start), Ok(end)) = (u32::try_from(heading.start_byte()), u32::try_from(end)) {
+            let qualified_name = qualified.clone();
+            let kind = (name != slug).then_some(SymbolKind::RepeatedHeading);
+            declarations.push(Declaration { byte_range: start..end, kind, name, qualified_name });
+        }
+        if level > 1 {
+            scopes.push((level, qualified));
+        }
+    }
+    return declarations;
+}
+
+/// Collect the declarations of a source laid out by indentation, such as
+/// Kotlin.
+///
+/// Every line indented like the first line of its scope starts an item,
+/// running to [`outline_item_end`]. `item` records the declarations the
+/// item makes, and returns the name qualifying its members when its deeper
+/// lines declare them, as a class body's do; the deeper lines of any other
+/// item, such as a function body, are skipped.
+fn collect_outline_declarations(
+    source: &str,
+    closes: fn(&str) -> bool,
+    item: fn(&OutlineItem<'_>, &mut Vec<Declaration>) -> Option<String>,
+) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    // Open scopes: where each ends, the indentation of its items, and its name.
+    let mut scopes: Vec<(usize, Option<usize>, Option<String>)> = vec![(source.len(), None, None)];
+    let mut offset = 0_usize;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let indent = line.len().saturating_sub(trimmed.len());
+        let start = offset.saturating_add(indent);
+        offset = offset.saturating_add(line.len());
+        while scopes.len() > 1 && scopes.last().is_some_and(|(end, _, _)| return *end <= start) {
+            scopes.pop();
+        }
+        let Some((_, items, parent)) = scopes.last_mut() else { continue };
+        if trimmed.is_empty() || *items.get_or_insert(indent) != indent {
+            continue;
+        }
+        let end = outline_item_end(source, start, indent, closes);
+        let text = source.get(start..end).unwrap_or_default();
+        let found = OutlineItem { line: trimmed.trim_end(), parent: parent.as_deref(), range: start..end, text };
+        if let Some(name) = item(&found, &mut declarations) {
+            scopes.push((end, None, Some(name)));
+        }
+    }
+    return declarations;
+}
+
+/// Walk the tree and collect all named PHP declarations.
+///
+/// Namespaces don't qualify names, so members are `Class.member`, whether
+/// the namespace is a statement or a braced block.
+fn collect_php_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    collect_php_items(root, None, source, &mut declarations);
+    return declarations;
+}
+
+/// Collect the constants or properties of one PHP declaration, qualified
+/// as "Class.NAME" or "Class.property" (without the `$`).
+fn collect_php_elements(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for element in node.named_children(&mut cursor) {
+        if !matches!(element.kind(), "const_element" | "property_element") {
+            continue;
+        }
+        let name_node = element.child_by_field_name("name").or_else(|| return first_child_of_kind(element, "name"));
+        let Some(Ok(name)) = name_node.map(|n| return n.utf8_text(source.as_bytes())) else {
+            continue;
+        };
+        declarations.extend(scoped_declaration(node, outer, name.trim_start_matches('$')));
+    }
+}
+
+/// Collect the declarations in one PHP statement or member of the type `outer`.
+fn collect_php_item(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    match node.kind() {
+        "class_declaration" | "enum_declaration" | "interface_declaration" | "trait_declaration" => {
+            let name = node.child_by_field_name("name").and_then(|n| return n.utf8_text(source.as_bytes()).ok());
+            let Some(declaration) = name.and_then(|name| return scoped_declaration(node, outer, name)) else {
+                return;
+            };
+            let qualified_name = declaration.qualified_name.clone();
+            declarations.push(declaration);
+            if let Some(body) = node.child_by_field_name("body") {
+                collect_php_items(body, Some(&qualified_name), source, declarations);
+            }
+        },
+        "const_declaration" | "property_declaration" => collect_php_elements(node, outer, source, declarations),
+        "enum_case" | "function_definition" | "method_declaration" => {
+            let name = node.child_by_field_name("name").and_then(|n| return n.utf8_text(source.as_bytes()).ok());
+            declarations.extend(name.and_then(|name| return scoped_declaration(node, outer, name)));
+        },
+        "namespace_definition" => {
+            if let Some(body) = node.child_by_field_name("body") {
+                collect_php_items(body, outer, source, declarations);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Collect every item among `node`'s children.
+fn collect_php_items(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_php_item(child, outer, source, declarations);
+    }
+}
+
+/// Collect the messages, enums, services, and their members from a
+/// Protocol Buffers schema, qualified by their enclosing definitions:
+/// `User.email`, `User.Role.ADMIN`, `UserService.GetUser`.
+fn collect_proto_declarations(source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    let mut scopes: Vec<(usize, String)> = Vec::new();
+    let mut offset = 0_usize;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let start = offset.saturating_add(line.len().saturating_sub(trimmed.len()));
+        offset = offset.saturating_add(line.len());
+        while scopes.last().is_some_and(|(end, _)| return *end <= start) {
+            scopes.pop();
+        }
+        let Some((keyword, name)) = proto_item(trimmed) else { continue };
+        let parent = scopes.last().map(|(_, parent)| return parent.clone());
+        let qualified = parent.as_ref().map_or_else(|| return name.to_string(), |p| return format!("{p}.{name}"));
+        let end = braced_item_end(source, start, braced_token_len);
+        match keyword {
+            "message" | "enum" | "service" => scopes.push((end, qualified.clone())),
+            "oneof" => scopes.push((end, parent.unwrap_or_default())),
+            _ => {},
+        }
+        declarations.extend(line_declaration(name, qualified, start..end));
+    }
+    return declarations;
+}
+
+/// Collect methods and class attributes (`MAX = 5`, `name: str`) from a
+/// Python class body, qualified as "Class.method".
+fn collect_py_class_members(
+    node: Node<'_>,
+    source: &str,
+    class_name: &str,
+    declarations: &mut Vec<Declaration>,
+) {
+    let Some(body) = node.child_by_field_name("body") else {
+        return;
+    };
+
+    let mut cursor = body.walk();
+    for child in body.children(&mut cursor) {
+        let inner = py_unwrap_decorated(child);
+
+        if inner.kind() == "class_definition"
+            && let Some(mut decl) = py_named_declaration(inner, source, child)
+        {
+            decl.qualified_name = format!("{class_name}.{}", decl.name);
+            collect_py_class_members(inner, source, &decl.qualified_name, declarations);
+            declarations.push(decl);
+            continue;
+        }
+        if inner.kind() == "expression_statement" {
+            let attribute = py_class_attribute(inner, source, class_name)
+                .filter(|decl| return !declarations.iter().any(|d| return d.qualified_name == decl.qualified_name));
+            declarations.extend(attribute);
+            continue;
+        }
+        if inner.kind() != "function_definition" {
+            continue;
+        }
+        let name_node = inner.child_by_field_name("name");
+        let Some(method_name) = name_node.and_then(|n| return n.utf8_text(source.as_bytes()).ok()) else {
+            continue;
+        };
+        if method_name == "__init__" {
+            collect_py_init_attributes(inner, source, class_name, declarations);
+            continue;
+        }
+        if let Some(decl) = py_method_declaration(inner, source, class_name, child) {
+            push_merging_overloads(declarations, decl);
+        }
+    }
+}
+
+/// Walk the tree and collect all named Python declarations.
+fn collect_py_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    let mut cursor = root.walk();
 
     for node in root.children(&mut cursor) {
         collect_py_top_level_node(node, source, &mut declarations);
@@ -488,7 +1516,7 @@ fn collect_py_init_attributes(
     let Some(body) = func.child_by_field_name("body") else {
         return;
     };
-    let mut seen = std::collections::HashSet::new();
+    let mut seen = declarations.iter().map(|d| return d.qualified_name.clone()).collect();
     collect_py_self_attributes_recursive(body, source, class_name, declarations, &mut seen);
 }
 
@@ -544,6 +1572,82 @@ fn collect_py_top_level_node(
     {
         declarations.push(decl);
     }
+    if inner.kind() == "type_alias_statement" {
+        declarations.extend(py_type_alias(inner, source));
+    }
+}
+
+/// Collect declarations with a `[grammars]` query: every `@declaration`
+/// capture is a symbol named by the `@name` capture of the same match, and
+/// qualified by the declarations enclosing it.
+fn collect_query_declarations(root: Node<'_>, source: &str, query: &Query) -> Vec<Declaration> {
+    let (Some(declaration), Some(name)) =
+        (query.capture_index_for_name("declaration"), query.capture_index_for_name("name"))
+    else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, root, source.as_bytes());
+    while let Some(found_match) = matches.next() {
+        let capture =
+            |index| return found_match.captures.iter().find(|c| return c.index == index).map(|c| return c.node);
+        let text = capture(name).and_then(|n| return n.utf8_text(source.as_bytes()).ok());
+        if let (Some(node), Some(text)) = (capture(declaration), text) {
+            found.push((node, text));
+        }
+    }
+    found.sort_by_key(|(node, _)| return (node.start_byte(), std::cmp::Reverse(node.end_byte())));
+    let mut enclosing: Vec<(usize, String)> = Vec::new();
+    let mut declarations = Vec::new();
+    for (node, text) in found {
+        enclosing.retain(|(end, _)| return *end > node.start_byte());
+        let outer = enclosing.last().map(|(_, qualified)| return qualified.as_str());
+        let Some(declaration) = scoped_declaration(node, outer, text) else {
+            continue;
+        };
+        enclosing.push((node.end_byte(), declaration.qualified_name.clone()));
+        declarations.push(declaration);
+    }
+    return declarations;
+}
+
+/// Regions fenced by `docref:begin <name>` and `docref:end <name>` marker
+/// comments, or by `#region` directives, as `@name` declarations.
+///
+/// Markers are found in the raw text, so they work in any comment syntax
+/// (`//`, `#`, `--`, `<!-- -->`). A region covers the lines between its
+/// markers; a `begin` without a matching `end` declares nothing. An
+/// `#endregion` closes the innermost open `#region`.
+fn collect_region_declarations(source: &str) -> Vec<Declaration> {
+    let mut markers: HashMap<&str, usize> = HashMap::new();
+    let mut directives: Vec<(String, usize)> = Vec::new();
+    let mut declarations = Vec::new();
+    let mut offset = 0_usize;
+    for line in source.split_inclusive('\n') {
+        let line_start = offset;
+        offset = offset.saturating_add(line.len());
+        let closed = if let Some(name) = region_marker(line, "docref:begin") {
+            markers.insert(name, offset);
+            None
+        } else if let Some(name) = region_marker(line, "docref:end") {
+            markers.remove(name).map(|start| return (name.to_string(), start))
+        } else if let Some(RegionDirective::Begin(name)) = region_directive(line) {
+            directives.push((name, offset));
+            None
+        } else if matches!(region_directive(line), Some(RegionDirective::End)) {
+            directives.pop()
+        } else {
+            None
+        };
+        if let Some((name, start)) = closed
+            && let (Ok(start), Ok(end)) = (u32::try_from(start), u32::try_from(line_start))
+        {
+            let (name, byte_range) = (format!("@{name}"), start..end);
+            declarations.push(Declaration { byte_range, kind: None, name: name.clone(), qualified_name: name });
+        }
+    }
+    return declarations;
 }
 
 /// Walk the tree and collect all named Rust declarations.
@@ -556,23 +1660,109 @@ fn collect_rust_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
             declarations.push(decl);
         }
         if node.kind() == "impl_item" {
-            collect_impl_methods(node, source, &mut declarations);
+            collect_impl_members(node, source, &mut declarations);
         }
-        if node.kind() == "struct_item" {
+        if matches!(node.kind(), "struct_item" | "union_item") {
             collect_struct_fields(node, source, &mut declarations);
         }
         if node.kind() == "enum_item" {
             collect_enum_variants(node, source, &mut declarations);
         }
         if node.kind() == "trait_item" {
-            collect_trait_methods(node, source, &mut declarations);
+            collect_trait_members(node, source, &mut declarations);
+        }
+        if node.kind() == "mod_item"
+            && let Some(name) = node.child_by_field_name("name")
+            && let Ok(module) = name.utf8_text(source.as_bytes())
+            && let Some(body) = node.child_by_field_name("body")
+        {
+            nest_declarations(module, collect_rust_declarations(body, source), &mut declarations);
+        }
+        if node.kind() == "foreign_mod_item"
+            && let Some(body) = node.child_by_field_name("body")
+        {
+            declarations.extend(collect_rust_declarations(body, source));
+        }
+    }
+
+    return declarations;
+}
+
+/// Collect the objects, classes, and traits of a Scala file with their
+/// members, and its top-level `def`s and `val`s. A companion object's
+/// members are qualified by its class: `User.apply`.
+fn collect_scala_declarations(source: &str) -> Vec<Declaration> {
+    return collect_outline_declarations(source, closes_block, scala_outline_item);
+}
+
+/// Collect the top-level mixins, functions, and variables of an SCSS file:
+/// `button-variant` for `@mixin button-variant($color)`, and `$brand`.
+fn collect_scss_declarations(source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    let (mut offset, mut item_end) = (0_usize, 0_usize);
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let start = offset.saturating_add(line.len().saturating_sub(trimmed.len()));
+        offset = offset.saturating_add(line.len());
+        if start < item_end || trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        item_end = if trimmed.starts_with("/*") {
+            start.saturating_add(scss_token_len(source.get(start..).unwrap_or_default()))
+        } else {
+            braced_item_end(source, start, scss_token_len)
+        };
+        let name = scss_item_name(trimmed);
+        declarations.extend(name.and_then(|name| return line_declaration(name, name.to_string(), start..item_end)));
+    }
+    return declarations;
+}
+
+/// Add the columns of the `CREATE TABLE` statement at `offset` as
+/// `table.column`, skipping table constraints.
+fn collect_sql_columns(statement: &str, offset: usize, table: &str, declarations: &mut Vec<Declaration>) {
+    let Some(open) = statement.find('(') else { return };
+    let (mut depth, mut column_start, mut pos) = (0_usize, open.saturating_add(1), open);
+    while let Some(rest) = statement.get(pos..) {
+        let Some(c) = rest.chars().next() else { break };
+        depth = match c {
+            '(' => depth.saturating_add(1),
+            ')' => depth.saturating_sub(1),
+            _ => depth,
+        };
+        if (c == ',' && depth == 1) || (c == ')' && depth == 0) {
+            let column = statement.get(column_start..pos).unwrap_or_default();
+            let trimmed = column.trim_start();
+            let start = offset.saturating_add(column_start).saturating_add(column.len().saturating_sub(trimmed.len()));
+            let name = sql_column_name(trimmed);
+            let range = start..start.saturating_add(trimmed.trim_end().len());
+            declarations.extend(name.and_then(|name| return line_declaration(&name, format!("{table}.{name}"), range)));
+            column_start = pos.saturating_add(1);
         }
+        if c == ')' && depth == 0 {
+            return;
+        }
+        pos = pos.saturating_add(sql_token_len(rest));
     }
+}
 
+/// Collect the `CREATE TABLE`, `VIEW`, `FUNCTION`, and `INDEX` statements
+/// of a SQL file, with each table's columns as `table.column`.
+fn collect_sql_declarations(source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    for statement in sql_statements(source) {
+        let text = source.get(statement.clone()).unwrap_or_default();
+        let Some((name, is_table)) = sql_created_name(text) else { continue };
+        let offset = statement.start;
+        declarations.extend(line_declaration(&name, name.clone(), statement));
+        if is_table {
+            collect_sql_columns(text, offset, &name, &mut declarations);
+        }
+    }
     return declarations;
 }
 
-/// Collect fields from a Rust struct, qualified as "Struct.field".
+/// Collect fields from a Rust struct or union, qualified as "Struct.field".
 fn collect_struct_fields(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
     let Some(name_node) = node.child_by_field_name("name") else {
         return;
@@ -605,14 +1795,188 @@ fn collect_struct_fields(node: Node<'_>, source: &str, declarations: &mut Vec<De
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: field_name.to_string(),
             qualified_name: format!("{struct_name}.{field_name}"),
         });
     }
 }
 
-/// Collect method signatures and default methods from a Rust trait, qualified as "Trait.method".
-fn collect_trait_methods(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
+/// Walk a Svelte component's scripts and collect its API: exported
+/// declarations, such as `export let` props and `export function`s, and the
+/// props it destructures from `$props()`.
+fn collect_svelte_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    let mut cursor = root.walk();
+    for node in root.named_children(&mut cursor) {
+        if node.kind() == "lexical_declaration" {
+            collect_svelte_props(node, source, &mut declarations);
+        }
+        if node.kind() != "export_statement" {
+            continue;
+        }
+        let inner = unwrap_export(node);
+        declarations.extend(ts_top_level_declaration(inner, source));
+        if inner.kind() == "lexical_declaration" {
+            collect_ts_variable_declarators(inner, source, &mut declarations);
+        }
+    }
+    return declarations;
+}
+
+/// Collect the props a `let { ... } = $props()` declaration destructures,
+/// each covering its own pattern so a changed default makes it stale.
+fn collect_svelte_props(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for declarator in node.named_children(&mut cursor).filter(|c| return c.kind() == "variable_declarator") {
+        let callee = declarator
+            .child_by_field_name("value")
+            .and_then(|value| return value.child_by_field_name("function"))
+            .and_then(|function| return function.utf8_text(source.as_bytes()).ok());
+        let Some(pattern) = declarator.child_by_field_name("name").filter(|name| return name.kind() == "object_pattern")
+        else {
+            continue;
+        };
+        if callee != Some("$props") {
+            continue;
+        }
+        let mut props = pattern.walk();
+        for prop in pattern.named_children(&mut props) {
+            let name = svelte_prop_name(prop, source);
+            declarations.extend(name.and_then(|name| return scoped_declaration(prop, None, name)));
+        }
+    }
+}
+
+/// Walk the tree and collect all named Swift declarations.
+fn collect_swift_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    collect_swift_items(root, None, source, &mut declarations);
+    return declarations;
+}
+
+/// Collect the declarations in one Swift item or member of the type `outer`.
+/// Initializers are `Type.init` and deinitializers `Type.deinit`.
+fn collect_swift_item(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    match node.kind() {
+        "class_declaration" | "protocol_declaration" => collect_swift_type(node, outer, source, declarations),
+        "deinit_declaration" | "init_declaration" => {
+            let name = node.kind().trim_end_matches("_declaration");
+            declarations.extend(scoped_declaration(node, outer, name));
+        },
+        "enum_entry" | "property_declaration" | "protocol_property_declaration" => {
+            collect_swift_names(node, outer, source, declarations);
+        },
+        "function_declaration" | "protocol_function_declaration" | "typealias_declaration" => {
+            // Later `name` fields belong to the return or aliased type.
+            let name = node.child_by_field_name("name").and_then(|n| return n.utf8_text(source.as_bytes()).ok());
+            declarations.extend(name.and_then(|name| return scoped_declaration(node, outer, name)));
+        },
+        _ => {},
+    }
+}
+
+/// Collect every item among `node`'s children.
+fn collect_swift_items(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_swift_item(child, outer, source, declarations);
+    }
+}
+
+/// Collect every name a Swift declaration binds, such as each case of
+/// `case fast, safe` or each variable of `let a = 1, b = 2`.
+fn collect_swift_names(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for name_node in node.children_by_field_name("name", &mut cursor) {
+        let bound = name_node.child_by_field_name("bound_identifier").unwrap_or(name_node);
+        let Ok(name) = bound.utf8_text(source.as_bytes()) else {
+            continue;
+        };
+        declarations.extend(scoped_declaration(node, outer, name));
+    }
+}
+
+/// Collect a Swift class, struct, enum, actor, or protocol and its members,
+/// qualified as "Type.member". An extension adds its members to the type it
+/// extends without declaring anything itself.
+fn collect_swift_type(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let Some(Ok(name)) = node.child_by_field_name("name").map(|n| return n.utf8_text(source.as_bytes())) else {
+        return;
+    };
+    let name = name.split('<').next().unwrap_or(name);
+    let Some(declaration) = scoped_declaration(node, outer, name) else {
+        return;
+    };
+    let qualified_name = declaration.qualified_name.clone();
+    let kind = node.child_by_field_name("declaration_kind").map(|k| return k.kind());
+    if kind != Some("extension") {
+        declarations.push(declaration);
+    }
+    if let Some(body) = node.child_by_field_name("body") {
+        collect_swift_items(body, Some(&qualified_name), source, declarations);
+    }
+}
+
+/// Collect the top-level `resource`, `data`, `module`, `variable`, and
+/// `output` blocks of a Terraform file, named by their addresses.
+fn collect_tf_declarations(source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    let mut offset = 0_usize;
+    for line in source.split_inclusive('\n') {
+        let start = offset;
+        offset = offset.saturating_add(line.len());
+        let Some(address) = tf_block_address(line) else { continue };
+        let name = address.rsplit('.').next().unwrap_or_default().to_string();
+        declarations.extend(line_declaration(&name, address, start..braced_item_end(source, start, braced_token_len)));
+    }
+    return declarations;
+}
+
+/// Walk the tree and collect every table and key as a declaration covering
+/// its value, qualified by its table: `dependencies.serde`.
+///
+/// Array tables (`[[bin]]`) aren't addressable, since a path can't index a list.
+fn collect_toml_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    collect_toml_pairs(root, None, source, &mut declarations);
+    let mut cursor = root.walk();
+    for table in root.named_children(&mut cursor).filter(|c| return c.kind() == "table") {
+        let Some(key) = table.named_child(0).and_then(|k| return toml_key(k, source)) else {
+            continue;
+        };
+        let Some(declaration) = toml_declaration(table, None, &key) else {
+            continue;
+        };
+        declarations.push(declaration);
+        collect_toml_pairs(table, Some(&key), source, &mut declarations);
+    }
+    declarations.sort_by_key(|d| return d.byte_range.start);
+    return declarations;
+}
+
+/// Collect the key-value pairs directly within `node`, including the keys of
+/// inline tables, qualified by `outer`.
+fn collect_toml_pairs(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for pair in node.named_children(&mut cursor).filter(|c| return c.kind() == "pair") {
+        let Some(key) = pair.named_child(0).and_then(|k| return toml_key(k, source)) else {
+            continue;
+        };
+        let Some(declaration) = toml_declaration(pair, outer, &key) else {
+            continue;
+        };
+        let qualified_name = declaration.qualified_name.clone();
+        declarations.push(declaration);
+        if let Some(table) = first_child_of_kind(pair, "inline_table") {
+            collect_toml_pairs(table, Some(&qualified_name), source, declarations);
+        }
+    }
+}
+
+/// Collect method signatures, default methods, associated consts, and
+/// associated types from a Rust trait, qualified as "Trait.method".
+fn collect_trait_members(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
     let Some(name_node) = node.child_by_field_name("name") else {
         return;
     };
@@ -627,7 +1991,7 @@ fn collect_trait_methods(node: Node<'_>, source: &str, declarations: &mut Vec<De
 
     let mut cursor = body.walk();
     for child in body.children(&mut cursor) {
-        if child.kind() != "function_signature_item" && child.kind() != "function_item" {
+        if !matches!(child.kind(), "function_signature_item" | "function_item" | "const_item" | "associated_type") {
             continue;
         }
         let Some(method_name_node) = child.child_by_field_name("name") else {
@@ -644,6 +2008,7 @@ fn collect_trait_methods(node: Node<'_>, source: &str, declarations: &mut Vec<De
         };
         declarations.push(Declaration {
             byte_range: start..end,
+            kind: None,
             name: method_name.to_string(),
             qualified_name: format!("{trait_name}.{method_name}"),
         });
@@ -657,13 +2022,14 @@ fn collect_ts_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
 
     for node in root.children(&mut cursor) {
         let inner = if node.kind() == "export_statement" {
+            declarations.extend(ts_default_export(node));
             unwrap_export(node)
         } else {
             node
         };
 
         if let Some(decl) = ts_top_level_declaration(inner, source) {
-            declarations.push(decl);
+            push_merging_overloads(&mut declarations, decl);
         }
         if inner.kind() == "lexical_declaration" {
             collect_ts_variable_declarators(inner, source, &mut declarations);
@@ -677,27 +2043,178 @@ fn collect_ts_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
         if inner.kind() == "enum_declaration" {
             collect_enum_members(inner, source, &mut declarations);
         }
+        if let Some(namespace) = ts_namespace(inner) {
+            collect_ts_namespace(namespace, source, &mut declarations);
+        }
     }
 
-    return declarations;
+    return declarations;
+}
+
+/// Collect a TypeScript `namespace` or `module` and the declarations in its
+/// body, qualified as "Namespace.member". Quoted ambient module names such
+/// as `declare module "express"` are skipped.
+fn collect_ts_namespace(node: Node<'_>, source: &str, declarations: &mut Vec<Declaration>) {
+    let Some(name_node) = node.child_by_field_name("name").filter(|n| return n.kind() != "string") else {
+        return;
+    };
+    let Ok(name) = name_node.utf8_text(source.as_bytes()) else {
+        return;
+    };
+    declarations.extend(scoped_declaration(node, None, name));
+    if let Some(body) = node.child_by_field_name("body") {
+        nest_declarations(name, collect_ts_declarations(body, source), declarations);
+    }
+}
+
+/// Extract variable names from a TypeScript `lexical_declaration` (const/let/var).
+fn collect_ts_variable_declarators(
+    node: Node<'_>,
+    source: &str,
+    declarations: &mut Vec<Declaration>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() != "variable_declarator" {
+            continue;
+        }
+        let Some(decl) = ts_variable_declarator(child, source, node) else {
+            continue;
+        };
+        declarations.push(decl);
+    }
+}
+
+/// Walk the tree and collect every mapping key as a declaration covering
+/// its value, qualified by the keys above it: `server.port`.
+///
+/// Keys inside sequences aren't addressable, since a path can't index a list.
+fn collect_yaml_declarations(root: Node<'_>, source: &str) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+    collect_yaml_mappings(root, None, source, &mut declarations);
+    return declarations;
+}
+
+/// Collect the key-value pairs of every mapping directly within `node`,
+/// looking through documents, anchors, and tags.
+fn collect_yaml_mappings(node: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "block_mapping" | "block_node" | "document" | "flow_mapping" | "flow_node" => {
+                collect_yaml_mappings(child, outer, source, declarations);
+            },
+            "block_mapping_pair" | "flow_pair" => collect_yaml_pair(child, outer, source, declarations),
+            _ => {},
+        }
+    }
+}
+
+/// Collect one key-value pair and, when the value is itself a mapping, the
+/// keys nested under it.
+fn collect_yaml_pair(pair: Node<'_>, outer: Option<&str>, source: &str, declarations: &mut Vec<Declaration>) {
+    let Some(Ok(key)) = pair.child_by_field_name("key").map(|k| return k.utf8_text(source.as_bytes())) else {
+        return;
+    };
+    let key = key.trim_matches(|c| return c == '"' || c == '\'');
+    let Some(declaration) = scoped_declaration(pair, outer, key) else {
+        return;
+    };
+    let qualified_name = declaration.qualified_name.clone();
+    declarations.push(declaration);
+    if let Some(value) = pair.child_by_field_name("value") {
+        collect_yaml_mappings(value, Some(&qualified_name), source, declarations);
+    }
+}
+
+/// A C++ declaration of the dot-path `path` within `scope`, covering `outer`.
+///
+/// Constructors and destructors are only addressable dot-scoped
+/// (`Server.Server`, `Server.~Server`), so a bare class name still means
+/// the class.
+fn cpp_declaration(outer: Node<'_>, scope: Option<&str>, path: &str) -> Option<Declaration> {
+    let qualified_name = scope.map_or_else(|| return path.to_string(), |scope| return format!("{scope}.{path}"));
+    let (parent, name) = qualified_name.rsplit_once('.').unwrap_or(("", qualified_name.as_str()));
+    let is_special = name.starts_with('~') || parent.rsplit('.').next() == Some(name);
+    let name = if is_special { qualified_name.clone() } else { name.to_string() };
+    let start = u32::try_from(outer.start_byte()).ok()?;
+    let end = u32::try_from(outer.end_byte()).ok()?;
+
+    return Some(Declaration {
+        byte_range: start..end,
+        kind: None,
+        name,
+        qualified_name,
+    });
+}
+
+/// The dot-path a C++ declarator names, and whether it declares a function.
+///
+/// Pointers, references, arrays, and initializers are unwrapped, and
+/// `Class::method` becomes `Class.method` with template arguments dropped.
+fn cpp_declarator_name(node: Node<'_>, source: &str) -> Option<(String, bool)> {
+    return match node.kind() {
+        "destructor_name" | "field_identifier" | "identifier" | "namespace_identifier" | "operator_name"
+        | "type_identifier" => {
+            let text = node.utf8_text(source.as_bytes()).ok()?;
+            Some((text.split_whitespace().collect(), false))
+        },
+        "function_declarator" => {
+            let (path, _) = cpp_declarator_name(node.child_by_field_name("declarator")?, source)?;
+            Some((path, true))
+        },
+        "qualified_identifier" => {
+            let (name, _) = cpp_declarator_name(node.child_by_field_name("name")?, source)?;
+            let Some(scope) = node.child_by_field_name("scope") else {
+                return Some((name, false));
+            };
+            let (scope, _) = cpp_declarator_name(scope, source)?;
+            Some((format!("{scope}.{name}"), false))
+        },
+        "template_function" | "template_type" => cpp_declarator_name(node.child_by_field_name("name")?, source),
+        "array_declarator" | "attributed_declarator" | "init_declarator" | "parenthesized_declarator"
+        | "pointer_declarator" | "reference_declarator" => {
+            let mut cursor = node.walk();
+            let inner = node
+                .child_by_field_name("declarator")
+                .or_else(|| return node.named_children(&mut cursor).last())?;
+            cpp_declarator_name(inner, source)
+        },
+        _ => None,
+    };
+}
+
+/// A C# declaration named `name` in the type `outer`, covering `node`.
+///
+/// Constructors and destructors are only addressable dot-scoped
+/// (`Server.Server`, `Server.~Server`), so a bare type name still means
+/// the type.
+fn cs_declaration(node: Node<'_>, outer: Option<&str>, name: String) -> Option<Declaration> {
+    let start = u32::try_from(node.start_byte()).ok()?;
+    let end = u32::try_from(node.end_byte()).ok()?;
+    let Some(outer) = outer else {
+        return Some(Declaration { byte_range: start..end, kind: None, qualified_name: name.clone(), name });
+    };
+    let qualified_name = format!("{outer}.{name}");
+    let is_special = name.starts_with('~') || outer.rsplit('.').next() == Some(name.as_str());
+
+    return Some(Declaration {
+        byte_range: start..end,
+        kind: None,
+        name: if is_special { qualified_name.clone() } else { name },
+        qualified_name,
+    });
 }
 
-/// Extract variable names from a TypeScript `lexical_declaration` (const/let/var).
-fn collect_ts_variable_declarators(
-    node: Node<'_>,
-    source: &str,
-    declarations: &mut Vec<Declaration>,
-) {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() != "variable_declarator" {
-            continue;
-        }
-        let Some(decl) = ts_variable_declarator(child, source, node) else {
-            continue;
-        };
-        declarations.push(decl);
-    }
+/// The selectors in a CSS selector list, with whitespace collapsed:
+/// `h1,\n  h2 > a` is `h1` and `h2 > a`.
+fn css_selectors(list: Node<'_>, source: &str) -> Vec<String> {
+    let mut cursor = list.walk();
+    return list
+        .named_children(&mut cursor)
+        .filter_map(|selector| return selector.utf8_text(source.as_bytes()).ok())
+        .map(|text| return text.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
 }
 
 /// Convert a declaration to its resolved symbol representation.
@@ -707,71 +2224,167 @@ fn declaration_to_resolved_symbol(decl: &Declaration) -> ResolvedSymbol {
     };
 }
 
-/// Process a single section node: extract its heading, build qualified name, recurse.
-fn extract_declaration_from_markdown_section(
-    section: Node<'_>,
-    source: &str,
-    parent_slug: &str,
-    declarations: &mut Vec<Declaration>,
-) {
-    let Some((slug, is_document_title)) =
-        extract_section_slug_and_title_flag(section, source)
-    else {
-        return;
-    };
+/// The instructions of a Dockerfile with their byte ranges, each joined
+/// across `\` line continuations. Comments and blank lines are skipped.
+fn dockerfile_instructions(source: &str) -> Vec<(Range<usize>, String)> {
+    let mut instructions = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    let mut offset = 0_usize;
+    for line in source.split_inclusive('\n') {
+        let text = line.trim_end();
+        let line_start = offset;
+        offset = offset.saturating_add(line.len());
+        let trimmed = text.trim_start();
+        if pending.is_none() && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            continue;
+        }
+        let indent = text.len().saturating_sub(trimmed.len());
+        let (start, mut joined) = pending.take().unwrap_or((line_start.saturating_add(indent), String::new()));
+        let continued = trimmed.strip_suffix('\\');
+        joined.push_str(continued.unwrap_or(trimmed));
+        joined.push(' ');
+        if continued.is_some() {
+            pending = Some((start, joined));
+        } else {
+            instructions.push((start..line_start.saturating_add(text.len()), joined));
+        }
+    }
+    return instructions;
+}
 
-    let qualified = if is_document_title || parent_slug.is_empty() {
-        slug.clone()
-    } else {
-        format!("{parent_slug}.{slug}")
-    };
+/// The name a `FROM image AS name` instruction gives its build stage.
+fn dockerfile_stage(args: &str) -> Option<&str> {
+    let mut words = args.split_whitespace();
+    words.find(|word| return word.eq_ignore_ascii_case("AS"))?;
+    return words.next();
+}
 
-    let start = u32::try_from(section.start_byte()).ok();
-    let end = u32::try_from(section.end_byte()).ok();
-    if let (Some(start), Some(end)) = (start, end) {
-        declarations.push(Declaration {
-            byte_range: start..end,
-            name: slug.clone(),
-            qualified_name: qualified.clone(),
-        });
+/// The variables an `ARG` or `ENV` instruction declares: `ARG A B=1`
+/// declares `A` and `B`, and the older `ENV PATH /bin` only `PATH`.
+fn dockerfile_variables<'a>(keyword: &str, args: &'a str) -> Vec<&'a str> {
+    let is_env = keyword.eq_ignore_ascii_case("ENV");
+    if !is_env && !keyword.eq_ignore_ascii_case("ARG") {
+        return Vec::new();
     }
+    let words = shell_words(args);
+    let is_legacy_env = is_env && words.first().is_some_and(|word| return !word.contains('='));
+    let names = words.iter().map(|word| return word.split_once('=').map_or(*word, |(name, _)| return name));
+    let is_name = |name: &&str| return !name.is_empty() && name.chars().all(|c| return c.is_alphanumeric() || c == '_');
+    return names.take(if is_legacy_env { 1 } else { usize::MAX }).filter(is_name).collect();
+}
+
+/// Resolve a symbol like [`resolve`], recording every declaration considered.
+///
+/// Resolution failures land in `Explanation::outcome` rather than the
+/// returned `Result`, so callers can show the candidates alongside the error.
+///
+/// # Errors
+///
+/// Returns `Error::FileTooLarge` or `Error::ParseFailed` on invalid input.
+pub fn explain(
+    file_path: &Path,
+    source: &str,
+    language: &Language,
+    query: &SymbolQuery,
+    slug_style: SlugStyle,
+    grammars: &Grammars,
+) -> Result<Explanation, Error> {
+    let (tree, declarations) = parse_declarations(file_path, source, language, slug_style, grammars)?;
+    let grammar = language.name().map_or_else(
+        || return file_path.extension().unwrap_or_default().to_string_lossy().to_string(),
+        str::to_string,
+    );
+    let path_of = |range: &Range<u32>| return node_path(tree.root_node(), range);
+    return Ok(explanation(&declarations, query, file_path, grammar, path_of));
+}
+
+/// Resolve a symbol like [`resolve_unparsed`], recording every declaration
+/// considered. There is no tree, so candidates have no node path.
+pub fn explain_unparsed(file_path: &Path, source: &str, query: &SymbolQuery) -> Explanation {
+    let declarations = unparsed_declarations(file_path, source);
+    return explanation(&declarations, query, file_path, "line scan".to_string(), |_| return Vec::new());
+}
+
+/// Resolve `query` against `declarations`, recording how each one fared.
+fn explanation(
+    declarations: &[Declaration],
+    query: &SymbolQuery,
+    file_path: &Path,
+    grammar: String,
+    path_of: impl Fn(&Range<u32>) -> Vec<&'static str>,
+) -> Explanation {
+    let outcome = find_declaration(declarations, query, file_path);
+    let matching = declarations
+        .iter()
+        .filter(|d| return matches!(query, SymbolQuery::Bare(name) if d.name == *name))
+        .count();
 
-    let child_scope = if is_document_title { "" } else { &qualified };
-    walk_markdown_sections_with_scope(section, source, child_scope, declarations);
+    let candidates = declarations
+        .iter()
+        .map(|d| {
+            let accepted = outcome.as_ref().is_ok_and(|r| return r.byte_range == d.byte_range);
+            return Candidate {
+                accepted,
+                byte_range: d.byte_range.clone(),
+                node_path: path_of(&d.byte_range),
+                qualified_name: d.qualified_name.clone(),
+                reason: candidate_reason(d, query, accepted, matching),
+            };
+        })
+        .collect();
+
+    return Explanation {
+        candidates,
+        grammar,
+        outcome,
+    };
 }
 
-/// Extract raw heading text by reading everything after the heading marker.
+/// Extract raw heading text by reading everything after the heading marker,
+/// or the text above the underline of a setext heading.
 fn extract_heading_inline_text(heading: Node<'_>, source: &str) -> Option<String> {
     let mut cursor = heading.walk();
     for child in heading.children(&mut cursor) {
         if child.kind() == "heading_content" || child.kind() == "inline" {
             return child.utf8_text(source.as_bytes()).ok().map(String::from);
         }
+        if child.kind() == "paragraph" {
+            return child.utf8_text(source.as_bytes()).ok().map(|text| return text.trim().to_string());
+        }
     }
     let text = heading.utf8_text(source.as_bytes()).ok()?;
     let stripped = text.trim_start_matches('#').trim();
     return Some(stripped.to_string());
 }
 
-/// Extract the slugified heading text and whether this is an h1 (document title).
-fn extract_section_slug_and_title_flag(
-    section: Node<'_>,
-    source: &str,
-) -> Option<(String, bool)> {
-    let mut cursor = section.walk();
-    for child in section.children(&mut cursor) {
-        if child.kind() != "atx_heading" {
-            continue;
-        }
-        let is_h1 = heading_has_h1_marker(child);
-        let text = extract_heading_inline_text(child, source)?;
-        let slug = slugify(&text);
-        if slug.is_empty() {
-            return None;
+/// Find the declaration a query selects. An absence assertion selects the
+/// declaration it asserts is gone.
+///
+/// # Errors
+///
+/// Returns `Error::SymbolNotFound` or `Error::AmbiguousSymbol` if the query
+/// doesn't select exactly one declaration, or `Error::NotASymbol` for a line
+/// range or whole-file query, which select no declaration.
+fn find_declaration(
+    declarations: &[Declaration],
+    query: &SymbolQuery,
+    file_path: &Path,
+) -> Result<ResolvedSymbol, Error> {
+    return match query {
+        SymbolQuery::Absent(inner) => find_declaration(declarations, inner, file_path),
+        SymbolQuery::Bare(name) => find_declaration_by_bare_name(declarations, name, file_path),
+        SymbolQuery::Members(parent) => match parent.split_once('.') {
+            None => find_declaration_by_bare_name(declarations, parent, file_path),
+            Some((outer, inner)) => find_declaration_by_qualified_dotpath(declarations, outer, inner, file_path),
+        },
+        SymbolQuery::Scoped { parent, child } => {
+            find_declaration_by_qualified_dotpath(declarations, parent, child, file_path)
         }
-        return Some((slug, is_h1));
-    }
-    return None;
+        SymbolQuery::Lines { .. } | SymbolQuery::WholeFile => Err(Error::NotASymbol {
+            file: file_path.to_path_buf(),
+            query: query.display_name(),
+        }),
+    };
 }
 
 /// Find a declaration by bare name.
@@ -784,10 +2397,14 @@ fn find_declaration_by_bare_name(
     name: &str,
     file_path: &Path,
 ) -> Result<ResolvedSymbol, Error> {
-    let matches: Vec<&Declaration> = declarations
+    let mut matches: Vec<&Declaration> = declarations
         .iter()
         .filter(|d| return d.name == name)
         .collect();
+    if matches.is_empty() {
+        let generic_free = strip_generics(name);
+        matches = declarations.iter().filter(|d| return strip_generics(&d.name) == generic_free).collect();
+    }
 
     match matches.len() {
         0 => return Err(symbol_not_found_error(file_path, name, declarations)),
@@ -814,6 +2431,10 @@ fn find_declaration_by_bare_name(
 
 /// Find a declaration by qualified dot-path (e.g., "Config.validate").
 ///
+/// An exact match wins; failing that, a trait impl member answers to its
+/// type's path, and generic parameters are ignored on both sides, so
+/// `HashMap<K,V>.insert` finds `impl<K, V> HashMap<K, V>`'s `insert`.
+///
 /// # Errors
 ///
 /// Returns `Error::SymbolNotFound` if no declaration matches the qualified name.
@@ -828,6 +2449,12 @@ fn find_declaration_by_qualified_dotpath(
     return declarations
         .iter()
         .find(|d| return d.qualified_name == qualified)
+        .or_else(|| return declarations.iter().find(|d| return without_trait(&d.qualified_name) == qualified))
+        .or_else(|| {
+            let generic_free = strip_generics(&qualified);
+            let matches = |d: &&Declaration| return strip_generics(&without_trait(&d.qualified_name)) == generic_free;
+            return declarations.iter().find(matches);
+        })
         .map(declaration_to_resolved_symbol)
         .ok_or_else(|| {
             return symbol_not_found_error(file_path, &qualified, declarations);
@@ -849,6 +2476,7 @@ fn go_function_declaration(node: Node<'_>, source: &str) -> Option<Declaration>
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: name.clone(),
         qualified_name: name,
     });
@@ -868,14 +2496,15 @@ fn go_method_declaration(node: Node<'_>, source: &str) -> Option<Declaration> {
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: method_name.to_string(),
         qualified_name: format!("{type_name}.{method_name}"),
     });
 }
 
-/// Extract the receiver type name, unwrapping pointer types.
+/// Extract the receiver type name, unwrapping pointer and generic types.
 ///
-/// `(c *Config)` → `Config`, `(c Config)` → `Config`.
+/// `(c *Config)` → `Config`, `(c Config)` → `Config`, `(s *Stack[T])` → `Stack`.
 fn go_receiver_type_name(receiver: Node<'_>, source: &str) -> Option<String> {
     // receiver is a parameter_list containing parameter_declaration(s).
     let mut cursor = receiver.walk();
@@ -884,20 +2513,22 @@ fn go_receiver_type_name(receiver: Node<'_>, source: &str) -> Option<String> {
             continue;
         }
         let type_node = child.child_by_field_name("type")?;
-        // Unwrap pointer_type if present.
-        let base = if type_node.kind() == "pointer_type" {
-            let mut inner_cursor = type_node.walk();
-            type_node
-                .children(&mut inner_cursor)
-                .find(|c| return c.kind() == "type_identifier")?
-        } else {
-            type_node
-        };
-        return base.utf8_text(source.as_bytes()).ok().map(String::from);
+        return go_type_base_name(type_node, source).map(String::from);
     }
     return None;
 }
 
+/// The bare name of a Go type, without pointers, package qualifiers, or
+/// type arguments: `*pkg.Stack[T]` is `Stack`.
+fn go_type_base_name<'a>(node: Node<'_>, source: &'a str) -> Option<&'a str> {
+    return match node.kind() {
+        "generic_type" => go_type_base_name(node.child_by_field_name("type")?, source),
+        "pointer_type" => go_type_base_name(node.named_child(0)?, source),
+        "qualified_type" => node.child_by_field_name("name")?.utf8_text(source.as_bytes()).ok(),
+        _ => node.utf8_text(source.as_bytes()).ok(),
+    };
+}
+
 /// Extract a single var spec as a declaration.
 fn go_var_spec_declaration(node: Node<'_>, source: &str) -> Option<Declaration> {
     let name_node = node.child_by_field_name("name")?;
@@ -907,26 +2538,120 @@ fn go_var_spec_declaration(node: Node<'_>, source: &str) -> Option<Declaration>
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: name.clone(),
         qualified_name: name,
     });
 }
 
-/// Check whether a heading is an h1 (document title) by looking for `atx_h1_marker`.
-fn heading_has_h1_marker(heading: Node<'_>) -> bool {
+/// Whether a file without a grammar still has symbols a line scan can
+/// find, beyond `@region` markers.
+pub fn has_unparsed_symbols(file_path: &Path) -> bool {
+    return line_scanner(file_path).is_some();
+}
+
+/// The name a Haskell type signature or equation on `line` binds, if it
+/// binds one by name rather than as an infix operator.
+fn haskell_binding(line: &str) -> Option<&str> {
+    let len = line.find(|c: char| return !c.is_alphanumeric() && c != '_' && c != '\'').unwrap_or(line.len());
+    let name = line.get(..len).filter(|name| return name.starts_with(|c: char| return c.is_lowercase() || c == '_'))?;
+    if HASKELL_KEYWORDS.contains(&name) {
+        return None;
+    }
+    let rest = line.get(len..).unwrap_or_default().trim_start();
+    let next = rest.split_whitespace().next().unwrap_or_default();
+    let is_operator = !next.is_empty() && next.chars().all(|c| return "!#$%&*+./<=>?@\\^|-~:".contains(c));
+    let infix = next.starts_with('`') || (is_operator && !matches!(next, "=" | "|" | "::") && !rest.starts_with("::"));
+    return (!infix).then_some(name);
+}
+
+/// The constructors of the Haskell `data` or `newtype` declaration `text`,
+/// which starts at `offset`, each spanning its alternative.
+fn haskell_constructors(text: &str, offset: usize) -> Vec<(&str, Range<usize>)> {
+    let equals = text.char_indices().find(|&(i, c)| {
+        return c == '=' && !text.get(i.saturating_add(1)..).is_some_and(|rest| return rest.starts_with('>'));
+    });
+    let Some((equals, _)) = equals else { return Vec::new() };
+    let body = text.get(equals.saturating_add(1)..).unwrap_or_default();
+    let deriving = body.match_indices("deriving").find(|&(i, _)| {
+        return body.get(..i).is_some_and(|before| return before.ends_with(char::is_whitespace));
+    });
+    let body = body.get(..deriving.map_or(body.len(), |(i, _)| return i)).unwrap_or_default();
+    let base = offset.saturating_add(equals).saturating_add(1);
+    let (mut constructors, mut depth, mut from) = (Vec::new(), 0_usize, 0_usize);
+    for (i, c) in body.char_indices().chain(std::iter::once((body.len(), '|'))) {
+        depth = match c {
+            '(' | '[' | '{' => depth.saturating_add(1),
+            ')' | ']' | '}' => depth.saturating_sub(1),
+            _ => depth,
+        };
+        if c == '|' && depth == 0 {
+            let alternative = body.get(from..i).unwrap_or_default();
+            let trimmed = alternative.trim_start();
+            let start = base.saturating_add(from).saturating_add(alternative.len().saturating_sub(trimmed.len()));
+            let name = leading_identifier(trimmed).filter(|name| return name.starts_with(char::is_uppercase));
+            constructors.extend(name.map(|name| return (name, start..start.saturating_add(trimmed.trim_end().len()))));
+            from = i.saturating_add(1);
+        }
+    }
+    return constructors;
+}
+
+/// Record the Haskell declaration `item` makes, returning the class name
+/// that qualifies its methods if it's a type class.
+fn haskell_outline_item(item: &OutlineItem<'_>, declarations: &mut Vec<Declaration>) -> Option<String> {
+    let (keyword, rest) = item.line.split_once(char::is_whitespace).unwrap_or((item.line, ""));
+    match keyword {
+        "class" | "data" | "newtype" | "type" => {
+            let name = haskell_type_name(rest.split(" where").next().unwrap_or_default())?;
+            let qualified = item.qualify(name);
+            declarations.extend(line_declaration(name, qualified.clone(), item.range.clone()));
+            if keyword == "class" {
+                return Some(qualified);
+            }
+            if keyword == "type" {
+                return None;
+            }
+            for (constructor, range) in haskell_constructors(item.text, item.range.start) {
+                declarations.extend(line_declaration(constructor, format!("{qualified}.{constructor}"), range));
+            }
+        },
+        _ => {
+            let name = haskell_binding(item.line).filter(|_| return item.text.contains(['=', ':']))?;
+            let declaration = line_declaration(name, item.qualify(name), item.range.clone())?;
+            push_merging_overloads(declarations, declaration);
+        },
+    }
+    return None;
+}
+
+/// The name a Haskell type or class declaration head introduces, after
+/// any context (`(Eq a) => Set a` names `Set`).
+fn haskell_type_name(head: &str) -> Option<&str> {
+    let head = head.rsplit("=>").next().unwrap_or_default().trim_start();
+    return leading_identifier(head).filter(|name| return name.starts_with(char::is_uppercase));
+}
+
+/// The level of a heading, 1 for a document title: the number of `#`s, or
+/// 1 and 2 for a setext heading underlined with `=` and `-`.
+fn heading_level(heading: Node<'_>) -> Option<usize> {
     let mut cursor = heading.walk();
-    return heading
-        .children(&mut cursor)
-        .any(|c| return c.kind() == "atx_h1_marker");
+    return heading.children(&mut cursor).find_map(|marker| {
+        let kind = marker.kind();
+        let level = kind.strip_prefix("atx_h").and_then(|rest| return rest.strip_suffix("_marker"));
+        let level = level.or_else(|| return kind.strip_prefix("setext_h")?.strip_suffix("_underline"));
+        return level.and_then(|digits| return digits.parse().ok());
+    });
 }
 
-/// Extract a method declaration from a Rust impl body child node.
-fn impl_method_declaration(
+/// Extract a method, associated const, or associated type declaration from
+/// a Rust impl body child node.
+fn impl_member_declaration(
     node: Node<'_>,
     source: &str,
     type_name: &str,
 ) -> Option<Declaration> {
-    if node.kind() != "function_item" {
+    if !matches!(node.kind(), "function_item" | "const_item" | "type_item") {
         return None;
     }
 
@@ -937,11 +2662,115 @@ fn impl_method_declaration(
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: method_name.to_string(),
         qualified_name: format!("{type_name}.{method_name}"),
     });
 }
 
+/// Whether `path` is a Dockerfile: `Dockerfile`, `Containerfile`,
+/// `Dockerfile.dev`, or `app.dockerfile`.
+fn is_dockerfile(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| return name.to_str()).unwrap_or_default();
+    let is_named = ["Dockerfile", "Containerfile"]
+        .iter()
+        .any(|base| return name == *base || name.strip_prefix(base).is_some_and(|rest| return rest.starts_with('.')));
+    let ext = path.extension().and_then(|ext| return ext.to_str()).unwrap_or_default();
+    return is_named || ext.eq_ignore_ascii_case("dockerfile");
+}
+
+/// Extract a named member of a Java type, such as a method, constructor,
+/// or enum constant, qualified as "Class.member".
+///
+/// Overloads share a qualified name; a dot-scoped reference resolves to
+/// the first one declared. Constructors are only addressable dot-scoped
+/// (`Server.Server`), so a bare class name still means the class.
+fn java_member_declaration(node: Node<'_>, source: &str, type_name: &str) -> Option<Declaration> {
+    let name_node = node.child_by_field_name("name")?;
+    let member_name = name_node.utf8_text(source.as_bytes()).ok()?;
+    let start = u32::try_from(node.start_byte()).ok()?;
+    let end = u32::try_from(node.end_byte()).ok()?;
+    let qualified_name = format!("{type_name}.{member_name}");
+    let name = if node.kind() == "constructor_declaration" { qualified_name.clone() } else { member_name.to_string() };
+
+    return Some(Declaration {
+        byte_range: start..end,
+        kind: None,
+        name,
+        qualified_name,
+    });
+}
+
+/// The keyword and name of the Kotlin declaration on `line`, past its
+/// annotations and modifiers. An extension is named without its receiver,
+/// and a companion object has no name of its own.
+fn kotlin_item(line: &str) -> Option<(&str, &str)> {
+    let mut rest = line;
+    let (keyword, after) = loop {
+        let (word, after) = rest.split_once(char::is_whitespace)?;
+        if !word.starts_with('@') && !KOTLIN_MODIFIERS.contains(&word) {
+            break (word, after.trim_start());
+        }
+        rest = after.trim_start();
+    };
+    return match keyword {
+        "companion" => Some((keyword, "")),
+        "class" | "interface" | "object" => leading_identifier(after).map(|name| return (keyword, name)),
+        "fun" if after.starts_with("interface ") => kotlin_item(after),
+        "fun" | "val" | "var" => {
+            let head = skip_type_parameters(after).split(['(', ':', '=', ' ']).next()?;
+            let name = head.rsplit('.').next().filter(|name| return leading_identifier(name) == Some(name))?;
+            Some((keyword, name))
+        },
+        _ => None,
+    };
+}
+
+/// Record the Kotlin declaration `item` makes, returning the name that
+/// qualifies its members if it has any.
+fn kotlin_outline_item(item: &OutlineItem<'_>, declarations: &mut Vec<Declaration>) -> Option<String> {
+    let (keyword, name) = kotlin_item(item.line)?;
+    if keyword == "companion" {
+        return item.parent.map(str::to_string);
+    }
+    let qualified = item.qualify(name);
+    declarations.extend(line_declaration(name, qualified.clone(), item.range.clone()));
+    return matches!(keyword, "class" | "interface" | "object").then_some(qualified);
+}
+
+/// The identifier `text` starts with, if any.
+fn leading_identifier(text: &str) -> Option<&str> {
+    let len = text.find(|c: char| return !c.is_alphanumeric() && c != '_').unwrap_or(text.len());
+    return text.get(..len).filter(|name| return !name.is_empty());
+}
+
+/// A declaration spanning `byte_range` of a line-scanned source.
+fn line_declaration(name: &str, qualified_name: String, byte_range: Range<usize>) -> Option<Declaration> {
+    let start = u32::try_from(byte_range.start).ok()?;
+    let end = u32::try_from(byte_range.end).ok()?;
+    return Some(Declaration { byte_range: start..end, kind: None, name: name.to_string(), qualified_name });
+}
+
+/// The line scanner collecting declarations from files like `file_path`,
+/// for languages without a grammar.
+fn line_scanner(file_path: &Path) -> Option<fn(&str) -> Vec<Declaration>> {
+    if is_dockerfile(file_path) {
+        return Some(collect_dockerfile_declarations);
+    }
+    let ext = file_path.extension().and_then(|ext| return ext.to_str()).unwrap_or_default();
+    return match ext {
+        "hs" => Some(collect_haskell_declarations),
+        "kt" | "kts" => Some(collect_kotlin_declarations),
+        "lua" => Some(collect_lua_declarations),
+        "proto" => Some(collect_proto_declarations),
+        "sc" | "scala" => Some(collect_scala_declarations),
+        "scss" => Some(collect_scss_declarations),
+        "sql" => Some(collect_sql_declarations),
+        "tf" => Some(collect_tf_declarations),
+        _ => None,
+    };
+}
+
 /// List all addressable symbols in a source file.
 ///
 /// # Errors
@@ -951,7 +2780,122 @@ pub fn list_symbols(
     file_path: &Path,
     source: &str,
     language: &Language,
+    slug_style: SlugStyle,
+    grammars: &Grammars,
 ) -> Result<Vec<SymbolInfo>, Error> {
+    let (_, declarations) = parse_declarations(file_path, source, language, slug_style, grammars)?;
+
+    return Ok(declarations
+        .into_iter()
+        .map(|d| {
+            return SymbolInfo {
+                kind: d.kind,
+                name: d.qualified_name,
+            };
+        })
+        .collect());
+}
+
+/// List the addressable symbols of a file without a grammar, as
+/// [`resolve_unparsed`] finds them.
+pub fn list_unparsed_symbols(file_path: &Path, source: &str) -> Vec<SymbolInfo> {
+    return unparsed_declarations(file_path, source)
+        .into_iter()
+        .map(|d| return SymbolInfo { kind: d.kind, name: d.qualified_name })
+        .collect();
+}
+
+/// The dotted path the Lua statement `line` binds a function or table to,
+/// and whether it's a table.
+fn lua_binding(line: &str) -> Option<(&str, bool)> {
+    let line = line.strip_prefix("local ").unwrap_or(line).trim_start();
+    let (path, table) = if let Some(rest) = line.strip_prefix("function ") {
+        (rest.split('(').next()?, false)
+    } else {
+        let (path, value) = line.split_once('=')?;
+        let value = value.trim_start();
+        let function = value.strip_prefix("function").is_some_and(|rest| return rest.trim_start().starts_with('('));
+        if !function && !value.starts_with('{') {
+            return None;
+        }
+        (path, !function)
+    };
+    let path = path.trim();
+    let valid = path.chars().all(|c| return c.is_alphanumeric() || matches!(c, '_' | '.' | ':'));
+    return (valid && path.starts_with(|c: char| return c.is_alphabetic() || c == '_')).then_some((path, table));
+}
+
+/// Record the Lua function or table `item` binds, returning the table's
+/// name to qualify the functions among its fields.
+fn lua_outline_item(item: &OutlineItem<'_>, declarations: &mut Vec<Declaration>) -> Option<String> {
+    let (path, table) = lua_binding(item.line)?;
+    let path = path.replace(':', ".");
+    let name = path.rsplit('.').next().unwrap_or_default();
+    let qualified = item.qualify(&path);
+    declarations.extend(line_declaration(name, qualified.clone(), item.range.clone()));
+    return table.then_some(qualified);
+}
+
+/// Add the declarations found inside `outer`, such as a module or inner
+/// class, with `outer.` prefixed to their qualified names so paths can nest
+/// to any depth.
+fn nest_declarations(outer: &str, nested: Vec<Declaration>, declarations: &mut Vec<Declaration>) {
+    declarations.extend(nested.into_iter().map(|decl| {
+        return Declaration { qualified_name: format!("{outer}.{}", decl.qualified_name), ..decl };
+    }));
+}
+
+/// CST node kinds from the root down to the smallest node spanning `range`.
+fn node_path(root: Node<'_>, range: &Range<u32>) -> Vec<&'static str> {
+    let start = usize::try_from(range.start).unwrap_or(0);
+    let end = usize::try_from(range.end).unwrap_or(0);
+    let mut path = Vec::new();
+    let mut current = root.descendant_for_byte_range(start, end);
+    while let Some(node) = current {
+        path.push(node.kind());
+        current = node.parent();
+    }
+    path.reverse();
+    return path;
+}
+
+/// End of the item starting at `start`, indented by `indent`.
+///
+/// That's the end of its last line before the next one indented no deeper,
+/// counting a line that `closes` it, like a `}`, as its own.
+fn outline_item_end(source: &str, start: usize, indent: usize, closes: fn(&str) -> bool) -> usize {
+    let first = source.get(start..).unwrap_or_default().split_inclusive('\n').next().unwrap_or_default();
+    let mut end = start.saturating_add(first.trim_end().len());
+    let mut offset = start.saturating_add(first.len());
+    for line in source.get(offset..).unwrap_or_default().split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let depth = line.len().saturating_sub(trimmed.len());
+        let line_start = offset;
+        offset = offset.saturating_add(line.len());
+        if trimmed.is_empty() {
+            continue;
+        }
+        if depth < indent || (depth == indent && !closes(trimmed)) {
+            break;
+        }
+        end = line_start.saturating_add(line.trim_end().len());
+    }
+    return end;
+}
+
+/// Size-check and parse a source file, then collect its declarations.
+///
+/// # Errors
+///
+/// Returns `Error::FileTooLarge` if the source exceeds the size limit,
+/// or `Error::ParseFailed` if tree-sitter cannot parse the source.
+fn parse_declarations(
+    file_path: &Path,
+    source: &str,
+    language: &Language,
+    slug_style: SlugStyle,
+    grammars: &Grammars,
+) -> Result<(Tree, Vec<Declaration>), Error> {
     let source_len: u64 = source.len().try_into().unwrap_or(u64::MAX);
     if source_len > MAX_FILE_SIZE {
         return Err(Error::FileTooLarge {
@@ -961,21 +2905,16 @@ pub fn list_symbols(
         });
     }
 
-    let tree = parse_source(file_path, source, language)?;
+    let tree = parse_source(file_path, &grammar::parsed_source(file_path, source), language)?;
     let ext = file_path
         .extension()
         .and_then(|e| return e.to_str())
         .unwrap_or("");
-    let declarations = collect_declarations(tree.root_node(), source, ext);
+    let mut declarations = collect_declarations(tree.root_node(), source, ext, slug_style, grammars);
+    alias_default_export(file_path, &mut declarations);
+    declarations.extend(collect_region_declarations(source));
 
-    return Ok(declarations
-        .into_iter()
-        .map(|d| {
-            return SymbolInfo {
-                name: d.qualified_name,
-            };
-        })
-        .collect());
+    return Ok((tree, declarations));
 }
 
 /// Parse source into a tree-sitter tree.
@@ -1004,6 +2943,43 @@ fn parse_source(file_path: &Path, source: &str, language: &Language) -> Result<T
         });
 }
 
+/// The keyword and name of the Protocol Buffers definition starting
+/// `line`: a `message`, `enum`, `service`, `oneof`, or `rpc`, or a field or
+/// enum value (`string email = 2;`), whose keyword is `=`.
+fn proto_item(line: &str) -> Option<(&str, &str)> {
+    let is_name = |word: &&str| return !word.is_empty() && word.chars().all(|c| return c.is_alphanumeric() || c == '_');
+    let mut words = line.split(|c: char| return c.is_whitespace() || c == '{' || c == '(');
+    let keyword = words.next()?;
+    if matches!(keyword, "message" | "enum" | "service" | "oneof" | "rpc") {
+        return words.find(|word| return !word.is_empty()).filter(is_name).map(|name| return (keyword, name));
+    }
+    let (declarator, _) = line.split_once('=').filter(|_| return !line.starts_with("//"))?;
+    if matches!(keyword, "option" | "syntax" | "edition" | "package" | "import") {
+        return None;
+    }
+    return declarator.split_whitespace().last().filter(is_name).map(|name| return ("=", name));
+}
+
+/// Push `decl`, or fold it into the previous declaration when that one has
+/// the same qualified name, so a function's overload signatures and its
+/// implementation hash as one symbol.
+fn push_merging_overloads(declarations: &mut Vec<Declaration>, decl: Declaration) {
+    match declarations.last_mut() {
+        Some(previous) if previous.qualified_name == decl.qualified_name => {
+            previous.byte_range.end = decl.byte_range.end;
+        },
+        _ => declarations.push(decl),
+    }
+}
+
+/// Extract a class-level `NAME = ...` or `name: type` assignment as a
+/// `Class.attr` declaration.
+fn py_class_attribute(node: Node<'_>, source: &str, class_name: &str) -> Option<Declaration> {
+    let mut decl = py_module_variable(node, source)?;
+    decl.qualified_name = format!("{class_name}.{}", decl.name);
+    return Some(decl);
+}
+
 /// Extract a method declaration from a Python class body.
 ///
 /// `outer` is the possibly-decorated node whose byte range covers decorators.
@@ -1026,11 +3002,34 @@ fn py_method_declaration(
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: Some(py_method_kind(outer, source)),
         name: method_name.to_string(),
         qualified_name: format!("{class_name}.{method_name}"),
     });
 }
 
+/// Tell a Python method's flavor from the decorators on `outer`, matching
+/// on their last dotted segment so `functools.cached_property` and
+/// `@name.setter` count as properties.
+fn py_method_kind(outer: Node<'_>, source: &str) -> SymbolKind {
+    let mut cursor = outer.walk();
+    let decorators: Vec<&str> = outer
+        .children(&mut cursor)
+        .filter(|child| return child.kind() == "decorator")
+        .filter_map(|decorator| return decorator.utf8_text(source.as_bytes()).ok())
+        .collect();
+    for decorator in decorators {
+        let name = decorator.trim_start_matches('@').split('(').next().unwrap_or_default().trim();
+        match name.rsplit('.').next().unwrap_or_default() {
+            "cached_property" | "deleter" | "getter" | "property" | "setter" => return SymbolKind::Property,
+            "classmethod" => return SymbolKind::ClassMethod,
+            "staticmethod" => return SymbolKind::StaticMethod,
+            _ => {},
+        }
+    }
+    return SymbolKind::Method;
+}
+
 /// Extract a top-level module variable from `NAME = ...` assignment.
 fn py_module_variable(node: Node<'_>, source: &str) -> Option<Declaration> {
     // expression_statement -> assignment
@@ -1053,6 +3052,7 @@ fn py_module_variable(node: Node<'_>, source: &str) -> Option<Declaration> {
 
         return Some(Declaration {
             byte_range: start..end,
+            kind: None,
             name: name.clone(),
             qualified_name: name,
         });
@@ -1076,6 +3076,7 @@ fn py_named_declaration(node: Node<'_>, source: &str, outer: Node<'_>) -> Option
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: name.clone(),
         qualified_name: name,
     });
@@ -1105,24 +3106,117 @@ fn py_self_attribute_assignment(
         let start = u32::try_from(node.start_byte()).ok()?;
         let end = u32::try_from(node.end_byte()).ok()?;
 
-        return Some(Declaration {
-            byte_range: start..end,
-            name: attr_name.to_string(),
-            qualified_name: format!("{class_name}.{attr_name}"),
-        });
+        return Some(Declaration {
+            byte_range: start..end,
+            kind: None,
+            name: attr_name.to_string(),
+            qualified_name: format!("{class_name}.{attr_name}"),
+        });
+    }
+    return None;
+}
+
+/// Extract a `type Vector = list[float]` statement, named without any type
+/// parameters.
+fn py_type_alias(node: Node<'_>, source: &str) -> Option<Declaration> {
+    let left = node.child_by_field_name("left")?.utf8_text(source.as_bytes()).ok()?;
+    let name = left.split('[').next()?.trim();
+    return scoped_declaration(node, None, name);
+}
+
+/// Unwrap a `decorated_definition` to its inner definition node.
+/// Returns the node itself if it's not a decorated definition.
+fn py_unwrap_decorated(node: Node<'_>) -> Node<'_> {
+    if node.kind() != "decorated_definition" {
+        return node;
+    }
+    return node
+        .child_by_field_name("definition")
+        .unwrap_or(node);
+}
+
+/// The names one `import_from_statement` binds.
+fn python_import_names(node: Node<'_>, source: &str) -> Vec<PythonImport> {
+    let text = |n: Node<'_>| return n.utf8_text(source.as_bytes()).unwrap_or_default().to_string();
+    let Some(module) = node.child_by_field_name("module_name").map(text) else {
+        return Vec::new();
+    };
+    if first_child_of_kind(node, "wildcard_import").is_some() {
+        return vec![PythonImport { alias: "*".to_string(), module, name: "*".to_string() }];
+    }
+    let mut cursor = node.walk();
+    return node
+        .children_by_field_name("name", &mut cursor)
+        .map(|name| {
+            let (imported, alias) = match (name.child_by_field_name("name"), name.child_by_field_name("alias")) {
+                (Some(imported), Some(alias)) => (text(imported), text(alias)),
+                _ => (text(name), text(name)),
+            };
+            return PythonImport { alias, module: module.clone(), name: imported };
+        })
+        .collect();
+}
+
+/// List the names a Python module imports with top-level `from ... import`
+/// statements, in source order.
+///
+/// # Errors
+///
+/// Returns `Error::ParseFailed` if tree-sitter cannot parse the source.
+pub fn python_imports(file_path: &Path, source: &str, language: &Language) -> Result<Vec<PythonImport>, Error> {
+    let tree = parse_source(file_path, source, language)?;
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let imports = root
+        .children(&mut cursor)
+        .filter(|node| return node.kind() == "import_from_statement")
+        .flat_map(|node| return python_import_names(node, source))
+        .collect();
+    return Ok(imports);
+}
+
+/// The `#region` or `#endregion` directive on `line`, as written in C#
+/// (`#region Public API`), in TypeScript and JavaScript comments
+/// (`//#region handlers`), and with MSVC's `#pragma region`.
+fn region_directive(line: &str) -> Option<RegionDirective> {
+    let trimmed = line.trim_start();
+    let trimmed = trimmed.strip_prefix("//").unwrap_or(trimmed).trim_start();
+    let directive = trimmed.strip_prefix("#pragma ").or_else(|| return trimmed.strip_prefix('#'))?.trim_start();
+    if directive.starts_with("endregion") {
+        return Some(RegionDirective::End);
     }
-    return None;
+    let label = directive.strip_prefix("region")?;
+    let name = label.split_whitespace().collect::<Vec<_>>().join("-");
+    let is_directive = label.starts_with(char::is_whitespace) && !name.is_empty();
+    return is_directive.then_some(RegionDirective::Begin(name));
 }
 
-/// Unwrap a `decorated_definition` to its inner definition node.
-/// Returns the node itself if it's not a decorated definition.
-fn py_unwrap_decorated(node: Node<'_>) -> Node<'_> {
-    if node.kind() != "decorated_definition" {
-        return node;
+/// The name a `docref:begin` or `docref:end` marker on `line` gives its
+/// region, such as `setup`.
+fn region_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let (_, rest) = line.split_once(marker)?;
+    let rest = rest.strip_prefix([' ', '\t'])?.trim_start();
+    let end = rest.find(|c: char| return !(c.is_alphanumeric() || c == '_' || c == '-')).unwrap_or(rest.len());
+    return rest.get(..end).filter(|name| return !name.is_empty());
+}
+
+/// Heading text as GitHub renders it before slugging: link destinations
+/// (`[guide](guide.md)` reads `[guide]`) and inline HTML tags are dropped.
+fn rendered_heading_text(text: &str) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let next = chars.peek().copied();
+        match c {
+            ']' if next == Some('(') => {
+                rendered.push(c);
+                skip_past(&mut chars, ')');
+            },
+            '<' if next.is_some_and(|n| return n.is_ascii_alphabetic() || n == '/') => skip_past(&mut chars, '>'),
+            _ => rendered.push(c),
+        }
     }
-    return node
-        .child_by_field_name("definition")
-        .unwrap_or(node);
+    return rendered;
 }
 
 /// Parse a source file and resolve one symbol query against it.
@@ -1138,37 +3232,35 @@ pub fn resolve(
     source: &str,
     language: &Language,
     query: &SymbolQuery,
+    slug_style: SlugStyle,
+    grammars: &Grammars,
 ) -> Result<ResolvedSymbol, Error> {
-    let source_len: u64 = source.len().try_into().unwrap_or(u64::MAX);
-    if source_len > MAX_FILE_SIZE {
-        return Err(Error::FileTooLarge {
-            file: file_path.to_path_buf(),
-            max_bytes: MAX_FILE_SIZE,
-            size_bytes: source_len,
-        });
-    }
-
-    let tree = parse_source(file_path, source, language)?;
-    let ext = file_path
-        .extension()
-        .and_then(|e| return e.to_str())
-        .unwrap_or("");
-    let declarations = collect_declarations(tree.root_node(), source, ext);
+    return Declarations::parse(file_path, source, language, slug_style, grammars)?.resolve(query);
+}
 
-    return match query {
-        SymbolQuery::Bare(name) => find_declaration_by_bare_name(&declarations, name, file_path),
-        SymbolQuery::Scoped { parent, child } => {
-            find_declaration_by_qualified_dotpath(&declarations, parent, child, file_path)
-        }
-        SymbolQuery::WholeFile => unreachable!("resolver should not be called for whole-file queries"),
-    };
+/// Resolve a query on a file without a grammar, from what a line scan
+/// finds.
+///
+/// That's `@region` markers, Dockerfile stages and variables, Haskell
+/// functions, types, and classes, Kotlin classes, objects, functions, and
+/// properties, Lua functions and module tables, Protocol Buffers
+/// definitions, Scala objects, classes, traits, `def`s, and `val`s, SCSS
+/// mixins, functions, and variables, SQL statements, and Terraform blocks.
+///
+/// # Errors
+///
+/// Returns `Error::SymbolNotFound` or `Error::AmbiguousSymbol` if the query
+/// doesn't select exactly one declaration.
+pub fn resolve_unparsed(file_path: &Path, source: &str, query: &SymbolQuery) -> Result<ResolvedSymbol, Error> {
+    return find_declaration(&unparsed_declarations(file_path, source), query, file_path);
 }
 
 /// Try to extract a top-level declaration from a Rust CST node.
 fn rust_top_level_declaration(node: Node<'_>, source: &str) -> Option<Declaration> {
     match node.kind() {
         "function_item" | "const_item" | "struct_item" | "enum_item" | "static_item"
-        | "type_item" | "trait_item" => {}
+        | "type_item" | "trait_item" | "mod_item" | "macro_definition" | "union_item"
+        | "function_signature_item" => {}
         _ => return None,
     }
 
@@ -1179,11 +3271,145 @@ fn rust_top_level_declaration(node: Node<'_>, source: &str) -> Option<Declaratio
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: name.clone(),
         qualified_name: name,
     });
 }
 
+/// The name of the trait an `impl` block implements, without its path or
+/// generic arguments: `fmt::Display` is `Display`, `From<u8>` is `From`.
+fn rust_trait_name<'a>(node: Node<'_>, source: &'a str) -> Option<&'a str> {
+    let name_node = match node.kind() {
+        "generic_type" => node.child_by_field_name("type").map(|t| return t.child_by_field_name("name").unwrap_or(t))?,
+        "scoped_type_identifier" => node.child_by_field_name("name")?,
+        _ => node,
+    };
+    return name_node.utf8_text(source.as_bytes()).ok();
+}
+
+/// The keyword and name of the Scala declaration on `line`, past its
+/// annotations and modifiers, including `case` and `private[scope]`.
+fn scala_item(line: &str) -> Option<(&str, &str)> {
+    let is_modifier = |word: &str| {
+        let scoped = word.strip_suffix(']').and_then(|word| return word.split_once('[')).map(|(word, _)| return word);
+        return word.starts_with('@') || SCALA_MODIFIERS.contains(&scoped.unwrap_or(word));
+    };
+    let mut rest = line;
+    let (keyword, after) = loop {
+        let (word, after) = rest.split_once(char::is_whitespace)?;
+        if !is_modifier(word) {
+            break (word, after.trim_start());
+        }
+        rest = after.trim_start();
+    };
+    if !matches!(keyword, "class" | "def" | "object" | "trait" | "val" | "var") {
+        return None;
+    }
+    let name = after.split(['(', '[', ':', '=', ',', ' ']).next()?;
+    return leading_identifier(name).filter(|identifier| return identifier == &name).map(|name| return (keyword, name));
+}
+
+/// Record the Scala declaration `item` makes, returning the name that
+/// qualifies its members if it has any. An object named like the class
+/// before it is that class's companion, so it only adds members.
+fn scala_outline_item(item: &OutlineItem<'_>, declarations: &mut Vec<Declaration>) -> Option<String> {
+    let (keyword, name) = scala_item(item.line)?;
+    let qualified = item.qualify(name);
+    let companion = keyword == "object" && declarations.iter().any(|d| return d.qualified_name == qualified);
+    if !companion {
+        declarations.extend(line_declaration(name, qualified.clone(), item.range.clone()));
+    }
+    return matches!(keyword, "class" | "object" | "trait").then_some(qualified);
+}
+
+/// A declaration named `name` in the type `outer` (unqualified without
+/// one), covering `node`.
+fn scoped_declaration(node: Node<'_>, outer: Option<&str>, name: &str) -> Option<Declaration> {
+    let start = u32::try_from(node.start_byte()).ok()?;
+    let end = u32::try_from(node.end_byte()).ok()?;
+
+    return Some(Declaration {
+        byte_range: start..end,
+        kind: None,
+        name: name.to_string(),
+        qualified_name: outer.map_or_else(|| return name.to_string(), |outer| return format!("{outer}.{name}")),
+    });
+}
+
+/// The name of the SCSS mixin, function, or variable declared at the start
+/// of `line`.
+fn scss_item_name(line: &str) -> Option<&str> {
+    let is_name_char = |c: char| return c.is_alphanumeric() || c == '-' || c == '_';
+    let Some(rest) = line.strip_prefix("@mixin ").or_else(|| return line.strip_prefix("@function ")) else {
+        let (variable, _) = line.split_once(':')?;
+        let name = variable.strip_prefix('$')?;
+        return (!name.is_empty() && name.chars().all(is_name_char)).then_some(variable);
+    };
+    let rest = rest.trim_start();
+    let end = rest.find(|c: char| return !is_name_char(c)).unwrap_or(rest.len());
+    return rest.get(..end).filter(|name| return !name.is_empty());
+}
+
+/// Length of the comment or string opening `rest` in SCSS, where `#`
+/// starts a color or an interpolation rather than a comment.
+fn scss_token_len(rest: &str) -> usize {
+    if rest.starts_with('#') {
+        return 1;
+    }
+    return braced_token_len(rest);
+}
+
+/// Split `text` into words at whitespace outside quotes, as a shell does,
+/// keeping the quotes.
+fn shell_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {},
+            (None, '"' | '\'') => {
+                start.get_or_insert(i);
+                quote = Some(c);
+            },
+            (None, _) if c.is_whitespace() => words.extend(start.take().and_then(|s| return text.get(s..i))),
+            (None, _) => _ = start.get_or_insert(i),
+        }
+    }
+    words.extend(start.and_then(|s| return text.get(s..)));
+    return words;
+}
+
+/// Advance `chars` past the next `end`, or to the end if there is none.
+fn skip_past(chars: &mut Peekable<Chars<'_>>, end: char) {
+    for c in chars.by_ref() {
+        if c == end {
+            return;
+        }
+    }
+}
+
+/// `text` past the type parameters (`<T : Any>`) it starts with, if any.
+fn skip_type_parameters(text: &str) -> &str {
+    if !text.starts_with('<') {
+        return text;
+    }
+    let mut depth = 0_usize;
+    for (i, c) in text.char_indices() {
+        depth = match c {
+            '<' => depth.saturating_add(1),
+            '>' => depth.saturating_sub(1),
+            _ => depth,
+        };
+        if depth == 0 {
+            return text.get(i.saturating_add(1)..).unwrap_or_default().trim_start();
+        }
+    }
+    return "";
+}
+
 /// Convert heading text to a URL-compatible slug.
 ///
 /// Lowercase, spaces/non-alphanumeric to hyphens, collapse runs, trim edges.
@@ -1211,6 +3437,125 @@ fn slugify(text: &str) -> String {
     return result;
 }
 
+/// Convert heading text to a GitHub-compatible anchor slug.
+///
+/// Mirrors GitHub's slugger on the rendered text: lowercase, drop
+/// everything except letters, numbers, `_`, `-`, and spaces, then turn each
+/// space into a hyphen. Unlike `slugify`, runs are not collapsed — `a  b`
+/// becomes `a--b`.
+fn slugify_github(text: &str) -> String {
+    return rendered_heading_text(text)
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c == ' ' {
+                return Some('-');
+            }
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                return Some(c);
+            }
+            return None;
+        })
+        .collect();
+}
+
+/// The name of a `CREATE` statement's column, or `None` for a table
+/// constraint such as `PRIMARY KEY (id)`.
+fn sql_column_name(column: &str) -> Option<String> {
+    let word = column.split(|c: char| return c.is_whitespace() || c == '(').next()?;
+    let constraints = ["CONSTRAINT", "PRIMARY", "FOREIGN", "UNIQUE", "CHECK", "EXCLUDE", "KEY", "INDEX", "LIKE"];
+    let is_constraint = constraints.iter().any(|keyword| return keyword.eq_ignore_ascii_case(word));
+    return (!word.is_empty() && !is_constraint).then(|| return sql_identifier(word));
+}
+
+/// The name a `CREATE TABLE`, `VIEW`, `FUNCTION`, or `INDEX` statement
+/// declares, without its schema, and whether it's a table.
+fn sql_created_name(statement: &str) -> Option<(String, bool)> {
+    let modifiers = ["OR", "REPLACE", "TEMP", "TEMPORARY", "UNLOGGED", "MATERIALIZED", "UNIQUE", "GLOBAL", "LOCAL"];
+    let options = ["IF", "NOT", "EXISTS", "CONCURRENTLY"];
+    let is_one_of = |keywords: &[&str], word: &str| return keywords.iter().any(|k| return k.eq_ignore_ascii_case(word));
+    let mut words = statement.split(|c: char| return c.is_whitespace() || c == '(').filter(|w| return !w.is_empty());
+    if !words.next()?.eq_ignore_ascii_case("CREATE") {
+        return None;
+    }
+    let kind = words.by_ref().find(|word| return !is_one_of(&modifiers, word))?;
+    if !is_one_of(&["TABLE", "VIEW", "FUNCTION", "INDEX"], kind) {
+        return None;
+    }
+    let name = words.find(|word| return !is_one_of(&options, word))?;
+    let is_unnamed_index = name.eq_ignore_ascii_case("ON");
+    return (!is_unnamed_index).then(|| return (sql_identifier(name), kind.eq_ignore_ascii_case("TABLE")));
+}
+
+/// The `$$` or `$tag$` opening a PostgreSQL dollar-quoted body at the
+/// start of `rest`.
+fn sql_dollar_tag(rest: &str) -> Option<&str> {
+    let after = rest.strip_prefix('$')?;
+    let len = after.find(|c: char| return !(c.is_alphanumeric() || c == '_'))?;
+    let closes = after.get(len..)?.starts_with('$');
+    return rest.get(..len.saturating_add(2)).filter(|_| return closes);
+}
+
+/// A SQL name without its schema and quoting: `"public"."users"` is `users`.
+fn sql_identifier(name: &str) -> String {
+    let unqualified = name.rsplit('.').next().unwrap_or(name);
+    return unqualified.trim_matches(['"', '`', '[', ']']).to_string();
+}
+
+/// Byte ranges of the statements in a SQL file, each running from its
+/// first keyword through the `;` that ends it.
+fn sql_statements(source: &str) -> Vec<Range<usize>> {
+    let mut statements = Vec::new();
+    let mut start = None;
+    let mut pos = 0_usize;
+    while let Some(rest) = source.get(pos..) {
+        let Some(c) = rest.chars().next() else { break };
+        if c == ';' {
+            statements.extend(start.take().map(|start| return start..pos.saturating_add(1)));
+        } else if !c.is_whitespace() && !rest.starts_with("--") && !rest.starts_with("/*") {
+            start.get_or_insert(pos);
+        }
+        pos = pos.saturating_add(sql_token_len(rest));
+    }
+    statements.extend(start.map(|start| return start..source.len()));
+    return statements;
+}
+
+/// Length of the comment, quoted name or string, or dollar-quoted body
+/// opening `rest`, so a `;` inside it doesn't end the statement; otherwise
+/// the length of its first character.
+fn sql_token_len(rest: &str) -> usize {
+    let delimiters = if rest.starts_with("--") {
+        Some(("--", "\n"))
+    } else if rest.starts_with("/*") {
+        Some(("/*", "*/"))
+    } else if rest.starts_with(['\'', '"', '`']) {
+        rest.get(..1).map(|quote| return (quote, quote))
+    } else {
+        sql_dollar_tag(rest).map(|tag| return (tag, tag))
+    };
+    let Some((open, close)) = delimiters else {
+        return rest.chars().next().map_or(1, char::len_utf8);
+    };
+    let body = rest.get(open.len()..).unwrap_or_default();
+    return body.find(close).map_or(rest.len(), |end| {
+        return open.len().saturating_add(end).saturating_add(close.len());
+    });
+}
+
+/// The prop a `$props()` pattern element names: `size` in `size`,
+/// `size = 'md'`, and `size: width`. Rest elements name no prop.
+fn svelte_prop_name<'a>(prop: Node<'_>, source: &'a str) -> Option<&'a str> {
+    let key = match prop.kind() {
+        "object_assignment_pattern" => prop.child_by_field_name("left")?,
+        "pair_pattern" => prop.child_by_field_name("key")?,
+        "shorthand_property_identifier_pattern" => prop,
+        _ => return None,
+    };
+    return key.utf8_text(source.as_bytes()).ok();
+}
+
 /// Build a `SymbolNotFound` error with suggestion names from available declarations.
 fn symbol_not_found_error(
     file_path: &Path,
@@ -1230,6 +3575,68 @@ fn symbol_not_found_error(
     };
 }
 
+/// The address of the top-level Terraform block opened on `line`.
+///
+/// Addresses read the way Terraform writes them: `aws_s3_bucket.logs`,
+/// `data.aws_ami.ubuntu`, `module.vpc`, `var.region`, or `output.bucket_arn`.
+/// Nested blocks are indented, so only unindented headers count.
+fn tf_block_address(line: &str) -> Option<String> {
+    let (header, _) = line.split_once('{')?;
+    let mut words = header.split_whitespace();
+    let kind = words.next().filter(|_| return !line.starts_with(char::is_whitespace))?;
+    let labels: Vec<&str> = words.map(|word| return word.trim_matches('"')).collect();
+    let prefix = match (kind, labels.len()) {
+        ("resource", 2) => "",
+        ("data", 2) => "data.",
+        ("module", 1) => "module.",
+        ("output", 1) => "output.",
+        ("variable", 1) => "var.",
+        _ => return None,
+    };
+    return Some(format!("{prefix}{}", labels.join(".")));
+}
+
+/// A TOML declaration for the dotted `key` within `outer`, covering `node`.
+/// Its name is the last key segment, so `[package.metadata]` is `metadata`.
+fn toml_declaration(node: Node<'_>, outer: Option<&str>, key: &str) -> Option<Declaration> {
+    let path = outer.map_or_else(|| return key.to_string(), |outer| return format!("{outer}.{key}"));
+    return match path.rsplit_once('.') {
+        Some((outer, name)) => scoped_declaration(node, Some(outer), name),
+        None => scoped_declaration(node, None, &path),
+    };
+}
+
+/// The dotted path a TOML key spells, with quotes and spacing dropped:
+/// `"a" . b` is `a.b`.
+fn toml_key(node: Node<'_>, source: &str) -> Option<String> {
+    let text = node.utf8_text(source.as_bytes()).ok()?;
+    let segments: Vec<&str> =
+        text.split('.').map(|s| return s.trim().trim_matches(|c| return c == '"' || c == '\'')).collect();
+    return Some(segments.join("."));
+}
+
+/// The name a TypeScript class body member is addressed by: its property
+/// name (including `#private` names), or `static` for a static block.
+fn ts_class_member_name<'a>(node: Node<'_>, source: &'a str) -> Option<&'a str> {
+    return match node.kind() {
+        "class_static_block" => Some("static"),
+        "abstract_method_signature" | "method_definition" | "method_signature" | "public_field_definition" => {
+            node.child_by_field_name("name")?.utf8_text(source.as_bytes()).ok()
+        },
+        _ => None,
+    };
+}
+
+/// A `default` declaration for an `export default` statement, named or not,
+/// covering the whole statement.
+fn ts_default_export(node: Node<'_>) -> Option<Declaration> {
+    let mut cursor = node.walk();
+    if !node.children(&mut cursor).any(|child| return child.kind() == "default") {
+        return None;
+    }
+    return scoped_declaration(node, None, "default");
+}
+
 /// Extract a single enum member declaration from a TypeScript enum body child.
 fn ts_enum_member_declaration(
     node: Node<'_>,
@@ -1249,15 +3656,29 @@ fn ts_enum_member_declaration(
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: name_text.to_string(),
         qualified_name: format!("{enum_name}.{name_text}"),
     });
 }
 
+/// The `namespace` or `module` a TypeScript statement declares, looking
+/// through the expression statement or `declare` that may wrap it.
+fn ts_namespace(node: Node<'_>) -> Option<Node<'_>> {
+    if matches!(node.kind(), "internal_module" | "module") {
+        return Some(node);
+    }
+    if !matches!(node.kind(), "expression_statement" | "ambient_declaration") {
+        return None;
+    }
+    let mut cursor = node.walk();
+    return node.named_children(&mut cursor).find(|child| return matches!(child.kind(), "internal_module" | "module"));
+}
+
 /// Try to extract a top-level TypeScript declaration with a direct "name" field.
 fn ts_top_level_declaration(node: Node<'_>, source: &str) -> Option<Declaration> {
     match node.kind() {
-        "function_declaration" | "class_declaration" | "interface_declaration"
+        "function_declaration" | "function_signature" | "class_declaration" | "interface_declaration"
         | "type_alias_declaration" | "enum_declaration" => {}
         _ => return None,
     }
@@ -1269,6 +3690,7 @@ fn ts_top_level_declaration(node: Node<'_>, source: &str) -> Option<Declaration>
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: name.clone(),
         qualified_name: name,
     });
@@ -1290,19 +3712,29 @@ fn ts_variable_declarator(
 
     return Some(Declaration {
         byte_range: start..end,
+        kind: None,
         name: name.clone(),
         qualified_name: name,
     });
 }
 
+/// Declarations a line scan finds in a file without a grammar: its
+/// `@region` markers, and whatever its language's line scanner collects.
+fn unparsed_declarations(file_path: &Path, source: &str) -> Vec<Declaration> {
+    let mut declarations = line_scanner(file_path).map(|scan| return scan(source)).unwrap_or_default();
+    declarations.extend(collect_region_declarations(source));
+    return declarations;
+}
+
 /// Unwrap an `export_statement` to its inner declaration node.
 /// Falls back to the export node itself if no declaration child is found.
 fn unwrap_export(export: Node<'_>) -> Node<'_> {
     let mut cursor = export.walk();
     for child in export.children(&mut cursor) {
         match child.kind() {
-            "function_declaration" | "class_declaration" | "interface_declaration"
-            | "type_alias_declaration" | "enum_declaration" | "lexical_declaration" => {
+            "function_declaration" | "function_signature" | "class_declaration" | "interface_declaration"
+            | "type_alias_declaration" | "enum_declaration" | "lexical_declaration" | "internal_module"
+            | "module" => {
                 return child;
             }
             _ => {}
@@ -1311,41 +3743,425 @@ fn unwrap_export(export: Node<'_>) -> Node<'_> {
     return export;
 }
 
-/// Recursively walk section nodes, threading the parent heading slug as context.
-fn walk_markdown_sections_with_scope(
-    node: Node<'_>,
-    source: &str,
-    parent_slug: &str,
-    declarations: &mut Vec<Declaration>,
-) {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() == "section" {
-            extract_declaration_from_markdown_section(child, source, parent_slug, declarations);
-        }
-    }
+/// A qualified name with any `@Trait` segment dropped, so a trait impl
+/// method such as `Config@Display.fmt` still answers to `Config.fmt`.
+fn without_trait(qualified_name: &str) -> String {
+    let Some((head, rest)) = qualified_name.split_once('@').filter(|(head, _)| return !head.is_empty()) else {
+        return qualified_name.to_string();
+    };
+    return rest.split_once('.').map_or_else(|| return head.to_string(), |(_, tail)| return format!("{head}.{tail}"));
 }
 
 #[cfg(test)]
 #[allow(clippy::missing_panics_doc, reason = "test code uses unwrap freely")]
 mod tests {
-    use super::slugify;
+    use std::path::Path;
+
+    use super::{
+        Declarations, Slugger, SymbolKind, list_symbols, list_unparsed_symbols, resolve_unparsed, slugify,
+        slugify_github,
+    };
+    use crate::config::SlugStyle;
+    use crate::error::Error;
+    use crate::grammar::{self, Grammars};
+    use crate::types::SymbolQuery;
 
     #[test]
     fn consecutive_spaces() {
         assert_eq!(slugify("  Hello   World  "), "hello-world");
     }
 
+    #[test]
+    fn declarations_answer_many_queries_from_one_parse() {
+        let path = Path::new("src/lib.rs");
+        let source = "struct Point;\nimpl Point { fn x() {} }\nfn x() {}\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let point = declarations.resolve(&SymbolQuery::Bare("Point".to_string())).unwrap();
+        assert_eq!(point.byte_range, 0..13);
+        let scoped = SymbolQuery::Scoped { child: "x".to_string(), parent: "Point".to_string() };
+        assert!(declarations.resolve(&scoped).is_ok());
+        let ambiguous = declarations.resolve(&SymbolQuery::Bare("x".to_string()));
+        assert!(matches!(ambiguous, Err(Error::AmbiguousSymbol { .. })));
+    }
+
+    #[test]
+    fn default_exports_answer_to_default_and_the_basename() {
+        let anonymous = ("src/Button.tsx", "export default function () {\n  return null;\n}\n");
+        let named = ("src/Card.ts", "export default class Panel {}\nexport function Card() {}\n");
+        for (file, source) in [anonymous, named] {
+            let file = Path::new(file);
+            let language = grammar::language_for_path(file, &Grammars::default()).unwrap();
+            let declarations =
+                Declarations::parse(file, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+            let default = declarations.resolve(&SymbolQuery::Bare("default".to_string())).unwrap();
+            assert_eq!(default.byte_range.start, 0, "{source}");
+        }
+        let (file, source) = anonymous;
+        let language = grammar::language_for_path(Path::new(file), &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(Path::new(file), source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        assert!(declarations.resolve(&SymbolQuery::Bare("Button".to_string())).is_ok());
+        let (file, source) = named;
+        let language = grammar::language_for_path(Path::new(file), &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(Path::new(file), source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let card = declarations.resolve(&SymbolQuery::Bare("Card".to_string())).unwrap();
+        assert_eq!(card.byte_range.start, 37, "the basename alias should not shadow a real Card");
+    }
+
+    #[test]
+    fn dockerfile_stages_qualify_their_variables() {
+        let path = Path::new("deploy/app.Dockerfile");
+        let source = "ARG V=1\nFROM a AS b\n# ENV SKIPPED=1\nENV X=\"1 Y=2\" \\\n  Z=3\nRUN true\n\n\
+                      FROM c\nENV P /bin\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["V", "b", "b.X", "b.Z", "P"]);
+        let stage = resolve_unparsed(path, source, &SymbolQuery::Bare("b".to_string())).unwrap();
+        assert_eq!(source.get(8..66), Some("FROM a AS b\n# ENV SKIPPED=1\nENV X=\"1 Y=2\" \\\n  Z=3\nRUN true"));
+        assert_eq!(stage.byte_range, 8..66);
+        let variable = resolve_unparsed(path, source, &SymbolQuery::Bare("Z".to_string())).unwrap();
+        assert_eq!(variable.byte_range, 36..57);
+    }
+
+    #[test]
+    fn duplicate_headings_under_one_parent_are_suffixed() {
+        let path = Path::new("docs/guide.md");
+        let source = "## Usage\n### Example\nA\n### Example\nB\n## Other\n### Example\nC\n## Usage\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let symbols = list_symbols(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let mut names: Vec<&str> = symbols.iter().map(|s| return s.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["other", "other.example", "usage", "usage-1", "usage.example", "usage.example-1"]);
+        let repeated: Vec<&str> = symbols
+            .iter()
+            .filter(|s| return s.kind == Some(SymbolKind::RepeatedHeading))
+            .map(|s| return s.name.as_str())
+            .collect();
+        assert_eq!(repeated, ["usage.example-1", "usage-1"]);
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let second = SymbolQuery::Scoped { child: "example-1".to_string(), parent: "usage".to_string() };
+        assert_eq!(declarations.resolve(&second).unwrap().byte_range, 23..37);
+    }
+
     #[test]
     fn empty_string() {
         assert_eq!(slugify(""), "");
     }
 
+    #[test]
+    fn generic_parameters_are_ignored_when_matching() {
+        let cases = [
+            ("src/map.rs", "struct HashMap<K, V>;\nimpl<K, V> HashMap<K, V> { fn insert() {} }\n", "HashMap"),
+            ("src/box.ts", "class Box<T> {\n  insert(value: T) {}\n}\n", "Box"),
+        ];
+        for (file, source, type_name) in cases {
+            let file = Path::new(file);
+            let language = grammar::language_for_path(file, &Grammars::default()).unwrap();
+            let declarations =
+                Declarations::parse(file, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+            let parents = [format!("{type_name}<K,V>"), format!("{type_name}<T>"), type_name.to_string()];
+            let unresolved: Vec<SymbolQuery> = parents
+                .into_iter()
+                .map(|parent| return SymbolQuery::Scoped { child: "insert".to_string(), parent })
+                .filter(|query| return declarations.resolve(query).is_err())
+                .collect();
+            assert!(unresolved.is_empty(), "{unresolved:?} in {source}");
+            assert!(declarations.resolve(&SymbolQuery::Bare(format!("{type_name}<T>"))).is_ok(), "{source}");
+        }
+    }
+
+    #[test]
+    fn go_generics_and_embedded_fields_are_collected() {
+        let path = Path::new("stack.go");
+        let source = "package main\n\ntype Stack[T any] struct {\n\tsync.Mutex\n\t*Logger\n\titems []T\n}\n\n\
+                      func (s *Stack[T]) Push(v T) {}\n\nfunc Map[T, U any](xs []T) []U { return nil }\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let members = declarations.members("Stack").unwrap();
+        assert_eq!(members, ["Stack.Logger", "Stack.Mutex", "Stack.Push", "Stack.items"]);
+        assert!(declarations.resolve(&SymbolQuery::Bare("Map".to_string())).is_ok());
+    }
+
+    #[test]
+    fn github_drops_punctuation() {
+        assert_eq!(slugify_github("What's New?"), "whats-new");
+    }
+
+    #[test]
+    fn github_keeps_space_runs_and_drops_emoji() {
+        assert_eq!(slugify_github("Hello  World 🚀"), "hello--world-");
+    }
+
+    #[test]
+    fn github_slugs_the_rendered_text_of_links_and_html() {
+        assert_eq!(slugify_github("See [the guide](docs/guide.md#setup)"), "see-the-guide");
+        assert_eq!(slugify_github("Beta <sup>new</sup> a < b"), "beta-new-a--b");
+    }
+
+    #[test]
+    fn github_suffixes_duplicates() {
+        let mut slugger = Slugger::new(SlugStyle::Github);
+        assert_eq!(slugger.slug("Example"), "example");
+        assert_eq!(slugger.slug("Example"), "example-1");
+        assert_eq!(slugger.slug("Example-1"), "example-1-1");
+        assert_eq!(slugger.slug("Example"), "example-2");
+    }
+
+    #[test]
+    fn haskell_constructors_and_methods_are_qualified_by_their_type() {
+        let path = Path::new("src/Shapes.hs");
+        let source = "module Shapes (Shape (..), area) where\n\ndata Shape\n  = Circle Double\n\
+                      \x20 | Rect { width :: Double, height :: Double }\n  deriving (Show, Eq)\n\n\
+                      class Container f where\n  empty :: f a\n  insert :: a -> f a -> f a\n  insert _ c = c\n\n\
+                      area :: Shape -> Double\narea (Circle r) = pi * r * r\narea (Rect w h) = w * h\n\
+                      \x20 where\n    unused = 0\n\na <+> b = area a + area b\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        let types = ["Shape", "Shape.Circle", "Shape.Rect", "Container", "Container.empty", "Container.insert"];
+        assert_eq!(names, [&types[..], &["area"]].concat());
+        let rect = resolve_unparsed(path, source, &SymbolQuery::Bare("Rect".to_string())).unwrap();
+        assert_eq!(source.get(73..115), Some("Rect { width :: Double, height :: Double }"));
+        assert_eq!(rect.byte_range, 73..115, "a constructor should span its alternative");
+        let area = resolve_unparsed(path, source, &SymbolQuery::Bare("area".to_string())).unwrap();
+        assert!(source.get(224..323).is_some_and(|text| return text.starts_with("area ::") && text.ends_with("= 0")));
+        assert_eq!(area.byte_range, 224..323, "the signature and every equation should be one symbol");
+    }
+
+    #[test]
+    fn kotlin_members_are_qualified_by_their_class() {
+        let path = Path::new("src/User.kt");
+        let source = "@Serializable\ndata class User(\n    val id: Long,\n) {\n    fun greet() {\n\
+                      \x20       val local = 1\n    }\n\n    companion object {\n        fun guest() = User(0)\n\
+                      \x20   }\n}\n\n\
+                      fun <T> List<T>.second(): T = get(1)\nprivate val DEFAULT = User.guest()\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["User", "User.id", "User.greet", "User.guest", "second", "DEFAULT"]);
+        let class = resolve_unparsed(path, source, &SymbolQuery::Bare("User".to_string())).unwrap();
+        assert!(source.get(14..160).is_some_and(|text| return text.starts_with("data") && text.ends_with("\n}")));
+        assert_eq!(class.byte_range, 14..160, "the class should run to its closing brace");
+        let greet = resolve_unparsed(path, source, &SymbolQuery::Bare("greet".to_string())).unwrap();
+        assert_eq!(source.get(57..98), Some("fun greet() {\n        val local = 1\n    }"));
+        assert_eq!(greet.byte_range, 57..98);
+    }
+
+    #[test]
+    fn lua_functions_are_qualified_by_their_table() {
+        let path = Path::new("src/greeter.lua");
+        let source = "local M = {\n  format = function(name)\n    return name\n  end,\n}\n\n\
+                      local function shout(text)\n  local upper = function(s) return s:upper() end\n\
+                      \x20 return upper(text)\nend\n\n\
+                      function M:greet(name)\n  print(M.format(name))\nend\n\nM.farewell = function() end\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["M", "M.format", "shout", "M.greet", "M.farewell"]);
+        let table = resolve_unparsed(path, source, &SymbolQuery::Bare("M".to_string())).unwrap();
+        assert_eq!(table.byte_range, 0..62, "the table should run to its closing brace");
+        let greet = resolve_unparsed(path, source, &SymbolQuery::Bare("greet".to_string())).unwrap();
+        assert_eq!(source.get(166..216), Some("function M:greet(name)\n  print(M.format(name))\nend"));
+        assert_eq!(greet.byte_range, 166..216, "a method should run to its `end`");
+    }
+
     #[test]
     fn multi_word() {
         assert_eq!(slugify("Getting Started"), "getting-started");
     }
 
+    #[test]
+    fn nested_scopes_qualify_to_any_depth() {
+        let cases = [
+            ("src/lib.rs", "mod net { pub mod http { pub struct Server; impl Server { fn listen() {} } } }\n"),
+            ("app.py", "class Outer:\n    class Inner:\n        def method(self):\n            pass\n"),
+            ("api.ts", "export namespace Net {\n  namespace Http {\n    export function listen() {}\n  }\n}\n"),
+        ];
+        let paths = ["net.http.Server.listen", "Outer.Inner.method", "Net.Http.listen"];
+        for ((file, source), path) in cases.into_iter().zip(paths) {
+            let file = Path::new(file);
+            let language = grammar::language_for_path(file, &Grammars::default()).unwrap();
+            let declarations =
+                Declarations::parse(file, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+            let (parent, child) = path.split_once('.').unwrap();
+            let query = SymbolQuery::Scoped { child: child.to_string(), parent: parent.to_string() };
+            assert!(declarations.resolve(&query).is_ok(), "{path} in {source}");
+            let (outer, member) = path.rsplit_once('.').unwrap();
+            assert_eq!(declarations.members(outer).unwrap(), [path], "{member} in {source}");
+        }
+    }
+
+    #[test]
+    fn proto_members_are_qualified_by_their_definitions() {
+        let path = Path::new("api/users.proto");
+        let source = "message A {\n  optional int32 x = 1;\n  oneof o {\n    B b = 2;\n  }\n}\nmessage C {}\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["A", "A.x", "A.o", "A.b", "C"]);
+        let field = resolve_unparsed(path, source, &SymbolQuery::Bare("x".to_string())).unwrap();
+        assert_eq!(source.get(14..35), Some("optional int32 x = 1;"));
+        assert_eq!(field.byte_range, 14..35);
+    }
+
+    #[test]
+    fn python_method_kinds_come_from_decorators() {
+        let path = Path::new("shapes.py");
+        let source = "class Circle:\n    @property\n    def area(self):\n        return 1\n    @area.setter\n\
+                      \x20   def area(self, v):\n        pass\n    @classmethod\n    def unit(cls):\n        pass\n\
+                      \x20   @staticmethod\n    def pi():\n        return 3\n    def grow(self):\n        pass\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let symbols = list_symbols(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let kinds: Vec<(&str, Option<&str>)> =
+            symbols.iter().map(|s| return (s.name.as_str(), s.kind.map(SymbolKind::name))).collect();
+        assert_eq!(kinds, [
+            ("Circle.area", Some("property")),
+            ("Circle.unit", Some("classmethod")),
+            ("Circle.pi", Some("staticmethod")),
+            ("Circle.grow", Some("method")),
+            ("Circle", None),
+        ]);
+    }
+
+    #[test]
+    fn python_class_attributes_and_type_aliases_are_symbols() {
+        let path = Path::new("models.py");
+        let source = "type Vector[T] = list[T]\n\nclass User:\n    MAX = 5\n    name: str\n    _secret = 1\n\
+                      \x20   def __init__(self):\n        self.name = 'x'\n        self.age = 0\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        assert_eq!(declarations.members("User").unwrap(), ["User.MAX", "User.age", "User.name"]);
+        let vector = declarations.resolve(&SymbolQuery::Bare("Vector".to_string())).unwrap();
+        assert_eq!(vector.byte_range, 0..24);
+    }
+
+    #[test]
+    fn region_directives_fence_named_regions() {
+        let path = Path::new("src/routes.ts");
+        let source = "//#region Route handlers\nget();\n// #region inner\npost();\n//#endregion\n#endregion\n";
+        let outer = resolve_unparsed(path, source, &SymbolQuery::Bare("@Route-handlers".to_string())).unwrap();
+        assert_eq!(outer.byte_range, 25..70);
+        let inner = resolve_unparsed(path, source, &SymbolQuery::Bare("@inner".to_string())).unwrap();
+        assert_eq!(source.get(49..56), Some("post();"));
+        assert_eq!(inner.byte_range, 49..57);
+        let unnamed = resolve_unparsed(path, "#region\nx\n#endregion\n", &SymbolQuery::Bare("@".to_string()));
+        assert!(matches!(unnamed, Err(Error::SymbolNotFound { .. })));
+    }
+
+    #[test]
+    fn region_markers_fence_named_regions() {
+        let path = Path::new("deploy.conf");
+        let source = "# docref:begin ports\nlisten 80\n# docref:end ports\n\
+                      # docref:beginning x\n-- docref:begin open\n";
+        let ports = resolve_unparsed(path, source, &SymbolQuery::Bare("@ports".to_string())).unwrap();
+        assert_eq!(source.get(21..31), Some("listen 80\n"));
+        assert_eq!(ports.byte_range, 21..31);
+        for unmatched in ["@open", "@x", "ports"] {
+            let result = resolve_unparsed(path, source, &SymbolQuery::Bare(unmatched.to_string()));
+            assert!(matches!(result, Err(Error::SymbolNotFound { .. })), "{unmatched}");
+        }
+    }
+
+    #[test]
+    fn rust_associated_consts_and_types_are_members() {
+        let path = Path::new("src/lib.rs");
+        let source = "trait Source { type Item; const LIMIT: usize; fn next(&self); }\n\
+                      impl Config { const MAX_RETRIES: u32 = 3; }\nimpl Source for Config { type Item = u8; }\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let members = [("Source", "Item"), ("Source", "LIMIT"), ("Config", "MAX_RETRIES"), ("Config", "Item")];
+        for (parent, child) in members {
+            let query = SymbolQuery::Scoped { child: child.to_string(), parent: parent.to_string() };
+            assert!(declarations.resolve(&query).is_ok(), "{parent}.{child}");
+        }
+        assert_eq!(declarations.members("Source").unwrap(), ["Source.Item", "Source.LIMIT", "Source.next"]);
+    }
+
+    #[test]
+    fn rust_extern_blocks_and_unions_are_symbols() {
+        let path = Path::new("src/lib.rs");
+        let source = "extern \"C\" {\n    fn abs(x: i32) -> i32;\n    static errno: i32;\n}\n\
+                      union Bits { int: u32, float: f32 }\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let abs = declarations.resolve(&SymbolQuery::Bare("abs".to_string())).unwrap();
+        assert_eq!(source.get(17..39), Some("fn abs(x: i32) -> i32;"));
+        assert_eq!(abs.byte_range, 17..39);
+        assert!(declarations.resolve(&SymbolQuery::Bare("errno".to_string())).is_ok());
+        assert_eq!(declarations.members("Bits").unwrap(), ["Bits.float", "Bits.int"]);
+    }
+
+    #[test]
+    fn rust_macro_definitions_are_symbols() {
+        let path = Path::new("src/lib.rs");
+        let source = "#[macro_export]\nmacro_rules! square {\n    ($x:expr) => { $x * $x };\n}\n\
+                      mod util { macro_rules! twice { ($x:expr) => { $x * 2 }; } }\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let square = declarations.resolve(&SymbolQuery::Bare("square".to_string())).unwrap();
+        assert_eq!(square.byte_range, 16..69);
+        let twice = SymbolQuery::Scoped { child: "twice".to_string(), parent: "util".to_string() };
+        assert!(declarations.resolve(&twice).is_ok());
+    }
+
+    #[test]
+    fn rust_trait_impl_methods_are_qualified_by_trait() {
+        let path = Path::new("src/lib.rs");
+        let source = "struct Config;\nimpl Config { fn fmt() {} }\nimpl fmt::Display for Config { fn fmt() {} }\n\
+                      impl From<u8> for Config { fn from() {} }\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let scoped = |parent: &str, child: &str| {
+            return SymbolQuery::Scoped { child: child.to_string(), parent: parent.to_string() };
+        };
+        assert_eq!(declarations.resolve(&scoped("Config", "fmt")).unwrap().byte_range, 29..40);
+        assert_eq!(declarations.resolve(&scoped("Config@Display", "fmt")).unwrap().byte_range, 74..85);
+        assert!(declarations.resolve(&scoped("Config", "from")).is_ok());
+        let members = declarations.members("Config").unwrap();
+        assert_eq!(members, ["Config.fmt", "Config@Display.fmt", "Config@From.from"]);
+    }
+
+    #[test]
+    fn scala_companion_members_are_qualified_by_their_class() {
+        let path = Path::new("src/Shapes.scala");
+        let source = "trait Shape {\n  def area: Double\n}\n\ncase class Circle(r: Double) extends Shape {\n\
+                      \x20 def area = r * r\n}\n\nobject Circle:\n  def unit(): Circle =\n    val r = 1.0\n\
+                      \x20   Circle(r)\nend Circle\n\nprivate[app] val origin = Circle(0)\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["Shape", "Shape.area", "Circle", "Circle.area", "Circle.unit", "origin"]);
+        let unit = resolve_unparsed(path, source, &SymbolQuery::Bare("unit".to_string())).unwrap();
+        assert_eq!(source.get(120..170), Some("def unit(): Circle =\n    val r = 1.0\n    Circle(r)"));
+        assert_eq!(unit.byte_range, 120..170);
+    }
+
+    #[test]
+    fn scss_colors_are_not_comments() {
+        let path = Path::new("styles/_tokens.scss");
+        let source = "@mixin a { color: #fff; }\n$b: 1px;\n.c {\n  $d: 2px;\n}\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["a", "$b"]);
+        let mixin = resolve_unparsed(path, source, &SymbolQuery::Bare("a".to_string())).unwrap();
+        assert_eq!(mixin.byte_range, 0..25);
+    }
+
+    #[test]
+    fn setext_headings_are_scoped_like_atx_headings() {
+        let path = Path::new("docs/guide.md");
+        let source = "Guide\n=====\n\nInstall\n-------\n\n### On Linux\n\nRun it.\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let symbols = list_symbols(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let mut names: Vec<&str> = symbols.iter().map(|s| return s.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["guide", "install", "install.on-linux"]);
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let install = declarations.resolve(&SymbolQuery::Bare("install".to_string())).unwrap();
+        assert_eq!(install.byte_range, 13..52, "the section should run to the end of the document");
+        let linux = SymbolQuery::Scoped { child: "on-linux".to_string(), parent: "install".to_string() };
+        assert_eq!(declarations.resolve(&linux).unwrap().byte_range, 30..52);
+    }
+
     #[test]
     fn simple_heading() {
         assert_eq!(slugify("Architecture"), "architecture");
@@ -1355,4 +4171,59 @@ mod tests {
     fn special_chars() {
         assert_eq!(slugify("What's New?"), "what-s-new");
     }
+
+    #[test]
+    fn sql_statements_and_table_columns_are_symbols() {
+        let path = Path::new("db/schema.sql");
+        let source = "-- users; sessions\nCREATE TABLE \"app\".users (\n  id int,\n  note text DEFAULT 'a;b',\n  \
+                      PRIMARY KEY (id)\n);\nCREATE INDEX ON users (note);\nCREATE FUNCTION f() AS $$ a; $$;\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["users", "users.id", "users.note", "f"]);
+        let table = resolve_unparsed(path, source, &SymbolQuery::Bare("users".to_string())).unwrap();
+        assert_eq!(source.get(19..20), Some("C"));
+        assert_eq!(table.byte_range, 19..104);
+        let note = resolve_unparsed(path, source, &SymbolQuery::Bare("note".to_string())).unwrap();
+        assert_eq!(source.get(58..82), Some("note text DEFAULT 'a;b',"));
+        assert_eq!(note.byte_range, 58..81);
+    }
+
+    #[test]
+    fn tf_blocks_end_at_their_closing_brace() {
+        let path = Path::new("infra/main.tf");
+        let source = "variable \"a\" { default = \"}\" }\nresource \"t\" \"b\" {\n  x {\n  }\n}\n";
+        let names: Vec<String> = list_unparsed_symbols(path, source).into_iter().map(|s| return s.name).collect();
+        assert_eq!(names, ["var.a", "t.b"]);
+        let variable = resolve_unparsed(path, source, &SymbolQuery::Bare("a".to_string())).unwrap();
+        assert_eq!(variable.byte_range, 0..30);
+        let resource = resolve_unparsed(path, source, &SymbolQuery::Bare("b".to_string())).unwrap();
+        assert_eq!(source.get(31..), Some("resource \"t\" \"b\" {\n  x {\n  }\n}\n"));
+        assert_eq!(resource.byte_range, 31..61);
+    }
+
+    #[test]
+    fn ts_accessors_static_and_private_members_are_collected() {
+        let path = Path::new("src/counter.ts");
+        let source = "class Counter {\n  #count = 0;\n  static zero = 0;\n  static { init(); }\n\
+                      get count() { return this.#count; }\n  set count(v: number) { this.#count = v; }\n}\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let members = declarations.members("Counter").unwrap();
+        assert_eq!(members, ["Counter.#count", "Counter.count", "Counter.static", "Counter.zero"]);
+        let count = declarations.resolve(&SymbolQuery::Bare("count".to_string())).unwrap();
+        assert_eq!(count.byte_range, 70..149, "the accessor pair should be one declaration");
+    }
+
+    #[test]
+    fn ts_function_overloads_are_one_symbol() {
+        let path = Path::new("src/parse.ts");
+        let source = "export function parse(x: string): number;\nexport function parse(x: number): number;\n\
+                      export function parse(x: any) {\n  return Number(x);\n}\n";
+        let language = grammar::language_for_path(path, &Grammars::default()).unwrap();
+        let declarations =
+            Declarations::parse(path, source, &language, SlugStyle::Default, &Grammars::default()).unwrap();
+        let parse = declarations.resolve(&SymbolQuery::Bare("parse".to_string())).unwrap();
+        assert_eq!(parse.byte_range, 7..137);
+        assert_eq!(source.get(137..), Some("\n"));
+    }
 }
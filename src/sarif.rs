@@ -0,0 +1,199 @@
+//! SARIF 2.1.0 logs for `check --format sarif`, so code scanning tools can
+//! show findings on the markdown line that holds each reference.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::config;
+
+/// Where the SARIF 2.1.0 schema is published.
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// File a result points at.
+#[derive(Serialize)]
+struct ArtifactLocationJson {
+    /// Path relative to the repository root, with forward slashes.
+    uri: String,
+}
+
+/// The tool that produced the log, with its rules.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DriverJson {
+    /// Project homepage.
+    information_uri: &'static str,
+    /// Tool name.
+    name: &'static str,
+    /// Every kind of finding docref reports.
+    rules: Vec<RuleJson>,
+    /// docref version.
+    version: &'static str,
+}
+
+/// One non-fresh reference to report.
+pub struct Finding<'a> {
+    /// What kind of finding this is; becomes the result's rule.
+    pub kind: config::Finding,
+    /// How the finding counts toward the check result.
+    pub level: Level,
+    /// The markdown line holding the link, when it can still be found.
+    pub line: Option<u32>,
+    /// Explanation shown to the reader.
+    pub message: String,
+    /// The markdown file holding the link, relative to the project root.
+    pub source: &'a Path,
+}
+
+/// How a finding is reported to SARIF consumers.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// Fails the check.
+    Error,
+    /// Recorded in the baseline; shown without failing anything.
+    Note,
+    /// Reported without failing the check.
+    Warning,
+}
+
+/// Where a result is, as a SARIF location.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LocationJson {
+    /// File and line of the reference.
+    physical_location: PhysicalLocationJson,
+}
+
+/// A plain-text SARIF message.
+#[derive(Serialize)]
+struct MessageJson {
+    /// The message text.
+    text: String,
+}
+
+/// File and, when known, line of a result.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocationJson {
+    /// The markdown file.
+    artifact_location: ArtifactLocationJson,
+    /// The line within it, omitted when the link can't be found.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<RegionJson>,
+}
+
+/// Line range of a result within its file.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegionJson {
+    /// 1-based line number.
+    start_line: u32,
+}
+
+/// One reported finding.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultJson {
+    /// Severity for this result.
+    level: Level,
+    /// Where the reference is documented.
+    locations: Vec<LocationJson>,
+    /// Explanation shown to the reader.
+    message: MessageJson,
+    /// Kind of finding, matching a rule's `id`.
+    rule_id: &'static str,
+}
+
+/// A kind of finding docref can report.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleJson {
+    /// Rule identifier, such as `stale`.
+    id: &'static str,
+    /// One-line description of the rule.
+    short_description: MessageJson,
+}
+
+/// A single analysis run.
+#[derive(Serialize)]
+struct RunJson {
+    /// Every finding in lockfile order.
+    results: Vec<ResultJson>,
+    /// The tool that produced the run.
+    tool: ToolJson,
+}
+
+/// The top-level SARIF log.
+#[derive(Serialize)]
+struct SarifJson {
+    /// The runs in the log; docref writes one.
+    runs: Vec<RunJson>,
+    /// Schema the log conforms to.
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    /// SARIF version.
+    version: &'static str,
+}
+
+/// Wrapper around the tool's driver.
+#[derive(Serialize)]
+struct ToolJson {
+    /// The docref driver.
+    driver: DriverJson,
+}
+
+/// Render `findings` as a pretty-printed SARIF 2.1.0 log.
+pub fn render(findings: &[Finding<'_>]) -> String {
+    let rules = [config::Finding::Ambiguous, config::Finding::Broken, config::Finding::Orphaned, config::Finding::Stale]
+        .into_iter()
+        .map(|kind| {
+            let short_description = MessageJson { text: rule_description(kind).to_string() };
+            return RuleJson { id: kind.name(), short_description };
+        })
+        .collect();
+    let results = findings.iter().map(result).collect();
+    let log = SarifJson {
+        runs: vec![RunJson {
+            results,
+            tool: ToolJson {
+                driver: DriverJson {
+                    information_uri: env!("CARGO_PKG_REPOSITORY"),
+                    name: "docref",
+                    rules,
+                    version: env!("CARGO_PKG_VERSION"),
+                },
+            },
+        }],
+        schema: SCHEMA,
+        version: "2.1.0",
+    };
+    return serde_json::to_string_pretty(&log).unwrap_or_default();
+}
+
+/// The SARIF result for one finding.
+fn result(finding: &Finding<'_>) -> ResultJson {
+    let uri = finding.source.components().map(|c| return c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+    let location = LocationJson {
+        physical_location: PhysicalLocationJson {
+            artifact_location: ArtifactLocationJson { uri },
+            region: finding.line.map(|start_line| return RegionJson { start_line }),
+        },
+    };
+    return ResultJson {
+        level: finding.level,
+        locations: vec![location],
+        message: MessageJson { text: finding.message.clone() },
+        rule_id: finding.kind.name(),
+    };
+}
+
+/// What a rule checks, for the log's rule list.
+const fn rule_description(kind: config::Finding) -> &'static str {
+    return match kind {
+        config::Finding::Ambiguous => "Referenced symbol matches more than one declaration",
+        config::Finding::Broken => "Referenced file, namespace, or symbol no longer exists",
+        config::Finding::Orphaned => "Markdown file holding a locked reference was removed",
+        config::Finding::Stale => "Referenced code changed since the docs were last reviewed",
+    };
+}
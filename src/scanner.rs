@@ -2,9 +2,16 @@
 //!
 //! Walks a directory tree, filters markdown files according to the project
 //! configuration, and groups discovered references by their target file path.
+//! Reference-style links are tracked at their `[ref]: path#symbol` definitions.
+//! Raw HTML `<a href="path#symbol">` anchors and Hugo and Jekyll shortcodes
+//! such as `{{< docref "path#symbol" >}}` are recognized too, and Obsidian-style `[[path#symbol|text]]` wiki links
+//! when enabled in the config. Org documents use `[[path#symbol][text]]` links.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::ffi::{OsStr, OsString};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
 
 use regex::{Captures, Regex};
 use walkdir::WalkDir;
@@ -12,42 +19,270 @@ use walkdir::WalkDir;
 use crate::config::Config;
 use crate::error::Error;
 use crate::grammar;
-use crate::types::{Reference, SymbolQuery};
+use crate::types::{HashMode, Reference, SymbolQuery};
+
+/// HTML anchor pattern: the `href` of an `<a>` tag, with an optional
+/// `#symbol` fragment. The value may be quoted or bare, and other
+/// attributes may come before or after it.
+const HTML_ANCHOR_PATTERN: &str =
+    r#"(?i)<a\s[^>]*?\bhref\s*=\s*["']?(?P<target>[^"'#\s>]+)(?:#(?P<symbol>[^"'\s>]+))?["']?[^>]*>"#;
+
+/// Markdown link pattern: text, target path, optional `#symbol` fragment,
+/// and an optional quoted title carrying `docref:` options.
+const LINK_PATTERN: &str = r#"\[[^\]]+\]\((?P<target>[^)#\s]+)(?:#(?P<symbol>[^)\s]+))?(?:\s+"(?P<title>[^"]*)")?\)"#;
+
+/// Fragment suffixes that pick a hash mode, as in `#add@doc`.
+const MODE_SUFFIXES: [(&str, HashMode); 2] = [("@doc", HashMode::Docs), ("@exists", HashMode::Exists)];
+
+/// Org link pattern: optional `file:` prefix, target path, optional
+/// `#symbol` fragment, and optional `[description]`.
+const ORG_LINK_PATTERN: &str = r"\[\[(?:file:)?(?P<target>[^\]\[#\s]+)(?:#(?P<symbol>[^\]\[\s]+))?\](?:\[[^\]]*\])?\]";
+
+/// Reference-style link definition pattern: `[ref]: path#symbol`.
+///
+/// The destination may be wrapped in `<...>` and followed by a quoted
+/// title. The `[text][ref]` links that use it need no tracking of their own.
+const REFERENCE_DEFINITION_PATTERN: &str =
+    r#"^ {0,3}\[[^\]]+\]:\s*<?(?P<target>[^\s<>#]+)(?:#(?P<symbol>[^\s<>]+))?>?(?:\s+"(?P<title>[^"]*)")?\s*$"#;
+
+/// Shortcode pattern, with `{name}` replaced by the configured shortcode:
+/// Hugo `{{< name "path#symbol" >}}` or `{{% ... %}}`, and Jekyll
+/// `{% name "path#symbol" %}`. Single or double quotes.
+const SHORTCODE_PATTERN: &str =
+    r#"(?:\{\{[<%]|\{%)\s*{name}\s+["'](?P<target>[^"'#\s]+)(?:#(?P<symbol>[^"'\s]+))?["']\s*(?:[>%]\}\}|%\})"#;
+
+/// Wiki link pattern: target path, optional `#symbol` fragment, and
+/// optional `|display text`.
+const WIKI_LINK_PATTERN: &str = r"\[\[(?P<target>[^\]|#]+)(?:#(?P<symbol>[^\]|]+))?(?:\|[^\]]*)?\]\]";
+
+/// Options parsed from a link title annotation.
+struct LinkOptions {
+    /// The link asserts that its symbol no longer exists.
+    absent: bool,
+    /// Which part of the symbol the hash covers.
+    mode: HashMode,
+}
+
+/// One link syntax the scanner recognizes.
+struct LinkPattern {
+    /// Regex with a `target` group and optional `symbol` and `title` groups.
+    regex: Regex,
+    /// Targets resolve from the project root rather than the markdown file's directory.
+    root_relative: bool,
+    /// Extension of the only documents the syntax applies to, such as `org`.
+    source_extension: Option<&'static str>,
+}
+
+/// The project a scan runs against, used to check bare-link targets on disk.
+struct Project<'a> {
+    /// Loaded configuration, for resolving namespaced targets.
+    config: &'a Config,
+    /// Project root that resolved targets are relative to.
+    root: &'a Path,
+}
+
+/// Append `line` to `out` with its JSX expressions blanked, tracking how
+/// deep `{...}` nesting is across lines in `depth`. ESM lines are blanked
+/// whole, and braces inside inline code don't count.
+fn blank_mdx_line(line: &str, is_esm: bool, depth: &mut usize, out: &mut String) {
+    let mut in_code = false;
+    for c in line.chars() {
+        in_code ^= c == '`' && *depth == 0;
+        if c == '{' && !in_code {
+            *depth = depth.saturating_add(1);
+        }
+        let blank = (is_esm || *depth > 0) && c != '\n';
+        if c == '}' && !in_code {
+            *depth = depth.saturating_sub(1);
+        }
+        if blank {
+            out.push_str(&" ".repeat(c.len_utf8()));
+        } else {
+            out.push(c);
+        }
+    }
+}
 
 /// Extract all `[text](path#symbol)` references from markdown content.
+/// Returns how many references were found.
 fn extract_references_from_markdown_content(
     content: &str,
     source: &Path,
-    pattern: &Regex,
+    project: &Project<'_>,
+    patterns: &[LinkPattern],
     grouped: &mut HashMap<PathBuf, Vec<Reference>>,
-) {
+) -> usize {
+    let content = if source.extension().is_some_and(|ext| return ext == "mdx") {
+        Cow::Owned(mdx_markdown(content))
+    } else {
+        Cow::Borrowed(content)
+    };
+    let mut found: usize = 0;
     for (idx, line) in content.lines().enumerate() {
         let line_number = u32::try_from(idx).unwrap_or(u32::MAX).saturating_add(1);
-        extract_references_from_markdown_line(line, line_number, source, pattern, grouped);
+        let count = extract_references_from_markdown_line(line, line_number, source, project, patterns, grouped);
+        found = found.saturating_add(count);
     }
+    return found;
 }
 
 /// Extract references from a single markdown line.
+/// Returns how many references were found.
 fn extract_references_from_markdown_line(
     line: &str,
     line_number: u32,
     source: &Path,
-    pattern: &Regex,
+    project: &Project<'_>,
+    patterns: &[LinkPattern],
     grouped: &mut HashMap<PathBuf, Vec<Reference>>,
-) {
-    for cap in pattern.captures_iter(line) {
-        let Some(reference) = parse_markdown_link_capture(&cap, source, line_number) else {
-            continue;
+) -> usize {
+    let mut found: usize = 0;
+    let captures = patterns
+        .iter()
+        .filter(|p| return p.source_extension.is_none_or(|ext| return source.extension() == Some(OsStr::new(ext))))
+        .flat_map(|p| return p.regex.captures_iter(line).map(move |cap| return (p, cap)));
+    for (pattern, cap) in captures {
+        for reference in parse_markdown_link_capture(&cap, pattern, source, line_number, project) {
+            let target = reference.target.clone();
+            grouped.entry(target).or_default().push(reference);
+            found = found.saturating_add(1);
+        }
+    }
+    return found;
+}
+
+/// The on-disk spelling of `relative` under `root`, when the path exists
+/// only with different letter case.
+///
+/// Each component is looked up in its directory listing, so a mismatch is
+/// found both on case-insensitive filesystems (where the path opens anyway)
+/// and on case-sensitive ones (where it doesn't). Returns `None` when the
+/// spelling already matches, nothing matches in any case, or the path isn't
+/// a plain relative path under `root`.
+pub fn find_case_mismatch(root: &Path, relative: &Path) -> Option<PathBuf> {
+    let mut on_disk = PathBuf::new();
+    let mut differs = false;
+    for component in relative.components() {
+        let Component::Normal(name) = component else {
+            return None;
         };
-        let target = reference.target.clone();
-        grouped.entry(target).or_default().push(reference);
+        let actual = on_disk_name(&root.join(&on_disk), name)?;
+        differs = differs || actual != name;
+        on_disk.push(actual);
     }
+    return differs.then_some(on_disk);
+}
+
+/// Split a mode suffix off a fragment and its `span`: `add@doc` is `add`
+/// hashed with its doc comments, and `add@exists` only asserts that `add`
+/// exists. Without a suffix, the link's `mode` applies.
+fn fragment_mode(fragment: &str, span: Range<usize>, mode: HashMode) -> (&str, HashMode, Range<usize>) {
+    let suffixed = MODE_SUFFIXES.iter().find_map(|&(suffix, suffix_mode)| {
+        let name = fragment.strip_suffix(suffix).filter(|name| return !name.is_empty())?;
+        return Some((name, suffix_mode));
+    });
+    let Some((name, suffix_mode)) = suffixed else {
+        return (fragment, mode, span);
+    };
+    return (name, suffix_mode, span.start..span.start.saturating_add(name.len()));
+}
+
+/// Whether a bare file link should be tracked as a whole-file reference.
+///
+/// Files with a tree-sitter grammar are always tracked. Anything else is
+/// hashed as raw content, so it is only tracked when the link names an
+/// existing UTF-8 file — links to images, directories, or pages that don't
+/// exist on disk are skipped.
+fn is_trackable_whole_file(target: &Path, project: &Project<'_>) -> bool {
+    if grammar::language_for_path(target, &project.config.grammars).is_ok() {
+        return true;
+    }
+    let Ok(disk_path) = project.config.resolve_target(target) else {
+        return false;
+    };
+    return std::fs::read_to_string(project.root.join(disk_path)).is_ok();
+}
+
+/// Compile the link syntaxes enabled by `config`.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if a pattern fails to compile.
+fn link_patterns(config: &Config) -> Result<Vec<LinkPattern>, Error> {
+    let compile = |pattern: &str, root_relative: bool| {
+        return Regex::new(pattern)
+            .map(|regex| return LinkPattern { regex, root_relative, source_extension: None })
+            .map_err(|e| return Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)));
+    };
+    let mut patterns = vec![
+        compile(LINK_PATTERN, false)?,
+        compile(REFERENCE_DEFINITION_PATTERN, false)?,
+        compile(HTML_ANCHOR_PATTERN, false)?,
+    ];
+    patterns.push(LinkPattern { source_extension: Some("org"), ..compile(ORG_LINK_PATTERN, false)? });
+    if !config.shortcode.is_empty() {
+        let shortcode = SHORTCODE_PATTERN.replace("{name}", &regex::escape(&config.shortcode));
+        patterns.push(compile(&shortcode, false)?);
+    }
+    if config.wiki_links {
+        // Obsidian resolves wiki link paths from the vault root.
+        patterns.push(compile(WIKI_LINK_PATTERN, true)?);
+    }
+    return Ok(patterns);
+}
+
+/// The query a link's `#fragment` asks for; an empty one is whole-file.
+/// `absent` wraps it in an absence assertion, except for line ranges.
+fn link_query(fragment: &str, absent: bool) -> SymbolQuery {
+    if fragment.is_empty() {
+        return SymbolQuery::WholeFile;
+    }
+    return match parse_symbol_fragment_as_query(fragment) {
+        query @ SymbolQuery::Lines { .. } => query,
+        query if absent => SymbolQuery::Absent(Box::new(query)),
+        query => query,
+    };
+}
+
+/// Every markdown file under `root` that the config's include/exclude
+/// filters let through, relative to `root`.
+pub fn markdown_files(root: &Path, config: &Config) -> Vec<PathBuf> {
+    return WalkDir::new(root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| return config.is_markdown(e.path()))
+        .filter_map(|e| return e.path().strip_prefix(root).ok().map(Path::to_path_buf))
+        .filter(|relative| return config.should_scan(&relative.to_string_lossy()))
+        .collect();
+}
+
+/// MDX content with its JavaScript blanked to spaces.
+///
+/// `import` and `export` statements and `{...}` expressions would otherwise
+/// yield false references. Fenced code blocks are left alone, and byte
+/// offsets and line numbers are unchanged.
+fn mdx_markdown(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut depth: usize = 0;
+    let mut in_fence = false;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let is_fence = depth == 0 && (trimmed.starts_with("```") || trimmed.starts_with("~~~"));
+        in_fence ^= is_fence;
+        if in_fence || is_fence {
+            out.push_str(line);
+            continue;
+        }
+        let is_esm = depth == 0 && (line.starts_with("import ") || line.starts_with("export "));
+        blank_mdx_line(line, is_esm, &mut depth, &mut out);
+    }
+    return out;
 }
 
 /// Collapse `.` and `..` components in a path without touching the filesystem.
 ///
 /// Preserves leading `..` when there is nothing left to pop.
-fn normalize_path(path: &Path) -> PathBuf {
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
     let mut components: Vec<std::path::Component<'_>> = Vec::new();
     for component in path.components() {
         push_normalized_component(&mut components, component);
@@ -55,54 +290,132 @@ fn normalize_path(path: &Path) -> PathBuf {
     return components.iter().collect();
 }
 
-/// Try to parse a regex capture into a local code reference.
+/// The entry of `dir` spelled exactly `name`, or else the one equal to it
+/// ignoring ASCII case.
+fn on_disk_name(dir: &Path, name: &OsStr) -> Option<OsString> {
+    let names: Vec<OsString> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| return entry.file_name())
+        .collect();
+    if names.iter().any(|candidate| return candidate == name) {
+        return Some(name.to_os_string());
+    }
+    let wanted = name.to_string_lossy();
+    return names.into_iter().find(|candidate| return candidate.to_string_lossy().eq_ignore_ascii_case(&wanted));
+}
+
+/// Parse a line-range fragment such as `L40-L75`, or `L40` for one line.
+pub fn parse_line_range(fragment: &str) -> Option<SymbolQuery> {
+    let line = |part: &str| {
+        let digits = part.strip_prefix('L').filter(|d| return d.bytes().all(|b| return b.is_ascii_digit()))?;
+        return digits.parse::<u32>().ok().filter(|&n| return n > 0);
+    };
+    let (first, last) = fragment.split_once('-').unwrap_or((fragment, fragment));
+    let (start, end) = (line(first)?, line(last)?);
+    return (start <= end).then_some(SymbolQuery::Lines { end, start });
+}
+
+/// Try to parse a regex capture into local code references: one per
+/// symbol, so a `#{add,sub}` set yields two.
 ///
-/// Returns `None` for external URLs, empty targets, or bare links to
-/// files without a tree-sitter grammar.
-fn parse_markdown_link_capture(cap: &Captures<'_>, source: &Path, line_number: u32) -> Option<Reference> {
-    let raw_target = &cap[2];
+/// Returns nothing for external URLs, empty targets, or bare links that
+/// `is_trackable_whole_file` rejects.
+fn parse_markdown_link_capture(
+    cap: &Captures<'_>,
+    pattern: &LinkPattern,
+    source: &Path,
+    line_number: u32,
+    project: &Project<'_>,
+) -> Vec<Reference> {
+    let target_match = cap.name("target");
+    let raw_target = target_match.map_or("", |m| return m.as_str()).trim();
 
     if raw_target.contains("://") || raw_target.is_empty() {
-        return None;
-    }
-
-    let symbol = match cap.get(3) {
-        Some(m) if !m.as_str().is_empty() => parse_symbol_fragment_as_query(m.as_str()),
-        _ => {
-            // Bare file link — only track if a grammar exists for the target.
-            if grammar::language_for_path(Path::new(raw_target)).is_err() {
-                return None;
-            }
-            SymbolQuery::WholeFile
-        },
-    };
+        return Vec::new();
+    }
+
+    let options = parse_title_options(cap.name("title").map_or("", |m| return m.as_str()), project.config.hash_mode);
+    let target_span = target_match.map_or(0..0, |m| {
+        let start = m.as_str().find(raw_target).unwrap_or(0).saturating_add(m.start());
+        return start..start.saturating_add(raw_target.len());
+    });
+    let fragments =
+        cap.name("symbol").map_or_else(|| return vec![("", 0..0)], |m| return symbol_set(m.as_str(), m.start()));
 
     // Namespaced reference: store as-is (resolved later through Config).
     let is_namespaced = raw_target.contains(':');
     let target = if is_namespaced {
         PathBuf::from(raw_target)
+    } else if pattern.root_relative {
+        vault_path(project.root, normalize_path(Path::new(raw_target)))
     } else {
         let source_dir = source.parent().unwrap_or(Path::new(""));
         normalize_path(&source_dir.join(raw_target))
     };
 
-    return Some(Reference {
-        source: source.to_path_buf(),
-        source_line: line_number,
-        symbol,
-        target,
-    });
+    return fragments
+        .into_iter()
+        .map(|(fragment, span)| return fragment_mode(fragment, span, options.mode))
+        .map(|(fragment, mode, span)| return (link_query(fragment, options.absent), mode, span))
+        .filter(|(symbol, ..)| {
+            return !matches!(symbol, SymbolQuery::WholeFile) || is_trackable_whole_file(&target, project);
+        })
+        .map(|(symbol, mode, symbol_span)| {
+            return Reference {
+                mode,
+                source: source.to_path_buf(),
+                source_line: line_number,
+                symbol,
+                symbol_span,
+                target: target.clone(),
+                target_span: target_span.clone(),
+            };
+        })
+        .collect();
 }
 
-/// Parse a symbol fragment into bare or dot-scoped form.
+/// Parse a symbol fragment into bare, dot-scoped, wildcard, or line-range form.
 fn parse_symbol_fragment_as_query(raw: &str) -> SymbolQuery {
+    if let Some(lines) = parse_line_range(raw) {
+        return lines;
+    }
+    let raw = symbol_path(raw);
+    if let Some(parent) = raw.strip_suffix(".*").filter(|parent| return !parent.is_empty()) {
+        return SymbolQuery::Members(parent.to_string());
+    }
     if let Some((parent, child)) = raw.split_once('.') {
         return SymbolQuery::Scoped {
             child: child.to_string(),
             parent: parent.to_string(),
         };
     }
-    return SymbolQuery::Bare(raw.to_string());
+    return SymbolQuery::Bare(raw);
+}
+
+/// Read `docref:` options from a link title such as `"docref: sig"`.
+///
+/// Titles without the `docref:` prefix are ordinary tooltips. Unrecognized
+/// options are ignored so annotations from newer versions don't break
+/// older ones; when several modes are given, the last one wins.
+fn parse_title_options(title: &str, default_mode: HashMode) -> LinkOptions {
+    let mut options = LinkOptions {
+        absent: false,
+        mode: default_mode,
+    };
+    let Some(raw) = title.trim().strip_prefix("docref:") else {
+        return options;
+    };
+    for option in raw.split(|c: char| return c == ',' || c.is_whitespace()) {
+        match option {
+            "absent" => options.absent = true,
+            "docs" => options.mode = HashMode::Docs,
+            "exists" => options.mode = HashMode::Exists,
+            "sig" | "signature" => options.mode = HashMode::Signature,
+            _ => {},
+        }
+    }
+    return options;
 }
 
 /// Handle a single path component during normalization.
@@ -135,28 +448,109 @@ fn push_normalized_component<'a>(
 ///
 /// Returns `Error::Io` if any markdown file cannot be read.
 pub fn scan(root: &Path, config: &Config) -> Result<HashMap<PathBuf, Vec<Reference>>, Error> {
-    let pattern = Regex::new(r"\[([^\]]+)\]\(([^)#]+)(?:#([^)]+))?\)")
-        .map_err(|e| return Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+    return scan_with_progress(root, config, &mut |_, _| {});
+}
+
+/// Extract the references in one markdown document, such as an editor
+/// buffer that hasn't been saved. `source` is the document's path relative
+/// to `root`. References are returned in document order.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if a link pattern fails to compile.
+pub fn scan_content(root: &Path, config: &Config, source: &Path, content: &str) -> Result<Vec<Reference>, Error> {
+    let patterns = link_patterns(config)?;
     let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+    let project = Project { config, root };
+    extract_references_from_markdown_content(content, source, &project, &patterns, &mut grouped);
+    let mut references: Vec<Reference> = grouped.into_values().flatten().collect();
+    references.sort_by_key(|r| return (r.source_line, r.target_span.start));
+    return Ok(references);
+}
 
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|e| return e.path().extension().is_some_and(|ext| return ext == "md"))
-    {
-        let md_path = entry.path();
-        let relative_source = md_path.strip_prefix(root).unwrap_or(md_path).to_path_buf();
+/// Like [`scan`], but calls `on_file` with each scanned markdown file (relative
+/// to `root`) and the number of references found in it.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if any markdown file cannot be read.
+pub fn scan_with_progress(
+    root: &Path,
+    config: &Config,
+    on_file: &mut dyn FnMut(&Path, usize),
+) -> Result<HashMap<PathBuf, Vec<Reference>>, Error> {
+    let patterns = link_patterns(config)?;
+    let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+    let project = Project { config, root };
+
+    for relative_source in markdown_files(root, config) {
+        let content = std::fs::read_to_string(root.join(&relative_source))?;
+        let found =
+            extract_references_from_markdown_content(&content, &relative_source, &project, &patterns, &mut grouped);
+        tracing::trace!(file = %relative_source.display(), references = found, "scanned markdown");
+        on_file(&relative_source, found);
+    }
 
-        let relative_str = relative_source.to_string_lossy();
-        if !config.should_scan(&relative_str) {
-            continue;
-        }
+    return Ok(grouped);
+}
+
+/// The dotted symbol path a link fragment names. A JSON pointer such as
+/// `/scripts/build` becomes `scripts.build`, with `~1` and `~0` unescaped;
+/// anything else is already a path.
+pub fn symbol_path(fragment: &str) -> String {
+    let Some(pointer) = fragment.strip_prefix('/') else {
+        return fragment.to_string();
+    };
+    return pointer
+        .split('/')
+        .map(|token| return token.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".");
+}
 
-        let content = std::fs::read_to_string(md_path)?;
-        extract_references_from_markdown_content(&content, &relative_source, &pattern, &mut grouped);
+/// Split a `#{add,sub,mul}` fragment into its symbols, each with its byte
+/// span in the line; `start` is where the fragment begins. Any other
+/// fragment is a set of one.
+fn symbol_set(fragment: &str, start: usize) -> Vec<(&str, Range<usize>)> {
+    let span = |offset: usize, text: &str| {
+        let begin = start.saturating_add(offset);
+        return begin..begin.saturating_add(text.len());
+    };
+    let Some(inner) = fragment.strip_prefix('{').and_then(|rest| return rest.strip_suffix('}')) else {
+        return vec![(fragment, span(0, fragment))];
+    };
+    let mut offset = 1_usize;
+    let mut symbols = Vec::new();
+    for symbol in inner.split(',') {
+        if !symbol.is_empty() {
+            symbols.push((symbol, span(offset, symbol)));
+        }
+        offset = offset.saturating_add(symbol.len()).saturating_add(1);
     }
+    return symbols;
+}
 
-    return Ok(grouped);
+/// Where a wiki link's path points.
+///
+/// Obsidian writes the shortest path that is unique in the vault, so a path
+/// that doesn't exist from the root is matched against the trailing
+/// components of every file. It is kept as written when no file or more
+/// than one matches.
+fn vault_path(root: &Path, path: PathBuf) -> PathBuf {
+    if root.join(&path).exists() {
+        return path;
+    }
+    let mut matches = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| return e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(Result::ok)
+        .filter(|e| return !e.file_type().is_dir())
+        .filter_map(|e| return e.path().strip_prefix(root).ok().map(Path::to_path_buf))
+        .filter(|candidate| return candidate.ends_with(&path));
+    return match (matches.next(), matches.next()) {
+        (Some(found), None) => found,
+        _ => path,
+    };
 }
 
 #[cfg(test)]
@@ -164,17 +558,24 @@ pub fn scan(root: &Path, config: &Config) -> Result<HashMap<PathBuf, Vec<Referen
 mod tests {
     use super::*;
 
-    fn test_pattern() -> Regex {
-        return Regex::new(r"\[([^\]]+)\]\(([^)#]+)(?:#([^)]+))?\)").unwrap();
+    fn empty_config() -> Config {
+        let tmp = tempfile::TempDir::new().unwrap();
+        return Config::load(tmp.path()).unwrap();
+    }
+
+    fn test_pattern() -> Vec<LinkPattern> {
+        return link_patterns(&empty_config()).unwrap();
     }
 
     #[test]
     fn non_namespaced_resolves_relative_to_markdown() {
         let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
         let source = Path::new("docs/guide.md");
         let line = "See [`add`](../src/lib.rs#add) for details.";
         let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        extract_references_from_markdown_line(line, 1, source, &pattern, &mut grouped);
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
 
         let refs: Vec<&Reference> = grouped.values().flatten().collect();
         assert_eq!(refs.len(), 1);
@@ -185,10 +586,12 @@ mod tests {
     #[test]
     fn parses_namespaced_reference() {
         let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
         let source = Path::new("docs/guide.md");
         let line = "See [`validate`](auth:src/lib.rs#validate) for details.";
         let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        extract_references_from_markdown_line(line, 7, source, &pattern, &mut grouped);
+        extract_references_from_markdown_line(line, 7, source, &project, &pattern, &mut grouped);
 
         let refs: Vec<&Reference> = grouped.values().flatten().collect();
         assert_eq!(refs.len(), 1);
@@ -196,13 +599,43 @@ mod tests {
         assert_eq!(refs[0].source_line, 7);
     }
 
+    #[test]
+    fn plain_text_bare_link_is_tracked_when_file_exists() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("fixtures")).unwrap();
+        std::fs::write(tmp.path().join("fixtures/data.csv"), "a,b\n1,2\n").unwrap();
+        let config = Config::load(tmp.path()).unwrap();
+        let project = Project { config: &config, root: tmp.path() };
+
+        let pattern = test_pattern();
+        let source = Path::new("docs/guide.md");
+        let line = "See [data](../fixtures/data.csv) and [missing](../fixtures/gone.csv).";
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
+
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        assert_eq!(refs.len(), 1);
+        let reference = refs.first().unwrap();
+        assert_eq!(reference.target, PathBuf::from("fixtures/data.csv"));
+        assert!(matches!(reference.symbol, SymbolQuery::WholeFile));
+    }
+
+    #[test]
+    fn json_pointer_fragments_become_dotted_paths() {
+        assert_eq!(symbol_path("/scripts/build"), "scripts.build");
+        assert_eq!(symbol_path("/a~1b/c~0d"), "a/b.c~d");
+        assert_eq!(symbol_path("Config.validate"), "Config.validate");
+    }
+
     #[test]
     fn whole_file_link_produces_whole_file_query() {
         let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
         let source = Path::new("docs/guide.md");
         let line = "See [core library](../src/lib.rs) for details.";
         let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        extract_references_from_markdown_line(line, 3, source, &pattern, &mut grouped);
+        extract_references_from_markdown_line(line, 3, source, &project, &pattern, &mut grouped);
 
         let refs: Vec<&Reference> = grouped.values().flatten().collect();
         assert_eq!(refs.len(), 1);
@@ -210,13 +643,162 @@ mod tests {
         assert!(matches!(refs[0].symbol, SymbolQuery::WholeFile));
     }
 
+    #[test]
+    fn link_title_sets_hash_mode() {
+        let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let source = Path::new("docs/guide.md");
+        let line = r#"See [add](../src/lib.rs#add "docref: sig") and [A](../src/lib.rs#A "the constant")."#;
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
+
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].symbol.display_name(), "add");
+        assert_eq!(refs[0].mode, HashMode::Signature);
+        assert_eq!(refs[1].symbol.display_name(), "A");
+        assert_eq!(refs[1].mode, HashMode::Body);
+    }
+
+    #[test]
+    fn absent_title_wraps_symbol_in_absence_assertion() {
+        let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let source = Path::new("docs/migration.md");
+        let line = r#"The [`legacy`](../src/lib.rs#legacy "docref: absent") helper was removed."#;
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
+
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        assert_eq!(refs.len(), 1);
+        assert!(matches!(&refs[0].symbol, SymbolQuery::Absent(inner) if inner.display_name() == "legacy"));
+    }
+
+    #[test]
+    fn wiki_links_resolve_from_root_when_enabled() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "wiki_links = true\n").unwrap();
+        let config = Config::load(tmp.path()).unwrap();
+        let project = Project { config: &config, root: tmp.path() };
+        let source = Path::new("vault/notes/guide.md");
+        let line = "See [[src/lib.rs#add|the add function]] and [[src/lib.rs#Config.validate]].";
+
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, source, &project, &test_pattern(), &mut grouped);
+        assert!(grouped.is_empty(), "wiki links are opt-in");
+
+        let patterns = link_patterns(&config).unwrap();
+        extract_references_from_markdown_line(line, 1, source, &project, &patterns, &mut grouped);
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].target, PathBuf::from("src/lib.rs"));
+        assert_eq!(refs[0].symbol.display_name(), "add");
+        assert_eq!(refs[1].symbol.display_name(), "Config.validate");
+    }
+
+    #[test]
+    fn wiki_links_find_files_by_their_shortest_unique_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "wiki_links = true\n").unwrap();
+        for dir in ["crates/core/src", "crates/cli/src"] {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+            std::fs::write(tmp.path().join(dir).join("lib.rs"), "fn add() {}\n").unwrap();
+        }
+        std::fs::write(tmp.path().join("crates/core/src/config.rs"), "struct Config;\n").unwrap();
+        let config = Config::load(tmp.path()).unwrap();
+        let project = Project { config: &config, root: tmp.path() };
+        let line = "[[config.rs#Config]] [[core/src/lib.rs#add]] [[lib.rs#add]]";
+
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        let patterns = link_patterns(&config).unwrap();
+        extract_references_from_markdown_line(line, 1, Path::new("notes/a.md"), &project, &patterns, &mut grouped);
+        let mut targets: Vec<&Path> = grouped.keys().map(PathBuf::as_path).collect();
+        targets.sort();
+        let expected = ["crates/core/src/config.rs", "crates/core/src/lib.rs", "lib.rs"].map(Path::new);
+        assert_eq!(targets, expected, "an ambiguous name is kept as written");
+    }
+
+    #[test]
+    fn shortcodes_are_scanned_by_configured_name() {
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let source = Path::new("content/guide.md");
+        let line =
+            r#"{{< docref "../src/lib.rs#add" >}} {% docref '../src/lib.rs#A' %} {{< other "../src/lib.rs#B" >}}"#;
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, source, &project, &test_pattern(), &mut grouped);
+
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        let symbols: Vec<String> = refs.iter().map(|r| return r.symbol.display_name()).collect();
+        assert_eq!(symbols, ["add", "A"]);
+        assert_eq!(refs[0].target, PathBuf::from("src/lib.rs"));
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join(".docref.toml"), "shortcode = \"other\"\n").unwrap();
+        let config = Config::load(tmp.path()).unwrap();
+        let project = Project { config: &config, root: Path::new(".") };
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, source, &project, &link_patterns(&config).unwrap(), &mut grouped);
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].symbol.display_name(), "B");
+    }
+
+    #[test]
+    fn html_anchor_href_is_scanned() {
+        let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let source = Path::new("docs/guide.md");
+        let line = r##"| <a class="ref" href="../src/lib.rs#add">add</a> | <A HREF='../src/lib.rs#A'>A</A> | <a href="#usage">usage</a> |"##;
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
+        let unquoted = "| <a href=../src/lib.rs#sub>sub</a> |";
+        extract_references_from_markdown_line(unquoted, 2, source, &project, &pattern, &mut grouped);
+
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        let symbols: Vec<String> = refs.iter().map(|r| return r.symbol.display_name()).collect();
+        assert_eq!(symbols, ["add", "A", "sub"]);
+        assert_eq!(refs[0].target, PathBuf::from("src/lib.rs"));
+    }
+
+    #[test]
+    fn reference_style_definitions_are_scanned() {
+        let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let source = Path::new("docs/guide.md");
+        let lines = [
+            "Call [`add`][add-fn] and [`sub`][].",
+            "[add-fn]: ../src/lib.rs#add",
+            "  [sub]: <../src/lib.rs#sub> \"docref:signature\"",
+            "[site]: https://example.com/docs#intro",
+        ];
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        for (idx, line) in (1..).zip(lines) {
+            extract_references_from_markdown_line(line, idx, source, &project, &pattern, &mut grouped);
+        }
+
+        let mut refs: Vec<&Reference> = grouped.values().flatten().collect();
+        refs.sort_by_key(|r| return r.source_line);
+        let found: Vec<(u32, String, HashMode)> =
+            refs.iter().map(|r| return (r.source_line, r.symbol.display_name(), r.mode)).collect();
+        assert_eq!(found, [(2, "add".to_string(), HashMode::Body), (3, "sub".to_string(), HashMode::Signature)]);
+        assert_eq!(refs[0].target, PathBuf::from("src/lib.rs"));
+        assert_eq!(&lines[1][refs[0].symbol_span.clone()], "add");
+    }
+
     #[test]
     fn https_url_is_skipped() {
         let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
         let source = Path::new("docs/guide.md");
         let line = "See [docs](https://example.com) for details.";
         let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        extract_references_from_markdown_line(line, 1, source, &pattern, &mut grouped);
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
 
         let refs: Vec<&Reference> = grouped.values().flatten().collect();
         assert_eq!(refs.len(), 0);
@@ -225,12 +807,14 @@ mod tests {
     #[test]
     fn quoted_url_is_skipped() {
         let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
         let source = Path::new("docs/guide.md");
         // Some markdown contains URLs with surrounding quotes or other characters
         // that defeat a starts_with("https://") check.
         let line = r#"See [docs]("https://example.com") for details."#;
         let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        extract_references_from_markdown_line(line, 1, source, &pattern, &mut grouped);
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
 
         let refs: Vec<&Reference> = grouped.values().flatten().collect();
         assert_eq!(refs.len(), 0);
@@ -239,12 +823,110 @@ mod tests {
     #[test]
     fn unsupported_extension_bare_link_is_skipped() {
         let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
         let source = Path::new("docs/guide.md");
         let line = "See [photo](./photo.png) for details.";
         let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        extract_references_from_markdown_line(line, 1, source, &pattern, &mut grouped);
+        extract_references_from_markdown_line(line, 1, source, &project, &pattern, &mut grouped);
 
         let refs: Vec<&Reference> = grouped.values().flatten().collect();
         assert_eq!(refs.len(), 0);
     }
+
+    #[test]
+    fn org_links_are_scanned_only_in_org_documents() {
+        let pattern = test_pattern();
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let line = "See [[../src/lib.rs#add][the add function]] and [[file:../src/lib.rs#Config.validate]].";
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        extract_references_from_markdown_line(line, 1, Path::new("docs/guide.md"), &project, &pattern, &mut grouped);
+        assert!(grouped.is_empty());
+
+        extract_references_from_markdown_line(line, 1, Path::new("docs/notes.org"), &project, &pattern, &mut grouped);
+        let refs: Vec<&Reference> = grouped.values().flatten().collect();
+        assert_eq!(refs.len(), 2);
+        assert!(refs.iter().all(|r| return r.target == Path::new("src/lib.rs")));
+        assert!(refs.iter().any(|r| return matches!(&r.symbol, SymbolQuery::Bare(name) if name == "add")));
+    }
+
+    #[test]
+    fn line_range_fragments_parse_as_lines() {
+        assert!(matches!(parse_line_range("L40-L75"), Some(SymbolQuery::Lines { end: 75, start: 40 })));
+        assert!(matches!(parse_line_range("L7"), Some(SymbolQuery::Lines { end: 7, start: 7 })));
+        for fragment in ["L75-L40", "L0", "L+3", "Line", "l40", "L40-"] {
+            assert!(parse_line_range(fragment).is_none(), "{fragment}");
+        }
+        assert!(matches!(parse_symbol_fragment_as_query("L40-L75"), SymbolQuery::Lines { .. }));
+    }
+
+    #[test]
+    fn symbol_sets_yield_one_reference_per_symbol() {
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let line = "The [operators](../src/lib.rs#{add,sub,Ops.mul}) share a signature.";
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        let (source, patterns) = (Path::new("docs/math.md"), test_pattern());
+        let found = extract_references_from_markdown_line(line, 1, source, &project, &patterns, &mut grouped);
+
+        assert_eq!(found, 3);
+        let refs = grouped.get(Path::new("src/lib.rs")).unwrap();
+        let fragments: Vec<&str> = refs.iter().map(|r| return line.get(r.symbol_span.clone()).unwrap()).collect();
+        assert_eq!(fragments, ["add", "sub", "Ops.mul"]);
+        assert!(matches!(&refs[2].symbol, SymbolQuery::Scoped { parent, .. } if parent == "Ops"));
+    }
+
+    #[test]
+    fn doc_suffix_opts_a_link_into_doc_hashing() {
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let line = "See [add](../src/lib.rs#add@doc) and [sub](../src/lib.rs#sub).";
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        let (source, patterns) = (Path::new("docs/math.md"), test_pattern());
+        extract_references_from_markdown_line(line, 1, source, &project, &patterns, &mut grouped);
+
+        let refs = grouped.get(Path::new("src/lib.rs")).unwrap();
+        assert!(matches!(&refs[0].symbol, SymbolQuery::Bare(name) if name == "add"));
+        assert_eq!(line.get(refs[0].symbol_span.clone()), Some("add"));
+        assert_eq!((refs[0].mode, refs[1].mode), (HashMode::Docs, HashMode::Body));
+    }
+
+    #[test]
+    fn exists_suffix_and_title_track_existence_only() {
+        let config = empty_config();
+        let project = Project { config: &config, root: Path::new(".") };
+        let line = r#"[add](../src/lib.rs#add@exists) and [sub](../src/lib.rs#sub "docref: exists")."#;
+        let mut grouped: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+        let (source, patterns) = (Path::new("docs/math.md"), test_pattern());
+        extract_references_from_markdown_line(line, 1, source, &project, &patterns, &mut grouped);
+
+        let refs = grouped.get(Path::new("src/lib.rs")).unwrap();
+        assert_eq!(line.get(refs[0].symbol_span.clone()), Some("add"));
+        assert_eq!((refs[0].mode, refs[1].mode), (HashMode::Exists, HashMode::Exists));
+    }
+
+    #[test]
+    fn wildcard_fragments_parse_as_members() {
+        let config = parse_symbol_fragment_as_query("Config.*");
+        assert!(matches!(config, SymbolQuery::Members(parent) if parent == "Config"));
+        let pointer = parse_symbol_fragment_as_query("/net/Server/*");
+        assert!(matches!(pointer, SymbolQuery::Members(parent) if parent == "net.Server"));
+        assert!(matches!(parse_symbol_fragment_as_query(".*"), SymbolQuery::Scoped { .. }));
+    }
+
+    #[test]
+    fn mdx_javascript_is_blanked_in_place() {
+        let content = "import Tabs from '@theme/Tabs';\n\
+                       {/* [old](../src/lib.rs#old) */}\n\
+                       See `{` and [add](../src/lib.rs#add) in <Tabs values={[{ a: 1 }]}>.\n\
+                       ```js\nconst x = {\n```\n";
+        let blanked = mdx_markdown(content);
+
+        assert_eq!(blanked.len(), content.len());
+        assert_eq!(blanked.lines().count(), content.lines().count());
+        assert!(!blanked.contains("import") && !blanked.contains("old") && !blanked.contains("a: 1"));
+        assert!(blanked.contains("See `{` and [add](../src/lib.rs#add) in <Tabs values="), "{blanked}");
+        assert!(blanked.contains("const x = {\n```"), "fenced code should be kept: {blanked}");
+    }
 }
@@ -0,0 +1,132 @@
+//! HTTP server for `docref serve`.
+//!
+//! Serves the `status` and `check` JSON reports under `/api/`, and a
+//! dashboard of freshness per markdown file at `/`.
+//!
+//! Every request re-checks the project, so the server can run for as long
+//! as a docs box is up. The hash cache keeps repeated checks cheap.
+
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::commands;
+use crate::error::Error;
+
+/// The dashboard page. It renders `/api/status` as one row per markdown
+/// file and refreshes every 30 seconds.
+const DASHBOARD: &str = r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>docref freshness</title>
+<style>
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3rem 0.8rem; text-align: right; border-bottom: 1px solid #ddd; }
+th:first-child, td:first-child { text-align: left; }
+.bad { color: #b00020; font-weight: bold; }
+.warn { color: #a15c00; }
+</style>
+</head>
+<body>
+<h1>docref freshness</h1>
+<p id="summary">Loading…</p>
+<table>
+<thead><tr>
+<th>Doc</th><th>Fresh</th><th>Stale</th><th>Broken</th><th>Ambiguous</th><th>Orphaned</th><th>% fresh</th>
+</tr></thead>
+<tbody id="docs"></tbody>
+</table>
+<script>
+const cell = (value, cls) => {
+  const td = document.createElement("td");
+  td.textContent = value;
+  if (value && cls) td.className = cls;
+  return td;
+};
+async function refresh() {
+  try {
+    const response = await fetch("/api/status");
+    const report = await response.json();
+    if (!response.ok) throw new Error(report.error);
+    const s = report.summary;
+    document.getElementById("summary").textContent =
+      `${s.percent_fresh}% of ${s.total} references fresh: ${s.stale} stale, ${s.broken} broken, ` +
+      `${s.ambiguous} ambiguous, ${s.orphaned} orphaned. Updated ${new Date().toLocaleTimeString()}.`;
+    const rows = s.sources.map((doc) => {
+      const tr = document.createElement("tr");
+      tr.append(cell(doc.source), cell(doc.fresh), cell(doc.stale, "warn"), cell(doc.broken, "bad"),
+        cell(doc.ambiguous, "bad"), cell(doc.orphaned, "warn"), cell(`${doc.percent_fresh}%`));
+      return tr;
+    });
+    document.getElementById("docs").replaceChildren(...rows);
+  } catch (error) {
+    document.getElementById("summary").textContent = `Could not load status: ${error.message}`;
+  }
+}
+refresh();
+setInterval(refresh, 30000);
+</script>
+</body>
+</html>
+"#;
+
+/// Content type of the dashboard.
+const HTML: &str = "text/html; charset=utf-8";
+
+/// Content type of the API's responses.
+const JSON: &str = "application/json";
+
+/// A JSON error body for a failed request.
+fn error_body(message: &str) -> String {
+    return serde_json::json!({ "error": message }).to_string();
+}
+
+/// Turn a report, or the error that prevented it, into a response.
+fn report(outcome: Result<String, Error>) -> (u16, &'static str, String) {
+    return outcome.map_or_else(|e| return (500, JSON, error_body(&e.to_string())), |json| return (200, JSON, json));
+}
+
+/// Answer one request for the project at `root` with a status code,
+/// content type, and body. Query strings are ignored.
+fn route(root: &Path, method: &Method, url: &str) -> (u16, &'static str, String) {
+    if *method != Method::Get {
+        return (405, JSON, error_body("only GET is supported"));
+    }
+    let path = url.split_once('?').map_or(url, |(path, _)| return path);
+    return match path {
+        "/" => (200, HTML, DASHBOARD.to_string()),
+        "/api/check" => report(commands::check_report_json(root)),
+        "/api/status" => report(commands::status_report_json(root)),
+        _ => (404, JSON, error_body(&format!("no such endpoint: {path}"))),
+    };
+}
+
+/// Serve the dashboard and API for the project at `root` on `addr`, such as
+/// `127.0.0.1:7878`, until the process is stopped. Port 0 picks a free port;
+/// the address actually bound is printed to stderr.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if `addr` can't be bound.
+pub fn run(root: &Path, addr: &str) -> Result<ExitCode, Error> {
+    let server = Server::http(addr).map_err(|e| return Error::Io(io::Error::other(e)))?;
+    let bound = server.server_addr().to_ip().map_or_else(|| return addr.to_string(), |ip| return ip.to_string());
+    eprintln!("serve: listening on http://{bound}, press Ctrl+C to stop");
+
+    for request in server.incoming_requests() {
+        let (status, content_type, body) = route(root, request.method(), request.url());
+        tracing::debug!(method = %request.method(), url = request.url(), status, "serving request");
+        let mut response = Response::from_string(body).with_status_code(status);
+        if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+            response = response.with_header(header);
+        }
+        if let Err(e) = request.respond(response) {
+            tracing::debug!(error = %e, "could not send response");
+        }
+    }
+    return Ok(ExitCode::SUCCESS);
+}
@@ -0,0 +1,498 @@
+//! Interactive dashboard for `watch --tui`.
+//!
+//! Shows every lockfile entry with its freshness in a live table that
+//! re-checks on file changes, a detail pane with the referenced code as it
+//! is now, and keys to accept a stale entry (as `update` would) or ignore a
+//! finding by adding it to the baseline.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::baseline::{self, Baseline, BaselineEntry};
+use crate::commands;
+use crate::config;
+use crate::error;
+use crate::freshness::{CheckResult, compare_lockfile_entries_against_source, parse_symbol_query};
+use crate::grammar;
+use crate::hasher;
+use crate::lockfile::{LockEntry, Lockfile};
+use crate::package;
+use crate::resolver;
+use crate::types::SymbolQuery;
+use crate::watch;
+
+/// How long to wait for a key press before checking for file changes again.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Dashboard state: the checked lockfile and what the user is looking at.
+struct Dashboard<'a> {
+    /// Findings ignored so far, from `.docref-baseline.toml`.
+    baseline: Baseline,
+    /// Project configuration, reloaded on every re-check.
+    config: config::Config,
+    /// Which rows the table shows.
+    filter: Filter,
+    /// Outcome of the last action, shown in the footer.
+    message: String,
+    /// Project root.
+    root: &'a Path,
+    /// Every lockfile entry with its latest check result.
+    rows: Vec<(LockEntry, CheckResult)>,
+    /// Selected row, as an index into the visible rows.
+    table: TableState,
+}
+
+impl<'a> Dashboard<'a> {
+    /// Re-hash the selected stale entry in the lockfile, like `docref update`.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from lockfile I/O, resolution, or hashing.
+    fn accept_selected(&mut self) -> Result<String, error::Error> {
+        let Some((entry, result)) = self.selected() else {
+            return Ok("Nothing selected".to_string());
+        };
+        if !matches!(result, CheckResult::Stale) {
+            return Ok(format!("Only stale entries can be accepted, this one is {}", result.name()));
+        }
+        let (target, symbol) = (entry.target.clone(), entry.symbol.clone());
+        let lock_path = self.root.join(".docref.lock");
+        let mut lockfile = Lockfile::read(&lock_path)?;
+        commands::accept_reference(self.root, &self.config, &mut lockfile, &target, &symbol, None)?;
+        lockfile.write(&lock_path)?;
+        self.refresh()?;
+        return Ok(format!("Accepted {}", label(&target, &symbol)));
+    }
+
+    /// Keep the selection on a visible row after the rows or filter change.
+    fn clamp_selection(&mut self) {
+        let visible = self.visible().len();
+        let selected = match (self.table.selected(), visible) {
+            (_, 0) => None,
+            (Some(idx), _) => Some(idx.min(visible.saturating_sub(1))),
+            (None, _) => Some(0),
+        };
+        self.table.select(selected);
+        return;
+    }
+
+    /// Lines for the detail pane: the selected entry and its current code.
+    fn detail_text(&self) -> Text<'static> {
+        let Some((entry, result)) = self.selected() else {
+            return Text::from("No reference selected");
+        };
+        let reason = result.reason().map(|r| return format!(" ({r})")).unwrap_or_default();
+        let mut lines = vec![
+            Line::from(format!("Reference: {}", label(&entry.target, &entry.symbol))),
+            Line::from(format!("Markdown:  {}", entry.source.display())),
+            Line::from(format!("Status:    {}{reason}", result.name())),
+        ];
+        lines.extend(entry.note.as_ref().map(|note| return Line::from(format!("Note:      {note}"))));
+        lines.push(Line::from(""));
+        match current_code(self.root, &self.config, entry) {
+            Some(code) => {
+                lines.push(Line::styled("Current code:", Style::new().add_modifier(Modifier::BOLD)));
+                lines.extend(code.lines().map(|line| return Line::from(line.to_string())));
+            },
+            None => lines.push(Line::from("(code not available)")),
+        }
+        return Text::from(lines);
+    }
+
+    /// Apply one key press. Returns `false` when the user quits.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+            KeyCode::Char('a') => {
+                let outcome = self.accept_selected();
+                self.report(outcome);
+            },
+            KeyCode::Char('f') => {
+                self.filter = self.filter.next();
+                self.clamp_selection();
+            },
+            KeyCode::Char('i') => {
+                let outcome = self.ignore_selected();
+                self.report(outcome);
+            },
+            KeyCode::Char('r') => {
+                let outcome = self.refresh().map(|()| return "Re-checked".to_string());
+                self.report(outcome);
+            },
+            _ => {},
+        }
+        return true;
+    }
+
+    /// Add the selected finding to the baseline, so `check --baseline` stops
+    /// failing on it and the default filter hides it.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from reading or writing the baseline.
+    fn ignore_selected(&mut self) -> Result<String, error::Error> {
+        let Some((entry, result)) = self.selected() else {
+            return Ok("Nothing selected".to_string());
+        };
+        let Some(finding) = result.finding() else {
+            return Ok("Fresh entries have nothing to ignore".to_string());
+        };
+        let ignored = BaselineEntry {
+            finding,
+            source: entry.source.clone(),
+            symbol: entry.symbol.clone(),
+            target: entry.target.clone(),
+        };
+        let message = format!("Ignored {} (added to {})", label(&entry.target, &entry.symbol), baseline::DEFAULT_PATH);
+        let mut entries = read_baseline(self.root)?.entries;
+        entries.push(ignored);
+        self.baseline = Baseline::new(entries);
+        self.baseline.write(&self.root.join(baseline::DEFAULT_PATH))?;
+        self.clamp_selection();
+        return Ok(message);
+    }
+
+    /// Whether a finding is recorded in the baseline.
+    fn is_ignored(&self, entry: &LockEntry, result: &CheckResult) -> bool {
+        return result.finding().is_some_and(|finding| return self.baseline.contains(entry, finding));
+    }
+
+    /// Load and check the project at `root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from config, lockfile, or baseline loading, or hashing.
+    fn new(root: &'a Path) -> Result<Self, error::Error> {
+        let mut dashboard = Self {
+            baseline: Baseline::default(),
+            config: config::Config::load(root)?,
+            filter: Filter::Problems,
+            message: String::new(),
+            root,
+            rows: Vec::new(),
+            table: TableState::default(),
+        };
+        dashboard.refresh()?;
+        return Ok(dashboard);
+    }
+
+    /// Reload config, lockfile, and baseline, then re-check every entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from config, lockfile, or baseline loading, or hashing.
+    fn refresh(&mut self) -> Result<(), error::Error> {
+        self.config = config::Config::load(self.root)?;
+        self.baseline = read_baseline(self.root)?;
+        let lockfile = Lockfile::read(&self.root.join(".docref.lock"))?;
+        let tracked: Vec<&LockEntry> = lockfile.entries.iter().collect();
+        let results = compare_lockfile_entries_against_source(self.root, &self.config, &tracked)?;
+        self.rows = lockfile.entries.into_iter().zip(results).collect();
+        self.clamp_selection();
+        return Ok(());
+    }
+
+    /// Draw the table, the detail pane, and the key help line.
+    fn render(&mut self, frame: &mut Frame<'_>) {
+        let [main, footer] = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [list, detail] =
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(main);
+        self.render_table(frame, list);
+        let detail_pane = Paragraph::new(self.detail_text())
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(" Detail "));
+        frame.render_widget(detail_pane, detail);
+        let help = format!(" j/k move  a accept  i ignore  f filter  r re-check  q quit  {}", self.message);
+        frame.render_widget(Paragraph::new(help), footer);
+        return;
+    }
+
+    /// Draw the reference table with the current filter applied.
+    fn render_table(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let visible = self.visible();
+        let rows: Vec<Row<'_>> = visible
+            .iter()
+            .filter_map(|&idx| return self.rows.get(idx))
+            .map(|row| return self.table_row(row))
+            .collect();
+        let title = format!(" References: {} ({}/{}) ", self.filter.name(), visible.len(), self.rows.len());
+        let widths = [Constraint::Length(19), Constraint::Percentage(40), Constraint::Percentage(60)];
+        let table = Table::new(rows, widths)
+            .header(Row::new(["Status", "Markdown", "Reference"]).style(Style::new().add_modifier(Modifier::BOLD)))
+            .block(Block::bordered().title(title))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.table);
+        return;
+    }
+
+    /// Show the outcome of an action in the footer.
+    fn report(&mut self, outcome: Result<String, error::Error>) {
+        self.message = outcome.unwrap_or_else(|e| return format!("error: {e}"));
+        return;
+    }
+
+    /// The selected entry and its result, if any row is selected.
+    fn selected(&self) -> Option<&(LockEntry, CheckResult)> {
+        let visible = self.visible();
+        let row = visible.get(self.table.selected()?)?;
+        return self.rows.get(*row);
+    }
+
+    /// Whether the current filter shows `row`.
+    fn shows(&self, (entry, result): &(LockEntry, CheckResult)) -> bool {
+        return self.filter.admits(result, self.is_ignored(entry, result));
+    }
+
+    /// One table row: status, markdown source, and reference.
+    fn table_row(&self, (entry, result): &(LockEntry, CheckResult)) -> Row<'static> {
+        let ignored = if self.is_ignored(entry, result) { " (ignored)" } else { "" };
+        return Row::new([
+            Cell::from(format!("{}{ignored}", result.name())).style(status_style(result)),
+            Cell::from(entry.source.display().to_string()),
+            Cell::from(label(&entry.target, &entry.symbol)),
+        ]);
+    }
+
+    /// Indices of the rows the current filter shows.
+    fn visible(&self) -> Vec<usize> {
+        return self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| return self.shows(row))
+            .map(|(idx, _)| return idx)
+            .collect();
+    }
+}
+
+/// Which rows the table shows; `f` cycles through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    /// Every entry, fresh or not.
+    All,
+    /// Broken, ambiguous, and orphaned entries not ignored.
+    Broken,
+    /// Every finding not ignored.
+    Problems,
+    /// Stale entries not ignored.
+    Stale,
+}
+
+impl Filter {
+    /// Whether a row with `result` is shown; `ignored` rows only show under `All`.
+    const fn admits(self, result: &CheckResult, ignored: bool) -> bool {
+        return match self {
+            Self::All => true,
+            Self::Broken => result.finding().is_some() && !matches!(result, CheckResult::Stale) && !ignored,
+            Self::Problems => result.finding().is_some() && !ignored,
+            Self::Stale => matches!(result, CheckResult::Stale) && !ignored,
+        };
+    }
+
+    /// Name shown in the table title.
+    const fn name(self) -> &'static str {
+        return match self {
+            Self::All => "all",
+            Self::Broken => "broken",
+            Self::Problems => "problems",
+            Self::Stale => "stale",
+        };
+    }
+
+    /// The filter `f` switches to.
+    const fn next(self) -> Self {
+        return match self {
+            Self::All => Self::Problems,
+            Self::Broken => Self::All,
+            Self::Problems => Self::Stale,
+            Self::Stale => Self::Broken,
+        };
+    }
+}
+
+/// What woke the event loop.
+enum Input {
+    /// Watched files changed; the project needs re-checking.
+    FilesChanged,
+    /// A key was pressed.
+    Key(KeyCode),
+    /// The terminal was resized or needs drawing again.
+    Redraw,
+}
+
+/// The referenced code as it is on disk now: the symbol's source text, the
+/// lines of a line-range reference, or the whole file for whole-file ones.
+fn current_code(root: &Path, config: &config::Config, entry: &LockEntry) -> Option<String> {
+    let disk_path = package::source_path(root, config.resolve_target(&entry.target).ok()?);
+    let source = package::read_source(&root.join(&disk_path)).ok()?;
+    let query = parse_symbol_query(&entry.symbol);
+    if matches!(query, SymbolQuery::WholeFile) {
+        return Some(source);
+    }
+    if let SymbolQuery::Lines { end, start } = query {
+        return hasher::line_range(&source, start, end);
+    }
+    let language = grammar::language_for_path(&disk_path, &config.grammars).ok()?;
+    let resolved = resolver::resolve(&disk_path, &source, &language, &query, config.slug_style, &config.grammars).ok()?;
+    let start = usize::try_from(resolved.byte_range.start).ok()?;
+    let end = usize::try_from(resolved.byte_range.end).ok()?;
+    return source.get(start..end).map(String::from);
+}
+
+/// Draw and handle input until the user quits.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if the terminal can't be drawn to or read from.
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    dashboard: &mut Dashboard<'_>,
+    changes: &crossbeam_channel::Receiver<Vec<PathBuf>>,
+) -> Result<(), error::Error> {
+    loop {
+        terminal.draw(|frame| return dashboard.render(frame))?;
+        match next_input(changes)? {
+            Input::FilesChanged => {
+                let outcome = dashboard.refresh().map(|()| return "Change detected, re-checked".to_string());
+                dashboard.report(outcome);
+            },
+            Input::Key(code) if !dashboard.handle_key(code) => return Ok(()),
+            Input::Key(_) | Input::Redraw => {},
+        }
+    }
+}
+
+/// `file#symbol`, or just `file` for whole-file references.
+fn label(target: &Path, symbol: &str) -> String {
+    if symbol.is_empty() {
+        return target.display().to_string();
+    }
+    return format!("{}#{symbol}", target.display());
+}
+
+/// Block until a key press, a resize, or a debounced batch of file changes.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if terminal events can't be read.
+fn next_input(changes: &crossbeam_channel::Receiver<Vec<PathBuf>>) -> Result<Input, error::Error> {
+    loop {
+        if changes.try_recv().is_ok() {
+            break;
+        }
+        if let Some(input) = poll_terminal()? {
+            return Ok(input);
+        }
+    }
+    let debounce = Duration::from_millis(watch::DEBOUNCE_MS);
+    while changes.recv_timeout(debounce).is_ok() {}
+    return Ok(Input::FilesChanged);
+}
+
+/// Wait briefly for a terminal event worth acting on.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if terminal events can't be read.
+fn poll_terminal() -> Result<Option<Input>, error::Error> {
+    if !event::poll(POLL_INTERVAL)? {
+        return Ok(None);
+    }
+    return match event::read()? {
+        TermEvent::Key(key) if key.kind == KeyEventKind::Press => Ok(Some(Input::Key(key.code))),
+        TermEvent::Resize(..) => Ok(Some(Input::Redraw)),
+        _ => Ok(None),
+    };
+}
+
+/// Read the baseline, or an empty one if the file doesn't exist yet.
+///
+/// # Errors
+///
+/// Returns errors from reading or parsing an existing baseline.
+fn read_baseline(root: &Path) -> Result<Baseline, error::Error> {
+    return match Baseline::read(&root.join(baseline::DEFAULT_PATH)) {
+        Err(error::Error::FileNotFound { .. }) => Ok(Baseline::default()),
+        other => other,
+    };
+}
+
+/// Run the dashboard until the user quits, re-checking as files change.
+///
+/// # Errors
+///
+/// Returns errors from the initial check, watcher setup, or the terminal.
+pub fn run(root: &Path) -> Result<ExitCode, error::Error> {
+    let mut dashboard = Dashboard::new(root)?;
+    let lockfile = Lockfile::read(&root.join(".docref.lock"))?;
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let (_watcher, _) = watch::start_watcher(root, &dashboard.config, &lockfile, tx)?;
+
+    let mut terminal = ratatui::init();
+    let outcome = event_loop(&mut terminal, &mut dashboard, &rx);
+    ratatui::restore();
+    outcome?;
+    return Ok(ExitCode::SUCCESS);
+}
+
+/// Color for a status cell.
+const fn status_style(result: &CheckResult) -> Style {
+    let color = match result {
+        CheckResult::Fresh => Color::Green,
+        CheckResult::Stale => Color::Yellow,
+        CheckResult::Ambiguous | CheckResult::Broken(_) | CheckResult::Orphaned => Color::Red,
+    };
+    return Style::new().fg(color);
+}
+
+#[cfg(test)]
+#[allow(clippy::missing_panics_doc, reason = "test code uses unwrap freely")]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    /// Render the dashboard and flatten the screen to one string.
+    fn screen(dashboard: &mut Dashboard<'_>) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+        terminal.draw(|frame| return dashboard.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        return buffer.content().iter().map(|cell| return cell.symbol()).collect();
+    }
+
+    #[test]
+    fn stale_entry_shows_current_code_and_can_be_accepted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn add(a: i32, b: i32) -> i32 { a + b }\n").unwrap();
+        std::fs::write(root.join("docs/guide.md"), "See [add](../src/lib.rs#add).\n").unwrap();
+        crate::Engine::builder().root(root).build().unwrap().init().unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn add(a: i32, b: i32) -> i32 { b + a }\n").unwrap();
+
+        let mut dashboard = Dashboard::new(root).unwrap();
+        let shown = screen(&mut dashboard);
+        assert!(shown.contains("stale"), "{shown}");
+        assert!(shown.contains("{ b + a }"), "{shown}");
+
+        assert!(dashboard.handle_key(KeyCode::Char('a')));
+        assert!(dashboard.visible().is_empty());
+        dashboard.handle_key(KeyCode::Char('f'));
+        dashboard.handle_key(KeyCode::Char('f'));
+        dashboard.handle_key(KeyCode::Char('f'));
+        assert_eq!(dashboard.filter, Filter::All);
+        assert!(screen(&mut dashboard).contains("fresh"));
+        assert!(!dashboard.handle_key(KeyCode::Char('q')));
+    }
+}
@@ -2,17 +2,64 @@
 use std::ops::Range;
 use std::path::PathBuf;
 
+/// Which part of a symbol a reference's hash covers.
+///
+/// Chosen per reference with a link title annotation such as
+/// `[add](src/lib.rs#add "docref: sig")`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashMode {
+    /// The full declaration, without comments.
+    #[default]
+    Body,
+    /// The full declaration plus its comments and leading doc comments.
+    Docs,
+    /// Nothing but the symbol's existence: edits never make it stale, and
+    /// only removing the symbol breaks it.
+    Exists,
+    /// The declaration without its body: name, parameters, and return type.
+    Signature,
+}
+
+impl HashMode {
+    /// Whether this is the default mode, which lockfiles leave implicit.
+    pub const fn is_body(&self) -> bool {
+        return matches!(*self, Self::Body);
+    }
+}
+
 /// Parsed from markdown link syntax by the scanner.
 #[derive(Debug, Clone)]
 pub struct Reference {
+    /// Hash mode requested by the link title, `Body` when unannotated.
+    pub mode: HashMode,
     /// Markdown file containing this reference.
     pub source: PathBuf,
     /// One-based line number of the reference in the source file.
     pub source_line: u32,
     /// Symbol query parsed from the fragment portion of the link.
     pub symbol: SymbolQuery,
+    /// Byte range of the fragment within its source line, excluding the `#`.
+    /// Empty for whole-file references.
+    pub symbol_span: Range<usize>,
     /// Path to the target source file.
     pub target: PathBuf,
+    /// Byte range of the link path as written within its source line.
+    pub target_span: Range<usize>,
+}
+
+/// A reference that could not be resolved during a batch run such as `init`.
+/// Collected so every failure can be reported at once.
+#[derive(Debug, Clone)]
+pub struct ReferenceFailure {
+    /// Rendered error message explaining why resolution failed.
+    pub reason: String,
+    /// Markdown file containing the reference.
+    pub source: PathBuf,
+    /// One-based line number of the reference in the source file.
+    pub source_line: u32,
+    /// The link target as written, e.g. `src/lib.rs#add`.
+    pub target: String,
 }
 
 /// Output of successful symbol resolution. Byte range is guaranteed
@@ -44,16 +91,29 @@ pub struct SourceRef {
 }
 
 /// Parsed from a symbol fragment. Either bare ("add"), dot-scoped ("Config.validate"),
-/// or whole-file (no fragment).
+/// or whole-file (no fragment), optionally wrapped in an absence assertion.
 #[derive(Debug, Clone)]
 pub enum SymbolQuery {
+    /// Assertion that the wrapped symbol no longer exists, such as a
+    /// removal documented in a deprecation guide.
+    Absent(Box<SymbolQuery>),
     /// Unscoped symbol name such as `add`.
     Bare(String),
-    /// Dot-scoped symbol such as `Config.validate`.
+    /// Inclusive, 1-based line range such as `L40-L75`, for files docref
+    /// can't parse or spans that aren't a single declaration.
+    Lines {
+        /// Last line of the range.
+        end: u32,
+        /// First line of the range.
+        start: u32,
+    },
+    /// Every member of a type, such as `Config.*`; holds the type's name.
+    Members(String),
+    /// Dot-scoped symbol such as `Config.validate` or `net.Server.listen`.
     Scoped {
-        /// Nested member name.
+        /// Path below the outermost name, itself dotted when nested deeper.
         child: String,
-        /// Enclosing type or module name.
+        /// Outermost enclosing type or module name.
         parent: String,
     },
     /// Entire file reference — no symbol fragment.
@@ -64,7 +124,11 @@ impl SymbolQuery {
     /// The display name used in lockfile entries and error messages.
     pub fn display_name(&self) -> String {
         return match self {
+            SymbolQuery::Absent(inner) => inner.display_name(),
             SymbolQuery::Bare(name) => name.clone(),
+            SymbolQuery::Members(parent) => format!("{parent}.*"),
+            SymbolQuery::Lines { end, start } if end == start => format!("L{start}"),
+            SymbolQuery::Lines { end, start } => format!("L{start}-L{end}"),
             SymbolQuery::Scoped {
                 parent,
                 child,
@@ -1,8 +1,10 @@
-//! File watcher: runs `check` on startup, then re-runs on source changes.
+//! File watcher: runs `check` on startup, then re-checks the entries each
+//! source change touches and reports markdown links the lockfile is missing.
 
-use std::collections::HashSet;
-use std::path::PathBuf;
-use std::process::ExitCode;
+use std::collections::{BTreeSet, HashSet};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
 use std::time::Duration;
 
 use notify::{RecursiveMode, Watcher as _};
@@ -10,24 +12,206 @@ use notify::{RecursiveMode, Watcher as _};
 use crate::commands;
 use crate::config;
 use crate::error;
-use crate::lockfile::Lockfile;
+use crate::freshness::{self, CheckResult};
+use crate::lockfile::{LockEntry, Lockfile};
+use crate::scanner;
 
 /// Debounce delay between filesystem events and re-check.
-const DEBOUNCE_MS: u64 = 100;
+pub(crate) const DEBOUNCE_MS: u64 = 100;
 
-/// Collect all parent directories of source and target files, plus resolved targets.
+/// The lockfile and every entry's latest result, kept between changes so
+/// only the entries a change touches are compared again.
+struct Session {
+    /// Project configuration, for resolving targets.
+    config: config::Config,
+    /// The lockfile the results belong to.
+    lockfile: Lockfile,
+    /// Latest result for each lockfile entry, in lockfile order.
+    results: Vec<CheckResult>,
+    /// Each entry's target as resolved on disk, if it resolves.
+    targets: Vec<Option<PathBuf>>,
+}
+
+impl Session {
+    /// Indices of the entries that depend on any of the `changed` paths,
+    /// printing how many each path affects.
+    fn affected(&self, changed: &BTreeSet<PathBuf>) -> BTreeSet<usize> {
+        let mut affected = BTreeSet::new();
+        let hits = changed.iter().map(|path| return (path, self.dependents(path)));
+        for (path, hits) in hits.filter(|(_, hits)| return !hits.is_empty()) {
+            print_affected(path, hits.len());
+            affected.extend(hits);
+        }
+        return affected;
+    }
+
+    /// Indices of the entries a change to `path` can affect.
+    fn dependents(&self, path: &Path) -> Vec<usize> {
+        return self
+            .lockfile
+            .entries
+            .iter()
+            .zip(&self.targets)
+            .enumerate()
+            .filter(|&(_, (entry, target))| return Self::depends_on(entry, target.as_deref(), path))
+            .map(|(idx, _)| return idx)
+            .collect();
+    }
+
+    /// Whether a change to `path` can change `entry`'s result. Python
+    /// re-exports follow imports into other modules, so any `.py` change
+    /// also touches entries targeting an `__init__.py`.
+    fn depends_on(entry: &LockEntry, target: Option<&Path>, path: &Path) -> bool {
+        let reexport = path.extension().is_some_and(|ext| return ext == "py")
+            && target.is_some_and(|t| return t.file_name().is_some_and(|name| return name == "__init__.py"));
+        return entry.source == path || target == Some(path) || reexport;
+    }
+
+    /// Re-scan the markdown file `source` and print the links it has that
+    /// the lockfile doesn't track, and the entries it no longer links to.
+    /// Returns whether the two differ.
+    fn drift(&self, root: &Path, source: &Path) -> bool {
+        // A deleted file's entries are already reported as orphaned.
+        let Ok(content) = std::fs::read_to_string(root.join(source)) else {
+            return false;
+        };
+        let references = match scanner::scan_content(root, &self.config, source, &content) {
+            Ok(references) => references,
+            Err(e) => {
+                eprintln!("error: {e}");
+                return false;
+            },
+        };
+        let tracked: Vec<&LockEntry> = self.lockfile.entries.iter().filter(|e| return e.source == source).collect();
+        let untracked: BTreeSet<String> = references
+            .iter()
+            .map(|r| return (&r.target, r.symbol.display_name()))
+            .filter(|(target, symbol)| return !tracked.iter().any(|e| return e.target == **target && e.symbol == *symbol))
+            .map(|(target, symbol)| return commands::format_ref(target, &symbol))
+            .collect();
+        let unlinked: BTreeSet<String> = tracked
+            .iter()
+            .filter(|e| {
+                return !references.iter().any(|r| return r.target == e.target && r.symbol.display_name() == e.symbol);
+            })
+            .map(|e| return commands::format_ref(&e.target, &e.symbol))
+            .collect();
+        print_drift(source, "links", "not in .docref.lock", &untracked);
+        print_drift(source, "no longer links", "still in .docref.lock", &unlinked);
+        return !untracked.is_empty() || !unlinked.is_empty();
+    }
+
+    /// Read the config and lockfile under `root` and compare every entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns errors from config loading, lockfile reading, or hashing.
+    fn load(root: &Path) -> Result<Self, error::Error> {
+        let config = config::Config::load(root)?;
+        let lockfile = Lockfile::read(&root.join(".docref.lock"))?;
+        let tracked: Vec<&LockEntry> = lockfile.entries.iter().collect();
+        let results = freshness::compare_lockfile_entries_against_source(root, &config, &tracked)?;
+        let targets = lockfile.entries.iter().map(|e| return config.resolve_target(&e.target).ok()).collect();
+        return Ok(Self { config, lockfile, results, targets });
+    }
+
+    /// Compare the entries that depend on the `changed` paths again and
+    /// report the delta. Returns `None` when no entry depends on them.
+    fn recheck(&mut self, root: &Path, changed: &BTreeSet<PathBuf>, format: &str) -> Option<ExitCode> {
+        let affected = self.affected(changed);
+        if affected.is_empty() {
+            return None;
+        }
+        let entries: Vec<&LockEntry> = affected.iter().filter_map(|&idx| return self.lockfile.entries.get(idx)).collect();
+        let fresh = match freshness::compare_lockfile_entries_against_source(root, &self.config, &entries) {
+            Ok(fresh) => fresh,
+            Err(e) => return Some(check_failed(&e)),
+        };
+        let slots = self.results.iter_mut().enumerate().filter(|(idx, _)| return affected.contains(idx));
+        for ((_, slot), result) in slots.zip(fresh) {
+            *slot = result;
+        }
+        let code = commands::check_changed(root, &self.config, &self.lockfile, &self.results, &entries, format);
+        return Some(code.unwrap_or_else(|e| return check_failed(&e)));
+    }
+
+    /// Re-run the full check after the lockfile or config changed, then
+    /// start over from the new lockfile. Keeps the old results if it can't
+    /// be read.
+    fn reload(&mut self, root: &Path, format: &str) -> ExitCode {
+        eprintln!("watch: change detected, re-checking...");
+        let code = run_check(root, format);
+        match Self::load(root) {
+            Ok(reloaded) => *self = reloaded,
+            Err(e) => eprintln!("error: {e}"),
+        }
+        return code;
+    }
+
+    /// Re-scan each changed markdown file the config covers, suggesting
+    /// `docref init` when its links and the lockfile have drifted apart.
+    fn report_drift(&self, root: &Path, changed: &BTreeSet<PathBuf>) {
+        let drifted = changed
+            .iter()
+            .filter(|p| return self.config.is_markdown(p))
+            .filter(|p| return self.config.should_scan(&p.to_string_lossy()))
+            .fold(false, |drifted, path| return self.drift(root, path) || drifted);
+        if drifted {
+            eprintln!("watch: run `docref init` to bring .docref.lock up to date");
+        }
+        return;
+    }
+
+    /// Watch the directories of the current lockfile and config, so a reload
+    /// picks up new sources and targets. Keeps `watcher` if a new one can't
+    /// be started.
+    fn rewatch(
+        &self,
+        root: &Path,
+        watcher: notify::RecommendedWatcher,
+        tx: &crossbeam_channel::Sender<Vec<PathBuf>>,
+    ) -> notify::RecommendedWatcher {
+        return match start_watcher(root, &self.config, &self.lockfile, tx.clone()) {
+            Ok((rewatched, dir_count)) => {
+                eprintln!("watch: monitoring {dir_count} directories");
+                rewatched
+            },
+            Err(e) => {
+                eprintln!("error: {e}");
+                watcher
+            },
+        };
+    }
+}
+
+/// Print a check error, returning the exit code for it.
+fn check_failed(error: &error::Error) -> ExitCode {
+    eprintln!("error: {error}");
+    return ExitCode::from(3_u8);
+}
+
+/// Collect all parent directories of source and target files, plus resolved
+/// targets and every markdown file the config scans.
+///
+/// The root is always included, so changes to `.docref.lock` and
+/// `.docref.toml` are seen.
 fn collect_watch_dirs(
     lockfile: &Lockfile,
-    root: &std::path::Path,
+    root: &Path,
     config: &config::Config,
 ) -> HashSet<PathBuf> {
-    let mut dirs = HashSet::new();
+    let mut dirs = HashSet::from([root.to_path_buf()]);
+    for markdown in scanner::markdown_files(root, config) {
+        if let Some(parent) = markdown.parent() {
+            dirs.insert(root.join(parent));
+        }
+    }
     for entry in &lockfile.entries {
         if let Some(parent) = entry.source.parent() {
-            dirs.insert(PathBuf::from(".").join(parent));
+            dirs.insert(root.join(parent));
         }
         if let Some(parent) = entry.target.parent() {
-            dirs.insert(PathBuf::from(".").join(parent));
+            dirs.insert(root.join(parent));
         }
         if let Ok(disk_path) = config.resolve_target(&entry.target)
             && let Some(parent) = disk_path.parent()
@@ -44,7 +228,7 @@ fn collect_watch_dirs(
 ///
 /// Returns an error if the watcher cannot be created.
 fn create_watcher(
-    tx: crossbeam_channel::Sender<()>,
+    tx: crossbeam_channel::Sender<Vec<PathBuf>>,
 ) -> Result<notify::RecommendedWatcher, error::Error> {
     return notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
         if let Ok(event) = res
@@ -55,7 +239,7 @@ fn create_watcher(
                     | notify::EventKind::Remove(_)
             )
         {
-            let _ = tx.send(());
+            let _ = tx.send(event.paths);
         }
     })
     .map_err(|e| {
@@ -65,53 +249,146 @@ fn create_watcher(
     });
 }
 
+/// Print how many entries a change to `path` affects.
+fn print_affected(path: &Path, count: usize) {
+    let noun = if count == 1 { "ref" } else { "refs" };
+    eprintln!("watch: {count} {noun} affected by {}", path.display());
+    return;
+}
+
+/// Print one side of a markdown file's drift from the lockfile, such as
+/// `docs/guide.md links 1 ref not in .docref.lock:` and then each ref.
+/// Prints nothing when `refs` is empty.
+fn print_drift(source: &Path, links: &str, status: &str, refs: &BTreeSet<String>) {
+    if refs.is_empty() {
+        return;
+    }
+    let noun = if refs.len() == 1 { "ref" } else { "refs" };
+    eprintln!("watch: {} {links} {} {noun} {status}:", source.display(), refs.len());
+    for reference in refs {
+        eprintln!("  {reference}");
+    }
+    return;
+}
+
+/// Event `paths` relative to `root`, dropping any outside it.
+fn relative_paths(root: &Path, paths: Vec<PathBuf>) -> BTreeSet<PathBuf> {
+    return paths.into_iter().filter_map(|p| return p.strip_prefix(root).ok().map(Path::to_path_buf)).collect();
+}
+
 /// Entry point for the watch command.
 ///
-/// Runs an initial check, then watches relevant files and re-checks on changes.
+/// Runs an initial check, then watches relevant files. A change re-checks
+/// only the entries whose markdown source or target changed; a change to
+/// the lockfile or config re-runs the full check. After every check, the
+/// `exec` hook runs if one was given.
 ///
 /// # Errors
 ///
-/// Returns errors from config loading, lockfile reading, or watcher setup.
-pub fn run(format: &str) -> Result<ExitCode, error::Error> {
-    let root = PathBuf::from(".");
-    let lock_path = root.join(".docref.lock");
+/// Returns errors from an unknown format, config loading, lockfile reading,
+/// or watcher setup.
+pub fn run(root: &Path, format: &str, exec: Option<&str>) -> Result<ExitCode, error::Error> {
+    commands::parse_output_format(format)?;
+    let root = root.canonicalize()?;
 
     eprintln!("watch: initial check");
-    let mut last_code = run_check(format);
-
-    let config = config::Config::load(&root)?;
-    let lockfile = Lockfile::read(&lock_path)?;
-    let watch_dirs = collect_watch_dirs(&lockfile, &root, &config);
+    let mut last_code = run_check(&root, format);
 
+    let mut session = Session::load(&root)?;
+    run_hook(exec, &root, &session);
     let (tx, rx) = crossbeam_channel::unbounded();
-    let mut watcher = create_watcher(tx)?;
-
-    for dir in &watch_dirs {
-        if dir.exists() {
-            let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
-        }
-    }
+    let (mut watcher, dir_count) = start_watcher(&root, &session.config, &session.lockfile, tx.clone())?;
 
-    let dir_count = watch_dirs.len();
     eprintln!("watch: monitoring {dir_count} directories, press Ctrl+C to stop");
 
-    while rx.recv().is_ok() {
+    while let Ok(paths) = rx.recv() {
         let debounce = Duration::from_millis(DEBOUNCE_MS);
-        while rx.recv_timeout(debounce).is_ok() {}
-        eprintln!("watch: change detected, re-checking...");
-        last_code = run_check(format);
+        let mut changed = relative_paths(&root, paths);
+        while let Ok(more) = rx.recv_timeout(debounce) {
+            changed.extend(relative_paths(&root, more));
+        }
+        let reload = [".docref.lock", ".docref.toml"].iter().any(|name| return changed.contains(Path::new(name)));
+        let checked = if reload {
+            let code = session.reload(&root, format);
+            watcher = session.rewatch(&root, watcher, &tx);
+            Some(code)
+        } else {
+            session.report_drift(&root, &changed);
+            session.recheck(&root, &changed, format)
+        };
+        if let Some(code) = checked {
+            last_code = code;
+            run_hook(exec, &root, &session);
+        }
     }
 
     return Ok(last_code);
 }
 
 /// Run check once and print result. Returns the exit code from check.
-fn run_check(format: &str) -> ExitCode {
-    return match commands::check(format) {
-        Ok(code) => code,
-        Err(e) => {
-            eprintln!("error: {e}");
-            ExitCode::from(3_u8)
-        },
+fn run_check(root: &Path, format: &str) -> ExitCode {
+    return commands::check(root, format, &commands::CheckOptions::default()).unwrap_or_else(|e| return check_failed(&e));
+}
+
+/// Run the `--exec` hook, if any, after a check.
+///
+/// The command runs through the shell in the project root, with the exit
+/// code in `DOCREF_EXIT_CODE` and the JSON summary of findings on stdin.
+/// A failing hook is reported, not fatal.
+fn run_hook(exec: Option<&str>, root: &Path, session: &Session) {
+    let Some(command) = exec else {
+        return;
     };
+    let (status, payload) =
+        match commands::watch_hook_payload(root, &session.config, &session.lockfile, &session.results) {
+            Ok(report) => report,
+            Err(e) => return eprintln!("error: {e}"),
+        };
+    let outcome = shell(command)
+        .current_dir(root)
+        .env("DOCREF_EXIT_CODE", status.to_string())
+        .stdin(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            // A hook that ignores its input may exit before reading it all.
+            let _ = child.stdin.take().map(|mut stdin| return stdin.write_all(payload.as_bytes()));
+            return child.wait();
+        });
+    match outcome {
+        Err(e) => eprintln!("watch: could not run hook: {e}"),
+        Ok(exit) if !exit.success() => eprintln!("watch: hook failed ({exit})"),
+        Ok(_) => {},
+    }
+    return;
+}
+
+/// A command that runs `command` through the platform shell.
+fn shell(command: &str) -> Command {
+    let (program, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut shell = Command::new(program);
+    shell.arg(flag).arg(command);
+    return shell;
+}
+
+/// Watch the directories of every source and target in `lockfile`, sending
+/// on `tx` for each change. Returns the watcher, which stops when dropped,
+/// and the number of directories watched.
+///
+/// # Errors
+///
+/// Returns an error if the watcher cannot be created.
+pub(crate) fn start_watcher(
+    root: &Path,
+    config: &config::Config,
+    lockfile: &Lockfile,
+    tx: crossbeam_channel::Sender<Vec<PathBuf>>,
+) -> Result<(notify::RecommendedWatcher, usize), error::Error> {
+    let watch_dirs = collect_watch_dirs(lockfile, root, config);
+    let mut watcher = create_watcher(tx)?;
+    for dir in &watch_dirs {
+        if dir.exists() {
+            let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+        }
+    }
+    return Ok((watcher, watch_dirs.len()));
 }
@@ -0,0 +1,27 @@
+#include "server.hpp"
+
+namespace net {
+
+Server::Server(std::string host) : host_(std::move(host)) {}
+
+Server::~Server() = default;
+
+bool Server::start() {
+    return !host_.empty();
+}
+
+int checksum(const char* data) {
+    int sum = 0;
+    while (*data) {
+        sum += *data++;
+    }
+    return sum;
+}
+
+}  // namespace net
+
+static int helper();
+
+static int helper() {
+    return 1;
+}
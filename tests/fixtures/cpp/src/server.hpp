@@ -0,0 +1,38 @@
+#ifndef SERVER_HPP
+#define SERVER_HPP
+
+#include <string>
+
+namespace net {
+
+constexpr int kMaxRetries = 3;
+
+enum class Mode { Fast, Safe };
+
+class Server {
+public:
+    explicit Server(std::string host);
+    ~Server();
+
+    bool start();
+    int port() const { return port_; }
+
+    struct Options {
+        int timeout;
+    };
+
+private:
+    std::string host_;
+    int port_ = 80;
+};
+
+template <typename T>
+T clamp(T value, T low, T high) {
+    return value < low ? low : (value > high ? high : value);
+}
+
+int checksum(const char* data);
+
+}  // namespace net
+
+#endif
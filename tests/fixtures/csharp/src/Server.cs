@@ -0,0 +1,48 @@
+using System;
+
+namespace Example.Net;
+
+public delegate void Callback(string message);
+
+public class Server
+{
+    public const int MaxRetries = 3;
+
+    private readonly string host, region;
+
+    public Server(string host)
+    {
+        this.host = host;
+    }
+
+    ~Server()
+    {
+    }
+
+    public int Port { get; set; } = 80;
+
+    public bool Start()
+    {
+        return host.Length > 0;
+    }
+
+    public class Options
+    {
+        public int Timeout { get; init; }
+    }
+}
+
+public interface IHandler
+{
+    string Name { get; }
+
+    void Handle(string message);
+}
+
+public record Endpoint(string Host, int Port);
+
+public enum Mode
+{
+    Fast,
+    Safe,
+}
@@ -0,0 +1,34 @@
+module Shapes
+  ( Shape (..)
+  , HasArea (..)
+  , largest
+  ) where
+
+import Data.List (maximumBy)
+import Data.Ord (comparing)
+
+-- | A plane figure.
+data Shape
+  = Circle Double
+  | Rect { width :: Double, height :: Double }
+  deriving (Show, Eq)
+
+-- | Figures that cover some of the plane.
+class HasArea a where
+  area :: a -> Double
+  describe :: a -> String
+  describe shape = "covers " ++ show (area shape)
+
+instance HasArea Shape where
+  area (Circle r) = pi * r * r
+  area (Rect w h) = w * h
+
+-- | The figure that covers the most.
+largest :: [Shape] -> Shape
+largest = maximumBy (comparing area)
+
+-- | A circle of the given diameter.
+fromDiameter :: Double -> Shape
+fromDiameter diameter = Circle radius
+  where
+    radius = diameter / 2
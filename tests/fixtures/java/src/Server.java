@@ -0,0 +1,40 @@
+package com.example;
+
+import java.util.List;
+
+public class Server {
+    public static final int MAX_RETRIES = 3;
+
+    private String host, region;
+
+    public Server(String host) {
+        this.host = host;
+    }
+
+    public boolean start() {
+        return host != null;
+    }
+
+    public static class Options {
+        int timeout;
+
+        int seconds() {
+            return timeout / 1000;
+        }
+    }
+}
+
+interface Handler {
+    String PREFIX = "handler";
+
+    void handle(String message);
+}
+
+enum Mode {
+    FAST,
+    SAFE;
+
+    boolean isFast() {
+        return this == FAST;
+    }
+}
@@ -0,0 +1,37 @@
+package app.users
+
+import kotlinx.serialization.Serializable
+
+/** A registered user. */
+@Serializable
+data class User(
+    val id: Long,
+    val name: String,
+) {
+    fun greet(): String {
+        val greeting = "Hello"
+        return "$greeting, $name"
+    }
+
+    companion object {
+        const val GUEST_ID = 0L
+
+        fun guest() = User(GUEST_ID, "guest")
+    }
+}
+
+sealed interface Event {
+    fun describe(): String
+}
+
+object Registry {
+    private val users = mutableMapOf<Long, User>()
+
+    fun register(user: User) {
+        users[user.id] = user
+    }
+}
+
+fun String.shout(): String = uppercase() + "!"
+
+val DEFAULT_USER = User.guest()
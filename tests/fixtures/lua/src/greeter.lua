@@ -0,0 +1,26 @@
+-- A small greeting module.
+local M = {
+  prefix = "Hello",
+  format = function(name)
+    return M.prefix .. ", " .. name
+  end,
+}
+
+local function shout(text)
+  return text:upper() .. "!"
+end
+
+function M.greet(name)
+  local message = M.format(name)
+  print(message)
+end
+
+function M:loud(name)
+  print(shout(self.format(name)))
+end
+
+M.farewell = function(name)
+  print("Goodbye, " .. name)
+end
+
+return M
@@ -0,0 +1,52 @@
+<?php
+
+namespace App\Net;
+
+const DEFAULT_PORT = 80;
+
+interface Handler
+{
+    public function handle(string $message): void;
+}
+
+trait Retries
+{
+    public const MAX_RETRIES = 3;
+
+    public function retry(callable $action): mixed
+    {
+        return $action();
+    }
+}
+
+class Server implements Handler
+{
+    use Retries;
+
+    private string $host, $region;
+
+    public function __construct(string $host)
+    {
+        $this->host = $host;
+    }
+
+    public function start(): bool
+    {
+        return $this->host !== '';
+    }
+
+    public function handle(string $message): void
+    {
+    }
+}
+
+enum Mode: string
+{
+    case Fast = 'fast';
+    case Safe = 'safe';
+}
+
+function checksum(string $data): int
+{
+    return array_sum(array_map('ord', str_split($data)));
+}
@@ -0,0 +1,10 @@
+public class Client
+{
+    #region Public API
+    public void Connect() { Open(); }
+
+    public void Close() { }
+    #endregion
+
+    private void Open() { }
+}
@@ -0,0 +1,12 @@
+pub struct Server {
+    port: u16,
+}
+
+pub fn start() -> Server {
+    let port = 8080;
+    // docref:begin setup
+    let server = Server { port };
+    println!("listening on {}", server.port);
+    // docref:end setup
+    return server;
+}
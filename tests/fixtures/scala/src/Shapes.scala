@@ -0,0 +1,28 @@
+package geometry
+
+sealed trait Shape {
+  def area: Double
+  def describe(): String = s"shape with area $area"
+}
+
+final case class Circle(radius: Double) extends Shape {
+  def area: Double = math.Pi * radius * radius
+}
+
+object Circle {
+  val Unit: Circle = Circle(1.0)
+
+  def fromDiameter(diameter: Double): Circle = {
+    val radius = diameter / 2
+    Circle(radius)
+  }
+}
+
+object Shapes:
+  private[geometry] val registry = Map.empty[String, Shape]
+
+  def largest(shapes: Seq[Shape]): Shape =
+    shapes.maxBy(_.area)
+end Shapes
+
+lazy val origin = Circle(0.0)
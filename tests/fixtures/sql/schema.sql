@@ -0,0 +1,24 @@
+-- Accounts and their sessions.
+
+CREATE TABLE IF NOT EXISTS public.users (
+    id bigserial PRIMARY KEY,
+    email text NOT NULL UNIQUE,
+    display_name text DEFAULT 'anonymous; unnamed',
+    created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE TABLE sessions (
+    id uuid,
+    user_id bigint REFERENCES users (id),
+    expires_at timestamptz,
+    CONSTRAINT sessions_pkey PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX users_email_idx ON users (lower(email));
+
+CREATE OR REPLACE VIEW active_sessions AS
+    SELECT * FROM sessions WHERE expires_at > now();
+
+CREATE FUNCTION session_count(uid bigint) RETURNS bigint AS $$
+    SELECT count(*) FROM sessions WHERE user_id = uid;
+$$ LANGUAGE sql;
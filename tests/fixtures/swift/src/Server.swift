@@ -0,0 +1,49 @@
+import Foundation
+
+let defaultPort = 80
+
+protocol Handler {
+    var name: String { get }
+    func handle(_ message: String)
+}
+
+enum Mode {
+    case fast, safe
+    case custom(timeout: Int)
+}
+
+struct Options {
+    var timeout: Int = 30
+}
+
+class Server {
+    let host: String
+    var port = defaultPort
+
+    init(host: String) {
+        self.host = host
+    }
+
+    deinit {
+    }
+
+    func start() -> Bool {
+        return !host.isEmpty
+    }
+
+    struct Limits {
+        let maxRetries = 3
+    }
+}
+
+extension Server: Handler {
+    var name: String { host }
+
+    func handle(_ message: String) {
+        print(message)
+    }
+}
+
+func checksum(_ data: String) -> Int {
+    return data.utf8.reduce(0) { $0 + Int($1) }
+}
@@ -0,0 +1,29 @@
+# Log storage for the web tier.
+
+variable "region" {
+  type    = string
+  default = "eu-west-1"
+}
+
+module "vpc" {
+  source = "./modules/vpc"
+  cidr   = "10.0.0.0/16"
+}
+
+resource "aws_s3_bucket" "logs" {
+  bucket = "web-logs-${var.region}" # "}" in a string doesn't close the block
+
+  lifecycle {
+    prevent_destroy = true
+  }
+}
+
+data "aws_iam_policy_document" "logs" {
+  statement {
+    actions = ["s3:PutObject"]
+  }
+}
+
+output "bucket_arn" {
+  value = aws_s3_bucket.logs.arn
+}
@@ -0,0 +1,8 @@
+pub struct Config {
+    pub host: String,
+    pub port: u16,
+}
+
+pub fn load() -> Config {
+    return Config { host: "localhost".to_string(), port: 8080 };
+}
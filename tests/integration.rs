@@ -53,6 +53,113 @@ fn init_then_check_passes() {
     );
 }
 
+/// Ask the dynamic completer for candidates for the last word of `words`.
+fn complete_at(dir: &Path, words: &[&str]) -> Vec<String> {
+    let output = docref_at(dir)
+        .env("COMPLETE", "bash")
+        .env("_CLAP_COMPLETE_INDEX", words.len().saturating_sub(1).to_string())
+        .env("_CLAP_IFS", "\n")
+        .arg("--")
+        .args(words)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "completion failed: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect()
+}
+
+#[test]
+fn completion_offers_tracked_and_resolved_symbols() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let tracked = complete_at(&dir, &["docref", "update", "src/lib.rs#"]);
+    assert!(tracked.contains(&"src/lib.rs#add".to_string()), "tracked symbols: {tracked:?}");
+
+    std::fs::write(dir.join("src/extra.rs"), "fn helper() {}\nfn other() {}\n").unwrap();
+    let resolved = complete_at(&dir, &["docref", "update", "src/extra.rs#he"]);
+    assert_eq!(resolved, vec!["src/extra.rs#helper".to_string()]);
+
+    let files = complete_at(&dir, &["docref", "refs", "src/l"]);
+    assert!(files.contains(&"src/lib.rs".to_string()), "tracked files: {files:?}");
+}
+
+#[test]
+fn cwd_flag_runs_against_another_directory() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let elsewhere = TempDir::new().unwrap();
+    std::fs::remove_file(dir.join(".docref.lock")).unwrap();
+
+    let init = docref_at(elsewhere.path()).arg("-C").arg(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    assert!(dir.join(".docref.lock").exists(), "lockfile should be written under -C dir");
+    assert!(!elsewhere.path().join(".docref.lock").exists());
+
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("x + A", "x + A + 1")).unwrap();
+
+    let check = docref_at(elsewhere.path()).arg("check").arg("--cwd").arg(&dir).output().unwrap();
+    assert_eq!(check.status.code(), Some(1), "expected stale via --cwd");
+
+    let resolve = docref_at(elsewhere.path())
+        .args(["resolve", "src/lib.rs", "add", "-C"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(resolve.status.success(), "resolve should read files relative to -C dir");
+}
+
+#[test]
+fn log_level_traces_config_and_entry_decisions() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    docref_at(&dir).arg("init").output().unwrap();
+
+    let quiet = docref_at(&dir).arg("check").output().unwrap();
+    assert!(!String::from_utf8_lossy(&quiet.stderr).contains("DEBUG"), "traces are off by default");
+
+    let check = docref_at(&dir).args(["check", "--log-level", "debug"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&check.stderr);
+    assert!(stderr.contains("no .docref.toml"), "config loading not traced: {stderr}");
+    assert!(stderr.contains("checked entry") && stderr.contains("symbol=add"), "entry not traced: {stderr}");
+
+    let env = docref_at(&dir).arg("check").env("DOCREF_LOG", "debug").output().unwrap();
+    assert!(String::from_utf8_lossy(&env.stderr).contains("checked entry"), "DOCREF_LOG ignored");
+}
+
+#[test]
+fn engine_reports_progress_through_event_callback() {
+    use std::sync::{Arc, Mutex};
+
+    use docref::{Engine, Event};
+
+    let (_tmp, dir) = isolated_fixture("basic");
+    let events: Arc<Mutex<Vec<String>>> = Arc::default();
+    let sink = Arc::clone(&events);
+    let engine = Engine::builder()
+        .root(&dir)
+        .on_event(move |event| {
+            let line = match event {
+                Event::EntryChecked { entry, result } => format!("checked {} {}", entry.symbol, result.name()),
+                Event::FileScanned { path, references } => format!("scanned {} {references}", path.display()),
+                Event::Finished { references } => format!("finished {references}"),
+                Event::TargetHashed { path, references } => format!("hashed {} {references}", path.display()),
+            };
+            sink.lock().unwrap().push(line);
+        })
+        .build()
+        .unwrap();
+
+    let lockfile = engine.init().unwrap();
+    let results = engine.check().unwrap();
+    assert_eq!(results.len(), lockfile.entries.len());
+
+    let events = events.lock().unwrap();
+    assert!(events.contains(&"scanned docs/guide.md 3".to_string()), "events: {events:?}");
+    assert!(events.contains(&"hashed src/lib.rs 2".to_string()), "events: {events:?}");
+    assert!(events.contains(&"checked add fresh".to_string()), "events: {events:?}");
+    let finished = format!("finished {}", lockfile.entries.len());
+    assert_eq!(events.iter().filter(|e| **e == finished).count(), 2, "events: {events:?}");
+}
+
 #[test]
 fn check_detects_stale_reference() {
     let (_tmp, dir) = isolated_fixture("basic");
@@ -77,267 +184,1843 @@ fn check_detects_stale_reference() {
 }
 
 #[test]
-fn check_detects_broken_reference() {
+fn link_title_hash_modes_track_signature_or_docs() {
     let (_tmp, dir) = isolated_fixture("basic");
     let src = dir.join("src/lib.rs");
+    std::fs::write(
+        &src,
+        "/// The base offset.\nconst A: i32 = 10;\n\nfn add(x: i32) -> i32 {\n    x + A\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("docs/guide.md"),
+        "# Guide\n\n[`A`](../src/lib.rs#A \"docref: docs\") and [`add`](../src/lib.rs#add \"docref: sig\").\n",
+    )
+    .unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("mode = \"signature\""), "lockfile should record the mode: {lock}");
 
+    // A body-only change is invisible to a signature-only reference.
     let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("x + A", "A + x")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code(), Some(0), "{}", String::from_utf8_lossy(&check.stdout));
 
-    // Init.
-    docref_at(&dir).arg("init").output().unwrap();
-
-    // Remove the referenced symbol entirely.
-    let broken = original.replace("const A: i32 = 10;\n", "");
-    std::fs::write(&src, &broken).unwrap();
-
-    // Check should fail with exit code 2 (broken).
+    // A doc comment change is visible to a doc-inclusive reference.
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("The base offset.", "The starting offset.")).unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
-    let code = check.status.code().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
-    assert_eq!(code, 2, "expected exit 2 (broken), got {code}\nstdout: {stdout}");
-    assert!(stdout.contains("BROKEN"), "output should mention BROKEN: {stdout}");
+    assert_eq!(check.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("STALE") && stdout.contains("#A"), "A should be stale: {stdout}");
 }
 
 #[test]
-fn update_updates_stale_reference() {
+fn doc_suffix_includes_leading_doc_comment_in_hash() {
     let (_tmp, dir) = isolated_fixture("basic");
     let src = dir.join("src/lib.rs");
+    std::fs::write(&src, "/// Adds the base offset.\nfn add(x: i32) -> i32 {\n    x + 10\n}\n").unwrap();
+    std::fs::write(dir.join("docs/guide.md"), "# Guide\n\n[`add`](../src/lib.rs#add@doc) offsets.\n").unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"add\""), "suffix should not be part of the symbol: {lock}");
+    assert!(lock.contains("mode = \"docs\""), "lockfile should record the mode: {lock}");
 
-    let original = std::fs::read_to_string(&src).unwrap();
-
-    // Init, then modify source.
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
-    let modified = original.replace("const A: i32 = 10;", "const A: i32 = 20;");
-    std::fs::write(&src, &modified).unwrap();
-
-    // Check should be stale.
-    let check = docref_at(&dir).arg("check").output().unwrap();
-    assert_eq!(check.status.code().unwrap(), 1);
-
-    // Update the specific reference.
-    let update = docref_at(&dir)
-        .args(["update", "src/lib.rs#A"])
-        .output()
-        .unwrap();
-    assert!(
-        update.status.success(),
-        "update failed: {}",
-        String::from_utf8_lossy(&update.stderr)
-    );
-
-    // Check should pass now.
+    std::fs::write(&src, "/// Adds the starting offset.\nfn add(x: i32) -> i32 {\n    x + 10\n}\n").unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check still failing after update: {}",
-        String::from_utf8_lossy(&check.stdout)
-    );
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("STALE") && stdout.contains("#add"), "add should be stale: {stdout}");
 }
 
 #[test]
-fn typescript_references_resolve_and_check() {
+fn exists_suffix_only_breaks_when_the_symbol_goes_away() {
     let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(dir.join("docs/guide.md"), "# Guide\n\nCall [`add`](../src/lib.rs#add@exists).\n").unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("mode = \"exists\""), "lockfile should record the mode: {lock}");
 
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
-
-    // Lockfile should contain TypeScript references.
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(content.contains("app.ts"), "lockfile missing TypeScript refs");
-    assert!(content.contains("VERSION"), "lockfile missing VERSION symbol");
-    assert!(content.contains("greet"), "lockfile missing greet symbol");
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("x + A", "A + x * 2")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code(), Some(0), "{}", String::from_utf8_lossy(&check.stdout));
 
-    // Check should pass.
+    std::fs::write(&src, original.replace("fn add", "fn plus")).unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check failed: {}",
-        String::from_utf8_lossy(&check.stderr)
-    );
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(2), "{stdout}");
+    assert!(stdout.contains("BROKEN") && stdout.contains("#add"), "add should be broken: {stdout}");
 }
 
 #[test]
-fn markdown_heading_references() {
+fn absent_reference_breaks_when_symbol_reappears() {
     let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(
+        dir.join("docs/guide.md"),
+        "# Guide\n\nThe [`sub`](../src/lib.rs#sub \"docref: absent\") helper was removed.\n",
+    )
+    .unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("absent = true"), "lockfile should flag the assertion: {lock}");
+    assert_eq!(docref_at(&dir).arg("check").output().unwrap().status.code(), Some(0));
 
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
-
-    // Lockfile should contain the markdown-to-markdown ref.
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("overview.md"),
-        "lockfile missing markdown ref: {content}"
-    );
-    assert!(
-        content.contains("architecture"),
-        "lockfile missing heading symbol: {content}"
-    );
-
-    // Check passes.
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, format!("{original}\nfn sub(x: i32) -> i32 {{\n    x - A\n}}\n")).unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check failed: {}",
-        String::from_utf8_lossy(&check.stderr)
-    );
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(2), "{stdout}");
+    assert!(stdout.contains("symbol reappeared"), "should explain the break: {stdout}");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    let stderr = String::from_utf8_lossy(&init.stderr);
+    assert_eq!(init.status.code(), Some(3), "{stderr}");
+    assert!(stderr.contains("Symbol Still Present"), "{stderr}");
 }
 
 #[test]
-fn reformatting_does_not_break_check() {
+fn check_detects_broken_reference() {
     let (_tmp, dir) = isolated_fixture("basic");
     let src = dir.join("src/lib.rs");
 
     let original = std::fs::read_to_string(&src).unwrap();
 
     // Init.
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    docref_at(&dir).arg("init").output().unwrap();
 
-    // Reformat: add whitespace around parameters and operators.
-    let reformatted = original
-        .replace("fn add(x: i32) -> i32 {", "fn add( x: i32 ) -> i32 {")
-        .replace("x + A", "x  +  A");
-    std::fs::write(&src, &reformatted).unwrap();
+    // Remove the referenced symbol entirely.
+    let broken = original.replace("const A: i32 = 10;\n", "");
+    std::fs::write(&src, &broken).unwrap();
 
-    // Check should STILL pass (whitespace is normalized away).
+    // Check should fail with exit code 2 (broken).
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "whitespace change broke check: {}",
-        String::from_utf8_lossy(&check.stdout)
-    );
+    let code = check.status.code().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(code, 2, "expected exit 2 (broken), got {code}\nstdout: {stdout}");
+    assert!(stdout.contains("BROKEN"), "output should mention BROKEN: {stdout}");
 }
 
 #[test]
-fn comment_changes_do_not_break_check() {
+fn fix_rewrites_only_the_link_fragment() {
     let (_tmp, dir) = isolated_fixture("basic");
-    let src = dir.join("src/lib.rs");
+    let guide = dir.join("docs/guide.md");
+    let line = "The [`add`](../src/lib.rs#add \"see #add\") function; #add in prose stays.";
+    std::fs::write(&guide, format!("# Guide\n\n{line}\n")).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
 
+    let src = dir.join("src/lib.rs");
     let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("fn add(", "fn adds(")).unwrap();
 
-    // Init.
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
-
-    // Add a comment above a referenced symbol.
-    let commented =
-        original.replace("const A: i32 = 10;", "// base offset\nconst A: i32 = 10;");
-    std::fs::write(&src, &commented).unwrap();
-
-    // Check should still pass (comments are stripped from hash).
-    let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "comment change broke check: {}",
-        String::from_utf8_lossy(&check.stdout)
-    );
+    let fix = docref_at(&dir).args(["fix", "src/lib.rs#add", "adds"]).output().unwrap();
+    assert!(fix.status.success(), "fix failed: {}", String::from_utf8_lossy(&fix.stderr));
+    let fixed = std::fs::read_to_string(&guide).unwrap();
+    let expected = "The [`add`](../src/lib.rs#adds \"see #add\") function; #add in prose stays.";
+    assert!(fixed.contains(expected), "only the link fragment should change: {fixed}");
 }
 
 #[test]
-fn resolve_lists_symbols_in_rust_file() {
+fn fix_rewrites_link_path_when_symbol_moves_to_another_file() {
     let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(dir.join(".docref.toml"), "include = [\"docs/\"]\n\n[namespaces]\ncore = \"src\"\n").unwrap();
+    std::fs::write(dir.join("docs/api.md"), "The [`add`](core:lib.rs#add \"docref: sig\") helper.\n").unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
 
-    let output = docref_at(&dir)
-        .args(["resolve", "src/lib.rs"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains('A'), "should list constant A: {stdout}");
-    assert!(stdout.contains("add"), "should list function add: {stdout}");
+    // Move `add` unchanged into a new file.
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    let (constant, function) = original.split_once("\n\n").unwrap();
+    std::fs::write(&src, format!("{constant}\n")).unwrap();
+    std::fs::create_dir_all(dir.join("src/math")).unwrap();
+    std::fs::write(dir.join("src/math/ops.rs"), function).unwrap();
+
+    let fix = docref_at(&dir).arg("fix").output().unwrap();
+    assert!(fix.status.success(), "fix failed: {}", String::from_utf8_lossy(&fix.stderr));
+    let guide = std::fs::read_to_string(dir.join("docs/guide.md")).unwrap();
+    assert!(guide.contains("[`add`](../src/math/ops.rs#add)"), "relative path not rewritten: {guide}");
+    let api = std::fs::read_to_string(dir.join("docs/api.md")).unwrap();
+    assert!(api.contains("(core:math/ops.rs#add \"docref: sig\")"), "namespaced path not rewritten: {api}");
+
+    docref_at(&dir).arg("init").output().unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "check after fix: {}", String::from_utf8_lossy(&check.stdout));
 }
 
 #[test]
-fn resolve_finds_specific_symbol() {
+fn fix_dry_run_diff_prints_edits_without_writing() {
     let (_tmp, dir) = isolated_fixture("basic");
+    docref_at(&dir).arg("init").output().unwrap();
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("fn add(", "fn adds(")).unwrap();
+    let before = std::fs::read_to_string(dir.join("docs/guide.md")).unwrap();
 
-    let output = docref_at(&dir)
-        .args(["resolve", "src/lib.rs", "add"])
+    let fix = docref_at(&dir)
+        .args(["fix", "src/lib.rs#add", "adds", "--dry-run", "--diff"])
         .output()
         .unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(
-        stdout.contains("src/lib.rs#add"),
-        "should show full reference path: {stdout}"
-    );
+    assert!(fix.status.success(), "fix failed: {}", String::from_utf8_lossy(&fix.stderr));
+    let stdout = String::from_utf8_lossy(&fix.stdout);
+    assert!(stdout.contains("--- a/docs/guide.md\n+++ b/docs/guide.md\n@@"), "missing diff header: {stdout}");
+    assert!(stdout.contains("-The [`add`](../src/lib.rs#add) function"), "missing removed line: {stdout}");
+    assert!(stdout.contains("+The [`add`](../src/lib.rs#adds) function"), "missing added line: {stdout}");
+    assert_eq!(std::fs::read_to_string(dir.join("docs/guide.md")).unwrap(), before, "dry run must not write");
 }
 
 #[test]
-fn resolve_lists_markdown_headings() {
+fn check_exit_zero_reports_findings_without_failing() {
     let (_tmp, dir) = isolated_fixture("basic");
+    docref_at(&dir).arg("init").output().unwrap();
 
-    let output = docref_at(&dir)
-        .args(["resolve", "docs/overview.md"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(
-        stdout.contains("architecture"),
-        "should list architecture heading: {stdout}"
-    );
-    assert!(
-        stdout.contains("configuration"),
-        "should list configuration heading: {stdout}"
-    );
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;\n", "")).unwrap();
+
+    let check = docref_at(&dir).args(["check", "--exit-zero"]).output().unwrap();
+    assert_eq!(check.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&check.stdout).contains("BROKEN"));
 }
 
 #[test]
-fn status_shows_all_references() {
+fn runtime_errors_exit_three() {
     let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::remove_file(dir.join(".docref.lock")).unwrap();
 
-    // Init first to create lockfile.
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    let check = docref_at(&dir).args(["check", "--exit-zero"]).output().unwrap();
+    assert_eq!(check.status.code(), Some(3), "missing lockfile is a runtime error");
 
-    let output = docref_at(&dir).arg("status").output().unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let usage = docref_at(&dir).args(["check", "--no-such-flag"]).output().unwrap();
+    assert_eq!(usage.status.code(), Some(3), "invalid arguments are a runtime error");
 
-    // Should list all tracked references.
-    assert!(stdout.contains("lib.rs") && stdout.contains('A'), "missing A: {stdout}");
-    assert!(stdout.contains("lib.rs") && stdout.contains("add"), "missing add: {stdout}");
-    assert!(
-        stdout.contains("app.ts") && stdout.contains("VERSION"),
-        "missing VERSION: {stdout}"
-    );
+    let update = docref_at(&dir).arg("update").output().unwrap();
+    assert_eq!(update.status.code(), Some(3), "missing update target is a runtime error");
 }
 
 #[test]
-fn dotpath_resolves_impl_method() {
-    let (_tmp, dir) = isolated_fixture("scoped");
-
+fn unloadable_grammar_is_a_runtime_error() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(
+        dir.join(".docref.toml"),
+        "[grammars.hcl]\nlibrary = \"grammars/missing.so\"\nquery = \"(block) @declaration\"\n",
+    )
+    .unwrap();
+
+    // The library isn't loaded until a reference needs it.
+    let unused = docref_at(&dir).arg("init").output().unwrap();
+    assert!(unused.status.success(), "{}", String::from_utf8_lossy(&unused.stderr));
+
+    std::fs::write(dir.join("src/main.hcl"), "resource \"server\" \"web\" {}\n").unwrap();
+    std::fs::write(dir.join("docs/infra.md"), "# Infra\n\nThe [web](../src/main.hcl#web) block.\n").unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
+    let stderr = String::from_utf8_lossy(&init.stderr);
+    assert_eq!(init.status.code(), Some(3), "{stderr}");
+    assert!(stderr.contains("Grammar Not Loaded") && stderr.contains("missing.so"), "{stderr}");
+}
+
+#[test]
+fn check_max_severity_warning_reports_without_failing() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let check = docref_at(&dir)
+        .args(["check", "--max-severity", "warning"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(check.status.success(), "expected exit 0, got {:?}\nstdout: {stdout}", check.status);
+    assert!(stdout.contains("STALE"), "stale ref should still be reported: {stdout}");
+    assert!(stdout.contains("[warning]"), "stale ref should be marked as a warning: {stdout}");
+}
+
+#[test]
+fn check_severity_override_demotes_stale_by_path() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    std::fs::write(
+        dir.join(".docref.toml"),
+        "[[severity.override]]\npath = \"docs/\"\nstale = \"warning\"\n",
+    )
+    .unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "stale refs under docs/ should only warn");
+
+    std::fs::write(
+        dir.join(".docref.toml"),
+        "[[severity.override]]\npath = \"notes/\"\nstale = \"warning\"\n",
+    )
+    .unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1, "override for another path should not apply");
+}
+
+#[test]
+fn check_severity_override_ignores_archived_docs_only() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    std::fs::write(
+        dir.join(".docref.toml"),
+        "[severity]\nstale = \"warning\"\n\n[[severity.override]]\npath = \"docs/guide.md\"\nstale = \"ignore\"\n",
+    )
+    .unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(check.status.success(), "ignored stale ref should not fail: {stdout}");
+    assert!(!stdout.contains("STALE"), "ignored stale ref should not be reported: {stdout}");
+
+    std::fs::write(
+        dir.join(".docref.toml"),
+        "[[severity.override]]\npath = \"docs/\"\nstale = \"ignore\"\n\n\
+         [[severity.override]]\npath = \"docs/guide.md\"\nstale = \"error\"\n",
+    )
+    .unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1, "a later override should win");
+}
+
+#[test]
+fn check_baseline_tolerates_known_findings_only() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let write = docref_at(&dir).args(["baseline", "write"]).output().unwrap();
+    assert!(write.status.success(), "baseline write failed: {}", String::from_utf8_lossy(&write.stderr));
+    assert!(dir.join(".docref-baseline.toml").exists(), "baseline not created");
+
+    let check = docref_at(&dir).args(["check", "--baseline"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(check.status.success(), "baselined stale ref should not fail: {stdout}");
+    assert!(stdout.contains("[baseline]"), "baselined ref should still be listed: {stdout}");
+
+    // Without --baseline the same finding fails as before.
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+
+    // A finding that changes kind is no longer covered.
+    let broken = original.replace("const A: i32 = 10;\n", "");
+    std::fs::write(&src, broken).unwrap();
+    let check = docref_at(&dir).args(["check", "--baseline"]).output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 2);
+}
+
+#[test]
+fn check_fail_fast_stops_at_first_failure() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+    let modified = original
+        .replace("const A: i32 = 10;", "const A: i32 = 20;")
+        .replace("x + A", "x + A + 1");
+    std::fs::write(&src, modified).unwrap();
+
+    let check = docref_at(&dir).args(["check", "--fail-fast"]).output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(stdout.matches("STALE").count(), 1, "should stop after one failure: {stdout}");
+    let stderr = String::from_utf8_lossy(&check.stderr);
+    assert!(stderr.contains("Stopped after reaching the failure limit"), "missing truncation note: {stderr}");
+
+    let check = docref_at(&dir)
+        .args(["check", "--format", "json", "--max-failures", "1"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    assert_eq!(json["truncated"]["max_failures"], 1);
+    assert!(json["truncated"]["checked"].as_u64().unwrap() < json["truncated"]["total"].as_u64().unwrap());
+}
+
+#[test]
+fn check_orphaned_entry_fails_when_promoted_to_error() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    docref_at(&dir).arg("init").output().unwrap();
+    std::fs::remove_file(dir.join("docs/guide.md")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(check.status.success(), "orphaned entries only warn by default: {stdout}");
+    assert!(stdout.contains("ORPHAN"), "output should mention ORPHAN: {stdout}");
+
+    std::fs::write(dir.join(".docref.toml"), "[severity]\norphaned = \"error\"\n").unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 2);
+}
+
+#[test]
+fn init_reports_every_unresolved_reference_grouped_by_file() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let lockfile_before = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    std::fs::write(
+        dir.join("docs/broken.md"),
+        "[gone](../src/lib.rs#gone)\n\n[missing](../src/missing.rs#add)\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("docs/also.md"), "[nope](../src/app.ts#nope)\n").unwrap();
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(!init.status.success());
+    let stderr = String::from_utf8_lossy(&init.stderr);
+    assert!(stderr.contains("3 references could not be resolved"), "missing count: {stderr}");
+    assert!(stderr.contains("docs/also.md"), "missing also.md group: {stderr}");
+    assert!(stderr.contains("line 1: `src/lib.rs#gone`"), "missing gone: {stderr}");
+    assert!(stderr.contains("line 3: `src/missing.rs#add`"), "missing file: {stderr}");
+    let lockfile_after = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert_eq!(lockfile_before, lockfile_after, "lockfile should not be rewritten on failure");
+}
+
+#[test]
+fn update_updates_stale_reference() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+
+    // Init, then modify source.
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+    let modified = original.replace("const A: i32 = 10;", "const A: i32 = 20;");
+    std::fs::write(&src, &modified).unwrap();
+
+    // Check should be stale.
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+
+    // Update the specific reference.
+    let update = docref_at(&dir)
+        .args(["update", "src/lib.rs#A"])
+        .output()
+        .unwrap();
+    assert!(
+        update.status.success(),
+        "update failed: {}",
+        String::from_utf8_lossy(&update.stderr)
+    );
+
+    // Check should pass now.
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check still failing after update: {}",
+        String::from_utf8_lossy(&check.stdout)
+    );
+}
+
+#[test]
+fn update_all_stale_only_rehashes_changed_entries() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+    docref_at(&dir).arg("init").output().unwrap();
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let update = docref_at(&dir).args(["update", "--all", "--stale-only"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&update.stderr);
+    assert!(update.status.success(), "update failed: {stderr}");
+    assert!(stderr.contains("Updated 1 stale references"), "only A changed: {stderr}");
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "check after update: {}", String::from_utf8_lossy(&check.stdout));
+}
+
+#[test]
+fn stale_age_comes_from_git_history_and_grace_days_soften_check() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    let commit = |days_ago: u64| {
+        let date = format!("@{} +0000", now - days_ago * 86_400 - 3_600);
+        for args in [&["add", "-A"][..], &["commit", "-qm", "change"][..]] {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(&dir)
+                .env("GIT_AUTHOR_DATE", &date)
+                .env("GIT_COMMITTER_DATE", &date)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+    };
+    assert!(std::process::Command::new("git").args(["init", "-q"]).current_dir(&dir).status().unwrap().success());
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    commit(60);
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+    commit(40);
+
+    let status = docref_at(&dir).arg("status").output().unwrap();
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("STALE   src/lib.rs#A\n"), "age found without --ages: {stdout}");
+    let status = docref_at(&dir).args(["status", "--ages"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("STALE   src/lib.rs#A (stale 40 days)"), "{stdout}");
+
+    std::fs::write(dir.join(".docref.toml"), "[severity]\nstale_grace_days = 50\n").unwrap();
+    let within = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(within.status.code(), Some(0), "{}", String::from_utf8_lossy(&within.stdout));
+    assert!(String::from_utf8_lossy(&within.stdout).contains("(stale 40 days) [warning]"));
+
+    std::fs::write(dir.join(".docref.toml"), "[severity]\nstale_grace_days = 30\n").unwrap();
+    assert_eq!(docref_at(&dir).arg("check").output().unwrap().status.code(), Some(1));
+}
+
+/// Send one framed JSON-RPC request, or a notification when `id` is `None`.
+fn lsp_send(stdin: &mut impl std::io::Write, id: Option<u64>, method: &str, params: serde_json::Value) {
+    let mut message = serde_json::json!({"jsonrpc": "2.0", "method": method, "params": params});
+    if let Some(id) = id {
+        message["id"] = serde_json::Value::from(id);
+    }
+    let body = message.to_string();
+    write!(stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+    stdin.flush().unwrap();
+}
+
+/// Read framed messages from a language server until one matches.
+fn lsp_read_until(
+    stdout: &mut impl std::io::BufRead,
+    matches: impl Fn(&serde_json::Value) -> bool,
+) -> serde_json::Value {
+    loop {
+        let mut length = 0;
+        let mut header = String::new();
+        while stdout.read_line(&mut header).unwrap() > 0 && header != "\r\n" {
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                length = value.trim().parse().unwrap();
+            }
+            header.clear();
+        }
+        let mut body = vec![0; length];
+        stdout.read_exact(&mut body).unwrap();
+        let message: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        if matches(&message) {
+            return message;
+        }
+    }
+}
+
+#[test]
+fn lsp_publishes_diagnostics_and_quick_fixes() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let dir = dir.canonicalize().unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    let changed = original.replace("const A: i32 = 10;", "const A: i32 = 20;").replace("fn add(", "fn adds(");
+    std::fs::write(&src, changed).unwrap();
+
+    let mut child = docref_at(&dir)
+        .arg("lsp")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+    let is_response = |id: u64| return move |m: &serde_json::Value| return m["id"] == id;
+    let is_publish = |m: &serde_json::Value| return m["method"] == "textDocument/publishDiagnostics";
+
+    lsp_send(&mut stdin, Some(1), "initialize", serde_json::json!({"capabilities": {}}));
+    lsp_read_until(&mut stdout, is_response(1));
+    lsp_send(&mut stdin, None, "initialized", serde_json::json!({}));
+    let uri = format!("file://{}", dir.join("docs/guide.md").display());
+    let text = std::fs::read_to_string(dir.join("docs/guide.md")).unwrap();
+    let document = serde_json::json!({"uri": uri, "languageId": "markdown", "version": 1, "text": text});
+    lsp_send(&mut stdin, None, "textDocument/didOpen", serde_json::json!({"textDocument": document}));
+
+    let published = lsp_read_until(&mut stdout, is_publish);
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap().clone();
+    assert_eq!(diagnostics.len(), 2, "{published}");
+    let stale = diagnostics.iter().find(|d| return d["code"] == "stale").unwrap();
+    assert_eq!(stale["severity"], 2);
+    assert_eq!(stale["range"]["start"], serde_json::json!({"line": 2, "character": 19}));
+    let broken = diagnostics.iter().find(|d| return d["code"] == "broken").unwrap();
+    assert_eq!(broken["severity"], 1);
+
+    let range = serde_json::json!({"start": {"line": 0, "character": 0}, "end": {"line": 9, "character": 0}});
+    let context = serde_json::json!({"diagnostics": diagnostics});
+    let params = serde_json::json!({"textDocument": {"uri": uri}, "range": range, "context": context});
+    lsp_send(&mut stdin, Some(2), "textDocument/codeAction", params);
+    let actions = lsp_read_until(&mut stdout, is_response(2));
+    let actions = actions["result"].as_array().unwrap();
+    let rename = actions.iter().find(|a| return a["edit"].is_object()).unwrap();
+    let edit = &rename["edit"]["changes"][uri.as_str()][0];
+    assert_eq!(edit["newText"], "#adds");
+    assert_eq!(edit["range"]["start"], serde_json::json!({"line": 4, "character": 25}));
+    let accept = actions.iter().find(|a| return a["command"].is_object()).unwrap();
+    assert_eq!(accept["command"]["arguments"], serde_json::json!(["src/lib.rs#A"]));
+
+    let command = &accept["command"];
+    let params = serde_json::json!({"command": command["command"], "arguments": command["arguments"]});
+    lsp_send(&mut stdin, Some(3), "workspace/executeCommand", params);
+    let republished = lsp_read_until(&mut stdout, is_publish);
+    let remaining = republished["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(remaining.len(), 1, "{republished}");
+    assert_eq!(remaining[0]["code"], "broken");
+
+    lsp_send(&mut stdin, Some(4), "shutdown", serde_json::Value::Null);
+    lsp_read_until(&mut stdout, is_response(4));
+    lsp_send(&mut stdin, None, "exit", serde_json::Value::Null);
+    assert!(child.wait().unwrap().success());
+}
+
+/// Send a GET request to a `docref serve` at `addr` and return the status
+/// line and body.
+fn http_get(addr: &str, path: &str) -> (String, String) {
+    use std::io::{Read as _, Write as _};
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    return (head.lines().next().unwrap().to_string(), body.to_string());
+}
+
+#[test]
+fn serve_exposes_status_check_and_dashboard() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let mut child = docref_at(&dir)
+        .args(["serve", "--addr", "127.0.0.1:0"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut banner = String::new();
+    std::io::BufRead::read_line(&mut std::io::BufReader::new(child.stderr.take().unwrap()), &mut banner).unwrap();
+    let addr = banner.split("http://").nth(1).unwrap().split(',').next().unwrap().to_string();
+
+    let (status_line, body) = http_get(&addr, "/api/status");
+    assert!(status_line.contains("200"), "{status_line}");
+    let status: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(status["summary"]["stale"], 1);
+    let sources = status["summary"]["sources"].as_array().unwrap();
+    let guide = sources.iter().find(|s| return s["source"] == "docs/guide.md").unwrap();
+    assert_eq!(guide["stale"], 1);
+
+    let (_, body) = http_get(&addr, "/api/check");
+    let check: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(check["summary"]["stale"], 1);
+
+    let (status_line, body) = http_get(&addr, "/");
+    assert!(status_line.contains("200"), "{status_line}");
+    assert!(body.contains("docref freshness"), "{body}");
+    assert!(http_get(&addr, "/api/nope").0.contains("404"));
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[test]
+fn check_reuses_hash_cache_until_version_changes() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    assert!(docref_at(&dir).arg("check").output().unwrap().status.success());
+
+    // Poison every cached hash: a check that trusts the cache reports drift
+    // even though no file changed.
+    let cache_path = dir.join(".docref.cache");
+    let mut cache: serde_json::Value = serde_json::from_slice(&std::fs::read(&cache_path).unwrap()).unwrap();
+    let entries = cache["entries"].as_object_mut().unwrap();
+    assert!(!entries.is_empty());
+    for hash in entries.values_mut() {
+        *hash = serde_json::Value::from("0".repeat(64));
+    }
+    std::fs::write(&cache_path, serde_json::to_vec(&cache).unwrap()).unwrap();
+    let poisoned = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(poisoned.status.code(), Some(1), "{}", String::from_utf8_lossy(&poisoned.stdout));
+
+    // A cache from another docref or grammar version is discarded.
+    cache["version"] = serde_json::Value::from("0.0.0");
+    std::fs::write(&cache_path, serde_json::to_vec(&cache).unwrap()).unwrap();
+    assert!(docref_at(&dir).arg("check").output().unwrap().status.success());
+}
+
+#[test]
+fn workspace_status_rolls_up_member_projects() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    for member in ["api", "web"] {
+        let dir = root.join(member);
+        std::fs::create_dir_all(&dir).unwrap();
+        copy_dir_recursive(Path::new("tests/fixtures/basic"), &dir);
+        assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    }
+    let src = root.join("web/src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let output = docref_at(root).args(["workspace", "status"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("api  5/5 fresh (100%), 0 stale"), "{stdout}");
+    assert!(stdout.contains("web  4/5 fresh (80%), 1 stale"), "{stdout}");
+    assert!(stdout.contains("2 projects, 10 references: 9 fresh, 1 stale"), "{stdout}");
+
+    std::fs::write(root.join(".docref.toml"), "[workspace]\nmembers = [\"api\", \"missing\"]\n").unwrap();
+    let output = docref_at(root).args(["workspace", "status", "--format", "json"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(json["projects"][0]["path"], "api");
+    assert_eq!(json["projects"][0]["exit_code"], 0);
+    assert!(json["projects"][1]["error"].is_string(), "{json}");
+    assert_eq!(json["summary"]["total"], 5);
+}
+
+#[test]
+fn status_attributes_findings_to_codeowners() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::create_dir_all(dir.join(".github")).unwrap();
+    std::fs::write(dir.join(".github/CODEOWNERS"), "* @org/all\n/src/ @org/core\ndocs/ @org/docs\n").unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let text = docref_at(&dir).args(["status", "--by", "owner"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&text.stdout);
+    assert!(stdout.contains("STALE   src/lib.rs#A  owners: @org/core @org/docs"), "{stdout}");
+
+    let json = docref_at(&dir).args(["status", "--by", "owner", "--format", "json"]).output().unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&json.stdout).unwrap();
+    assert_eq!(report["summary"]["grouped_by"], "owner");
+    assert_eq!(report["summary"]["groups"]["@org/core"]["stale"], 1);
+    assert_eq!(report["summary"]["groups"]["@org/docs"]["stale"], 1);
+    assert!(report["summary"]["groups"].get("@org/all").is_none(), "{report}");
+    let stale = report["entries"].as_array().unwrap().iter().find(|e| e["status"] == "stale").unwrap();
+    assert_eq!(stale["code_owners"], serde_json::json!(["@org/core", "@org/docs"]));
+}
+
+#[test]
+fn lock_entry_metadata_survives_init_and_filters_status() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let lock_path = dir.join(".docref.lock");
+    docref_at(&dir).arg("init").output().unwrap();
+    let lock = std::fs::read_to_string(&lock_path).unwrap();
+    std::fs::write(&lock_path, lock.replace("symbol = \"add\"\n", "symbol = \"add\"\ntags = [\"public\"]\n")).unwrap();
+
+    let update = docref_at(&dir).args(["update", "src/lib.rs#add", "--note", "signature reviewed"]).output().unwrap();
+    assert!(update.status.success(), "update failed: {}", String::from_utf8_lossy(&update.stderr));
+    docref_at(&dir).arg("init").output().unwrap();
+
+    let status = docref_at(&dir).args(["status", "--format", "json", "--tag", "public"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&status.stdout).unwrap();
+    let entries = json["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1, "only the tagged entry: {json}");
+    assert_eq!(entries[0]["symbol"], "add");
+    assert_eq!(entries[0]["note"], "signature reviewed");
+    assert_eq!(entries[0]["tags"][0], "public");
+}
+
+#[test]
+fn update_records_acceptance_provenance_when_enabled() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(dir.join(".docref.toml"), "record_acceptance = true\n").unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(!lock.contains("accepted"), "init does not accept anything: {lock}");
+
+    let update = docref_at(&dir).args(["update", "src/lib.rs#add"]).env("USER", "reviewer").output().unwrap();
+    assert!(update.status.success(), "update failed: {}", String::from_utf8_lossy(&update.stderr));
+
+    let status = docref_at(&dir).args(["status", "--format", "json"]).output().unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&status.stdout).unwrap();
+    let entries = json["entries"].as_array().unwrap();
+    let add = entries.iter().find(|e| e["symbol"] == "add").unwrap();
+    assert!(add["accepted"]["at"].as_str().is_some_and(|at| at.ends_with('Z')), "timestamp: {add}");
+    assert!(add["accepted"]["by"].is_string(), "user: {add}");
+    let constant = entries.iter().find(|e| e["symbol"] == "A").unwrap();
+    assert!(constant.get("accepted").is_none(), "only the updated entry: {constant}");
+}
+
+#[test]
+fn init_rejects_targets_outside_root_unless_allowed() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let site = dir.join("site");
+    std::fs::create_dir_all(site.join("docs")).unwrap();
+    std::fs::write(site.join("docs/escape.md"), "[add](../../src/lib.rs#add)\n").unwrap();
+
+    let init = docref_at(&site).arg("init").output().unwrap();
+    let stderr = String::from_utf8_lossy(&init.stderr);
+    assert!(!init.status.success(), "escaping target must be rejected");
+    assert!(stderr.contains("resolves outside the project root"), "diagnostic names the problem: {stderr}");
+
+    std::fs::write(site.join(".docref.toml"), "allow_outside_root = true\n").unwrap();
+    let init = docref_at(&site).arg("init").output().unwrap();
+    assert!(init.status.success(), "opt-in allows it: {}", String::from_utf8_lossy(&init.stderr));
+}
+
+#[test]
+fn fix_rewrites_link_path_to_on_disk_case() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let case_md = dir.join("docs/case.md");
+    std::fs::write(&case_md, "The [add](../SRC/Lib.rs#add) function.\n").unwrap();
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    let stderr = String::from_utf8_lossy(&init.stderr);
+    assert!(stderr.contains("docs/case.md:1  SRC/Lib.rs differs in case from src/lib.rs"), "warns: {stderr}");
+
+    let fix = docref_at(&dir).arg("fix").output().unwrap();
+    assert!(fix.status.success(), "fix failed: {}", String::from_utf8_lossy(&fix.stderr));
+    let fixed = std::fs::read_to_string(&case_md).unwrap();
+    assert_eq!(fixed, "The [add](../src/lib.rs#add) function.\n");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init after fix: {}", String::from_utf8_lossy(&init.stderr));
+}
+
+#[test]
+fn info_topic_prints_a_single_section() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let output = docref_at(&dir).args(["info", "--topic", "languages"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.starts_with("## Supported Languages"), "only the section: {stdout}");
+    assert!(stdout.contains("| .md .markdown   | Markdown"), "grammars come from the registry: {stdout}");
+    assert!(!stdout.contains("## Commands"), "other sections omitted: {stdout}");
+
+    let examples = docref_at(&dir).args(["info", "--topic", "examples"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&examples.stdout).contains("docref fix --dry-run --diff"));
+
+    let unknown = docref_at(&dir).args(["info", "--topic", "nope"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&unknown.stderr).contains("expected one of: how-it-works"));
+}
+
+#[test]
+fn typescript_references_resolve_and_check() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    // Lockfile should contain TypeScript references.
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(content.contains("app.ts"), "lockfile missing TypeScript refs");
+    assert!(content.contains("VERSION"), "lockfile missing VERSION symbol");
+    assert!(content.contains("greet"), "lockfile missing greet symbol");
+
+    // Check should pass.
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
+}
+
+#[test]
+fn markdown_heading_references() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    // Lockfile should contain the markdown-to-markdown ref.
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("overview.md"),
+        "lockfile missing markdown ref: {content}"
+    );
+    assert!(
+        content.contains("architecture"),
+        "lockfile missing heading symbol: {content}"
+    );
+
+    // Check passes.
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
+}
+
+#[test]
+fn reformatting_does_not_break_check() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+
+    // Init.
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    // Reformat: add whitespace around parameters and operators.
+    let reformatted = original
+        .replace("fn add(x: i32) -> i32 {", "fn add( x: i32 ) -> i32 {")
+        .replace("x + A", "x  +  A");
+    std::fs::write(&src, &reformatted).unwrap();
+
+    // Check should STILL pass (whitespace is normalized away).
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "whitespace change broke check: {}",
+        String::from_utf8_lossy(&check.stdout)
+    );
+}
+
+#[test]
+fn comment_changes_do_not_break_check() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+
+    // Init.
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    // Add a comment above a referenced symbol.
+    let commented =
+        original.replace("const A: i32 = 10;", "// base offset\nconst A: i32 = 10;");
+    std::fs::write(&src, &commented).unwrap();
+
+    // Check should still pass (comments are stripped from hash).
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "comment change broke check: {}",
+        String::from_utf8_lossy(&check.stdout)
+    );
+}
+
+#[test]
+fn resolve_lists_symbols_in_rust_file() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let output = docref_at(&dir)
+        .args(["resolve", "src/lib.rs"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains('A'), "should list constant A: {stdout}");
+    assert!(stdout.contains("add"), "should list function add: {stdout}");
+}
+
+#[test]
+fn rust_mod_block_items_resolve_by_module_path() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+    std::fs::write(&src, "mod parsing {\n    pub fn run() -> u8 {\n        1\n    }\n}\n").unwrap();
+    let output = docref_at(&dir).args(["resolve", "src/lib.rs"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("parsing.run"), "should list the module item: {stdout}");
+
+    std::fs::write(dir.join("docs/guide.md"), "# Guide\n\n[`run`](../src/lib.rs#parsing.run) parses.\n").unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    std::fs::write(&src, "mod parsing {\n    pub fn run() -> u8 {\n        2\n    }\n}\n").unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("#parsing.run"), "the module item should be stale: {stdout}");
+}
+
+#[test]
+fn resolve_finds_specific_symbol() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let output = docref_at(&dir)
+        .args(["resolve", "src/lib.rs", "add"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("src/lib.rs#add"),
+        "should show full reference path: {stdout}"
+    );
+}
+
+#[test]
+fn resolve_explain_traces_candidates_and_byte_range() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let output = docref_at(&dir)
+        .args(["resolve", "src/lib.rs", "add", "--explain"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("grammar: rust"), "should name the grammar: {stdout}");
+    assert!(
+        stdout.contains("accepted  add  [source_file > function_item]"),
+        "should accept add with its node path: {stdout}"
+    );
+    assert!(
+        stdout.contains("rejected  A  [source_file > const_item]"),
+        "should reject A with a reason: {stdout}"
+    );
+    assert!(
+        stdout.contains("result: src/lib.rs#add at bytes 20..55 (lines 3-5)"),
+        "should report the final byte range: {stdout}"
+    );
+}
+
+#[test]
+fn resolve_lists_markdown_headings() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let output = docref_at(&dir)
+        .args(["resolve", "docs/overview.md"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("architecture"),
+        "should list architecture heading: {stdout}"
+    );
+    assert!(
+        stdout.contains("configuration"),
+        "should list configuration heading: {stdout}"
+    );
+}
+
+#[test]
+fn github_slug_style_matches_github_anchors() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(dir.join(".docref.toml"), "slug_style = \"github\"\n").unwrap();
+    std::fs::write(
+        dir.join("docs/changes.md"),
+        "# Changes\n\n## What's New?\n\nA.\n\n## Example\n\nB.\n\n## Example\n\nC.\n",
+    )
+    .unwrap();
+
+    let output = docref_at(&dir)
+        .args(["resolve", "docs/changes.md"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("#whats-new"), "should use GitHub slug: {stdout}");
+    assert!(stdout.contains("#example-1"), "should suffix duplicate: {stdout}");
+}
+
+#[test]
+fn status_shows_all_references() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    // Init first to create lockfile.
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let output = docref_at(&dir).arg("status").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Should list all tracked references.
+    assert!(stdout.contains("lib.rs") && stdout.contains('A'), "missing A: {stdout}");
+    assert!(stdout.contains("lib.rs") && stdout.contains("add"), "missing add: {stdout}");
+    assert!(
+        stdout.contains("app.ts") && stdout.contains("VERSION"),
+        "missing VERSION: {stdout}"
+    );
+}
+
+#[test]
+fn dotpath_resolves_impl_method() {
+    let (_tmp, dir) = isolated_fixture("scoped");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("Config.validate"),
+        "lockfile missing Config.validate: {content}"
+    );
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success());
+}
+
+#[test]
+fn dotpath_resolves_scoped_heading() {
+    let (_tmp, dir) = isolated_fixture("scoped");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("foo.example"),
+        "lockfile missing foo.example: {content}"
+    );
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success());
+}
+
+#[test]
+fn ambiguous_bare_symbol_errors_with_candidates() {
+    let (_tmp, dir) = isolated_fixture("scoped");
+
+    // "example" is ambiguous — two ### Example headings under different parents.
+    let output = docref_at(&dir)
+        .args(["resolve", "docs/overview.md", "example"])
+        .output()
+        .unwrap();
+
+    assert!(
+        !output.status.success(),
+        "should fail on ambiguous symbol"
+    );
+
+    // Error output should suggest qualified dot-paths.
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("foo.example") && stderr.contains("bar.example"),
+        "should suggest qualified candidates: {stderr}"
+    );
+}
+
+#[test]
+fn namespaced_references_resolve_and_check() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    // Lockfile should contain the namespace-prefixed target.
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("auth:src/lib.rs"),
+        "lockfile should preserve namespace form: {content}"
+    );
+    assert!(
+        content.contains("validate"),
+        "lockfile should contain validate symbol: {content}"
+    );
+    // Also contains the local non-namespaced reference.
+    assert!(
+        content.contains("\"src/lib.rs\""),
+        "lockfile should contain local ref: {content}"
+    );
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
+}
+
+#[test]
+fn namespaced_reference_detects_stale() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    // Modify the namespaced target.
+    let auth_src = dir.join("services/auth/src/lib.rs");
+    std::fs::write(&auth_src, "pub fn validate(input: &str) -> bool {\n    input.len() > 3\n}\n").unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let code = check.status.code().unwrap();
+    assert_eq!(code, 1, "expected stale after modifying namespaced target");
+}
+
+#[test]
+fn config_excludes_directories() {
+    let (_tmp, dir) = isolated_fixture("configured");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("guide.md"),
+        "should include guide.md: {content}"
+    );
+    assert!(
+        !content.contains("ignored.md"),
+        "should exclude docs/external/: {content}"
+    );
+}
+
+#[test]
+fn extends_inherits_parent_namespaces() {
+    let (_tmp, dir) = isolated_fixture("monorepo");
+
+    // Run docref from the sub-project directory.
+    let web_dir = dir.join("services/web");
+    let init = docref_at(&web_dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    let content = std::fs::read_to_string(web_dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("shared:src/lib.rs"),
+        "lockfile should use inherited namespace: {content}"
+    );
+    assert!(
+        content.contains("greet"),
+        "lockfile should contain greet symbol: {content}"
+    );
+
+    let check = docref_at(&web_dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
+}
+
+#[test]
+fn man_writes_pages_and_markdown_reference() {
+    let tmp = TempDir::new().unwrap();
+
+    let output = docref_at(tmp.path()).args(["man", "--out-dir", "out"]).output().unwrap();
+    assert!(output.status.success(), "man failed: {}", String::from_utf8_lossy(&output.stderr));
+    let out = tmp.path().join("out");
+    assert!(out.join("docref.1").exists());
+    assert!(out.join("docref-check.1").exists());
+    assert!(!out.join("docref-man.1").exists(), "hidden man command should not get a page");
+    let markdown = std::fs::read_to_string(out.join("docref.md")).unwrap();
+    assert!(markdown.contains("docref check"), "markdown should cover subcommands: {markdown}");
+}
+
+#[test]
+fn namespace_list_shows_configured_namespaces() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let output = docref_at(&dir)
+        .args(["namespace", "list"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("auth"),
+        "should list auth namespace: {stdout}"
+    );
+    assert!(
+        stdout.contains("services/auth"),
+        "should show namespace path: {stdout}"
+    );
+}
+
+#[test]
+fn namespace_list_json_reports_origin_and_reference_counts() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let output = docref_at(&dir)
+        .args(["namespace", "list", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    let auth = json["namespaces"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|n| n["name"] == "auth")
+        .unwrap();
+    assert_eq!(auth["inherited"], false);
+    assert_eq!(auth["config_file"], ".docref.toml");
+    assert!(auth["references"].as_u64().unwrap() > 0, "auth should have references: {stdout}");
+    assert!(Path::new(auth["absolute_path"].as_str().unwrap()).is_absolute());
+}
+
+#[test]
+fn namespace_list_fails_on_a_corrupt_lockfile() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+    std::fs::write(dir.join(".docref.lock"), "not = [valid").unwrap();
+
+    let output = docref_at(&dir).args(["namespace", "list"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "a corrupt lockfile should not read as empty: {stderr}");
+}
+
+#[test]
+fn unknown_namespace_suggests_closest_configured_name() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let output = docref_at(&dir)
+        .args(["update", "auht:src/lib.rs#validate"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Did you mean `auth:`?"), "missing suggestion: {stderr}");
+    assert!(stderr.contains("- `auth`"), "missing configured list: {stderr}");
+}
+
+#[test]
+fn namespace_rename_updates_config_lockfile_and_markdown() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let output = docref_at(&dir)
+        .args(["namespace", "rename", "auth", "authn"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "rename failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config_content = std::fs::read_to_string(dir.join(".docref.toml")).unwrap();
+    assert!(
+        config_content.contains("authn"),
+        "config missing authn: {config_content}"
+    );
+
+    let lock_content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        lock_content.contains("authn:src/lib.rs"),
+        "lockfile missing authn: {lock_content}"
+    );
+    assert!(
+        !lock_content.contains("auth:src/lib.rs"),
+        "lockfile still has old auth: {lock_content}"
+    );
+
+    let md_content = std::fs::read_to_string(dir.join("docs/guide.md")).unwrap();
+    assert!(
+        md_content.contains("authn:src/lib.rs"),
+        "markdown missing authn: {md_content}"
+    );
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check failed after rename: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
+}
+
+#[test]
+fn namespace_rename_leaves_quoted_prose_alone_without_shortcodes() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+    let config = std::fs::read_to_string(dir.join(".docref.toml")).unwrap();
+    std::fs::write(dir.join(".docref.toml"), format!("shortcode = \"\"\n\n{config}")).unwrap();
+    let guide = dir.join("docs/guide.md");
+    let original = std::fs::read_to_string(&guide).unwrap();
+    let prose = "Log in with \"auth:admin\" and see {{< ref \"auth:src/lib.rs#validate\" >}}.\n";
+    std::fs::write(&guide, format!("{original}\n{prose}")).unwrap();
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let output = docref_at(&dir).args(["namespace", "rename", "auth", "authn"]).output().unwrap();
+    assert!(output.status.success(), "rename failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let md_content = std::fs::read_to_string(&guide).unwrap();
+    assert!(md_content.contains("](authn:src/lib.rs#validate)"), "link not rewritten: {md_content}");
+    assert!(md_content.contains(prose), "prose or disabled shortcode rewritten: {md_content}");
+}
+
+#[test]
+fn namespace_remove_refuses_with_active_references() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let output = docref_at(&dir)
+        .args(["namespace", "remove", "auth"])
+        .output()
+        .unwrap();
+    assert!(
+        !output.status.success(),
+        "remove should fail with active references"
+    );
+}
+
+#[test]
+fn namespace_remove_force_succeeds() {
+    let (_tmp, dir) = isolated_fixture("namespaced");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let output = docref_at(&dir)
+        .args(["namespace", "remove", "auth", "--force"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "remove --force failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let config = std::fs::read_to_string(dir.join(".docref.toml")).unwrap();
+    assert!(!config.contains("auth"), "config still has auth: {config}");
+
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        !lock.contains("auth:"),
+        "lockfile still has auth refs: {lock}"
+    );
+}
+
+#[test]
+fn namespace_add_creates_mapping() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    // basic fixture has no .docref.toml — create a minimal one.
+    std::fs::write(dir.join(".docref.toml"), "").unwrap();
+
+    let output = docref_at(&dir)
+        .args(["namespace", "add", "mylib", "packages/mylib"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "namespace add failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let content = std::fs::read_to_string(dir.join(".docref.toml")).unwrap();
+    assert!(
+        content.contains("mylib"),
+        "config should contain namespace: {content}"
+    );
+    assert!(
+        content.contains("packages/mylib"),
+        "config should contain path: {content}"
+    );
+
+    let list = docref_at(&dir)
+        .args(["namespace", "list"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&list.stdout);
+    assert!(
+        stdout.contains("mylib"),
+        "list should show added namespace: {stdout}"
+    );
+}
+
+#[test]
+fn update_from_file_updates_all_refs_in_doc() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+
+    // Init, then modify both referenced symbols.
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let modified = original
+        .replace("const A: i32 = 10;", "const A: i32 = 99;")
+        .replace("x + A", "x * A");
+    std::fs::write(&src, &modified).unwrap();
+
+    // Both A and add should be stale.
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1, "expected stale");
+
+    // Update all refs originating from guide.md.
+    let update = docref_at(&dir)
+        .args(["update", "--from", "docs/guide.md"])
+        .output()
+        .unwrap();
+    assert!(
+        update.status.success(),
+        "update --from failed: {}",
+        String::from_utf8_lossy(&update.stderr)
+    );
+
+    // Check should pass now — guide.md's refs are updated,
+    // and api.md's refs (app.ts) were never stale.
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check still failing after update --from: {}",
+        String::from_utf8_lossy(&check.stdout)
+    );
+}
+
+// --- Sub-declaration tests ---
+
+#[test]
+fn subdecl_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
+}
+
+#[test]
+fn subdecl_struct_field_in_lockfile() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("Config.host"),
+        "lockfile missing Config.host: {content}"
+    );
+}
+
+#[test]
+fn subdecl_enum_variant_in_lockfile() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("Message.Quit"),
+        "lockfile missing Message.Quit: {content}"
+    );
+}
+
+#[test]
+fn subdecl_trait_method_in_lockfile() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("Handler.handle"),
+        "lockfile missing Handler.handle: {content}"
+    );
+}
+
+#[test]
+fn subdecl_ts_interface_prop_in_lockfile() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("ServerConfig.host"),
+        "lockfile missing ServerConfig.host: {content}"
+    );
+}
+
+#[test]
+fn subdecl_ts_class_method_in_lockfile() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(
+        content.contains("App.render"),
+        "lockfile missing App.render: {content}"
+    );
+}
+
+#[test]
+fn subdecl_ts_enum_member_in_lockfile() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
 
     let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
     assert!(
-        content.contains("Config.validate"),
-        "lockfile missing Config.validate: {content}"
+        content.contains("Status.Active"),
+        "lockfile missing Status.Active: {content}"
+    );
+    assert!(
+        content.contains("Direction.Up"),
+        "lockfile missing Direction.Up: {content}"
+    );
+}
+
+#[test]
+fn subdecl_field_change_detected_stale() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    // Change the type of the host field.
+    let modified = original.replace("host: String", "host: Vec<u8>");
+    std::fs::write(&src, &modified).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let code = check.status.code().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(code, 1, "expected exit 1 (stale), got {code}\nstdout: {stdout}");
+}
+
+#[test]
+fn subdecl_resolve_lists_sub_symbols() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+
+    let output = docref_at(&dir)
+        .args(["resolve", "src/lib.rs"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Config.host"), "missing Config.host: {stdout}");
+    assert!(stdout.contains("Message.Quit"), "missing Message.Quit: {stdout}");
+    assert!(stdout.contains("Handler.handle"), "missing Handler.handle: {stdout}");
+}
+
+#[test]
+fn subdecl_field_removal_detected_broken() {
+    let (_tmp, dir) = isolated_fixture("subdecl");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    // Remove the host field entirely.
+    let broken = original.replace("    host: String,\n", "");
+    std::fs::write(&src, &broken).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let code = check.status.code().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(code, 2, "expected exit 2 (broken), got {code}\nstdout: {stdout}");
+}
+
+// --- Whole-file reference tests ---
+
+#[test]
+fn wholefile_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("wholefile");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    // Whole-file entry: symbol is empty string.
+    assert!(
+        lock.contains("symbol = \"\""),
+        "lockfile missing whole-file entry: {lock}"
+    );
+    // Symbol-scoped entry should still be present.
+    assert!(
+        lock.contains("symbol = \"add\""),
+        "lockfile missing add entry: {lock}"
+    );
+    // png link should NOT produce an entry.
+    assert!(
+        !lock.contains("photo.png"),
+        "lockfile should not track png: {lock}"
+    );
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
+}
+
+#[test]
+fn wholefile_detects_stale_after_file_change() {
+    let (_tmp, dir) = isolated_fixture("wholefile");
+    let src = dir.join("src/lib.rs");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    // Modify the file content.
+    let original = std::fs::read_to_string(&src).unwrap();
+    let modified = original.replace("x + y", "x * y");
+    std::fs::write(&src, &modified).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let code = check.status.code().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(code, 1, "expected stale, got {code}\nstdout: {stdout}");
+    assert!(stdout.contains("STALE"), "expected STALE in: {stdout}");
+}
+
+#[test]
+fn wholefile_plain_text_tracked_by_raw_content() {
+    let (_tmp, dir) = isolated_fixture("wholefile");
+    std::fs::create_dir_all(dir.join("fixtures")).unwrap();
+    std::fs::write(dir.join("fixtures/data.csv"), "id,name\n1,alpha\n").unwrap();
+    let guide = dir.join("docs/guide.md");
+    let mut content = std::fs::read_to_string(&guide).unwrap();
+    content.push_str("\nThe [sample data](../fixtures/data.csv) has two columns.\n");
+    std::fs::write(&guide, content).unwrap();
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(
+        init.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&init.stderr)
+    );
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("fixtures/data.csv"), "lockfile missing csv entry: {lock}");
+
+    // Line-ending changes alone don't make the reference stale.
+    std::fs::write(dir.join("fixtures/data.csv"), "id,name\r\n1,alpha\r\n").unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(
+        check.status.success(),
+        "CRLF conversion should stay fresh: {}",
+        String::from_utf8_lossy(&check.stdout)
+    );
+
+    std::fs::write(dir.join("fixtures/data.csv"), "id,name\n1,beta\n").unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code().unwrap(), 1, "expected stale: {stdout}");
+    assert!(stdout.contains("STALE   fixtures/data.csv"), "expected csv stale: {stdout}");
+}
+
+#[test]
+fn wholefile_update_bare_path() {
+    let (_tmp, dir) = isolated_fixture("wholefile");
+    let src = dir.join("src/lib.rs");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    // Modify and make stale.
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("x + y", "x * y")).unwrap();
+
+    // Update with bare path (no #symbol).
+    let update = docref_at(&dir)
+        .args(["update", "src/lib.rs"])
+        .output()
+        .unwrap();
+    assert!(
+        update.status.success(),
+        "update bare path failed: {}",
+        String::from_utf8_lossy(&update.stderr)
     );
 
+    // The whole-file ref should be fresh now, but add is still stale.
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(check.status.success());
+    let code = check.status.code().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(code, 1, "expected stale (add still changed), got {code}\nstdout: {stdout}");
+    // Verify the whole-file entry is no longer listed as stale.
+    let stale_lines: Vec<&str> = stdout.lines().filter(|l| l.starts_with("STALE")).collect();
+    assert_eq!(stale_lines.len(), 1, "expected 1 stale ref (add), got: {stale_lines:?}");
 }
 
 #[test]
-fn dotpath_resolves_scoped_heading() {
-    let (_tmp, dir) = isolated_fixture("scoped");
+fn wholefile_status_display() {
+    let (_tmp, dir) = isolated_fixture("wholefile");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let status = docref_at(&dir).arg("status").output().unwrap();
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    // Whole-file ref should show without # suffix.
+    assert!(
+        stdout.contains("src/lib.rs\n") || stdout.contains("src/lib.rs\r"),
+        "status should show bare file path without #: {stdout}"
+    );
+    // Symbol ref should still show with #.
+    assert!(
+        stdout.contains("src/lib.rs#add"),
+        "status should show symbol ref: {stdout}"
+    );
+}
+
+// --- JavaScript / JSX tests ---
+
+#[test]
+fn javascript_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("javascript");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(
@@ -346,338 +2029,442 @@ fn dotpath_resolves_scoped_heading() {
         String::from_utf8_lossy(&init.stderr)
     );
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("foo.example"),
-        "lockfile missing foo.example: {content}"
-    );
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("app.js"), "lockfile missing JS refs: {lock}");
+    assert!(lock.contains("VERSION"), "lockfile missing VERSION: {lock}");
+    assert!(lock.contains("greet"), "lockfile missing greet: {lock}");
+    assert!(lock.contains("App"), "lockfile missing App: {lock}");
+    assert!(lock.contains("App.render"), "lockfile missing App.render: {lock}");
 
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(check.status.success());
+    assert!(
+        check.status.success(),
+        "check failed: {}",
+        String::from_utf8_lossy(&check.stderr)
+    );
 }
 
 #[test]
-fn ambiguous_bare_symbol_errors_with_candidates() {
-    let (_tmp, dir) = isolated_fixture("scoped");
+fn javascript_resolve_lists_symbols() {
+    let (_tmp, dir) = isolated_fixture("javascript");
 
-    // "example" is ambiguous — two ### Example headings under different parents.
     let output = docref_at(&dir)
-        .args(["resolve", "docs/overview.md", "example"])
+        .args(["resolve", "src/app.js"])
         .output()
         .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("VERSION"), "missing VERSION: {stdout}");
+    assert!(stdout.contains("greet"), "missing greet: {stdout}");
+    assert!(stdout.contains("App"), "missing App: {stdout}");
+}
 
-    assert!(
-        !output.status.success(),
-        "should fail on ambiguous symbol"
-    );
+// --- JSON format tests ---
 
-    // Error output should suggest qualified dot-paths.
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(
-        stderr.contains("foo.example") && stderr.contains("bar.example"),
-        "should suggest qualified candidates: {stderr}"
-    );
+#[test]
+fn check_json_output_all_fresh() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let check = docref_at(&dir)
+        .args(["check", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(check.status.success());
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    assert_eq!(json["summary"]["broken"], 0);
+    assert_eq!(json["summary"]["stale"], 0);
+    assert!(json["summary"]["fresh"].as_u64().unwrap() > 0);
+    assert!(json["entries"].as_array().unwrap().len() > 0);
 }
 
 #[test]
-fn namespaced_references_resolve_and_check() {
-    let (_tmp, dir) = isolated_fixture("namespaced");
+fn check_markdown_output_lists_findings_for_a_pr_comment() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
 
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
+    assert!(init.status.success());
 
-    // Lockfile should contain the namespace-prefixed target.
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("auth:src/lib.rs"),
-        "lockfile should preserve namespace form: {content}"
-    );
-    assert!(
-        content.contains("validate"),
-        "lockfile should contain validate symbol: {content}"
-    );
-    // Also contains the local non-namespaced reference.
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let check = docref_at(&dir)
+        .args(["check", "--format", "markdown", "--link-base", "https://example.com/repo/blob/abc/"])
+        .env_remove("GITHUB_SHA")
+        .output()
+        .unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(stdout.starts_with("### docref: 0 broken, 1 stale\n"), "{stdout}");
+    assert!(stdout.contains("<details>"), "{stdout}");
     assert!(
-        content.contains("\"src/lib.rs\""),
-        "lockfile should contain local ref: {content}"
+        stdout.contains("| [`src/lib.rs#A`](https://example.com/repo/blob/abc/src/lib.rs) | [`docs/guide.md`]"),
+        "{stdout}"
     );
+    assert!(stdout.contains("docref update src/lib.rs#A\n"), "{stdout}");
+    assert!(!stdout.contains("#### Broken"), "{stdout}");
+}
 
-    let check = docref_at(&dir).arg("check").output().unwrap();
+#[test]
+fn check_markdown_output_suggests_each_update_once() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+    let api = dir.join("docs/api.md");
+    let docs = std::fs::read_to_string(&api).unwrap();
+    std::fs::write(&api, format!("{docs}\nIt adds [`A`](../src/lib.rs#A) via [`add`](../src/lib.rs#add).\n")).unwrap();
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+    std::fs::write(&src, original.replace("10", "20").replace("x + A", "A + x")).unwrap();
+
+    let check = docref_at(&dir).args(["check", "--format", "markdown"]).output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(stdout.matches("docref update src/lib.rs#A\n").count(), 1, "{stdout}");
+    assert_eq!(stdout.matches("docref update src/lib.rs#add\n").count(), 1, "{stdout}");
+}
+
+#[test]
+fn check_sarif_output_locates_findings_in_markdown() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let check = docref_at(&dir).args(["check", "--format", "sarif"]).output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+    let log: serde_json::Value = serde_json::from_slice(&check.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "docref");
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1, "{results:?}");
+    assert_eq!(results[0]["ruleId"], "stale");
+    assert_eq!(results[0]["level"], "error");
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "docs/guide.md");
+    assert_eq!(location["region"]["startLine"], 3);
+    assert!(results[0]["message"]["text"].as_str().unwrap().contains("docref update src/lib.rs#A"));
+}
+
+#[test]
+fn check_junit_output_has_one_case_per_entry() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let check = docref_at(&dir).args(["check", "--format", "junit"]).output().unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(stdout.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"), "{stdout}");
+    assert!(stdout.contains("<testsuite name=\"docref\" tests=\"5\" failures=\"1\">"), "{stdout}");
+    assert_eq!(stdout.matches("<testcase ").count(), 5, "{stdout}");
+    assert!(stdout.contains("<testcase classname=\"docs/guide.md\" name=\"src/lib.rs#add\""), "{stdout}");
     assert!(
-        check.status.success(),
-        "check failed: {}",
-        String::from_utf8_lossy(&check.stderr)
+        stdout.contains("<failure type=\"stale\" message=\"docs/guide.md:3: src/lib.rs#A changed since"),
+        "{stdout}"
     );
 }
 
 #[test]
-fn namespaced_reference_detects_stale() {
-    let (_tmp, dir) = isolated_fixture("namespaced");
+fn check_json_output_stale() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
 
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    // Modify the namespaced target.
-    let auth_src = dir.join("services/auth/src/lib.rs");
-    std::fs::write(&auth_src, "pub fn validate(input: &str) -> bool {\n    input.len() > 3\n}\n").unwrap();
+    let modified = original.replace("const A: i32 = 10;", "const A: i32 = 20;");
+    std::fs::write(&src, &modified).unwrap();
+
+    let check = docref_at(&dir)
+        .args(["check", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(check.status.code().unwrap(), 1);
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    assert!(json["summary"]["stale"].as_u64().unwrap() > 0);
+    let entries = json["entries"].as_array().unwrap();
+    assert!(entries.iter().any(|e| e["status"] == "stale"));
+}
+
+#[test]
+fn check_json_broken_includes_reason() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+
+    let broken = original.replace("const A: i32 = 10;\n", "");
+    std::fs::write(&src, &broken).unwrap();
+
+    let check = docref_at(&dir)
+        .args(["check", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(check.status.code().unwrap(), 2);
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    assert!(json["summary"]["broken"].as_u64().unwrap() > 0);
+    let entries = json["entries"].as_array().unwrap();
+    let broken_entry = entries.iter().find(|e| e["status"] == "broken").unwrap();
+    assert!(broken_entry["reason"].as_str().unwrap().len() > 0);
+}
+
+#[test]
+fn status_summary_reports_counts_and_per_source_rollup() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let original = std::fs::read_to_string(&src).unwrap();
+    docref_at(&dir).arg("init").output().unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let output = docref_at(&dir).args(["status", "--summary-only"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("STALE"), "--summary-only should omit entry lines: {stdout}");
+    assert!(stdout.contains("1 stale"), "summary should count stale refs: {stdout}");
+    assert!(stdout.contains("docs/guide.md"), "summary should roll up by source: {stdout}");
+
+    let output = docref_at(&dir)
+        .args(["status", "--format", "json", "--summary-only"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    assert!(json.get("entries").is_none(), "--summary-only should omit entries: {stdout}");
+    assert_eq!(json["summary"]["stale"], 1);
+    let guide = json["summary"]["sources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["source"] == "docs/guide.md")
+        .unwrap();
+    assert_eq!(guide["stale"], 1);
+    assert!(guide["percent_fresh"].as_u64().unwrap() < 100);
+}
+
+#[test]
+fn status_by_groups_summary_by_language_and_team() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(dir.join(".docref.toml"), "[teams]\nfrontend = [\"src/app.ts\"]\n").unwrap();
+    let src = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&src).unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+
+    let output = docref_at(&dir).args(["status", "--summary-only", "--by", "language"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let breakdown = stdout.split("By language:").nth(1).unwrap_or_else(|| panic!("no breakdown: {stdout}"));
+    let rust = breakdown.find("rust").unwrap();
+    let typescript = breakdown.find("typescript  2/2 fresh (100%)").unwrap();
+    assert!(rust < typescript, "worst-drifting language should come first: {stdout}");
 
-    let check = docref_at(&dir).arg("check").output().unwrap();
-    let code = check.status.code().unwrap();
-    assert_eq!(code, 1, "expected stale after modifying namespaced target");
+    let output = docref_at(&dir)
+        .args(["status", "--format", "json", "--summary-only", "--by", "team"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    assert_eq!(json["summary"]["grouped_by"], "team");
+    assert_eq!(json["summary"]["groups"]["frontend"]["fresh"], 2);
+    assert_eq!(json["summary"]["groups"]["(unowned)"]["stale"], 1);
 }
 
 #[test]
-fn config_excludes_directories() {
-    let (_tmp, dir) = isolated_fixture("configured");
-
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
+fn check_output_writes_json_report_with_metadata() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    docref_at(&dir).arg("init").output().unwrap();
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("guide.md"),
-        "should include guide.md: {content}"
-    );
-    assert!(
-        !content.contains("ignored.md"),
-        "should exclude docs/external/: {content}"
-    );
+    let check = docref_at(&dir).args(["check", "--output", "report.json"]).output().unwrap();
+    assert!(check.status.success());
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert!(!stdout.contains("\"metadata\""), "stdout should keep the text report: {stdout}");
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("report.json")).unwrap()).unwrap();
+    assert_eq!(report["metadata"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(report["metadata"]["timestamp"].as_str().unwrap().ends_with('Z'), "timestamp: {report}");
+    assert!(Path::new(report["metadata"]["root"].as_str().unwrap()).is_absolute(), "root: {report}");
+    assert!(report["summary"].is_object(), "summary missing: {report}");
 }
 
 #[test]
-fn extends_inherits_parent_namespaces() {
-    let (_tmp, dir) = isolated_fixture("monorepo");
-
-    // Run docref from the sub-project directory.
-    let web_dir = dir.join("services/web");
-    let init = docref_at(&web_dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
+fn status_json_output() {
+    let (_tmp, dir) = isolated_fixture("basic");
 
-    let content = std::fs::read_to_string(web_dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("shared:src/lib.rs"),
-        "lockfile should use inherited namespace: {content}"
-    );
-    assert!(
-        content.contains("greet"),
-        "lockfile should contain greet symbol: {content}"
-    );
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
 
-    let check = docref_at(&web_dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check failed: {}",
-        String::from_utf8_lossy(&check.stderr)
-    );
+    let status = docref_at(&dir)
+        .args(["status", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(status.status.success());
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    let entries = json["entries"].as_array().unwrap();
+    assert!(entries.len() > 0);
+    // Status entries should have a hash field.
+    let first = &entries[0];
+    assert!(first["hash"].as_str().unwrap().len() > 0);
+    assert!(first["status"].as_str().is_some());
 }
 
+// --- Refs (reverse lookup) tests ---
+
 #[test]
-fn namespace_list_shows_configured_namespaces() {
-    let (_tmp, dir) = isolated_fixture("namespaced");
+fn refs_shows_all_docs_for_file() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
 
     let output = docref_at(&dir)
-        .args(["namespace", "list"])
+        .args(["refs", "src/lib.rs"])
         .output()
         .unwrap();
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(
-        stdout.contains("auth"),
-        "should list auth namespace: {stdout}"
-    );
-    assert!(
-        stdout.contains("services/auth"),
-        "should show namespace path: {stdout}"
-    );
+    assert!(stdout.contains("docs/guide.md"), "missing guide.md source: {stdout}");
 }
 
 #[test]
-fn namespace_rename_updates_config_lockfile_and_markdown() {
-    let (_tmp, dir) = isolated_fixture("namespaced");
+fn refs_filters_by_symbol() {
+    let (_tmp, dir) = isolated_fixture("basic");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
     let output = docref_at(&dir)
-        .args(["namespace", "rename", "auth", "authn"])
+        .args(["refs", "src/lib.rs#add"])
         .output()
         .unwrap();
-    assert!(
-        output.status.success(),
-        "rename failed: {}",
-        String::from_utf8_lossy(&output.stderr)
-    );
-
-    let config_content = std::fs::read_to_string(dir.join(".docref.toml")).unwrap();
-    assert!(
-        config_content.contains("authn"),
-        "config missing authn: {config_content}"
-    );
-
-    let lock_content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        lock_content.contains("authn:src/lib.rs"),
-        "lockfile missing authn: {lock_content}"
-    );
-    assert!(
-        !lock_content.contains("auth:src/lib.rs"),
-        "lockfile still has old auth: {lock_content}"
-    );
-
-    let md_content = std::fs::read_to_string(dir.join("docs/guide.md")).unwrap();
-    assert!(
-        md_content.contains("authn:src/lib.rs"),
-        "markdown missing authn: {md_content}"
-    );
-
-    let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check failed after rename: {}",
-        String::from_utf8_lossy(&check.stderr)
-    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("add"), "should show add ref: {stdout}");
+    // Should NOT show the A reference.
+    assert!(!stdout.contains("#A\n"), "should not show A when filtering by add: {stdout}");
 }
 
 #[test]
-fn namespace_remove_refuses_with_active_references() {
-    let (_tmp, dir) = isolated_fixture("namespaced");
+fn refs_symbol_matches_across_targets_as_json() {
+    let (_tmp, dir) = isolated_fixture("basic");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
     let output = docref_at(&dir)
-        .args(["namespace", "remove", "auth"])
+        .args(["refs", "--symbol", "add", "--format", "json"])
         .output()
         .unwrap();
-    assert!(
-        !output.status.success(),
-        "remove should fail with active references"
-    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
+    let entries = json["entries"].as_array().unwrap();
+    assert!(!entries.is_empty(), "expected matches for add: {stdout}");
+    assert!(entries.iter().all(|e| e["symbol"] == "add"), "only add refs expected: {stdout}");
 }
 
 #[test]
-fn namespace_remove_force_succeeds() {
+fn refs_works_with_namespaced_targets() {
     let (_tmp, dir) = isolated_fixture("namespaced");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
     let output = docref_at(&dir)
-        .args(["namespace", "remove", "auth", "--force"])
+        .args(["refs", "auth:src/lib.rs"])
         .output()
         .unwrap();
-    assert!(
-        output.status.success(),
-        "remove --force failed: {}",
-        String::from_utf8_lossy(&output.stderr)
-    );
-
-    let config = std::fs::read_to_string(dir.join(".docref.toml")).unwrap();
-    assert!(!config.contains("auth"), "config still has auth: {config}");
-
-    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        !lock.contains("auth:"),
-        "lockfile still has auth refs: {lock}"
-    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("docs/guide.md"), "missing guide.md: {stdout}");
 }
 
 #[test]
-fn namespace_add_creates_mapping() {
+fn refs_no_matches_shows_message() {
     let (_tmp, dir) = isolated_fixture("basic");
 
-    // basic fixture has no .docref.toml — create a minimal one.
-    std::fs::write(dir.join(".docref.toml"), "").unwrap();
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
 
     let output = docref_at(&dir)
-        .args(["namespace", "add", "mylib", "packages/mylib"])
-        .output()
-        .unwrap();
-    assert!(
-        output.status.success(),
-        "namespace add failed: {}",
-        String::from_utf8_lossy(&output.stderr)
-    );
-
-    let content = std::fs::read_to_string(dir.join(".docref.toml")).unwrap();
-    assert!(
-        content.contains("mylib"),
-        "config should contain namespace: {content}"
-    );
-    assert!(
-        content.contains("packages/mylib"),
-        "config should contain path: {content}"
-    );
-
-    let list = docref_at(&dir)
-        .args(["namespace", "list"])
+        .args(["refs", "nonexistent.rs"])
         .output()
         .unwrap();
-    let stdout = String::from_utf8_lossy(&list.stdout);
-    assert!(
-        stdout.contains("mylib"),
-        "list should show added namespace: {stdout}"
-    );
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No references"), "should show no matches message: {stderr}");
 }
 
 #[test]
-fn update_from_file_updates_all_refs_in_doc() {
+fn org_documents_are_scanned_for_references() {
     let (_tmp, dir) = isolated_fixture("basic");
-    let src = dir.join("src/lib.rs");
+    std::fs::write(dir.join("docs/notes.org"), "* Notes\n\nSee [[../src/lib.rs#add][add]] for the sum.\n").unwrap();
 
-    let original = std::fs::read_to_string(&src).unwrap();
-
-    // Init, then modify both referenced symbols.
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
-
-    let modified = original
-        .replace("const A: i32 = 10;", "const A: i32 = 99;")
-        .replace("x + A", "x * A");
-    std::fs::write(&src, &modified).unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("docs/notes.org"), "org references should be locked: {lock}");
+}
 
-    // Both A and add should be stale.
-    let check = docref_at(&dir).arg("check").output().unwrap();
-    assert_eq!(check.status.code().unwrap(), 1, "expected stale");
+#[test]
+fn mdx_docs_are_scanned_without_jsx_false_references() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(
+        dir.join("docs/intro.mdx"),
+        "import Tabs from '@theme/Tabs';\n\n\
+         Call [`add`](../src/lib.rs#add) to sum.\n\n\
+         {/* Removed: [`sub`](../src/lib.rs#sub) */}\n\
+         <Tabs groupId={[\"a\"][0]}>\n</Tabs>\n",
+    )
+    .unwrap();
 
-    // Update all refs originating from guide.md.
-    let update = docref_at(&dir)
-        .args(["update", "--from", "docs/guide.md"])
-        .output()
-        .unwrap();
-    assert!(
-        update.status.success(),
-        "update --from failed: {}",
-        String::from_utf8_lossy(&update.stderr)
-    );
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("docs/intro.mdx"), "mdx references should be locked: {lock}");
+    assert!(!lock.contains("symbol = \"sub\""), "commented-out JSX link was tracked: {lock}");
 
-    // Check should pass now — guide.md's refs are updated,
-    // and api.md's refs (app.ts) were never stale.
-    let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check still failing after update --from: {}",
-        String::from_utf8_lossy(&check.stdout)
-    );
+    std::fs::write(dir.join(".docref.toml"), "markdown_extensions = [\"md\"]\n").unwrap();
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(!lock.contains("docs/intro.mdx"), "mdx should be skipped when not configured: {lock}");
 }
 
-// --- Sub-declaration tests ---
+// --- Python support tests ---
 
 #[test]
-fn subdecl_init_then_check_passes() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn python_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("python");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(
@@ -686,6 +2473,15 @@ fn subdecl_init_then_check_passes() {
         String::from_utf8_lossy(&init.stderr)
     );
 
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("app.py"), "lockfile missing Python refs: {lock}");
+    assert!(lock.contains("MAX_RETRIES"), "lockfile missing MAX_RETRIES: {lock}");
+    assert!(lock.contains("process"), "lockfile missing process: {lock}");
+    assert!(lock.contains("Config"), "lockfile missing Config: {lock}");
+    assert!(lock.contains("Config.host"), "lockfile missing Config.host: {lock}");
+    assert!(lock.contains("Config.validate"), "lockfile missing Config.validate: {lock}");
+    assert!(lock.contains("Config.address"), "lockfile missing Config.address: {lock}");
+
     let check = docref_at(&dir).arg("check").output().unwrap();
     assert!(
         check.status.success(),
@@ -695,153 +2491,226 @@ fn subdecl_init_then_check_passes() {
 }
 
 #[test]
-fn subdecl_struct_field_in_lockfile() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn python_resolve_lists_symbols() {
+    let (_tmp, dir) = isolated_fixture("python");
+
+    let output = docref_at(&dir)
+        .args(["resolve", "src/app.py"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("MAX_RETRIES"), "missing MAX_RETRIES: {stdout}");
+    assert!(stdout.contains("process"), "missing process: {stdout}");
+    assert!(stdout.contains("Config.host"), "missing Config.host: {stdout}");
+    assert!(stdout.contains("Config.validate"), "missing Config.validate: {stdout}");
+}
+
+#[test]
+fn python_detects_stale_on_method_change() {
+    let (_tmp, dir) = isolated_fixture("python");
+    let src = dir.join("src/app.py");
 
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("Config.host"),
-        "lockfile missing Config.host: {content}"
-    );
+    let modified = original.replace("return len(self.host) > 0", "return bool(self.host)");
+    std::fs::write(&src, &modified).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let code = check.status.code().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(code, 1, "expected stale, got {code}\nstdout: {stdout}");
 }
 
 #[test]
-fn subdecl_enum_variant_in_lockfile() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn python_package_reexports_resolve_to_defining_module() {
+    let (_tmp, dir) = isolated_fixture("python");
+    std::fs::create_dir_all(dir.join("src/pkg/api")).unwrap();
+    std::fs::write(dir.join("src/pkg/__init__.py"), "from .api import ClientError as Error\n").unwrap();
+    std::fs::write(dir.join("src/pkg/api/__init__.py"), "from .errors import *\n").unwrap();
+    let errors = dir.join("src/pkg/api/errors.py");
+    std::fs::write(&errors, "class ClientError(Exception):\n    def code(self):\n        return 400\n").unwrap();
+    std::fs::write(
+        dir.join("docs/errors.md"),
+        "[`Error`](../src/pkg/__init__.py#Error) and [`code`](../src/pkg/__init__.py#Error.code).\n",
+    )
+    .unwrap();
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    assert!(docref_at(&dir).arg("check").output().unwrap().status.success());
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("Message.Quit"),
-        "lockfile missing Message.Quit: {content}"
-    );
+    std::fs::write(&errors, "class ClientError(Exception):\n    def code(self):\n        return 418\n").unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("src/pkg/__init__.py#Error.code"), "{stdout}");
 }
 
+// --- C++ support tests ---
+
 #[test]
-fn subdecl_trait_method_in_lockfile() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn cpp_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("cpp");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("Handler.handle"),
-        "lockfile missing Handler.handle: {content}"
-    );
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    for symbol in ["net.kMaxRetries", "net.Server.start", "net.Server.Options.timeout", "clamp", "net.Server.Server"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "lockfile missing {symbol}: {lock}");
+    }
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "check failed: {}", String::from_utf8_lossy(&check.stdout));
 }
 
 #[test]
-fn subdecl_ts_interface_prop_in_lockfile() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn cpp_resolve_prefers_definitions_over_prototypes() {
+    let (_tmp, dir) = isolated_fixture("cpp");
 
-    let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    let output = docref_at(&dir).args(["resolve", "src/server.cpp", "helper"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "prototype should not make helper ambiguous: {stdout}");
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("ServerConfig.host"),
-        "lockfile missing ServerConfig.host: {content}"
-    );
+    let output = docref_at(&dir).args(["resolve", "src/server.hpp"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in ["net.Mode.Fast", "net.Server.~Server", "net.Server.port_", "net.checksum"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
 }
 
 #[test]
-fn subdecl_ts_class_method_in_lockfile() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn cpp_detects_stale_on_method_change() {
+    let (_tmp, dir) = isolated_fixture("cpp");
+    let src = dir.join("src/server.cpp");
 
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("App.render"),
-        "lockfile missing App.render: {content}"
-    );
+    std::fs::write(&src, original.replace("return !host_.empty();", "return true;")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("net.Server.start"), "{stdout}");
+    assert!(!stdout.contains("checksum"), "unchanged function reported: {stdout}");
 }
 
+// --- CSS support tests ---
+
 #[test]
-fn subdecl_ts_enum_member_in_lockfile() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn css_selectors_and_custom_properties_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("css");
+    let theme = dir.join("theme.css");
+
+    let output = docref_at(&dir).args(["resolve", "theme.css"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in [".button-link", "#main > .card", ":root.--radius", ".theme-dark.--brand-color", ".card"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    let bare = docref_at(&dir).args(["resolve", "theme.css", "--brand-color"]).output().unwrap();
+    assert!(!bare.status.success(), "a property set by two rules should be ambiguous");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \":root.--brand-color\""), "{lock}");
 
-    let content = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(
-        content.contains("Status.Active"),
-        "lockfile missing Status.Active: {content}"
-    );
-    assert!(
-        content.contains("Direction.Up"),
-        "lockfile missing Direction.Up: {content}"
-    );
+    let original = std::fs::read_to_string(&theme).unwrap();
+    std::fs::write(&theme, original.replace("--radius: 4px;", "--radius: 6px;")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("--radius"), "{stdout}");
+    assert!(!stdout.contains(".button"), "unchanged rule reported: {stdout}");
 }
 
 #[test]
-fn subdecl_field_change_detected_stale() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
-    let src = dir.join("src/lib.rs");
+fn scss_mixins_functions_and_variables_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("scss");
+    let theme = dir.join("_theme.scss");
 
-    let original = std::fs::read_to_string(&src).unwrap();
+    let output = docref_at(&dir).args(["resolve", "_theme.scss"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#$brand", "#$breakpoints", "#button-variant", "#rem"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("$local"), "nested variable listed: {stdout}");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"$brand\""), "{lock}");
 
-    // Change the type of the host field.
-    let modified = original.replace("host: String", "host: Vec<u8>");
-    std::fs::write(&src, &modified).unwrap();
+    let original = std::fs::read_to_string(&theme).unwrap();
+    std::fs::write(&theme, original.replace("darken($color, 10%)", "darken($color, 15%)")).unwrap();
 
     let check = docref_at(&dir).arg("check").output().unwrap();
-    let code = check.status.code().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
-    assert_eq!(code, 1, "expected exit 1 (stale), got {code}\nstdout: {stdout}");
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("button-variant"), "{stdout}");
+    assert!(!stdout.contains("$brand"), "unchanged variable reported: {stdout}");
 }
 
+// --- C# support tests ---
+
 #[test]
-fn subdecl_resolve_lists_sub_symbols() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
+fn csharp_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("csharp");
 
-    let output = docref_at(&dir)
-        .args(["resolve", "src/lib.rs"])
-        .output()
-        .unwrap();
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    for symbol in ["Server.MaxRetries", "Server.Port", "Server.Options.Timeout", "IHandler.Handle", "Endpoint.Host"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "lockfile missing {symbol}: {lock}");
+    }
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "check failed: {}", String::from_utf8_lossy(&check.stdout));
+}
+
+#[test]
+fn csharp_resolve_lists_symbols() {
+    let (_tmp, dir) = isolated_fixture("csharp");
+
+    let output = docref_at(&dir).args(["resolve", "src/Server.cs"]).output().unwrap();
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Config.host"), "missing Config.host: {stdout}");
-    assert!(stdout.contains("Message.Quit"), "missing Message.Quit: {stdout}");
-    assert!(stdout.contains("Handler.handle"), "missing Handler.handle: {stdout}");
+    for symbol in ["Callback", "Server.region", "Server.Server", "Server.~Server", "IHandler.Name", "Mode.Fast"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
 }
 
 #[test]
-fn subdecl_field_removal_detected_broken() {
-    let (_tmp, dir) = isolated_fixture("subdecl");
-    let src = dir.join("src/lib.rs");
+fn csharp_detects_stale_on_method_change() {
+    let (_tmp, dir) = isolated_fixture("csharp");
+    let src = dir.join("src/Server.cs");
 
     let original = std::fs::read_to_string(&src).unwrap();
-
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    // Remove the host field entirely.
-    let broken = original.replace("    host: String,\n", "");
-    std::fs::write(&src, &broken).unwrap();
+    std::fs::write(&src, original.replace("return host.Length > 0;", "return true;")).unwrap();
 
     let check = docref_at(&dir).arg("check").output().unwrap();
-    let code = check.status.code().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
-    assert_eq!(code, 2, "expected exit 2 (broken), got {code}\nstdout: {stdout}");
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("Server.Start"), "{stdout}");
+    assert!(!stdout.contains("Server.Port"), "unchanged property reported: {stdout}");
 }
 
-// --- Whole-file reference tests ---
+// --- Go support tests ---
 
 #[test]
-fn wholefile_init_then_check_passes() {
-    let (_tmp, dir) = isolated_fixture("wholefile");
+fn go_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("golang");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(
@@ -851,21 +2720,15 @@ fn wholefile_init_then_check_passes() {
     );
 
     let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    // Whole-file entry: symbol is empty string.
-    assert!(
-        lock.contains("symbol = \"\""),
-        "lockfile missing whole-file entry: {lock}"
-    );
-    // Symbol-scoped entry should still be present.
-    assert!(
-        lock.contains("symbol = \"add\""),
-        "lockfile missing add entry: {lock}"
-    );
-    // png link should NOT produce an entry.
-    assert!(
-        !lock.contains("photo.png"),
-        "lockfile should not track png: {lock}"
-    );
+    assert!(lock.contains("main.go"), "lockfile missing Go refs: {lock}");
+    assert!(lock.contains("MaxRetries"), "lockfile missing MaxRetries: {lock}");
+    assert!(lock.contains("globalState"), "lockfile missing globalState: {lock}");
+    assert!(lock.contains("process"), "lockfile missing process: {lock}");
+    assert!(lock.contains("Config"), "lockfile missing Config: {lock}");
+    assert!(lock.contains("Config.Host"), "lockfile missing Config.Host: {lock}");
+    assert!(lock.contains("Config.Validate"), "lockfile missing Config.Validate: {lock}");
+    assert!(lock.contains("Handler"), "lockfile missing Handler: {lock}");
+    assert!(lock.contains("Handler.Handle"), "lockfile missing Handler.Handle: {lock}");
 
     let check = docref_at(&dir).arg("check").output().unwrap();
     assert!(
@@ -876,414 +2739,515 @@ fn wholefile_init_then_check_passes() {
 }
 
 #[test]
-fn wholefile_detects_stale_after_file_change() {
-    let (_tmp, dir) = isolated_fixture("wholefile");
-    let src = dir.join("src/lib.rs");
+fn go_resolve_lists_symbols() {
+    let (_tmp, dir) = isolated_fixture("golang");
+
+    let output = docref_at(&dir)
+        .args(["resolve", "src/main.go"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("MaxRetries"), "missing MaxRetries: {stdout}");
+    assert!(stdout.contains("Config.Host"), "missing Config.Host: {stdout}");
+    assert!(stdout.contains("Config.Validate"), "missing Config.Validate: {stdout}");
+    assert!(stdout.contains("Handler.Handle"), "missing Handler.Handle: {stdout}");
+}
 
+#[test]
+fn go_detects_stale_on_function_change() {
+    let (_tmp, dir) = isolated_fixture("golang");
+    let src = dir.join("src/main.go");
+
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    // Modify the file content.
-    let original = std::fs::read_to_string(&src).unwrap();
-    let modified = original.replace("x + y", "x * y");
+    let modified = original.replace("return data", "return data + data");
     std::fs::write(&src, &modified).unwrap();
 
     let check = docref_at(&dir).arg("check").output().unwrap();
     let code = check.status.code().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
     assert_eq!(code, 1, "expected stale, got {code}\nstdout: {stdout}");
-    assert!(stdout.contains("STALE"), "expected STALE in: {stdout}");
 }
 
 #[test]
-fn wholefile_update_bare_path() {
-    let (_tmp, dir) = isolated_fixture("wholefile");
-    let src = dir.join("src/lib.rs");
+fn go_package_directory_resolves_symbols_across_files() {
+    let (_tmp, dir) = isolated_fixture("golang");
+    let pkg = dir.join("src/auth");
+    std::fs::create_dir_all(&pkg).unwrap();
+    std::fs::write(pkg.join("token.go"), "package auth\n\ntype Token struct {\n\tValue string\n}\n").unwrap();
+    let validate = "package auth\n\nimport \"errors\"\n\nfunc Validate(t Token) error {\n\treturn nil\n}\n";
+    std::fs::write(pkg.join("validate.go"), validate).unwrap();
+    std::fs::write(pkg.join("validate_test.go"), "package auth\n\nfunc Validate() {}\n").unwrap();
+    let guide = "# Auth\n\n[`Validate`](../src/auth#Validate) checks a [`Token`](../src/auth#Token).\n";
+    std::fs::write(dir.join("docs/auth.md"), guide).unwrap();
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"Validate\"") && lock.contains("symbol = \"Token\""), "{lock}");
 
-    // Modify and make stale.
-    let original = std::fs::read_to_string(&src).unwrap();
-    std::fs::write(&src, original.replace("x + y", "x * y")).unwrap();
+    std::fs::write(pkg.join("validate.go"), validate.replace("return nil", "return errors.New(\"bad\")")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("#Validate") && !stdout.contains("#Token"), "only Validate is stale: {stdout}");
+}
 
-    // Update with bare path (no #symbol).
-    let update = docref_at(&dir)
-        .args(["update", "src/lib.rs"])
-        .output()
-        .unwrap();
-    assert!(
-        update.status.success(),
-        "update bare path failed: {}",
-        String::from_utf8_lossy(&update.stderr)
-    );
+// --- Java support tests ---
+
+#[test]
+fn java_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("java");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    for symbol in ["Server.MAX_RETRIES", "Server.host", "Server.start", "Server.Options.seconds", "Mode.isFast"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "lockfile missing {symbol}: {lock}");
+    }
 
-    // The whole-file ref should be fresh now, but add is still stale.
     let check = docref_at(&dir).arg("check").output().unwrap();
-    let code = check.status.code().unwrap();
-    let stdout = String::from_utf8_lossy(&check.stdout);
-    assert_eq!(code, 1, "expected stale (add still changed), got {code}\nstdout: {stdout}");
-    // Verify the whole-file entry is no longer listed as stale.
-    let stale_lines: Vec<&str> = stdout.lines().filter(|l| l.starts_with("STALE")).collect();
-    assert_eq!(stale_lines.len(), 1, "expected 1 stale ref (add), got: {stale_lines:?}");
+    assert!(check.status.success(), "check failed: {}", String::from_utf8_lossy(&check.stdout));
 }
 
 #[test]
-fn wholefile_status_display() {
-    let (_tmp, dir) = isolated_fixture("wholefile");
+fn java_resolve_lists_symbols() {
+    let (_tmp, dir) = isolated_fixture("java");
+
+    let output = docref_at(&dir).args(["resolve", "src/Server.java"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in ["Server.Server", "Server.region", "Server.Options", "Handler.PREFIX", "Mode.FAST"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+}
+
+#[test]
+fn java_detects_stale_on_method_change() {
+    let (_tmp, dir) = isolated_fixture("java");
+    let src = dir.join("src/Server.java");
 
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    let status = docref_at(&dir).arg("status").output().unwrap();
-    let stdout = String::from_utf8_lossy(&status.stdout);
-    // Whole-file ref should show without # suffix.
-    assert!(
-        stdout.contains("src/lib.rs\n") || stdout.contains("src/lib.rs\r"),
-        "status should show bare file path without #: {stdout}"
-    );
-    // Symbol ref should still show with #.
-    assert!(
-        stdout.contains("src/lib.rs#add"),
-        "status should show symbol ref: {stdout}"
-    );
+    std::fs::write(&src, original.replace("timeout / 1000", "timeout / 60")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("Server.Options.seconds"), "{stdout}");
+    assert!(!stdout.contains("Server.Options.timeout"), "unchanged field reported: {stdout}");
 }
 
-// --- JavaScript / JSX tests ---
+// --- PHP support tests ---
 
 #[test]
-fn javascript_init_then_check_passes() {
-    let (_tmp, dir) = isolated_fixture("javascript");
+fn php_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("php");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
 
     let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(lock.contains("app.js"), "lockfile missing JS refs: {lock}");
-    assert!(lock.contains("VERSION"), "lockfile missing VERSION: {lock}");
-    assert!(lock.contains("greet"), "lockfile missing greet: {lock}");
-    assert!(lock.contains("App"), "lockfile missing App: {lock}");
-    assert!(lock.contains("App.render"), "lockfile missing App.render: {lock}");
+    for symbol in ["DEFAULT_PORT", "Server.start", "Server.host", "Retries.MAX_RETRIES", "Handler.handle"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "lockfile missing {symbol}: {lock}");
+    }
 
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check failed: {}",
-        String::from_utf8_lossy(&check.stderr)
-    );
+    assert!(check.status.success(), "check failed: {}", String::from_utf8_lossy(&check.stdout));
 }
 
 #[test]
-fn javascript_resolve_lists_symbols() {
-    let (_tmp, dir) = isolated_fixture("javascript");
+fn php_resolve_lists_symbols() {
+    let (_tmp, dir) = isolated_fixture("php");
 
-    let output = docref_at(&dir)
-        .args(["resolve", "src/app.js"])
-        .output()
-        .unwrap();
+    let output = docref_at(&dir).args(["resolve", "src/Server.php"]).output().unwrap();
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("VERSION"), "missing VERSION: {stdout}");
-    assert!(stdout.contains("greet"), "missing greet: {stdout}");
-    assert!(stdout.contains("App"), "missing App: {stdout}");
+    for symbol in ["Server.__construct", "Server.region", "Retries.retry", "Mode.Fast", "checksum"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
 }
 
-// --- JSON format tests ---
-
 #[test]
-fn check_json_output_all_fresh() {
-    let (_tmp, dir) = isolated_fixture("basic");
+fn php_detects_stale_on_method_change() {
+    let (_tmp, dir) = isolated_fixture("php");
+    let src = dir.join("src/Server.php");
 
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    let check = docref_at(&dir)
-        .args(["check", "--format", "json"])
-        .output()
-        .unwrap();
-    assert!(check.status.success());
+    std::fs::write(&src, original.replace("return $this->host !== '';", "return true;")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout)
-        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
-    assert_eq!(json["summary"]["broken"], 0);
-    assert_eq!(json["summary"]["stale"], 0);
-    assert!(json["summary"]["fresh"].as_u64().unwrap() > 0);
-    assert!(json["entries"].as_array().unwrap().len() > 0);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("Server.start"), "{stdout}");
+    assert!(!stdout.contains("Server.host"), "unchanged property reported: {stdout}");
 }
 
+// --- Svelte support tests ---
+
 #[test]
-fn check_json_output_stale() {
-    let (_tmp, dir) = isolated_fixture("basic");
-    let src = dir.join("src/lib.rs");
+fn svelte_exports_and_props_resolve_from_the_script_block() {
+    let (_tmp, dir) = isolated_fixture("svelte");
 
-    let original = std::fs::read_to_string(&src).unwrap();
+    let output = docref_at(&dir).args(["resolve", "src/lib/Button.svelte"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in ["variant", "disabled", "focus"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("dispatch"), "unexported declarations should not be listed: {stdout}");
+
+    let output = docref_at(&dir).args(["resolve", "src/lib/Badge.svelte"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in ["label", "tone", "class"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("count") && !stdout.contains("rest"), "only props should be listed: {stdout}");
+}
+
+#[test]
+fn svelte_props_go_stale_independently_of_markup() {
+    let (_tmp, dir) = isolated_fixture("svelte");
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
 
-    let modified = original.replace("const A: i32 = 10;", "const A: i32 = 20;");
-    std::fs::write(&src, &modified).unwrap();
+    let button = dir.join("src/lib/Button.svelte");
+    let original = std::fs::read_to_string(&button).unwrap();
+    std::fs::write(&button, original.replace("padding: 0.5rem 1rem;", "padding: 1rem;")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "markup edits should not stale props: {}", String::from_utf8_lossy(&check.stdout));
 
-    let check = docref_at(&dir)
-        .args(["check", "--format", "json"])
-        .output()
-        .unwrap();
-    assert_eq!(check.status.code().unwrap(), 1);
+    let badge = dir.join("src/lib/Badge.svelte");
+    let original = std::fs::read_to_string(&badge).unwrap();
+    std::fs::write(&badge, original.replace("tone = \"neutral\"", "tone = \"info\"")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout)
-        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
-    assert!(json["summary"]["stale"].as_u64().unwrap() > 0);
-    let entries = json["entries"].as_array().unwrap();
-    assert!(entries.iter().any(|e| e["status"] == "stale"));
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("Badge.svelte#tone"), "{stdout}");
 }
 
+// --- Swift support tests ---
+
 #[test]
-fn check_json_broken_includes_reason() {
-    let (_tmp, dir) = isolated_fixture("basic");
-    let src = dir.join("src/lib.rs");
+fn swift_init_then_check_passes() {
+    let (_tmp, dir) = isolated_fixture("swift");
 
-    let original = std::fs::read_to_string(&src).unwrap();
-    docref_at(&dir).arg("init").output().unwrap();
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
 
-    let broken = original.replace("const A: i32 = 10;\n", "");
-    std::fs::write(&src, &broken).unwrap();
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    for symbol in ["Server.start", "Server.Limits.maxRetries", "Server.handle", "Mode.safe", "Options.timeout"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "lockfile missing {symbol}: {lock}");
+    }
 
-    let check = docref_at(&dir)
-        .args(["check", "--format", "json"])
-        .output()
-        .unwrap();
-    assert_eq!(check.status.code().unwrap(), 2);
-    let stdout = String::from_utf8_lossy(&check.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout)
-        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
-    assert!(json["summary"]["broken"].as_u64().unwrap() > 0);
-    let entries = json["entries"].as_array().unwrap();
-    let broken_entry = entries.iter().find(|e| e["status"] == "broken").unwrap();
-    assert!(broken_entry["reason"].as_str().unwrap().len() > 0);
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "check failed: {}", String::from_utf8_lossy(&check.stdout));
 }
 
 #[test]
-fn status_json_output() {
-    let (_tmp, dir) = isolated_fixture("basic");
+fn swift_resolve_lists_symbols() {
+    let (_tmp, dir) = isolated_fixture("swift");
+
+    let output = docref_at(&dir).args(["resolve", "src/Server.swift"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in ["Server.init", "Server.deinit", "Mode.fast", "Mode.custom", "Handler.name", "Server.name"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("Server.Bool"), "return type listed as a symbol: {stdout}");
+}
+
+#[test]
+fn swift_detects_stale_on_extension_method_change() {
+    let (_tmp, dir) = isolated_fixture("swift");
+    let src = dir.join("src/Server.swift");
 
+    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
     assert!(init.status.success());
 
-    let status = docref_at(&dir)
-        .args(["status", "--format", "json"])
-        .output()
-        .unwrap();
-    assert!(status.status.success());
-    let stdout = String::from_utf8_lossy(&status.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout)
-        .unwrap_or_else(|e| panic!("invalid JSON: {e}\n{stdout}"));
-    let entries = json["entries"].as_array().unwrap();
-    assert!(entries.len() > 0);
-    // Status entries should have a hash field.
-    let first = &entries[0];
-    assert!(first["hash"].as_str().unwrap().len() > 0);
-    assert!(first["status"].as_str().is_some());
+    std::fs::write(&src, original.replace("print(message)", "debugPrint(message)")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("Server.handle"), "{stdout}");
+    assert!(!stdout.contains("Handler.handle"), "unchanged requirement reported: {stdout}");
 }
 
-// --- Refs (reverse lookup) tests ---
+// --- YAML support tests ---
 
 #[test]
-fn refs_shows_all_docs_for_file() {
-    let (_tmp, dir) = isolated_fixture("basic");
+fn yaml_key_paths_resolve_and_go_stale_by_subtree() {
+    let (_tmp, dir) = isolated_fixture("yaml");
+    let config = dir.join("config.yaml");
+
+    let output = docref_at(&dir).args(["resolve", "config.yaml"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in ["server.host", "server.tls.enabled", "server.tls.cert-file", "database.url", "workers"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("workers.name"), "keys inside sequences should not be listed: {stdout}");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "check failed: {}", String::from_utf8_lossy(&check.stdout));
 
-    let output = docref_at(&dir)
-        .args(["refs", "src/lib.rs"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("docs/guide.md"), "missing guide.md source: {stdout}");
+    let original = std::fs::read_to_string(&config).unwrap();
+    let edited = original.replace("port: 8080", "port: 9090").replace("# Service configuration.", "# Config.");
+    std::fs::write(&config, edited).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("server.port"), "{stdout}");
+    assert!(!stdout.contains("database.pool"), "unchanged key reported: {stdout}");
 }
 
 #[test]
-fn refs_filters_by_symbol() {
-    let (_tmp, dir) = isolated_fixture("basic");
-
+fn whole_file_entries_locked_as_raw_text_stay_fresh_once_a_grammar_exists() {
+    let (_tmp, dir) = isolated_fixture("yaml");
+    std::fs::copy(dir.join("config.yaml"), dir.join("settings.txt")).unwrap();
+    std::fs::write(dir.join("docs/legacy.md"), "See [settings](../settings.txt).\n").unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
 
-    let output = docref_at(&dir)
-        .args(["refs", "src/lib.rs#add"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("add"), "should show add ref: {stdout}");
-    // Should NOT show the A reference.
-    assert!(!stdout.contains("#A\n"), "should not show A when filtering by add: {stdout}");
+    // Simulate a lockfile written before `.yaml` had a grammar.
+    std::fs::rename(dir.join("settings.txt"), dir.join("settings.yaml")).unwrap();
+    std::fs::write(dir.join("docs/legacy.md"), "See [settings](../settings.yaml).\n").unwrap();
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    std::fs::write(dir.join(".docref.lock"), lock.replace("settings.txt", "settings.yaml")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "raw hash should still match: {}", String::from_utf8_lossy(&check.stdout));
+
+    let settings = std::fs::read_to_string(dir.join("settings.yaml")).unwrap();
+    std::fs::write(dir.join("settings.yaml"), settings.replace("pool: 10", "pool: 20")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert_eq!(check.status.code(), Some(1), "edited file should be stale");
 }
 
+// --- JSON support tests ---
+
 #[test]
-fn refs_works_with_namespaced_targets() {
-    let (_tmp, dir) = isolated_fixture("namespaced");
+fn json_dotted_and_pointer_fragments_track_values() {
+    let (_tmp, dir) = isolated_fixture("json");
+    let package = dir.join("package.json");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    for symbol in ["scripts.build", "scripts.test", "scripts.lint/fix", "engines.node"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "lockfile missing {symbol}: {lock}");
+    }
 
-    let output = docref_at(&dir)
-        .args(["refs", "auth:src/lib.rs"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("docs/guide.md"), "missing guide.md: {stdout}");
+    let original = std::fs::read_to_string(&package).unwrap();
+    std::fs::write(&package, original.replace("vitest run", "vitest run --coverage")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("scripts.test"), "{stdout}");
+    assert!(!stdout.contains("scripts.build"), "unchanged script reported: {stdout}");
+
+    let update = docref_at(&dir).args(["update", "package.json#/scripts/test"]).output().unwrap();
+    assert!(update.status.success(), "update failed: {}", String::from_utf8_lossy(&update.stderr));
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "pointer update should refresh: {}", String::from_utf8_lossy(&check.stdout));
 }
 
+// --- TOML support tests ---
+
 #[test]
-fn refs_no_matches_shows_message() {
-    let (_tmp, dir) = isolated_fixture("basic");
+fn toml_tables_and_keys_resolve_and_go_stale_by_value() {
+    let (_tmp, dir) = isolated_fixture("toml");
+    let manifest = dir.join("Cargo.toml");
+
+    let output = docref_at(&dir).args(["resolve", "Cargo.toml"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for symbol in ["package.version", "dependencies.serde.version", "dependencies.tokio", "profile.release"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"dependencies.serde.features\""), "{lock}");
 
-    let output = docref_at(&dir)
-        .args(["refs", "nonexistent.rs"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("No references"), "should show no matches message: {stderr}");
+    let original = std::fs::read_to_string(&manifest).unwrap();
+    std::fs::write(&manifest, original.replace("lto = true", "lto = \"thin\"")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("profile.release.lto"), "{stdout}");
+    assert!(!stdout.contains("dependencies.serde"), "unchanged key reported: {stdout}");
 }
 
-// --- Python support tests ---
+#[test]
+fn line_ranges_are_not_symbols_to_fix_or_resolve() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    std::fs::write(dir.join("docs/lines.md"), "# Lines\n\nThe [first lines](../src/lib.rs#L1-L2).\n").unwrap();
+    assert!(docref_at(&dir).arg("init").output().unwrap().status.success());
+
+    let fix = docref_at(&dir).arg("fix").output().unwrap();
+    assert_eq!(fix.status.code(), Some(0), "fix failed: {}", String::from_utf8_lossy(&fix.stderr));
+
+    let attempts: [&[&str]; 3] = [
+        &["resolve", "src/lib.rs", "L1-L2"],
+        &["resolve", "--explain", "src/lib.rs", "L1"],
+        &["fix", "src/lib.rs#add", "L3"],
+    ];
+    for args in attempts {
+        let output = docref_at(&dir).args(args).output().unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(3), "{args:?} should fail cleanly: {stderr}");
+        assert!(stderr.contains("not a symbol") && !stderr.contains("panicked"), "{args:?}: {stderr}");
+    }
+}
 
 #[test]
-fn python_init_then_check_passes() {
-    let (_tmp, dir) = isolated_fixture("python");
+fn line_ranges_are_tracked_and_report_where_moved_lines_went() {
+    let (_tmp, dir) = isolated_fixture("lines");
+    let conf = dir.join("nginx.conf");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
-
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
     let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(lock.contains("app.py"), "lockfile missing Python refs: {lock}");
-    assert!(lock.contains("MAX_RETRIES"), "lockfile missing MAX_RETRIES: {lock}");
-    assert!(lock.contains("process"), "lockfile missing process: {lock}");
-    assert!(lock.contains("Config"), "lockfile missing Config: {lock}");
-    assert!(lock.contains("Config.host"), "lockfile missing Config.host: {lock}");
-    assert!(lock.contains("Config.validate"), "lockfile missing Config.validate: {lock}");
-    assert!(lock.contains("Config.address"), "lockfile missing Config.address: {lock}");
+    assert!(lock.contains("symbol = \"L3-L6\""), "{lock}");
+    assert!(lock.contains("symbol = \"L9\""), "{lock}");
 
+    let original = std::fs::read_to_string(&conf).unwrap();
+    std::fs::write(&conf, format!("# managed by ansible\n{original}")).unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
-    assert!(
-        check.status.success(),
-        "check failed: {}",
-        String::from_utf8_lossy(&check.stderr)
-    );
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("nginx.conf#L3-L6 (lines moved to #L4-L7)"), "{stdout}");
+    assert!(stdout.contains("nginx.conf#L9 (lines moved to #L10)"), "{stdout}");
+
+    std::fs::write(&conf, original.replace("1024", "2048")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("nginx.conf#L9"), "{stdout}");
+    assert!(!stdout.contains("moved"), "changed lines reported as moved: {stdout}");
+    assert!(!stdout.contains("L3-L6"), "unchanged range reported: {stdout}");
 }
 
 #[test]
-fn python_resolve_lists_symbols() {
-    let (_tmp, dir) = isolated_fixture("python");
+fn marker_comment_regions_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("regions");
+    let server = dir.join("src/server.rs");
 
-    let output = docref_at(&dir)
-        .args(["resolve", "src/app.py"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
+    let output = docref_at(&dir).args(["resolve", "src/server.rs"]).output().unwrap();
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("MAX_RETRIES"), "missing MAX_RETRIES: {stdout}");
-    assert!(stdout.contains("process"), "missing process: {stdout}");
-    assert!(stdout.contains("Config.host"), "missing Config.host: {stdout}");
-    assert!(stdout.contains("Config.validate"), "missing Config.validate: {stdout}");
+    assert!(stdout.contains("@setup"), "{stdout}");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"@setup\""), "{lock}");
+    assert!(lock.contains("symbol = \"@ports\""), "{lock}");
+
+    let original = std::fs::read_to_string(&server).unwrap();
+    std::fs::write(&server, original.replace("8080", "9090")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "change outside the region: {}", String::from_utf8_lossy(&check.stdout));
+
+    std::fs::write(&server, original.replace("listening on", "serving on")).unwrap();
+    let conf = dir.join("deploy.conf");
+    std::fs::write(&conf, std::fs::read_to_string(&conf).unwrap().replace("8443", "9443")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("src/server.rs#@setup"), "{stdout}");
+    assert!(stdout.contains("deploy.conf#@ports"), "{stdout}");
 }
 
 #[test]
-fn python_detects_stale_on_method_change() {
-    let (_tmp, dir) = isolated_fixture("python");
-    let src = dir.join("src/app.py");
+fn region_directives_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("regions");
+    let client = dir.join("src/Client.cs");
 
-    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"@Public-API\""), "{lock}");
 
-    let modified = original.replace("return len(self.host) > 0", "return bool(self.host)");
-    std::fs::write(&src, &modified).unwrap();
+    let original = std::fs::read_to_string(&client).unwrap();
+    std::fs::write(&client, original.replace("private void Open() { }", "private void Open() { Log(); }")).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    assert!(check.status.success(), "change outside the region: {}", String::from_utf8_lossy(&check.stdout));
 
+    std::fs::write(&client, original.replace("Connect() { Open(); }", "Connect() { Open(); Open(); }")).unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
-    let code = check.status.code().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
-    assert_eq!(code, 1, "expected stale, got {code}\nstdout: {stdout}");
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("src/Client.cs#@Public-API"), "{stdout}");
 }
 
-// --- Go support tests ---
-
 #[test]
-fn go_init_then_check_passes() {
-    let (_tmp, dir) = isolated_fixture("golang");
+fn wildcard_references_track_each_member_and_report_added_or_removed_ones() {
+    let (_tmp, dir) = isolated_fixture("wildcard");
+    let config = dir.join("src/config.rs");
 
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(
-        init.status.success(),
-        "init failed: {}",
-        String::from_utf8_lossy(&init.stderr)
-    );
-
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
     let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
-    assert!(lock.contains("main.go"), "lockfile missing Go refs: {lock}");
-    assert!(lock.contains("MaxRetries"), "lockfile missing MaxRetries: {lock}");
-    assert!(lock.contains("globalState"), "lockfile missing globalState: {lock}");
-    assert!(lock.contains("process"), "lockfile missing process: {lock}");
-    assert!(lock.contains("Config"), "lockfile missing Config: {lock}");
-    assert!(lock.contains("Config.Host"), "lockfile missing Config.Host: {lock}");
-    assert!(lock.contains("Config.Validate"), "lockfile missing Config.Validate: {lock}");
-    assert!(lock.contains("Handler"), "lockfile missing Handler: {lock}");
-    assert!(lock.contains("Handler.Handle"), "lockfile missing Handler.Handle: {lock}");
+    for symbol in ["Config.*", "Config.host", "Config.port"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "missing {symbol}: {lock}");
+    }
 
+    let original = std::fs::read_to_string(&config).unwrap();
+    std::fs::write(&config, original.replace("port: u16", "port: u32")).unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("src/config.rs#Config.port"), "{stdout}");
+    assert!(!stdout.contains("Config.*"), "member set unchanged: {stdout}");
+
+    let reshaped = original.replace("    pub host: String,\n", "    pub timeout: u64,\n");
+    std::fs::write(&config, reshaped).unwrap();
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
     assert!(
-        check.status.success(),
-        "check failed: {}",
-        String::from_utf8_lossy(&check.stderr)
+        stdout.contains("src/config.rs#Config.* (members added: Config.timeout; removed: Config.host)"),
+        "{stdout}"
     );
 }
 
 #[test]
-fn go_resolve_lists_symbols() {
-    let (_tmp, dir) = isolated_fixture("golang");
-
-    let output = docref_at(&dir)
-        .args(["resolve", "src/main.go"])
-        .output()
-        .unwrap();
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("MaxRetries"), "missing MaxRetries: {stdout}");
-    assert!(stdout.contains("Config.Host"), "missing Config.Host: {stdout}");
-    assert!(stdout.contains("Config.Validate"), "missing Config.Validate: {stdout}");
-    assert!(stdout.contains("Handler.Handle"), "missing Handler.Handle: {stdout}");
-}
-
-#[test]
-fn go_detects_stale_on_function_change() {
-    let (_tmp, dir) = isolated_fixture("golang");
-    let src = dir.join("src/main.go");
+fn symbol_set_links_lock_one_entry_per_symbol() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let lib = dir.join("src/lib.rs");
+    let original = std::fs::read_to_string(&lib).unwrap();
+    let operators = "\nfn sub(x: i32) -> i32 {\n    x - A\n}\n\nfn mul(x: i32) -> i32 {\n    x * A\n}\n";
+    std::fs::write(&lib, format!("{original}{operators}")).unwrap();
+    std::fs::write(dir.join("docs/math.md"), "The [operators](../src/lib.rs#{add,sub,mul}) all take `A`.\n").unwrap();
 
-    let original = std::fs::read_to_string(&src).unwrap();
     let init = docref_at(&dir).arg("init").output().unwrap();
-    assert!(init.status.success());
-
-    let modified = original.replace("return data", "return data + data");
-    std::fs::write(&src, &modified).unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    for symbol in ["sub", "mul"] {
+        assert!(lock.contains(&format!("symbol = \"{symbol}\"")), "missing {symbol}: {lock}");
+    }
+    assert!(!lock.contains('{'), "set locked as one symbol: {lock}");
 
+    let edited = std::fs::read_to_string(&lib).unwrap().replace("x - A", "A - x");
+    std::fs::write(&lib, edited).unwrap();
     let check = docref_at(&dir).arg("check").output().unwrap();
-    let code = check.status.code().unwrap();
     let stdout = String::from_utf8_lossy(&check.stdout);
-    assert_eq!(code, 1, "expected stale, got {code}\nstdout: {stdout}");
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("src/lib.rs#sub"), "{stdout}");
+    assert!(!stdout.contains("src/lib.rs#mul"), "{stdout}");
 }
 
 // --- Bash support tests ---
@@ -1350,6 +3314,244 @@ fn bash_detects_stale_on_function_change() {
     assert_eq!(code, 1, "expected stale, got {code}\nstdout: {stdout}");
 }
 
+// --- Dockerfile support tests ---
+
+#[test]
+fn dockerfile_stages_and_variables_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("dockerfile");
+    let dockerfile = dir.join("Dockerfile");
+
+    let output = docref_at(&dir).args(["resolve", "Dockerfile"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#GO_VERSION", "#build", "#build.GOFLAGS", "#runtime.LOG_FORMAT", "#runtime.TZ"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"runtime.PORT\""), "{lock}");
+
+    let original = std::fs::read_to_string(&dockerfile).unwrap();
+    std::fs::write(&dockerfile, original.replace("COPY . .", "COPY go.mod go.sum ./\nCOPY . .")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("Dockerfile#build"), "{stdout}");
+    assert!(!stdout.contains("GO_VERSION"), "unchanged variable reported: {stdout}");
+    assert!(!stdout.contains("runtime.PORT"), "unchanged variable reported: {stdout}");
+}
+
+// --- Haskell support tests ---
+
+#[test]
+fn haskell_types_classes_and_functions_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("haskell");
+    let shapes = dir.join("src/Shapes.hs");
+
+    let output = docref_at(&dir).args(["resolve", "src/Shapes.hs"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#Shape.Circle", "#Shape.Rect", "#HasArea.area", "#HasArea.describe", "#largest", "#fromDiameter"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("radius"), "local binding listed: {stdout}");
+    let area = docref_at(&dir).args(["resolve", "src/Shapes.hs", "area"]).output().unwrap();
+    assert!(area.status.success(), "instance methods should not make the class method ambiguous");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let original = std::fs::read_to_string(&shapes).unwrap();
+    std::fs::write(&shapes, original.replace("radius = diameter / 2", "radius = diameter * 0.5")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("fromDiameter"), "{stdout}");
+    assert!(!stdout.contains("largest"), "unchanged function reported: {stdout}");
+}
+
+// --- Kotlin support tests ---
+
+#[test]
+fn kotlin_classes_and_members_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("kotlin");
+    let user = dir.join("src/User.kt");
+
+    let output = docref_at(&dir).args(["resolve", "src/User.kt"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#User.name", "#User.GUEST_ID", "#Event.describe", "#Registry.register", "#shout", "#DEFAULT_USER"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("greeting"), "local variable listed: {stdout}");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let original = std::fs::read_to_string(&user).unwrap();
+    std::fs::write(&user, original.replace("val greeting = \"Hello\"", "val greeting = \"Hi\"")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("User.greet"), "{stdout}");
+    assert!(!stdout.contains("User.guest"), "unchanged companion member reported: {stdout}");
+}
+
+// --- Lua support tests ---
+
+#[test]
+fn lua_functions_and_tables_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("lua");
+    let greeter = dir.join("src/greeter.lua");
+
+    let output = docref_at(&dir).args(["resolve", "src/greeter.lua"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#M\n", "#M.format", "#M.greet", "#M.loud", "#M.farewell", "#shout"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("message"), "local variable listed: {stdout}");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let original = std::fs::read_to_string(&greeter).unwrap();
+    std::fs::write(&greeter, original.replace("print(\"Goodbye, \" .. name)", "print(\"Bye, \" .. name)")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("M.farewell"), "{stdout}");
+    assert!(!stdout.contains("M.greet"), "unchanged function reported: {stdout}");
+}
+
+// --- Protocol Buffers support tests ---
+
+#[test]
+fn proto_definitions_and_members_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("proto");
+    let schema = dir.join("users.proto");
+
+    let output = docref_at(&dir).args(["resolve", "users.proto"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#User.display_name", "#User.labels", "#User.Role.ADMIN", "#User.phone", "#UserService.WatchUsers"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("go_package"), "option listed: {stdout}");
+    let bare = docref_at(&dir).args(["resolve", "users.proto", "id"]).output().unwrap();
+    assert_eq!(bare.status.code(), Some(3), "a field of two messages should be ambiguous");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let original = std::fs::read_to_string(&schema).unwrap();
+    std::fs::write(&schema, original.replace("string email = 2;", "bytes email = 2;")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("User.email"), "{stdout}");
+    assert!(!stdout.contains("UserService.GetUser"), "unchanged rpc reported: {stdout}");
+}
+
+// --- Scala support tests ---
+
+#[test]
+fn scala_objects_and_members_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("scala");
+    let shapes = dir.join("src/Shapes.scala");
+
+    let output = docref_at(&dir).args(["resolve", "src/Shapes.scala"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#Shape.describe", "#Circle.Unit", "#Shapes.registry", "#Shapes.largest", "#origin"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("radius"), "local value listed: {stdout}");
+    let circle = docref_at(&dir).args(["resolve", "src/Shapes.scala", "Circle"]).output().unwrap();
+    assert!(circle.status.success(), "a companion object should not make its class ambiguous");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let original = std::fs::read_to_string(&shapes).unwrap();
+    std::fs::write(&shapes, original.replace("val radius = diameter / 2", "val radius = diameter * 0.5")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("Circle.fromDiameter"), "{stdout}");
+    assert!(!stdout.contains("Shapes.largest"), "unchanged def reported: {stdout}");
+}
+
+// --- SQL support tests ---
+
+#[test]
+fn sql_statements_and_columns_resolve_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("sql");
+    let schema = dir.join("schema.sql");
+
+    let output = docref_at(&dir).args(["resolve", "schema.sql"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#users", "#users.display_name", "#sessions.user_id", "#users_email_idx", "#active_sessions"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("sessions_pkey"), "constraint listed as a column: {stdout}");
+    let bare = docref_at(&dir).args(["resolve", "schema.sql", "id"]).output().unwrap();
+    assert_eq!(bare.status.code(), Some(3), "a column of two tables should be ambiguous");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+    let lock = std::fs::read_to_string(dir.join(".docref.lock")).unwrap();
+    assert!(lock.contains("symbol = \"users.email\""), "{lock}");
+
+    let original = std::fs::read_to_string(&schema).unwrap();
+    std::fs::write(&schema, original.replace("expires_at timestamptz,", "expires_at timestamp,")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("sessions.expires_at"), "{stdout}");
+    assert!(!stdout.contains("users.email"), "unchanged column reported: {stdout}");
+}
+
+// --- Terraform support tests ---
+
+#[test]
+fn terraform_blocks_resolve_by_address_and_go_stale() {
+    let (_tmp, dir) = isolated_fixture("terraform");
+    let main = dir.join("main.tf");
+
+    let output = docref_at(&dir).args(["resolve", "main.tf"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "resolve failed: {}", String::from_utf8_lossy(&output.stderr));
+    for symbol in ["#var.region", "#module.vpc", "#aws_s3_bucket.logs", "#data.aws_iam_policy_document.logs"] {
+        assert!(stdout.contains(symbol), "missing {symbol}: {stdout}");
+    }
+    assert!(!stdout.contains("lifecycle"), "nested block listed: {stdout}");
+    let bare = docref_at(&dir).args(["resolve", "main.tf", "logs"]).output().unwrap();
+    assert_eq!(bare.status.code(), Some(3), "a resource and a data source named logs should be ambiguous");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success(), "init failed: {}", String::from_utf8_lossy(&init.stderr));
+
+    let original = std::fs::read_to_string(&main).unwrap();
+    std::fs::write(&main, original.replace("prevent_destroy = true", "prevent_destroy = false")).unwrap();
+
+    let check = docref_at(&dir).arg("check").output().unwrap();
+    let stdout = String::from_utf8_lossy(&check.stdout);
+    assert_eq!(check.status.code(), Some(1), "expected stale: {stdout}");
+    assert!(stdout.contains("aws_s3_bucket.logs"), "{stdout}");
+    assert!(!stdout.contains("var.region"), "unchanged block reported: {stdout}");
+}
+
 // --- Watch tests ---
 
 #[test]
@@ -1379,6 +3581,20 @@ fn watch_runs_initial_check() {
     );
 }
 
+#[test]
+fn watch_rejects_formats_it_cannot_stream() {
+    let (_tmp, dir) = isolated_fixture("basic");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let output = docref_at(&dir).args(["watch", "--format", "sarif"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "{stderr}");
+    assert!(stderr.contains("unknown format: sarif"), "{stderr}");
+    assert!(!stderr.contains("initial check"), "should fail before checking: {stderr}");
+}
+
 #[test]
 fn watch_detects_change() {
     let (_tmp, dir) = isolated_fixture("basic");
@@ -1414,3 +3630,98 @@ fn watch_detects_change() {
         "should detect change: stderr={stderr}\nstdout={stdout}"
     );
 }
+
+#[test]
+fn watch_rechecks_only_affected_entries() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_docref"))
+        .arg("watch")
+        .current_dir(&dir)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    let _ = child.kill();
+    let output = child.wait_with_output().unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stderr.contains("refs affected by src/lib.rs"), "should print the delta: {stderr}");
+    assert!(!stderr.contains("re-checking"), "should not re-run the full check: {stderr}");
+    assert!(stdout.contains("STALE   src/lib.rs#A"), "should report the stale entry: {stdout}");
+    assert!(!stdout.contains("docs/overview.md"), "unaffected entries stay quiet: {stdout}");
+}
+
+#[test]
+fn watch_reports_markdown_links_missing_from_lockfile() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let guide = dir.join("docs/guide.md");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+    let mut lib = std::fs::read_to_string(dir.join("src/lib.rs")).unwrap();
+    lib.push_str("\npub fn sub(a: i32, b: i32) -> i32 { a - b }\n");
+    std::fs::write(dir.join("src/lib.rs"), lib).unwrap();
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_docref"))
+        .arg("watch")
+        .current_dir(&dir)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let original = std::fs::read_to_string(&guide).unwrap();
+    std::fs::write(&guide, original.replace("../src/lib.rs#A", "../src/lib.rs#sub")).unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    let _ = child.kill();
+    let output = child.wait_with_output().unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("docs/guide.md links 1 ref not in .docref.lock:\n  src/lib.rs#sub"), "{stderr}");
+    assert!(stderr.contains("docs/guide.md no longer links 1 ref still in .docref.lock:\n  src/lib.rs#A"), "{stderr}");
+    assert!(stderr.contains("run `docref init`"), "should suggest init: {stderr}");
+}
+
+#[test]
+fn watch_exec_runs_hook_after_each_check() {
+    let (_tmp, dir) = isolated_fixture("basic");
+    let src = dir.join("src/lib.rs");
+
+    let init = docref_at(&dir).arg("init").output().unwrap();
+    assert!(init.status.success());
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_docref"))
+        .args(["watch", "--exec", "cat > hook.json; echo $DOCREF_EXIT_CODE >> hook.codes"])
+        .current_dir(&dir)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let original = std::fs::read_to_string(&src).unwrap();
+    std::fs::write(&src, original.replace("const A: i32 = 10;", "const A: i32 = 20;")).unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let codes = std::fs::read_to_string(dir.join("hook.codes")).unwrap();
+    assert_eq!(codes, "0\n1\n", "hook should run after the initial check and the re-check");
+    let payload: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("hook.json")).unwrap()).unwrap();
+    assert_eq!(payload["exit_code"], 1);
+    assert_eq!(payload["summary"]["stale"], 1);
+    assert_eq!(payload["findings"][0]["symbol"], "A");
+}